@@ -1,13 +1,97 @@
 //! Archive management for tabdiff snapshots
 
-use crate::error::Result;
+use crate::error::{Result, TabdiffError};
 use crate::progress::create_file_progress;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use tar::{Archive, Builder};
 use zstd::{Decoder, Encoder};
 
+/// First bytes of every zstd frame; archives without them are stored uncompressed
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Suffix of an entry that stands in for identical content stored in another snapshot's archive
+pub const REFERENCE_SUFFIX: &str = ".ref";
+
+/// Progress callback of archive writes, called with (processed bytes, total bytes, message)
+pub type ProgressCallback<'a> = &'a dyn Fn(u64, u64, &str);
+
+/// Archive compression from 0 (store only, fastest) to 9 (smallest, slowest)
+///
+/// Levels 1-9 are zstd compression of increasing strength; the default, 3, is the zstd level
+/// archives have always been written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CompressionLevel(u8);
+
+impl Default for CompressionLevel {
+    fn default() -> Self {
+        Self(3)
+    }
+}
+
+impl CompressionLevel {
+    pub const MAX: u8 = 9;
+
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.trim().parse::<u8>() {
+            Ok(level) if level <= Self::MAX => Ok(Self(level)),
+            _ => Err(format!("Invalid compression level: {}. Use a number from 0 (store only) to {}", s, Self::MAX)),
+        }
+    }
+
+    pub fn level(&self) -> u8 {
+        self.0
+    }
+
+    /// zstd level to compress with, or None to store the archive uncompressed
+    fn zstd_level(&self) -> Option<i32> {
+        const ZSTD_LEVELS: [i32; 9] = [1, 2, 3, 5, 7, 9, 12, 15, 19];
+        self.0.checked_sub(1).map(|i| ZSTD_LEVELS[i as usize])
+    }
+}
+
+/// Destination of a tar stream, compressed unless the level is 0
+enum ArchiveWriter<W: Write> {
+    Stored(W),
+    Zstd(Encoder<'static, W>),
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    fn new(writer: W, level: CompressionLevel) -> Result<Self> {
+        Ok(match level.zstd_level() {
+            Some(zstd_level) => Self::Zstd(Encoder::new(writer, zstd_level)?),
+            None => Self::Stored(writer),
+        })
+    }
+
+    /// Complete the compressed stream and return the underlying writer
+    fn finish(self) -> Result<W> {
+        match self {
+            Self::Stored(writer) => Ok(writer),
+            Self::Zstd(encoder) => Ok(encoder.finish()?),
+        }
+    }
+}
+
+impl<W: Write> Write for ArchiveWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Stored(writer) => writer.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Stored(writer) => writer.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
 /// Archive manager for creating and extracting .tabdiff files
 pub struct ArchiveManager;
 
@@ -17,70 +101,167 @@ impl ArchiveManager {
         archive_path: P,
         files: &[(String, Vec<u8>)], // (filename, content)
     ) -> Result<()> {
-        Self::create_archive_with_progress(archive_path, files, None)
+        Self::create_archive_with_progress(archive_path, files, CompressionLevel::default(), None)
     }
 
     /// Create a compressed archive from multiple files with optional progress callback
     pub fn create_archive_with_progress<P: AsRef<Path>>(
         archive_path: P,
         files: &[(String, Vec<u8>)], // (filename, content)
-        progress_callback: Option<&dyn Fn(u64, u64, &str)>, // (processed, total, message)
+        level: CompressionLevel,
+        progress_callback: Option<ProgressCallback>,
     ) -> Result<()> {
         let archive_file = File::create(archive_path)?;
         
         // Calculate total size for progress
         let total_size: u64 = files.iter().map(|(_, content)| content.len() as u64).sum();
         
-        // Create zstd encoder
-        let mut encoder = Encoder::new(archive_file, 3)?; // Compression level 3
+        let mut encoder = ArchiveWriter::new(BufWriter::new(archive_file), level)?;
+        Self::append_files(&mut encoder, files, progress_callback)?;
+        encoder.finish()?.flush()?;
         
-        // Create tar builder
-        {
-            let mut tar_builder = Builder::new(&mut encoder);
-            
-            let mut processed = 0u64;
-            
-            for (filename, content) in files {
-                let mut header = tar::Header::new_gnu();
-                header.set_size(content.len() as u64);
-                header.set_mode(0o644);
-                header.set_cksum();
-                
-                tar_builder.append_data(&mut header, filename, content.as_slice())?;
-                
-                processed += content.len() as u64;
-                
-                // Report progress if callback provided
-                if let Some(callback) = progress_callback {
-                    callback(processed, total_size, "Creating archive");
-                }
+        // Final progress update
+        if let Some(callback) = progress_callback {
+            callback(total_size, total_size, "Archive created");
+        }
+        
+        Ok(())
+    }
+
+    /// Create a compressed archive, copying large entries from readers instead of memory
+    ///
+    /// Each stream is copied into the archive in small chunks, so only the in-memory `files`
+    /// are ever fully buffered.
+    pub fn create_archive_streaming<P: AsRef<Path>, R: Read>(
+        archive_path: P,
+        files: &[(String, Vec<u8>)], // (filename, content)
+        streams: Vec<StreamEntry<R>>,
+        level: CompressionLevel,
+        progress_callback: Option<ProgressCallback>,
+    ) -> Result<()> {
+        let archive_file = File::create(archive_path)?;
+        
+        let total_size: u64 = files.iter().map(|(_, content)| content.len() as u64).sum::<u64>()
+            + streams.iter().map(|stream| stream.size).sum::<u64>();
+        
+        let mut encoder = ArchiveWriter::new(BufWriter::new(archive_file), level)?;
+        let mut tar_builder = Builder::new(&mut encoder);
+        let mut processed = 0u64;
+        
+        for (filename, content) in files {
+            Self::append_entry(&mut tar_builder, filename, content.len() as u64, content.as_slice())?;
+            processed += content.len() as u64;
+            if let Some(callback) = progress_callback {
+                callback(processed, total_size, "Creating archive");
             }
-            
-            tar_builder.finish()?;
-        } // tar_builder is dropped here, releasing the borrow
+        }
         
-        encoder.finish()?;
+        for stream in streams {
+            Self::append_entry(&mut tar_builder, &stream.name, stream.size, stream.reader.take(stream.size))?;
+            processed += stream.size;
+            if let Some(callback) = progress_callback {
+                callback(processed, total_size, "Creating archive");
+            }
+        }
+        
+        tar_builder.finish()?;
+        drop(tar_builder);
+        encoder.finish()?.flush()?;
         
-        // Final progress update
         if let Some(callback) = progress_callback {
             callback(total_size, total_size, "Archive created");
         }
         
         Ok(())
     }
+
+    /// Create a compressed archive in memory
+    pub fn create_archive_bytes(files: &[(String, Vec<u8>)], level: CompressionLevel) -> Result<Vec<u8>> {
+        let mut encoder = ArchiveWriter::new(Vec::new(), level)?;
+        Self::append_files(&mut encoder, files, None)?;
+        encoder.finish()
+    }
+
+    /// Read an archive's tar stream, decompressing it unless it was stored uncompressed
+    fn decompress<'a, R: BufRead + 'a>(mut reader: R) -> Result<Box<dyn Read + 'a>> {
+        if reader.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+            Ok(Box::new(Decoder::with_buffer(reader)?))
+        } else {
+            Ok(Box::new(reader))
+        }
+    }
+
+    /// Write files as tar entries
+    fn append_files<W: Write>(
+        writer: W,
+        files: &[(String, Vec<u8>)],
+        progress_callback: Option<ProgressCallback>,
+    ) -> Result<()> {
+        let total_size: u64 = files.iter().map(|(_, content)| content.len() as u64).sum();
+        let mut tar_builder = Builder::new(writer);
+        
+        let mut processed = 0u64;
+        
+        for (filename, content) in files {
+            Self::append_entry(&mut tar_builder, filename, content.len() as u64, content.as_slice())?;
+            
+            processed += content.len() as u64;
+            
+            // Report progress if callback provided
+            if let Some(callback) = progress_callback {
+                callback(processed, total_size, "Creating archive");
+            }
+        }
+        
+        tar_builder.finish()?;
+        Ok(())
+    }
+
+    /// Write one tar entry of `size` bytes read from `content`
+    fn append_entry<W: Write, R: Read>(
+        tar_builder: &mut Builder<W>,
+        filename: &str,
+        size: u64,
+        content: R,
+    ) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_cksum();
+        
+        tar_builder.append_data(&mut header, filename, content)?;
+        Ok(())
+    }
     
-    /// Extract files from a compressed archive
+    /// Extract files from a compressed archive, resolving payload references
+    ///
+    /// Referenced content is read from the named snapshot's archive in the same directory.
     pub fn extract_archive<P: AsRef<Path>>(
         archive_path: P,
     ) -> Result<Vec<(String, Vec<u8>)>> {
+        let archive_path = archive_path.as_ref();
+        let files = Self::extract_archive_with_progress(archive_path)?;
+        Self::resolve_references(files, |snapshot| {
+            Self::extract_archive_entries(archive_path.with_file_name(format!("{}.tabdiff", snapshot)))
+        })
+    }
+
+    /// Extract files exactly as stored, leaving payload references unresolved
+    pub fn extract_archive_entries<P: AsRef<Path>>(
+        archive_path: P,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let archive_file = File::open(archive_path)?;
+        Self::read_entries(Self::decompress(BufReader::new(archive_file))?)
+    }
+
+    fn extract_archive_with_progress(archive_path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
         let archive_file = File::open(archive_path)?;
         
         // Get file size for progress
         let file_size = archive_file.metadata()?.len();
         let progress = create_file_progress(file_size, "Extracting archive");
         
-        // Create zstd decoder
-        let mut decoder = Decoder::new(archive_file)?;
+        let mut decoder = Self::decompress(BufReader::new(archive_file))?;
         
         // Create tar archive
         let mut archive = Archive::new(&mut decoder);
@@ -106,12 +287,75 @@ impl ArchiveManager {
         Ok(files)
     }
     
+    /// Extract files from a compressed archive held in memory, leaving payload references unresolved
+    pub fn extract_archive_bytes(archive: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+        Self::read_entries(Self::decompress(archive)?)
+    }
+
+    /// Read every tar entry of a decompressed archive into memory
+    fn read_entries<R: Read>(mut decoder: R) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut archive = Archive::new(&mut decoder);
+        
+        let mut files = Vec::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.push((path, content));
+        }
+        
+        Ok(files)
+    }
+    
+    /// Entry that stands in for `name`, whose content is stored as described by `reference`
+    pub fn reference_entry(name: &str, reference: &PayloadReference) -> Result<(String, Vec<u8>)> {
+        Ok((format!("{}{}", name, REFERENCE_SUFFIX), serde_json::to_vec(reference)?))
+    }
+
+    /// Name of the entry `entry` stands in for and where its content is stored, if it is a reference
+    pub fn parse_reference(entry: &(String, Vec<u8>)) -> Result<Option<(String, PayloadReference)>> {
+        match entry.0.strip_suffix(REFERENCE_SUFFIX) {
+            Some(name) => Ok(Some((name.to_string(), serde_json::from_slice(&entry.1)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace reference entries with the content they point at
+    ///
+    /// `load` returns the stored entries of a snapshot's archive. Content is checked against the
+    /// hash recorded in the reference, so a payload that was changed or removed is an error.
+    pub fn resolve_references<F>(files: Vec<(String, Vec<u8>)>, mut load: F) -> Result<Vec<(String, Vec<u8>)>>
+    where
+        F: FnMut(&str) -> Result<Vec<(String, Vec<u8>)>>,
+    {
+        let mut resolved = Vec::with_capacity(files.len());
+        for entry in files {
+            let Some((name, reference)) = Self::parse_reference(&entry)? else {
+                resolved.push(entry);
+                continue;
+            };
+            let content = load(&reference.snapshot)?
+                .into_iter()
+                .find(|(path, _)| *path == name)
+                .map(|(_, content)| content)
+                .filter(|content| blake3::hash(content).to_hex().as_str() == reference.hash)
+                .ok_or_else(|| TabdiffError::archive(format!(
+                    "{} is shared with snapshot '{}', whose archive no longer holds it",
+                    name, reference.snapshot
+                )))?;
+            resolved.push((name, content));
+        }
+        Ok(resolved)
+    }
+    
     /// List files in an archive without extracting
     pub fn list_archive_contents<P: AsRef<Path>>(
         archive_path: P,
     ) -> Result<Vec<ArchiveEntry>> {
         let archive_file = File::open(archive_path)?;
-        let mut decoder = Decoder::new(archive_file)?;
+        let mut decoder = Self::decompress(BufReader::new(archive_file))?;
         let mut archive = Archive::new(&mut decoder);
         
         let mut entries = Vec::new();
@@ -132,28 +376,53 @@ impl ArchiveManager {
         Ok(entries)
     }
     
-    /// Extract a single file from archive
+    /// Extract a single file from archive, resolving a payload reference
     pub fn extract_file<P: AsRef<Path>>(
         archive_path: P,
         filename: &str,
     ) -> Result<Option<Vec<u8>>> {
+        Ok(Self::extract_files(archive_path, &[filename])?.into_iter().next().map(|(_, content)| content))
+    }
+
+    /// Extract only the named files, stopping once all of them have been read
+    ///
+    /// Other entries are skipped without being buffered, and nothing after the last wanted entry
+    /// is decompressed; the row data is written last, so reading `schema.json` never touches it.
+    /// Files that are missing from the archive are left out of the result.
+    pub fn extract_files<P: AsRef<Path>>(
+        archive_path: P,
+        names: &[&str],
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let archive_path = archive_path.as_ref();
         let archive_file = File::open(archive_path)?;
-        let mut decoder = Decoder::new(archive_file)?;
+        let mut decoder = Self::decompress(BufReader::new(archive_file))?;
         let mut archive = Archive::new(&mut decoder);
-        
+
+        let mut pending: Vec<&str> = names.to_vec();
+        let mut files = Vec::new();
+        if pending.is_empty() {
+            return Ok(files);
+        }
         for entry in archive.entries()? {
             let mut entry = entry?;
-            let path_buf = entry.path()?.to_path_buf();
-            let path = path_buf.to_string_lossy();
-            
-            if path == filename {
-                let mut content = Vec::new();
-                entry.read_to_end(&mut content)?;
-                return Ok(Some(content));
+            let path = entry.path()?.to_string_lossy().to_string();
+            let name = path.strip_suffix(REFERENCE_SUFFIX).unwrap_or(&path);
+            let Some(position) = pending.iter().position(|wanted| *wanted == name) else {
+                continue;
+            };
+            pending.swap_remove(position);
+
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.push((path, content));
+            if pending.is_empty() {
+                break;
             }
         }
-        
-        Ok(None)
+
+        Self::resolve_references(files, |snapshot| {
+            Self::extract_files(archive_path.with_file_name(format!("{}.tabdiff", snapshot)), names)
+        })
     }
     
     /// Check if archive exists and is valid
@@ -194,6 +463,94 @@ impl ArchiveManager {
     }
 }
 
+/// Archive entry whose content is copied from a reader of known length
+pub struct StreamEntry<R> {
+    pub name: String,
+    pub size: u64,
+    pub reader: R,
+}
+
+/// Archive entry written to a temporary file so it can be streamed into an archive later
+///
+/// Tar headers need the entry size up front, so content produced incrementally is spooled to
+/// disk first rather than collected in memory. The file is removed when the entry is dropped.
+pub struct SpooledEntry {
+    name: String,
+    path: PathBuf,
+    size: u64,
+}
+
+impl SpooledEntry {
+    /// Spool the output of `write` as the content of entry `name`
+    pub fn write_with<F>(name: &str, write: F) -> Result<Self>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> Result<()>,
+    {
+        let path = std::env::temp_dir().join(format!(
+            "tabdiff-spool-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        let mut entry = Self {
+            name: name.to_string(),
+            path,
+            size: 0,
+        };
+
+        let mut writer = BufWriter::new(File::create(&entry.path)?);
+        write(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        entry.size = std::fs::metadata(&entry.path)?.len();
+
+        Ok(entry)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Open the spooled content for streaming into an archive
+    pub fn stream(&self) -> Result<StreamEntry<BufReader<File>>> {
+        Ok(StreamEntry {
+            name: self.name.clone(),
+            size: self.size,
+            reader: BufReader::new(File::open(&self.path)?),
+        })
+    }
+
+    /// blake3 hash of the spooled content, identifying it for deduplication
+    pub fn content_hash(&self) -> Result<String> {
+        let mut hasher = blake3::Hasher::new();
+        std::io::copy(&mut BufReader::new(File::open(&self.path)?), &mut hasher)?;
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    /// Read the spooled content into memory, for destinations that only accept bytes
+    pub fn read_to_vec(&self) -> Result<Vec<u8>> {
+        Ok(std::fs::read(&self.path)?)
+    }
+}
+
+impl Drop for SpooledEntry {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Where the content of an entry stored once for several archives is kept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadReference {
+    /// Snapshot whose archive holds the content
+    pub snapshot: String,
+    /// blake3 hash of the content
+    pub hash: String,
+}
+
 /// Information about a file in an archive
 #[derive(Debug, Clone)]
 pub struct ArchiveEntry {
@@ -249,6 +606,28 @@ mod tests {
         assert_eq!(file2.1, b"{\"test\": true}");
     }
     
+    #[test]
+    fn test_extract_files_reads_only_wanted_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("test.tabdiff");
+        let files = vec![
+            ("metadata.json".to_string(), b"{}".to_vec()),
+            ("schema.json".to_string(), b"{\"columns\": []}".to_vec()),
+            ("data.parquet".to_string(), vec![b'x'; 64 * 1024]),
+        ];
+        ArchiveManager::create_archive_with_progress(&archive_path, &files, CompressionLevel::parse("0").unwrap(), None).unwrap();
+
+        // Cut the archive inside data.parquet: entries before it are still readable
+        let archive = std::fs::read(&archive_path).unwrap();
+        std::fs::write(&archive_path, &archive[..archive.len() / 2]).unwrap();
+
+        let extracted = ArchiveManager::extract_files(&archive_path, &["schema.json", "metadata.json"]).unwrap();
+        let names: Vec<&str> = extracted.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["metadata.json", "schema.json"]);
+        assert_eq!(ArchiveManager::extract_file(&archive_path, "schema.json").unwrap().unwrap(), b"{\"columns\": []}");
+        assert!(ArchiveManager::extract_files(&archive_path, &[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_list_archive_contents() {
         let temp_dir = TempDir::new().unwrap();
@@ -288,4 +667,122 @@ mod tests {
         let missing = ArchiveManager::extract_file(&archive_path, "missing.txt").unwrap();
         assert!(missing.is_none());
     }
+
+    #[test]
+    fn test_compression_levels_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let content: Vec<u8> = (0..200_000u32).flat_map(|i| format!("{},row {}\n", i % 97, i % 13).into_bytes()).collect();
+        let files = vec![("data.parquet".to_string(), content.clone())];
+        
+        let mut sizes = Vec::new();
+        for level in ["0", "1", "9"] {
+            let archive_path = temp_dir.path().join(format!("level{}.tar.zst", level));
+            let level = CompressionLevel::parse(level).unwrap();
+            ArchiveManager::create_archive_with_progress(&archive_path, &files, level, None).unwrap();
+            sizes.push(std::fs::metadata(&archive_path).unwrap().len());
+            
+            let extracted = ArchiveManager::extract_archive(&archive_path).unwrap();
+            assert_eq!(extracted, files);
+        }
+        // Level 0 stores the tar stream as is
+        assert!(sizes[0] > content.len() as u64);
+        assert!(sizes[2] <= sizes[1] && sizes[1] < sizes[0], "sizes: {:?}", sizes);
+        
+        let stored = ArchiveManager::create_archive_bytes(&files, CompressionLevel::parse("0").unwrap()).unwrap();
+        assert_eq!(ArchiveManager::extract_archive_bytes(&stored).unwrap(), files);
+        assert!(CompressionLevel::parse("10").is_err());
+    }
+
+    /// Reader generating `remaining` bytes on demand that records how it was read
+    struct CountingReader {
+        remaining: u64,
+        reads: std::rc::Rc<std::cell::Cell<usize>>,
+        largest_read: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.remaining as usize);
+            buf[..len].iter_mut().for_each(|b| *b = b'x');
+            self.remaining -= len as u64;
+            self.reads.set(self.reads.get() + 1);
+            self.largest_read.set(self.largest_read.get().max(len));
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_create_archive_streaming_reads_incrementally() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("test.tar.zst");
+        let size = 32 * 1024 * 1024;
+        let reads = std::rc::Rc::new(std::cell::Cell::new(0));
+        let largest_read = std::rc::Rc::new(std::cell::Cell::new(0));
+        
+        let files = vec![("metadata.json".to_string(), b"{}".to_vec())];
+        let stream = StreamEntry {
+            name: "data.parquet".to_string(),
+            size,
+            reader: CountingReader { remaining: size, reads: reads.clone(), largest_read: largest_read.clone() },
+        };
+        ArchiveManager::create_archive_streaming(&archive_path, &files, vec![stream], CompressionLevel::default(), None).unwrap();
+        
+        // The entry was copied in bounded chunks, never buffered whole
+        assert!(reads.get() > 1);
+        assert!(largest_read.get() <= 1024 * 1024, "read {} bytes at once", largest_read.get());
+        
+        let entries = ArchiveManager::list_archive_contents(&archive_path).unwrap();
+        assert_eq!(entries.len(), 2);
+        let data = entries.iter().find(|e| e.path == "data.parquet").unwrap();
+        assert_eq!(data.size, size);
+    }
+    
+    #[test]
+    fn test_spooled_entry_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("test.tar.zst");
+        
+        let spooled = SpooledEntry::write_with("data.parquet", |writer| {
+            writer.write_all(b"streamed rows")?;
+            Ok(())
+        }).unwrap();
+        assert_eq!(spooled.size(), 13);
+        
+        ArchiveManager::create_archive_streaming(&archive_path, &[], vec![spooled.stream().unwrap()], CompressionLevel::default(), None).unwrap();
+        let content = ArchiveManager::extract_file(&archive_path, "data.parquet").unwrap();
+        assert_eq!(content.unwrap(), b"streamed rows");
+        
+        let path = spooled.path.clone();
+        drop(spooled);
+        assert!(!path.exists());
+    }
+    
+    #[test]
+    fn test_extract_archive_resolves_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let holder_path = temp_dir.path().join("v1.tabdiff");
+        let referrer_path = temp_dir.path().join("v2.tabdiff");
+        
+        let payload = b"shared rows".to_vec();
+        ArchiveManager::create_archive(&holder_path, &[("data.parquet".to_string(), payload.clone())]).unwrap();
+        let reference = PayloadReference {
+            snapshot: "v1".to_string(),
+            hash: blake3::hash(&payload).to_hex().to_string(),
+        };
+        let files = vec![
+            ("schema.json".to_string(), b"{}".to_vec()),
+            ArchiveManager::reference_entry("data.parquet", &reference).unwrap(),
+        ];
+        ArchiveManager::create_archive(&referrer_path, &files).unwrap();
+        
+        // Stored entries keep the reference; extraction replaces it with the holder's content
+        let stored = ArchiveManager::extract_archive_entries(&referrer_path).unwrap();
+        assert_eq!(stored[1].0, "data.parquet.ref");
+        let extracted = ArchiveManager::extract_archive(&referrer_path).unwrap();
+        assert_eq!(extracted[1], ("data.parquet".to_string(), payload));
+        
+        // A holder whose content changed no longer satisfies the reference
+        ArchiveManager::create_archive(&holder_path, &[("data.parquet".to_string(), b"other rows".to_vec())]).unwrap();
+        assert!(ArchiveManager::extract_archive(&referrer_path).is_err());
+    }
 }
@@ -0,0 +1,122 @@
+//! Data-quality rules checked by `snapshot --assert` before a snapshot is written
+//!
+//! Each rule is measured by one DuckDB query over the loaded rows, so bad data is rejected
+//! before any row is hashed or archived.
+
+use crate::sql::quote_identifier;
+use std::fmt;
+
+/// One `--assert` rule, parsed from "kind=argument"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataAssertion {
+    /// No row has a NULL in this column
+    NoNulls(String),
+    /// No two rows share the values of these columns
+    Unique(Vec<String>),
+    /// At least this many rows
+    RowCountMin(u64),
+    /// At most this many rows
+    RowCountMax(u64),
+}
+
+impl DataAssertion {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let (kind, argument) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid assertion: '{}'. Use rule=argument, e.g. no-nulls=id", s))?;
+        let argument = argument.trim();
+        if argument.is_empty() {
+            return Err(format!("Invalid assertion: '{}'. The argument must not be empty", s));
+        }
+        let count = || argument.parse::<u64>()
+            .map_err(|_| format!("Invalid assertion: '{}'. The row count must be a non-negative integer", s));
+
+        match kind.trim().to_lowercase().as_str() {
+            "no-nulls" => Ok(Self::NoNulls(argument.to_string())),
+            "unique" => Ok(Self::Unique(argument.split(',').map(|column| column.trim().to_string()).collect())),
+            "row-count-min" => Ok(Self::RowCountMin(count()?)),
+            "row-count-max" => Ok(Self::RowCountMax(count()?)),
+            other => Err(format!(
+                "Invalid assertion rule: '{}'. Use no-nulls, unique, row-count-min or row-count-max",
+                other
+            )),
+        }
+    }
+
+    /// Columns the rule reads
+    pub fn columns(&self) -> &[String] {
+        match self {
+            Self::NoNulls(column) => std::slice::from_ref(column),
+            Self::Unique(columns) => columns,
+            Self::RowCountMin(_) | Self::RowCountMax(_) => &[],
+        }
+    }
+
+    /// Query over `source` returning the one number the rule is judged by
+    pub fn measure_sql(&self, source: &str) -> String {
+        match self {
+            Self::NoNulls(column) => format!("SELECT COUNT(*) FROM {} WHERE {} IS NULL", source, quote_identifier(column)),
+            Self::Unique(columns) => {
+                let key = columns.iter().map(|column| quote_identifier(column)).collect::<Vec<_>>().join(", ");
+                format!(
+                    "SELECT CAST(COALESCE(SUM(n - 1), 0) AS BIGINT) FROM (SELECT COUNT(*) AS n FROM {} GROUP BY {} HAVING COUNT(*) > 1)",
+                    source, key
+                )
+            }
+            Self::RowCountMin(_) | Self::RowCountMax(_) => format!("SELECT COUNT(*) FROM {}", source),
+        }
+    }
+
+    /// Description of the violation `measure` amounts to, or None when the rule holds
+    pub fn violation(&self, measure: u64) -> Option<String> {
+        let rows = |count: u64| if count == 1 { "1 row".to_string() } else { format!("{} rows", count) };
+        match self {
+            Self::NoNulls(column) if measure > 0 => Some(format!("{}: {} with NULL {}", self, rows(measure), column)),
+            Self::Unique(columns) if measure > 0 => Some(format!("{}: {} duplicating the {} of an earlier row", self, rows(measure), columns.join(", "))),
+            Self::RowCountMin(min) if measure < *min => Some(format!("{}: only {}", self, rows(measure))),
+            Self::RowCountMax(max) if measure > *max => Some(format!("{}: {}", self, rows(measure))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DataAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoNulls(column) => write!(f, "no-nulls={}", column),
+            Self::Unique(columns) => write!(f, "unique={}", columns.join(",")),
+            Self::RowCountMin(min) => write!(f, "row-count-min={}", min),
+            Self::RowCountMax(max) => write!(f, "row-count-max={}", max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assertions() {
+        assert_eq!(DataAssertion::parse("no-nulls=id").unwrap(), DataAssertion::NoNulls("id".to_string()));
+        assert_eq!(
+            DataAssertion::parse("unique=region, id").unwrap(),
+            DataAssertion::Unique(vec!["region".to_string(), "id".to_string()])
+        );
+        assert_eq!(DataAssertion::parse("row-count-min=1000").unwrap(), DataAssertion::RowCountMin(1000));
+        assert!(DataAssertion::parse("row-count-min=many").is_err());
+        assert!(DataAssertion::parse("not-empty=id").is_err());
+        assert!(DataAssertion::parse("no-nulls").is_err());
+        assert!(DataAssertion::parse("no-nulls=").is_err());
+    }
+
+    #[test]
+    fn test_violation_descriptions() {
+        let no_nulls = DataAssertion::NoNulls("id".to_string());
+        assert_eq!(no_nulls.violation(0), None);
+        assert_eq!(no_nulls.violation(2).unwrap(), "no-nulls=id: 2 rows with NULL id");
+
+        let min = DataAssertion::RowCountMin(10);
+        assert_eq!(min.violation(10), None);
+        assert_eq!(min.violation(1).unwrap(), "row-count-min=10: only 1 row");
+    }
+}
@@ -0,0 +1,124 @@
+//! Cooperative cancellation of long-running snapshot and diff operations
+//!
+//! Hashing, extraction and change detection check a `CancellationToken` as they go and stop
+//! with `TabdiffError::Cancelled` once it is set. The CLI sets the process-wide token on
+//! Ctrl-C; library users can hand their own token to `DataProcessor`, `SnapshotCreator` or
+//! `DetectionOptions` and cancel it from another thread.
+
+use crate::error::{Result, TabdiffError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+static PROCESS_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+/// Shared flag that asks running operations to stop; clones observe the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token shared by the whole process, set by the Ctrl-C handler; the default everywhere
+    pub fn global() -> Self {
+        PROCESS_TOKEN.get_or_init(Self::new).clone()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Clear the flag so the next operation can run, e.g. between iterations of a watch loop
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(Cancelled)` once the token is set, for checks inside loops
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(TabdiffError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Cancel the process-wide token on the first SIGINT; a second SIGINT exits immediately
+///
+/// Watch loops poll the same token to stop between iterations.
+pub fn install_interrupt_handler() {
+    let _ = CancellationToken::global();
+
+    #[cfg(unix)]
+    {
+        extern "C" fn on_interrupt(_signal: libc::c_int) {
+            if let Some(token) = PROCESS_TOKEN.get() {
+                if token.0.swap(true, Ordering::SeqCst) {
+                    // SAFETY: _exit is async-signal-safe
+                    unsafe { libc::_exit(130) };
+                }
+            }
+        }
+
+        let handler: extern "C" fn(libc::c_int) = on_interrupt;
+        // SAFETY: the handler only touches an already initialized atomic and calls _exit
+        unsafe {
+            libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+        }
+    }
+}
+
+/// Reader that fails once `token` is cancelled, so copying a large stream stops part-way
+pub struct CancellableReader<R> {
+    inner: R,
+    token: CancellationToken,
+}
+
+impl<R> CancellableReader<R> {
+    pub fn new(inner: R, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for CancellableReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.token.is_cancelled() {
+            return Err(std::io::Error::other("operation cancelled"));
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(clone.check().is_ok());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(matches!(clone.check(), Err(TabdiffError::Cancelled)));
+        assert!(!CancellationToken::new().is_cancelled());
+    }
+
+    #[test]
+    fn test_cancellable_reader_stops_reading() {
+        let token = CancellationToken::new();
+        let mut reader = CancellableReader::new(&b"abcdef"[..], token.clone());
+        let mut buf = [0u8; 3];
+        assert_eq!(reader.read(&mut buf).unwrap(), 3);
+
+        token.cancel();
+        assert!(reader.read(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Render the snapshot chain as a Graphviz DOT or Mermaid graph
+//!
+//! Nodes are snapshots labeled with their sequence number and row count; edges run from each
+//! parent to its children. Snapshots whose delta can reconstruct their parent are highlighted.
+
+use crate::snapshot::SnapshotMetadata;
+
+/// Graph description printed by `chain --graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphFormat {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            _ => Err(format!("Invalid graph format: {}. Use 'dot' or 'mermaid'", s)),
+        }
+    }
+
+    /// Render the parent links between `snapshots`
+    pub fn render(&self, snapshots: &[SnapshotMetadata]) -> String {
+        match self {
+            Self::Dot => dot_graph(snapshots),
+            Self::Mermaid => mermaid_graph(snapshots),
+        }
+    }
+}
+
+/// Node label, e.g. "v2" / "seq 1, 150 rows"
+fn label_lines(snapshot: &SnapshotMetadata) -> [String; 2] {
+    let rows = if snapshot.row_count == 1 { "row" } else { "rows" };
+    [snapshot.name.clone(), format!("seq {}, {} {}", snapshot.sequence_number, snapshot.row_count, rows)]
+}
+
+/// (parent index, child index) of every parent link whose parent is in `snapshots`
+fn parent_edges(snapshots: &[SnapshotMetadata]) -> Vec<(usize, usize)> {
+    snapshots.iter().enumerate()
+        .filter_map(|(child, snapshot)| {
+            let parent = snapshot.parent_snapshot.as_ref()?;
+            let parent = snapshots.iter().position(|candidate| &candidate.name == parent)?;
+            Some((parent, child))
+        })
+        .collect()
+}
+
+/// DOT digraph; reconstructing snapshots are filled green and their parent edges drawn solid
+pub fn dot_graph(snapshots: &[SnapshotMetadata]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut lines = vec![
+        "digraph snapshots {".to_string(),
+        "    rankdir=LR;".to_string(),
+        "    node [shape=box, style=rounded];".to_string(),
+    ];
+    for snapshot in snapshots {
+        let [name, details] = label_lines(snapshot);
+        let style = if snapshot.can_reconstruct_parent { ", style=\"rounded,filled\", fillcolor=palegreen" } else { "" };
+        lines.push(format!("    \"{}\" [label=\"{}\\n{}\"{}];", escape(&snapshot.name), escape(&name), escape(&details), style));
+    }
+    for (parent, child) in parent_edges(snapshots) {
+        let style = if snapshots[child].can_reconstruct_parent { "color=forestgreen" } else { "color=gray, style=dashed" };
+        lines.push(format!("    \"{}\" -> \"{}\" [{}];", escape(&snapshots[parent].name), escape(&snapshots[child].name), style));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Mermaid flowchart; nodes get positional ids since snapshot names may not be valid ones
+pub fn mermaid_graph(snapshots: &[SnapshotMetadata]) -> String {
+    let escape = |s: &str| s.replace('"', "#quot;");
+    let mut lines = vec!["flowchart LR".to_string()];
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let [name, details] = label_lines(snapshot);
+        lines.push(format!("    s{}[\"{}<br/>{}\"]", index, escape(&name), escape(&details)));
+    }
+    for (parent, child) in parent_edges(snapshots) {
+        let arrow = if snapshots[child].can_reconstruct_parent { "==>" } else { "-.->" };
+        lines.push(format!("    s{} {} s{}", parent, arrow, child));
+    }
+    let reconstructing: Vec<String> = snapshots.iter().enumerate()
+        .filter(|(_, snapshot)| snapshot.can_reconstruct_parent)
+        .map(|(index, _)| format!("s{}", index))
+        .collect();
+    if !reconstructing.is_empty() {
+        lines.push("    classDef reconstructs fill:#d4f7d4,stroke:#228b22".to_string());
+        lines.push(format!("    class {} reconstructs", reconstructing.join(",")));
+    }
+    lines.join("\n")
+}
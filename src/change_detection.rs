@@ -3,17 +3,23 @@
 use crate::error::Result;
 use crate::hash::ColumnInfo;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Comprehensive change detection result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChangeDetectionResult {
     pub schema_changes: SchemaChanges,
     pub row_changes: RowChanges,
+    /// Values in `--coerce` columns that could not be parsed and were compared as-is
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coercion_failures: Vec<CoercionFailure>,
+    /// Set when so many rows share a hash that content-based row matching is unreliable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_quality: Option<crate::hash::HashQualityMetrics>,
 }
 
 /// Schema-level changes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaChanges {
     pub column_order: Option<ColumnOrderChange>,
     pub columns_added: Vec<ColumnAddition>,
@@ -29,6 +35,30 @@ pub struct ColumnOrderChange {
     pub after: Vec<String>,
 }
 
+impl ColumnOrderChange {
+    /// Columns present on both sides whose position changed, in their new order
+    pub fn moves(&self) -> Vec<ColumnMove> {
+        self.after.iter().enumerate()
+            .filter_map(|(to, column)| {
+                let from = self.before.iter().position(|name| name == column)?;
+                (from != to).then(|| ColumnMove {
+                    column: column.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect()
+    }
+}
+
+/// One column's move within a column order change (zero-based positions)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMove {
+    pub column: String,
+    pub from: usize,
+    pub to: usize,
+}
+
 /// Column addition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnAddition {
@@ -53,6 +83,9 @@ pub struct ColumnRemoval {
 pub struct ColumnRename {
     pub from: String,
     pub to: String,
+    /// Similarity of the two columns' values when detected by content (None for positional renames)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
 }
 
 /// Type change
@@ -64,7 +97,7 @@ pub struct TypeChange {
 }
 
 /// Row-level changes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RowChanges {
     pub modified: Vec<RowModification>,
     pub added: Vec<RowAddition>,
@@ -83,6 +116,44 @@ pub struct RowModification {
 pub struct CellChange {
     pub before: String,
     pub after: String,
+    /// after − before, set by `add_numeric_delta` when both values are numbers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+    /// Percent change relative to before, set with `delta` unless before is zero
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pct_change: Option<f64>,
+}
+
+impl CellChange {
+    pub fn new(before: impl Into<String>, after: impl Into<String>) -> Self {
+        Self {
+            before: before.into(),
+            after: after.into(),
+            delta: None,
+            pct_change: None,
+        }
+    }
+
+    /// Add the delta and percent change when both values parse as finite numbers
+    ///
+    /// The delta is rounded to the decimal places of the more precise value, so 1.50 → 1.75
+    /// gives 0.25 rather than the float subtraction's rounding error.
+    pub fn add_numeric_delta(&mut self) {
+        let parse = |value: &str| value.trim().parse::<f64>().ok().filter(|n| n.is_finite());
+        if let (Some(before), Some(after)) = (parse(&self.before), parse(&self.after)) {
+            let scale = 10f64.powi(self.delta_decimals() as i32);
+            self.delta = Some(((after - before) * scale).round() / scale);
+            self.pct_change = (before != 0.0).then(|| (after - before) / before.abs() * 100.0);
+        }
+    }
+
+    /// Decimal places of the more precise of the two values, used to show the delta
+    pub fn delta_decimals(&self) -> usize {
+        let decimals = |value: &str| value.trim().split_once('.')
+            .map(|(_, fraction)| fraction.chars().take_while(|c| c.is_ascii_digit()).count())
+            .unwrap_or(0);
+        decimals(&self.before).max(decimals(&self.after))
+    }
 }
 
 /// Row addition
@@ -99,242 +170,1523 @@ pub struct RowRemoval {
     pub data: HashMap<String, String>,
 }
 
+/// Unchanged row (current state of a row that hashed identically)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowUnchanged {
+    pub row_index: u64,
+    pub data: HashMap<String, String>,
+}
 
-/// Change detector for comprehensive analysis
-pub struct ChangeDetector;
+/// Row change counts per value of one column, from `--group-by`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeGroups {
+    pub column: String,
+    /// Groups with the most changed rows first
+    pub groups: Vec<GroupChanges>,
+}
 
-impl ChangeDetector {
-    /// Detect all changes between baseline and current data
-    pub fn detect_changes(
-        baseline_schema: &[ColumnInfo],
-        baseline_data: &[Vec<String>],
-        current_schema: &[ColumnInfo],
-        current_data: &[Vec<String>],
-    ) -> Result<ChangeDetectionResult> {
-        let schema_changes = Self::detect_schema_changes(baseline_schema, current_schema)?;
-        let row_changes = Self::detect_row_changes(
-            baseline_schema,
-            baseline_data,
-            current_schema,
-            current_data,
-        )?;
-        Ok(ChangeDetectionResult {
-            schema_changes,
-            row_changes,
-        })
+/// Changed rows whose group column holds `value`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupChanges {
+    pub value: String,
+    pub modified: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl GroupChanges {
+    /// Number of changed rows in the group
+    pub fn total(&self) -> usize {
+        self.modified + self.added + self.removed
     }
+}
 
-    /// Detect schema changes using position-based comparison
-    fn detect_schema_changes(
-        baseline: &[ColumnInfo],
-        current: &[ColumnInfo],
-    ) -> Result<SchemaChanges> {
-        let baseline_names: Vec<String> = baseline.iter().map(|c| c.name.clone()).collect();
-        let current_names: Vec<String> = current.iter().map(|c| c.name.clone()).collect();
+/// One step that moves the current data back to a snapshot's state
+///
+/// Row indices of restored cells and removed rows refer to the current data; restored rows
+/// carry their index in the snapshot, which is also how `--rows` selects them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RollbackOperation {
+    /// Set a cell back to its snapshot value
+    RestoreCell {
+        row_index: u64,
+        column: String,
+        current: String,
+        value: String,
+    },
+    /// Delete a row that is not in the snapshot
+    RemoveRow {
+        row_index: u64,
+        data: HashMap<String, String>,
+    },
+    /// Re-insert a snapshot row that is missing from the current data
+    RestoreRow {
+        row_index: u64,
+        data: HashMap<String, String>,
+    },
+}
 
-        // Detect column order changes (if column names are reordered)
-        let column_order = if baseline_names != current_names && baseline.len() == current.len() {
-            // Check if it's just a reordering (same columns, different order)
-            let mut baseline_sorted = baseline_names.clone();
-            let mut current_sorted = current_names.clone();
-            baseline_sorted.sort();
-            current_sorted.sort();
-            
-            if baseline_sorted == current_sorted {
-                Some(ColumnOrderChange {
-                    before: baseline_names.clone(),
-                    after: current_names.clone(),
-                })
-            } else {
-                None // Not just reordering, there are additions/removals/renames
+/// Inclusive range of row indices, parsed from "5" or "5-8"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl RowRange {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let parse_index = |value: &str| value.trim().parse::<u64>()
+            .map_err(|_| format!("Invalid row index: '{}'. Use an index like 5 or a range like 5-8", value.trim()));
+        let (start, end) = match s.split_once('-') {
+            Some((start, end)) => (parse_index(start)?, parse_index(end)?),
+            None => {
+                let index = parse_index(s)?;
+                (index, index)
             }
-        } else {
-            None
         };
+        if start > end {
+            return Err(format!("Invalid row range: '{}'. The start must not exceed the end", s));
+        }
+        Ok(Self { start, end })
+    }
 
-        let mut columns_added = Vec::new();
-        let mut columns_removed = Vec::new();
-        let mut columns_renamed = Vec::new();
-        let mut type_changes = Vec::new();
+    pub fn contains(&self, row_index: u64) -> bool {
+        (self.start..=self.end).contains(&row_index)
+    }
+}
 
-        // Handle different column counts (additions/removals)
-        if baseline.len() != current.len() {
-            if current.len() > baseline.len() {
-                // Columns were added at the end
-                for (pos, col) in current.iter().enumerate().skip(baseline.len()) {
-                    columns_added.push(ColumnAddition {
-                        name: col.name.clone(),
-                        data_type: col.data_type.clone(),
-                        position: pos,
-                        nullable: col.nullable,
-                        default_value: None,
-                    });
-                }
-            } else {
-                // Columns were removed from the end
-                for (pos, col) in baseline.iter().enumerate().skip(current.len()) {
-                    columns_removed.push(ColumnRemoval {
-                        name: col.name.clone(),
-                        data_type: col.data_type.clone(),
-                        position: pos,
-                        nullable: col.nullable,
-                    });
-                }
+/// Row and column selection for a partial rollback; an empty list selects everything
+#[derive(Debug, Clone, Copy)]
+pub struct RollbackFilter<'a> {
+    rows: &'a [RowRange],
+    columns: &'a [String],
+}
+
+impl<'a> RollbackFilter<'a> {
+    pub fn new(rows: &'a [RowRange], columns: &'a [String]) -> Self {
+        Self { rows, columns }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.rows.is_empty() || !self.columns.is_empty()
+    }
+
+    pub fn columns(&self) -> &'a [String] {
+        self.columns
+    }
+
+    /// Whether an operation falls inside the selection
+    ///
+    /// Whole-row operations only match when no columns are selected.
+    pub fn matches(&self, operation: &RollbackOperation) -> bool {
+        let row_selected = |row_index: u64| self.rows.is_empty() || self.rows.iter().any(|r| r.contains(row_index));
+        match operation {
+            RollbackOperation::RestoreCell { row_index, column, .. } => {
+                row_selected(*row_index) && (self.columns.is_empty() || self.columns.contains(column))
+            }
+            RollbackOperation::RemoveRow { row_index, .. } | RollbackOperation::RestoreRow { row_index, .. } => {
+                self.columns.is_empty() && row_selected(*row_index)
             }
         }
+    }
 
-        // Compare columns position by position (for common length)
-        let min_len = baseline.len().min(current.len());
-        for pos in 0..min_len {
-            let baseline_col = &baseline[pos];
-            let current_col = &current[pos];
+    pub fn apply(&self, operations: Vec<RollbackOperation>) -> Vec<RollbackOperation> {
+        operations.into_iter().filter(|op| self.matches(op)).collect()
+    }
+}
 
-            // Check for column rename at this position
-            if baseline_col.name != current_col.name {
-                columns_renamed.push(ColumnRename {
-                    from: baseline_col.name.clone(),
-                    to: current_col.name.clone(),
-                });
+impl RollbackOperation {
+    /// Split operations into the groups `rollback --interactive` asks about: the restored cells
+    /// of one row, or a single removed or restored row
+    pub fn group_by_row(operations: Vec<RollbackOperation>) -> Vec<Vec<RollbackOperation>> {
+        let mut groups: Vec<Vec<RollbackOperation>> = Vec::new();
+        for operation in operations {
+            let same_row = match (groups.last().and_then(|group| group.last()), &operation) {
+                (
+                    Some(RollbackOperation::RestoreCell { row_index: previous, .. }),
+                    RollbackOperation::RestoreCell { row_index, .. },
+                ) => previous == row_index,
+                _ => false,
+            };
+            match groups.last_mut() {
+                Some(group) if same_row => group.push(operation),
+                _ => groups.push(vec![operation]),
             }
+        }
+        groups
+    }
 
-            // Check for type change at this position
-            if baseline_col.data_type != current_col.data_type {
-                type_changes.push(TypeChange {
-                    column: current_col.name.clone(), // Use current name in case it was renamed
-                    from: baseline_col.data_type.clone(),
-                    to: current_col.data_type.clone(),
-                });
+    /// Apply operations to the current rows, keeping the current schema
+    ///
+    /// Cells are restored first, then rows removed, then snapshot rows re-inserted at their
+    /// snapshot position (clamped to the end of the data).
+    pub fn apply_all(
+        operations: &[RollbackOperation],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+    ) -> Vec<Vec<String>> {
+        let column_positions: HashMap<&str, usize> = current_schema.iter()
+            .enumerate()
+            .map(|(i, col)| (col.name.as_str(), i))
+            .collect();
+
+        let mut rows = current_data.to_vec();
+        let mut removed = HashSet::new();
+        let mut restored = Vec::new();
+        for operation in operations {
+            match operation {
+                RollbackOperation::RestoreCell { row_index, column, value, .. } => {
+                    if let (Some(row), Some(&i)) = (rows.get_mut(*row_index as usize), column_positions.get(column.as_str())) {
+                        if let Some(cell) = row.get_mut(i) {
+                            *cell = value.clone();
+                        }
+                    }
+                }
+                RollbackOperation::RemoveRow { row_index, .. } => {
+                    removed.insert(*row_index as usize);
+                }
+                RollbackOperation::RestoreRow { row_index, data } => {
+                    let row: Vec<String> = current_schema.iter()
+                        .map(|col| data.get(&col.name).cloned().unwrap_or_default())
+                        .collect();
+                    restored.push((*row_index as usize, row));
+                }
             }
         }
 
-        Ok(SchemaChanges {
-            column_order,
-            columns_added,
-            columns_removed,
-            columns_renamed,
-            type_changes,
-        })
+        let mut rows: Vec<Vec<String>> = rows.into_iter()
+            .enumerate()
+            .filter(|(i, _)| !removed.contains(i))
+            .map(|(_, row)| row)
+            .collect();
+        restored.sort_by_key(|(row_index, _)| *row_index);
+        for (row_index, row) in restored {
+            let position = row_index.min(rows.len());
+            rows.insert(position, row);
+        }
+        rows
     }
+}
 
-    /// Detect row changes using optimized hash-based comparison with intelligent modification detection
-    fn detect_row_changes(
-        baseline_schema: &[ColumnInfo],
-        baseline_data: &[Vec<String>],
-        current_schema: &[ColumnInfo],
-        current_data: &[Vec<String>],
-    ) -> Result<RowChanges> {
-        // Phase 1: Fast hash-based filtering to identify changed rows
-        let hash_computer = crate::hash::HashComputer::new(10000);
-        let baseline_hashes = hash_computer.hash_rows(baseline_data)?;
-        let current_hashes = hash_computer.hash_rows(current_data)?;
-        let comparison = hash_computer.compare_row_hashes(&baseline_hashes, &current_hashes);
-        
-        // Phase 2: Intelligent row classification for changed subset only
-        let (modifications, genuine_additions, genuine_removals) = Self::classify_changed_rows(
-            baseline_schema,
-            baseline_data,
-            current_schema,
-            current_data,
-            &comparison.added_rows,
-            &comparison.removed_rows,
-        )?;
-        
-        // Phase 3: Parallel cell-level analysis for modifications only
-        let detailed_modifications = Self::analyze_modifications_parallel(
-            baseline_schema,
-            baseline_data,
-            current_schema,
-            current_data,
-            &modifications,
-        )?;
-        
-        // Convert results to final format
-        let added = Self::convert_additions_parallel(current_schema, current_data, &genuine_additions)?;
-        let removed = Self::convert_removals_parallel(baseline_schema, baseline_data, &genuine_removals)?;
 
-        Ok(RowChanges {
-            modified: detailed_modifications,
-            added,
-            removed,
-        })
+/// Number of values sampled per column when matching renamed columns by content
+const RENAME_SAMPLE_SIZE: usize = 1000;
+
+/// Minimum value similarity for two columns to be considered a rename
+const RENAME_MIN_CONFIDENCE: f64 = 0.8;
+
+/// Share of common columns that must hold equal values for an added and a removed row to pair
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.5;
+
+/// Maximum number of distinct unparseable values reported per coerced column
+const MAX_COERCION_FAILURES_PER_COLUMN: usize = 10;
+
+/// Maximum number of duplicated keys, and of rows per key, listed when a key is not unique
+const MAX_DUPLICATE_KEY_SAMPLES: usize = 5;
+
+/// Whether two cells both parse as numbers differing by at most `tolerance`
+///
+/// The difference of two parsed decimals carries rounding error, so a difference of exactly
+/// `tolerance` (1.5 vs 1.501 at 0.001) gets a few ulps of slack.
+fn within_float_tolerance(a: &str, b: &str, tolerance: f64) -> bool {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) if a.is_finite() && b.is_finite() => {
+            (a - b).abs() <= tolerance + 4.0 * f64::EPSILON * a.abs().max(b.abs())
+        }
+        _ => false,
     }
+}
 
-    /// Classify changed rows into modifications vs genuine additions/removals
-    fn classify_changed_rows(
-        baseline_schema: &[ColumnInfo],
-        baseline_data: &[Vec<String>],
-        current_schema: &[ColumnInfo],
-        current_data: &[Vec<String>],
-        added_indices: &[u64],
-        removed_indices: &[u64],
-    ) -> Result<(Vec<(u64, u64)>, Vec<u64>, Vec<u64>)> {
-        use rayon::prelude::*;
-        
-        // Early exit if no changes
-        if added_indices.is_empty() && removed_indices.is_empty() {
-            return Ok((Vec::new(), Vec::new(), Vec::new()));
+/// Canonical type a column is normalized to before comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CoerceType {
+    Bool,
+    Integer,
+    Date,
+}
+
+impl CoerceType {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "bool" | "boolean" => Ok(Self::Bool),
+            "int" | "integer" => Ok(Self::Integer),
+            "date" => Ok(Self::Date),
+            _ => Err(format!("Invalid coercion type: {}. Use 'bool', 'integer', or 'date'", s)),
         }
-        
-        // Create column mapping for schema-aware comparison
-        let common_columns = Self::find_common_columns(baseline_schema, current_schema);
-        
-        // Parallel matching: find likely modifications using position and content heuristics
-        let mut modifications = Vec::new();
-        let mut unmatched_added = added_indices.to_vec();
-        let mut unmatched_removed = removed_indices.to_vec();
-        
-        // Strategy 1: Position-based matching (most common case)
-        let position_matches: Vec<_> = removed_indices
-            .par_iter()
-            .filter_map(|&removed_idx| {
-                // Look for an added row at the same position
-                if let Some(added_pos) = added_indices.iter().position(|&added_idx| added_idx == removed_idx) {
-                    Some((removed_idx, added_indices[added_pos]))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Integer => "integer",
+            Self::Date => "date",
+        }
+    }
+
+    /// Canonical form of a value, or None when it does not parse as this type
+    ///
+    /// Empty values are left empty so that missing data never counts as a parse failure.
+    pub fn normalize(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Some(String::new());
+        }
+        match self {
+            Self::Bool => match value.to_lowercase().as_str() {
+                "true" | "t" | "yes" | "y" | "1" => Some("true".to_string()),
+                "false" | "f" | "no" | "n" | "0" => Some("false".to_string()),
+                _ => None,
+            },
+            Self::Integer => {
+                if let Ok(int) = value.parse::<i64>() {
+                    return Some(int.to_string());
+                }
+                // Accept integral floats such as "1.0" written by other tools
+                let float = value.parse::<f64>().ok()?;
+                if float.is_finite() && float.fract() == 0.0 && float.abs() < i64::MAX as f64 {
+                    Some((float as i64).to_string())
                 } else {
                     None
                 }
-            })
-            .collect();
-        
-        // Remove position matches from unmatched lists
-        for &(removed_idx, added_idx) in &position_matches {
-            modifications.push((removed_idx, added_idx));
-            unmatched_removed.retain(|&x| x != removed_idx);
-            unmatched_added.retain(|&x| x != added_idx);
-        }
-        
-        // Strategy 2: Content-based matching for remaining rows (using key columns)
-        if !unmatched_removed.is_empty() && !unmatched_added.is_empty() && !common_columns.is_empty() {
-            let content_matches = Self::find_content_matches_parallel(
-                baseline_data,
-                current_data,
-                &unmatched_removed,
-                &unmatched_added,
-                &common_columns,
-            )?;
-            
-            for &(removed_idx, added_idx) in &content_matches {
-                modifications.push((removed_idx, added_idx));
-                unmatched_removed.retain(|&x| x != removed_idx);
-                unmatched_added.retain(|&x| x != added_idx);
+            }
+            Self::Date => {
+                const DATE_FORMATS: [&str; 6] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d", "%d.%m.%Y", "%m/%d/%Y", "%d %b %Y"];
+                const DATETIME_FORMATS: [&str; 3] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y/%m/%d %H:%M:%S%.f"];
+
+                DATE_FORMATS
+                    .iter()
+                    .find_map(|format| chrono::NaiveDate::parse_from_str(value, format).ok())
+                    .or_else(|| {
+                        DATETIME_FORMATS
+                            .iter()
+                            .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+                            .map(|datetime| datetime.date())
+                    })
+                    .or_else(|| chrono::DateTime::parse_from_rfc3339(value).ok().map(|datetime| datetime.date_naive()))
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+            }
+        }
+    }
+
+    /// Type to compare a column as after its type changed from `from` to `to`, if either is one
+    ///
+    /// The typed side wins, so a column that went from INTEGER to VARCHAR is compared as integers.
+    pub fn for_type_change(from: &str, to: &str) -> Option<Self> {
+        let of = |data_type: &str| match data_type.to_uppercase().as_str() {
+            "BOOLEAN" | "BOOL" => Some(Self::Bool),
+            "TINYINT" | "SMALLINT" | "INTEGER" | "INT" | "BIGINT" | "HUGEINT"
+            | "UTINYINT" | "USMALLINT" | "UINTEGER" | "UBIGINT" | "UHUGEINT" => Some(Self::Integer),
+            "DATE" => Some(Self::Date),
+            _ => None,
+        };
+        match (of(from), of(to)) {
+            (Some(a), Some(b)) => (a == b).then_some(a),
+            (Some(coerce), None) | (None, Some(coerce)) => Some(coerce),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether two values are equal once both are normalized; unparseable values never match
+    pub fn equivalent(&self, a: &str, b: &str) -> bool {
+        match (self.normalize(a), self.normalize(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Coercion rule for one column, parsed from "column:type"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnCoercion {
+    pub column: String,
+    pub to: CoerceType,
+}
+
+impl ColumnCoercion {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let (column, to) = s
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Invalid coercion: '{}'. Use column:type, e.g. active:bool", s))?;
+        let column = column.trim();
+        if column.is_empty() {
+            return Err(format!("Invalid coercion: '{}'. The column name must not be empty", s));
+        }
+        Ok(Self {
+            column: column.to_string(),
+            to: CoerceType::parse(to)?,
+        })
+    }
+}
+
+/// Condition on the before and after values of one modified cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CellCondition {
+    Increased,
+    Decreased,
+    ChangedFrom(String),
+    ChangedTo(String),
+    /// The new value compared to a constant
+    After(Comparison, String),
+}
+
+/// Comparison operator of a `--where` predicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparison {
+    // Two-character operators first so ">=" isn't read as ">"
+    const OPERATORS: [(&'static str, Comparison); 7] = [
+        ("!=", Comparison::Ne),
+        (">=", Comparison::Ge),
+        ("<=", Comparison::Le),
+        ("==", Comparison::Eq),
+        ("=", Comparison::Eq),
+        (">", Comparison::Gt),
+        ("<", Comparison::Lt),
+    ];
+
+    fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            Self::Eq => ordering == Equal,
+            Self::Ne => ordering != Equal,
+            Self::Gt => ordering == Greater,
+            Self::Ge => ordering != Less,
+            Self::Lt => ordering == Less,
+            Self::Le => ordering != Greater,
+        }
+    }
+}
+
+/// Filter on modified rows, parsed from "column op value", e.g. "price decreased" or "status changed-from open"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellPredicate {
+    pub column: String,
+    pub condition: CellCondition,
+}
+
+impl CellPredicate {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        let s = s.trim();
+        let invalid = || format!(
+            "Invalid --where predicate: '{}'. Use 'column op value' with op one of =, !=, >, >=, <, <=, changed-from, changed-to, or 'column increased' / 'column decreased'",
+            s
+        );
+
+        let (column, condition) = if let Some(column) = s.strip_suffix(" increased") {
+            (column, CellCondition::Increased)
+        } else if let Some(column) = s.strip_suffix(" decreased") {
+            (column, CellCondition::Decreased)
+        } else if let Some((column, value)) = s.split_once(" changed-from ") {
+            (column, CellCondition::ChangedFrom(unquote(value)))
+        } else if let Some((column, value)) = s.split_once(" changed-to ") {
+            (column, CellCondition::ChangedTo(unquote(value)))
+        } else {
+            // The earliest operator in the string separates the column from the value
+            let (idx, op, comparison) = Comparison::OPERATORS
+                .iter()
+                .filter_map(|(op, comparison)| s.find(op).map(|idx| (idx, *op, *comparison)))
+                .min_by_key(|(idx, op, _)| (*idx, std::cmp::Reverse(op.len())))
+                .ok_or_else(invalid)?;
+            (&s[..idx], CellCondition::After(comparison, unquote(&s[idx + op.len()..])))
+        };
+
+        let column = column.trim();
+        if column.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            column: column.to_string(),
+            condition,
+        })
+    }
+
+    /// Whether the modification changed this predicate's column in the required way
+    pub fn matches(&self, modification: &RowModification) -> bool {
+        let Some(change) = modification.changes.get(&self.column) else {
+            return false;
+        };
+        match &self.condition {
+            CellCondition::Increased => compare_values(&change.after, &change.before).is_gt(),
+            CellCondition::Decreased => compare_values(&change.after, &change.before).is_lt(),
+            CellCondition::ChangedFrom(value) => compare_values(&change.before, value).is_eq(),
+            CellCondition::ChangedTo(value) => compare_values(&change.after, value).is_eq(),
+            CellCondition::After(comparison, value) => comparison.holds(compare_values(&change.after, value)),
+        }
+    }
+}
+
+/// Strip whitespace and one pair of matching quotes around a predicate value
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+/// Numeric comparison when both values are numbers, otherwise lexicographic
+fn compare_values(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+/// A value in a coerced column that did not parse and was compared as-is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoercionFailure {
+    pub column: String,
+    pub value: String,
+    pub coerce_to: String,
+}
+
+/// How baseline and current rows are paired up before their cells are compared
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RowIdentity {
+    /// Rows with identical content match; changed rows are paired by position, then by similarity
+    #[default]
+    Auto,
+    /// Row N of the baseline is row N of the current data, whatever either contains
+    Positional,
+    /// Rows with equal values in these columns are the same row, wherever they are
+    Key(Vec<String>),
+    /// Rows are identified only by their content: any change is a removal plus an addition
+    ContentHash,
+}
+
+impl RowIdentity {
+    /// Key-based identity when key columns are given, otherwise the default heuristics
+    pub fn from_key_columns(columns: Vec<String>) -> Self {
+        if columns.is_empty() {
+            RowIdentity::Auto
+        } else {
+            RowIdentity::Key(columns)
+        }
+    }
+}
+
+/// How row indices are reported: zero-based positions by default
+///
+/// With `one_based`, rows are numbered from 1, or by the line they start on when that side was
+/// read from a CSV/TSV file. Modified and added rows are numbered on the current side, removed
+/// rows on the baseline side.
+#[derive(Debug, Clone, Default)]
+pub struct RowNumbering {
+    one_based: bool,
+    baseline_lines: Option<Vec<u64>>,
+    current_lines: Option<Vec<u64>>,
+}
+
+impl RowNumbering {
+    pub fn one_based() -> Self {
+        Self {
+            one_based: true,
+            ..Self::default()
+        }
+    }
+
+    /// Source line of each baseline row, from `DataProcessor::source_line_numbers`
+    pub fn with_baseline_lines(mut self, lines: Option<Vec<u64>>) -> Self {
+        self.baseline_lines = lines;
+        self
+    }
+
+    /// Source line of each current row, from `DataProcessor::source_line_numbers`
+    pub fn with_current_lines(mut self, lines: Option<Vec<u64>>) -> Self {
+        self.current_lines = lines;
+        self
+    }
+
+    pub fn baseline(&self, row_index: u64) -> u64 {
+        self.number(self.baseline_lines.as_deref(), row_index)
+    }
+
+    pub fn current(&self, row_index: u64) -> u64 {
+        self.number(self.current_lines.as_deref(), row_index)
+    }
+
+    fn number(&self, lines: Option<&[u64]>, row_index: u64) -> u64 {
+        if !self.one_based {
+            return row_index;
+        }
+        lines
+            .and_then(|lines| lines.get(row_index as usize).copied())
+            .unwrap_or(row_index + 1)
+    }
+
+    /// Copy of `changes` with every row index renumbered
+    pub fn apply(&self, changes: &ChangeDetectionResult) -> ChangeDetectionResult {
+        let mut numbered = changes.clone();
+        if !self.one_based {
+            return numbered;
+        }
+        for modification in &mut numbered.row_changes.modified {
+            modification.row_index = self.current(modification.row_index);
+        }
+        for addition in &mut numbered.row_changes.added {
+            addition.row_index = self.current(addition.row_index);
+        }
+        for removal in &mut numbered.row_changes.removed {
+            removal.row_index = self.baseline(removal.row_index);
+        }
+        numbered
+    }
+}
+
+/// Options controlling how rows and columns are matched during change detection
+#[derive(Debug, Clone)]
+pub struct DetectionOptions {
+    /// Match renamed columns by their data instead of by position
+    pub detect_renames: bool,
+    /// Columns normalized to a canonical type before comparing cells
+    pub coercions: Vec<ColumnCoercion>,
+    /// How rows are paired between the two sides
+    pub row_identity: RowIdentity,
+    /// Worker threads for the parallel phases (None or 0 uses rayon's global pool)
+    pub threads: Option<usize>,
+    /// Only modifications satisfying all of these are kept; additions and removals are unaffected
+    pub cell_predicates: Vec<CellPredicate>,
+    /// Detection stops with `TabdiffError::Cancelled` once this is cancelled
+    pub cancellation: crate::cancel::CancellationToken,
+    /// With key identity, pair rows sharing a key by content instead of rejecting the key
+    pub allow_duplicate_keys: bool,
+    /// Numeric cells differing by at most this much are not reported as changed
+    pub float_tolerance: Option<f64>,
+    /// Compare cells of columns whose type changed as that type, so re-rendered values are equal
+    pub coerce_type_changes: bool,
+    /// An added and a removed row at different positions are paired into a modification when more
+    /// than this share of their common columns hold equal values
+    pub match_threshold: f64,
+    /// Records the time spent detecting schema changes, classifying rows and analyzing cells
+    pub timings: crate::timings::Timings,
+}
+
+impl Default for DetectionOptions {
+    fn default() -> Self {
+        Self {
+            detect_renames: false,
+            coercions: Vec::new(),
+            row_identity: RowIdentity::default(),
+            threads: None,
+            cell_predicates: Vec::new(),
+            cancellation: crate::cancel::CancellationToken::global(),
+            allow_duplicate_keys: false,
+            float_tolerance: None,
+            coerce_type_changes: false,
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+            timings: crate::timings::Timings::default(),
+        }
+    }
+}
+
+impl DetectionOptions {
+    pub fn with_renames(mut self, detect_renames: bool) -> Self {
+        self.detect_renames = detect_renames;
+        self
+    }
+
+    pub fn with_coercions(mut self, coercions: Vec<ColumnCoercion>) -> Self {
+        self.coercions = coercions;
+        self
+    }
+
+    pub fn with_row_identity(mut self, row_identity: RowIdentity) -> Self {
+        self.row_identity = row_identity;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: Option<usize>) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn with_cell_predicates(mut self, cell_predicates: Vec<CellPredicate>) -> Self {
+        self.cell_predicates = cell_predicates;
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: crate::cancel::CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn with_allow_duplicate_keys(mut self, allow_duplicate_keys: bool) -> Self {
+        self.allow_duplicate_keys = allow_duplicate_keys;
+        self
+    }
+
+    pub fn with_type_change_coercion(mut self, coerce_type_changes: bool) -> Self {
+        self.coerce_type_changes = coerce_type_changes;
+        self
+    }
+
+    pub fn with_match_threshold(mut self, match_threshold: f64) -> Self {
+        self.match_threshold = match_threshold;
+        self
+    }
+
+    pub fn with_float_tolerance(mut self, float_tolerance: Option<f64>) -> Self {
+        self.float_tolerance = float_tolerance;
+        self
+    }
+
+    pub fn with_timings(mut self, timings: crate::timings::Timings) -> Self {
+        self.timings = timings;
+        self
+    }
+}
+
+/// Row indices of one side, grouped by the values of their key columns
+type RowsByKey = HashMap<Vec<String>, Vec<usize>>;
+
+/// How the rows of both sides were paired up
+#[derive(Debug, Default)]
+struct RowPairing {
+    /// (baseline, current) indices of rows paired as the same row, whose content differs
+    matched: Vec<(u64, u64)>,
+    /// Current rows without a baseline counterpart
+    added: Vec<u64>,
+    /// Baseline rows without a current counterpart
+    removed: Vec<u64>,
+}
+
+/// The schemas and rows of both sides, with renamed baseline columns mapped to their current names
+#[derive(Clone, Copy)]
+struct ComparedData<'a> {
+    baseline_schema: &'a [ColumnInfo],
+    baseline_data: &'a [Vec<String>],
+    current_schema: &'a [ColumnInfo],
+    current_data: &'a [Vec<String>],
+    aliases: &'a HashMap<String, String>,
+}
+
+/// Change detector for comprehensive analysis
+pub struct ChangeDetector;
+
+impl ChangeDetector {
+    /// Detect all changes between baseline and current data
+    pub fn detect_changes(
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+    ) -> Result<ChangeDetectionResult> {
+        Self::detect_changes_with_options(
+            baseline_schema,
+            baseline_data,
+            current_schema,
+            current_data,
+            &DetectionOptions::default(),
+        )
+    }
+
+    /// Detect all changes, matching renamed columns by their data instead of by position
+    pub fn detect_changes_with_renames(
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+    ) -> Result<ChangeDetectionResult> {
+        Self::detect_changes_with_options(
+            baseline_schema,
+            baseline_data,
+            current_schema,
+            current_data,
+            &DetectionOptions::default().with_renames(true),
+        )
+    }
+
+    /// Check the columns `options` names against an unchanged schema, for callers that skip
+    /// comparing rows because nothing changed
+    pub fn validate_options(schema: &[ColumnInfo], options: &DetectionOptions) -> Result<()> {
+        Self::coercion_map(schema, schema, &HashMap::new(), &options.coercions)?;
+        Self::check_cell_predicates(schema, &options.cell_predicates)
+    }
+
+    /// Every `--where` predicate must name a column of the current data
+    fn check_cell_predicates(current_schema: &[ColumnInfo], predicates: &[CellPredicate]) -> Result<()> {
+        match predicates.iter().find(|p| !current_schema.iter().any(|col| col.name == p.column)) {
+            Some(unknown) => Err(crate::error::TabdiffError::invalid_input(format!(
+                "Column '{}' in --where does not exist in the current data",
+                unknown.column
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Compare column lists only, for snapshots that recorded no rows
+    pub fn detect_schema_only(
+        baseline_schema: &[ColumnInfo],
+        current_schema: &[ColumnInfo],
+    ) -> Result<ChangeDetectionResult> {
+        Ok(ChangeDetectionResult {
+            schema_changes: Self::detect_schema_changes(baseline_schema, current_schema)?,
+            ..ChangeDetectionResult::default()
+        })
+    }
+
+    /// Changes of a source that was only appended to: `appended_rows` are its rows from
+    /// `first_row` on, each reported as an addition
+    ///
+    /// The caller has verified that the rows before `first_row` are the baseline's rows (by the
+    /// baseline snapshot's row order hash), so they are neither read nor compared here.
+    pub fn detect_appended_rows(
+        current_schema: &[ColumnInfo],
+        appended_rows: &[Vec<String>],
+        first_row: u64,
+    ) -> Result<ChangeDetectionResult> {
+        let positions: Vec<u64> = (0..appended_rows.len() as u64).collect();
+        let mut added = Self::convert_additions_parallel(current_schema, appended_rows, &positions)?;
+        for addition in &mut added {
+            addition.row_index += first_row;
+        }
+        Ok(ChangeDetectionResult {
+            row_changes: RowChanges {
+                added,
+                ..RowChanges::default()
+            },
+            ..ChangeDetectionResult::default()
+        })
+    }
+
+    /// Fraction of all column names that both schemas share (1.0 when neither has columns)
+    ///
+    /// A low overlap suggests the two sides are unrelated datasets rather than versions of one.
+    pub fn column_overlap(baseline_schema: &[ColumnInfo], current_schema: &[ColumnInfo]) -> f64 {
+        let shared = Self::find_common_columns(baseline_schema, current_schema, &HashMap::new()).len();
+        let total = baseline_schema.len() + current_schema.len() - shared;
+        if total == 0 {
+            1.0
+        } else {
+            shared as f64 / total as f64
+        }
+    }
+
+    /// Detect all changes using the given detection options
+    ///
+    /// With `options.threads` set, the parallel phases run on a dedicated pool of that size
+    /// instead of rayon's global pool.
+    pub fn detect_changes_with_options(
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+        options: &DetectionOptions,
+    ) -> Result<ChangeDetectionResult> {
+        let detect = || Self::detect_changes_in_current_pool(
+            baseline_schema,
+            baseline_data,
+            current_schema,
+            current_data,
+            options,
+        );
+        match options.threads {
+            Some(threads) if threads > 0 => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| crate::error::TabdiffError::data_processing(
+                        format!("Failed to create a pool of {} detection threads: {}", threads, e)
+                    ))?;
+                pool.install(detect)
+            }
+            _ => detect(),
+        }
+    }
+
+    fn detect_changes_in_current_pool(
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+        options: &DetectionOptions,
+    ) -> Result<ChangeDetectionResult> {
+        let phase = std::time::Instant::now();
+        let schema_changes = if options.detect_renames {
+            Self::detect_schema_changes_by_content(
+                baseline_schema,
+                baseline_data,
+                current_schema,
+                current_data,
+            )?
+        } else {
+            Self::detect_schema_changes(baseline_schema, current_schema)?
+        };
+        options.timings.record("detect schema", phase);
+        options.cancellation.check()?;
+        let aliases = Self::column_aliases(baseline_schema, current_schema, &schema_changes.columns_renamed);
+        let coercions = Self::coercion_map(baseline_schema, current_schema, &aliases, &options.coercions)?;
+        // Only `--coerce` columns report unparseable values; type changes fall back to plain comparison
+        let mut cell_coercions = coercions.clone();
+        if options.coerce_type_changes {
+            for change in &schema_changes.type_changes {
+                if let Some(to) = CoerceType::for_type_change(&change.from, &change.to) {
+                    cell_coercions.entry(change.column.clone()).or_insert(to);
+                }
+            }
+        }
+        Self::check_cell_predicates(current_schema, &options.cell_predicates)?;
+        let sides = ComparedData { baseline_schema, baseline_data, current_schema, current_data, aliases: &aliases };
+        let (mut row_changes, hash_quality) = Self::detect_row_changes(sides, &cell_coercions, options)?;
+        if !options.cell_predicates.is_empty() {
+            row_changes.modified.retain(|modification| {
+                options.cell_predicates.iter().all(|predicate| predicate.matches(modification))
+            });
+        }
+        let coercion_failures = Self::find_coercion_failures(
+            baseline_schema,
+            baseline_data,
+            current_schema,
+            current_data,
+            &aliases,
+            &coercions,
+        );
+        Ok(ChangeDetectionResult {
+            schema_changes,
+            row_changes,
+            coercion_failures,
+            hash_quality,
+        })
+    }
+
+    /// Resolve `--coerce` rules to the current name of each column they apply to
+    fn coercion_map(
+        baseline_schema: &[ColumnInfo],
+        current_schema: &[ColumnInfo],
+        aliases: &HashMap<String, String>,
+        coercions: &[ColumnCoercion],
+    ) -> Result<HashMap<String, CoerceType>> {
+        let mut map = HashMap::new();
+        for coercion in coercions {
+            let current_name = if current_schema.iter().any(|col| col.name == coercion.column) {
+                coercion.column.clone()
+            } else if let Some(renamed) = aliases.get(&coercion.column) {
+                renamed.clone()
+            } else if baseline_schema.iter().any(|col| col.name == coercion.column) {
+                // Only present in the baseline; nothing to compare it against
+                continue;
+            } else {
+                return Err(crate::error::TabdiffError::invalid_input(format!(
+                    "Cannot coerce unknown column '{}'",
+                    coercion.column
+                )));
+            };
+            map.insert(current_name, coercion.to);
+        }
+        Ok(map)
+    }
+
+    /// Collect the distinct values of coerced columns that do not parse as their target type
+    fn find_coercion_failures(
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_data: &[Vec<String>],
+        aliases: &HashMap<String, String>,
+        coercions: &HashMap<String, CoerceType>,
+    ) -> Vec<CoercionFailure> {
+        let mut failures = Vec::new();
+        let mut columns: Vec<_> = coercions.iter().collect();
+        columns.sort();
+
+        for (current_name, &to) in columns {
+            let baseline_idx = baseline_schema
+                .iter()
+                .position(|col| aliases.get(&col.name).unwrap_or(&col.name) == current_name);
+            let current_idx = current_schema.iter().position(|col| &col.name == current_name);
+
+            let mut values = std::collections::BTreeSet::new();
+            for (data, idx) in [(baseline_data, baseline_idx), (current_data, current_idx)] {
+                let Some(idx) = idx else { continue };
+                for value in data.iter().filter_map(|row| row.get(idx)) {
+                    if to.normalize(value).is_none() {
+                        values.insert(value.clone());
+                    }
+                }
+            }
+
+            failures.extend(
+                values
+                    .into_iter()
+                    .take(MAX_COERCION_FAILURES_PER_COLUMN)
+                    .map(|value| CoercionFailure {
+                        column: current_name.clone(),
+                        value,
+                        coerce_to: to.as_str().to_string(),
+                    }),
+            );
+        }
+
+        failures
+    }
+
+    /// Detect schema changes by column name, pairing removed and added columns whose values match
+    fn detect_schema_changes_by_content(
+        baseline: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current: &[ColumnInfo],
+        current_data: &[Vec<String>],
+    ) -> Result<SchemaChanges> {
+        let removed: Vec<usize> = (0..baseline.len())
+            .filter(|&i| !current.iter().any(|c| c.name == baseline[i].name))
+            .collect();
+        let added: Vec<usize> = (0..current.len())
+            .filter(|&j| !baseline.iter().any(|c| c.name == current[j].name))
+            .collect();
+
+        // Score every removed/added pair by value similarity and greedily take the best matches
+        let hash_computer = crate::hash::HashComputer::new(RENAME_SAMPLE_SIZE);
+        let baseline_samples: HashMap<usize, HashMap<String, usize>> = removed.iter()
+            .map(|&i| (i, Self::sample_value_hashes(&hash_computer, baseline_data, i)))
+            .collect();
+        let current_samples: HashMap<usize, HashMap<String, usize>> = added.iter()
+            .map(|&j| (j, Self::sample_value_hashes(&hash_computer, current_data, j)))
+            .collect();
+
+        let mut candidates = Vec::new();
+        for &i in &removed {
+            for &j in &added {
+                let score = Self::value_similarity(&baseline_samples[&i], &current_samples[&j]);
+                if score >= RENAME_MIN_CONFIDENCE {
+                    candidates.push((score, i, j));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Map of baseline position -> current position for every column present on both sides
+        let mut matched: HashMap<usize, usize> = HashMap::new();
+        let mut columns_renamed = Vec::new();
+        for (score, i, j) in candidates {
+            if matched.contains_key(&i) || matched.values().any(|&m| m == j) {
+                continue;
+            }
+            matched.insert(i, j);
+            columns_renamed.push(ColumnRename {
+                from: baseline[i].name.clone(),
+                to: current[j].name.clone(),
+                confidence: Some(score),
+            });
+        }
+        for (i, col) in baseline.iter().enumerate() {
+            if let Some(j) = current.iter().position(|c| c.name == col.name) {
+                matched.insert(i, j);
+            }
+        }
+
+        let columns_removed = removed.iter()
+            .filter(|i| !matched.contains_key(i))
+            .map(|&i| ColumnRemoval {
+                name: baseline[i].name.clone(),
+                data_type: baseline[i].data_type.clone(),
+                position: i,
+                nullable: baseline[i].nullable,
+            })
+            .collect();
+        let columns_added = added.iter()
+            .filter(|j| !matched.values().any(|m| m == *j))
+            .map(|&j| ColumnAddition {
+                name: current[j].name.clone(),
+                data_type: current[j].data_type.clone(),
+                position: j,
+                nullable: current[j].nullable,
+                default_value: None,
+            })
+            .collect();
+
+        let mut type_changes = Vec::new();
+        let mut current_order = Vec::new();
+        for (i, baseline_col) in baseline.iter().enumerate() {
+            if let Some(&j) = matched.get(&i) {
+                current_order.push(j);
+                if baseline_col.data_type != current[j].data_type {
+                    type_changes.push(TypeChange {
+                        column: current[j].name.clone(),
+                        from: baseline_col.data_type.clone(),
+                        to: current[j].data_type.clone(),
+                    });
+                }
+            }
+        }
+
+        // Matched columns no longer appear in the same relative order
+        let column_order = if current_order.windows(2).any(|w| w[0] > w[1]) {
+            Some(ColumnOrderChange {
+                before: baseline.iter().map(|c| c.name.clone()).collect(),
+                after: current.iter().map(|c| c.name.clone()).collect(),
+            })
+        } else {
+            None
+        };
+
+        Ok(SchemaChanges {
+            column_order,
+            columns_added,
+            columns_removed,
+            columns_renamed,
+            type_changes,
+        })
+    }
+
+    /// Count the hashes of a sample of values from one column
+    fn sample_value_hashes(
+        hash_computer: &crate::hash::HashComputer,
+        data: &[Vec<String>],
+        column_index: usize,
+    ) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for row in data.iter().take(RENAME_SAMPLE_SIZE) {
+            if let Some(value) = row.get(column_index) {
+                *counts.entry(hash_computer.hash_value(value)).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Fraction of sampled values shared by two columns (multiset overlap)
+    fn value_similarity(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+        let total_a: usize = a.values().sum();
+        let total_b: usize = b.values().sum();
+        let total = total_a.max(total_b);
+        if total == 0 {
+            return 0.0;
+        }
+        
+        let shared: usize = a.iter()
+            .map(|(hash, count)| (*count).min(*b.get(hash).unwrap_or(&0)))
+            .sum();
+        shared as f64 / total as f64
+    }
+
+    /// Detect schema changes using position-based comparison
+    fn detect_schema_changes(
+        baseline: &[ColumnInfo],
+        current: &[ColumnInfo],
+    ) -> Result<SchemaChanges> {
+        let baseline_names: Vec<String> = baseline.iter().map(|c| c.name.clone()).collect();
+        let current_names: Vec<String> = current.iter().map(|c| c.name.clone()).collect();
+
+        // Detect column order changes (if column names are reordered)
+        let column_order = if baseline_names != current_names && baseline.len() == current.len() {
+            // Check if it's just a reordering (same columns, different order)
+            let mut baseline_sorted = baseline_names.clone();
+            let mut current_sorted = current_names.clone();
+            baseline_sorted.sort();
+            current_sorted.sort();
+            
+            if baseline_sorted == current_sorted {
+                Some(ColumnOrderChange {
+                    before: baseline_names.clone(),
+                    after: current_names.clone(),
+                })
+            } else {
+                None // Not just reordering, there are additions/removals/renames
+            }
+        } else {
+            None
+        };
+
+        let mut columns_added = Vec::new();
+        let mut columns_removed = Vec::new();
+        let mut columns_renamed = Vec::new();
+        let mut type_changes = Vec::new();
+
+        // Handle different column counts (additions/removals)
+        if baseline.len() != current.len() {
+            if current.len() > baseline.len() {
+                // Columns were added at the end
+                for (pos, col) in current.iter().enumerate().skip(baseline.len()) {
+                    columns_added.push(ColumnAddition {
+                        name: col.name.clone(),
+                        data_type: col.data_type.clone(),
+                        position: pos,
+                        nullable: col.nullable,
+                        default_value: None,
+                    });
+                }
+            } else {
+                // Columns were removed from the end
+                for (pos, col) in baseline.iter().enumerate().skip(current.len()) {
+                    columns_removed.push(ColumnRemoval {
+                        name: col.name.clone(),
+                        data_type: col.data_type.clone(),
+                        position: pos,
+                        nullable: col.nullable,
+                    });
+                }
+            }
+        }
+
+        // Compare columns position by position (for common length)
+        let min_len = baseline.len().min(current.len());
+        for pos in 0..min_len {
+            let baseline_col = &baseline[pos];
+            let current_col = &current[pos];
+
+            // Check for column rename at this position
+            if baseline_col.name != current_col.name {
+                columns_renamed.push(ColumnRename {
+                    from: baseline_col.name.clone(),
+                    to: current_col.name.clone(),
+                    confidence: None,
+                });
+            }
+
+            // Check for type change at this position
+            if baseline_col.data_type != current_col.data_type {
+                type_changes.push(TypeChange {
+                    column: current_col.name.clone(), // Use current name in case it was renamed
+                    from: baseline_col.data_type.clone(),
+                    to: current_col.data_type.clone(),
+                });
+            }
+        }
+
+        Ok(SchemaChanges {
+            column_order,
+            columns_added,
+            columns_removed,
+            columns_renamed,
+            type_changes,
+        })
+    }
+
+    /// Detect row changes, pairing baseline and current rows as `identity` prescribes
+    ///
+    /// Renamed columns are matched and compared under their new name rather than dropped.
+    /// Hash quality metrics are returned when content-based matching was hampered by rows
+    /// sharing a hash.
+    fn detect_row_changes(
+        sides: ComparedData,
+        coercions: &HashMap<String, CoerceType>,
+        options: &DetectionOptions,
+    ) -> Result<(RowChanges, Option<crate::hash::HashQualityMetrics>)> {
+        let ComparedData { baseline_schema, baseline_data, current_schema, current_data, .. } = sides;
+        let cancellation = &options.cancellation;
+        // Phase 1: Fast hash-based filtering to identify changed rows
+        let phase = std::time::Instant::now();
+        let hash_computer = crate::hash::HashComputer::new(10000);
+        let baseline_hashes = hash_computer.hash_rows(baseline_data)?;
+        cancellation.check()?;
+        let current_hashes = hash_computer.hash_rows(current_data)?;
+        cancellation.check()?;
+        
+        // Phase 2: Pair rows into modifications; the rest are genuine additions/removals
+        let mut hash_quality = None;
+        let pairing = match &options.row_identity {
+            RowIdentity::Auto => {
+                let comparison = hash_computer.compare_row_hashes(&baseline_hashes, &current_hashes);
+                hash_quality = Some(comparison.hash_quality);
+                Self::classify_changed_rows(
+                    sides,
+                    &comparison.added_rows,
+                    &comparison.removed_rows,
+                    options.match_threshold,
+                    cancellation,
+                )?
+            }
+            RowIdentity::ContentHash => {
+                let comparison = hash_computer.compare_row_hashes(&baseline_hashes, &current_hashes);
+                hash_quality = Some(comparison.hash_quality);
+                RowPairing { matched: Vec::new(), added: comparison.added_rows, removed: comparison.removed_rows }
+            }
+            RowIdentity::Positional => Self::match_by_position(&baseline_hashes, &current_hashes),
+            RowIdentity::Key(columns) => Self::match_by_key(
+                sides,
+                &baseline_hashes,
+                &current_hashes,
+                columns,
+                options.allow_duplicate_keys,
+            )?,
+        };
+        options.timings.record("classify rows", phase);
+        
+        cancellation.check()?;
+
+        // Phase 3: Parallel cell-level analysis for modifications only
+        let phase = std::time::Instant::now();
+        let detailed_modifications = Self::analyze_modifications_parallel(
+            sides,
+            &pairing.matched,
+            coercions,
+            options.float_tolerance,
+        )?;
+        
+        // Convert results to final format
+        let added = Self::convert_additions_parallel(current_schema, current_data, &pairing.added)?;
+        let removed = Self::convert_removals_parallel(baseline_schema, baseline_data, &pairing.removed)?;
+        options.timings.record("cell analysis", phase);
+
+        let row_changes = RowChanges {
+            modified: detailed_modifications,
+            added,
+            removed,
+        };
+        Ok((row_changes, hash_quality.filter(|quality| quality.has_significant_collisions())))
+    }
+
+    /// Pair rows at the same index; rows past the end of the other side are added or removed
+    fn match_by_position(
+        baseline_hashes: &[crate::hash::RowHash],
+        current_hashes: &[crate::hash::RowHash],
+    ) -> RowPairing {
+        let matched = baseline_hashes
+            .iter()
+            .zip(current_hashes)
+            .filter(|(baseline, current)| baseline.hash != current.hash)
+            .map(|(baseline, current)| (baseline.row_index, current.row_index))
+            .collect();
+        let added = current_hashes.iter().skip(baseline_hashes.len()).map(|h| h.row_index).collect();
+        let removed = baseline_hashes.iter().skip(current_hashes.len()).map(|h| h.row_index).collect();
+        
+        RowPairing { matched, added, removed }
+    }
+
+    /// Pair rows with equal values in the key columns; unmatched keys are added or removed
+    ///
+    /// A key shared by several rows on either side is an error unless `allow_duplicate_keys` is
+    /// set, in which case those rows are paired by identical content first, then in file order.
+    fn match_by_key(
+        sides: ComparedData,
+        baseline_hashes: &[crate::hash::RowHash],
+        current_hashes: &[crate::hash::RowHash],
+        key_columns: &[String],
+        allow_duplicate_keys: bool,
+    ) -> Result<RowPairing> {
+        let ComparedData { baseline_schema, baseline_data, current_schema, current_data, aliases } = sides;
+        let mut baseline_key_indices = Vec::new();
+        let mut current_key_indices = Vec::new();
+        for key in key_columns {
+            let baseline_idx = baseline_schema
+                .iter()
+                .position(|col| &col.name == key || aliases.get(&col.name) == Some(key));
+            let current_idx = current_schema.iter().position(|col| &col.name == key);
+            match (baseline_idx, current_idx) {
+                (Some(baseline_idx), Some(current_idx)) => {
+                    baseline_key_indices.push(baseline_idx);
+                    current_key_indices.push(current_idx);
+                }
+                _ => {
+                    return Err(crate::error::TabdiffError::invalid_input(format!(
+                        "Key column '{}' must exist in both the baseline and the current data",
+                        key
+                    )));
+                }
+            }
+        }
+        
+        let key_of = |row: &[String], indices: &[usize]| -> Vec<String> {
+            indices.iter().map(|&idx| row.get(idx).cloned().unwrap_or_default()).collect()
+        };
+        let rows_by_key = |data: &[Vec<String>], indices: &[usize]| {
+            let mut rows = RowsByKey::new();
+            for (idx, row) in data.iter().enumerate() {
+                rows.entry(key_of(row, indices)).or_default().push(idx);
+            }
+            rows
+        };
+        let baseline_keys = rows_by_key(baseline_data, &baseline_key_indices);
+        if !allow_duplicate_keys {
+            let current_keys = rows_by_key(current_data, &current_key_indices);
+            if let Some(duplicates) = Self::describe_duplicate_keys(key_columns, &[("baseline", &baseline_keys), ("current", &current_keys)]) {
+                return Err(crate::error::TabdiffError::invalid_input(format!(
+                    "Key {} does not identify rows uniquely: {}. Use columns that are unique on both sides, \
+                     or --allow-duplicate-keys to pair rows sharing a key by content",
+                    key_columns.join(", "),
+                    duplicates
+                )));
+            }
+        }
+        
+        // Rows with identical content pair up first, so duplicated keys match as a multiset
+        let candidates = |current_idx: usize| {
+            baseline_keys
+                .get(&key_of(&current_data[current_idx], &current_key_indices))
+                .map_or(&[][..], Vec::as_slice)
+        };
+        let mut matched = HashSet::new();
+        let mut pairs: Vec<Option<usize>> = vec![None; current_data.len()];
+        for (current_idx, pair) in pairs.iter_mut().enumerate() {
+            *pair = candidates(current_idx)
+                .iter()
+                .copied()
+                .find(|&baseline_idx| {
+                    !matched.contains(&baseline_idx) && baseline_hashes[baseline_idx].hash == current_hashes[current_idx].hash
+                });
+            if let Some(baseline_idx) = *pair {
+                matched.insert(baseline_idx);
+            }
+        }
+        
+        let mut modifications = Vec::new();
+        let mut added = Vec::new();
+        for (current_idx, pair) in pairs.iter().enumerate() {
+            if pair.is_some() {
+                continue;
+            }
+            match candidates(current_idx).iter().copied().find(|baseline_idx| matched.insert(*baseline_idx)) {
+                Some(baseline_idx) => modifications.push((baseline_idx as u64, current_idx as u64)),
+                None => added.push(current_idx as u64),
             }
         }
+        let removed = (0..baseline_data.len())
+            .filter(|idx| !matched.contains(idx))
+            .map(|idx| idx as u64)
+            .collect();
         
-        Ok((modifications, unmatched_added, unmatched_removed))
+        Ok(RowPairing { matched: modifications, added, removed })
     }
-    
-    /// Find common columns between schemas for content matching
-    fn find_common_columns(baseline_schema: &[ColumnInfo], current_schema: &[ColumnInfo]) -> Vec<String> {
-        let current_names: std::collections::HashSet<_> = current_schema.iter().map(|c| &c.name).collect();
-        baseline_schema
+
+    /// `id=1 (baseline rows 0, 2); ...` for keys held by more than one row, or None when every
+    /// key is unique; at most `MAX_DUPLICATE_KEY_SAMPLES` keys are listed
+    fn describe_duplicate_keys(key_columns: &[String], sides: &[(&str, &RowsByKey)]) -> Option<String> {
+        let mut duplicates: Vec<(&str, &Vec<String>, &Vec<usize>)> = sides
+            .iter()
+            .flat_map(|(side, keys)| {
+                keys.iter()
+                    .filter(|(_, rows)| rows.len() > 1)
+                    .map(move |(key, rows)| (*side, key, rows))
+            })
+            .collect();
+        if duplicates.is_empty() {
+            return None;
+        }
+        // Earliest duplicates first, baseline before current
+        duplicates.sort_by_key(|(side, _, rows)| (*side != "baseline", rows[0]));
+        
+        let mut samples: Vec<String> = duplicates
             .iter()
-            .filter_map(|col| {
-                if current_names.contains(&col.name) {
-                    Some(col.name.clone())
+            .take(MAX_DUPLICATE_KEY_SAMPLES)
+            .map(|(side, key, rows)| {
+                let key = key_columns.iter().zip(key.iter())
+                    .map(|(column, value)| format!("{}={}", column, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut indices = rows.iter().take(MAX_DUPLICATE_KEY_SAMPLES).map(|idx| idx.to_string()).collect::<Vec<_>>();
+                if rows.len() > MAX_DUPLICATE_KEY_SAMPLES {
+                    indices.push(format!("… {} rows", rows.len()));
+                }
+                format!("{} ({} rows {})", key, side, indices.join(", "))
+            })
+            .collect();
+        if duplicates.len() > MAX_DUPLICATE_KEY_SAMPLES {
+            samples.push(format!("and {} more", duplicates.len() - MAX_DUPLICATE_KEY_SAMPLES));
+        }
+        Some(samples.join("; "))
+    }
+
+    /// Map baseline column names to current names for columns that were genuinely renamed
+    ///
+    /// Positional detection also reports columns shifted by an insertion as renames; a rename is
+    /// only trusted when its old name is gone and its new name did not exist before.
+    fn column_aliases(
+        baseline_schema: &[ColumnInfo],
+        current_schema: &[ColumnInfo],
+        renames: &[ColumnRename],
+    ) -> HashMap<String, String> {
+        renames.iter()
+            .filter(|rename| {
+                !current_schema.iter().any(|col| col.name == rename.from)
+                    && !baseline_schema.iter().any(|col| col.name == rename.to)
+            })
+            .map(|rename| (rename.from.clone(), rename.to.clone()))
+            .collect()
+    }
+
+    /// Classify changed rows into modifications vs genuine additions/removals
+    ///
+    /// Rows at the same position always pair; others pair when their similarity exceeds `match_threshold`.
+    fn classify_changed_rows(
+        sides: ComparedData,
+        added_indices: &[u64],
+        removed_indices: &[u64],
+        match_threshold: f64,
+        cancellation: &crate::cancel::CancellationToken,
+    ) -> Result<RowPairing> {
+        use rayon::prelude::*;
+        let ComparedData { baseline_schema, baseline_data, current_schema, current_data, aliases } = sides;
+        
+        // Early exit if no changes
+        if added_indices.is_empty() && removed_indices.is_empty() {
+            return Ok(RowPairing::default());
+        }
+        
+        // Create column mapping for schema-aware comparison
+        let common_columns = Self::find_common_columns(baseline_schema, current_schema, aliases);
+        
+        // Parallel matching: find likely modifications using position and content heuristics
+        let mut modifications = Vec::new();
+        let mut unmatched_added = added_indices.to_vec();
+        let mut unmatched_removed = removed_indices.to_vec();
+        
+        // Strategy 1: Position-based matching (most common case)
+        let position_matches: Vec<_> = removed_indices
+            .par_iter()
+            .filter_map(|&removed_idx| {
+                // Look for an added row at the same position
+                if let Some(added_pos) = added_indices.iter().position(|&added_idx| added_idx == removed_idx) {
+                    Some((removed_idx, added_indices[added_pos]))
                 } else {
                     None
                 }
             })
+            .collect();
+        
+        // Remove position matches from unmatched lists
+        for &(removed_idx, added_idx) in &position_matches {
+            modifications.push((removed_idx, added_idx));
+            unmatched_removed.retain(|&x| x != removed_idx);
+            unmatched_added.retain(|&x| x != added_idx);
+        }
+        
+        // Strategy 2: Content-based matching for remaining rows (using key columns)
+        if !unmatched_removed.is_empty() && !unmatched_added.is_empty() && !common_columns.is_empty() {
+            let content_matches = Self::find_content_matches_parallel(
+                baseline_data,
+                current_data,
+                &unmatched_removed,
+                &unmatched_added,
+                &common_columns,
+                match_threshold,
+                cancellation,
+            )?;
+            
+            for &(removed_idx, added_idx) in &content_matches {
+                modifications.push((removed_idx, added_idx));
+                unmatched_removed.retain(|&x| x != removed_idx);
+                unmatched_added.retain(|&x| x != added_idx);
+            }
+        }
+        
+        Ok(RowPairing { matched: modifications, added: unmatched_added, removed: unmatched_removed })
+    }
+    
+    /// Find common columns between schemas for content matching, as (baseline, current) positions
+    fn find_common_columns(
+        baseline_schema: &[ColumnInfo],
+        current_schema: &[ColumnInfo],
+        aliases: &HashMap<String, String>,
+    ) -> Vec<(usize, usize)> {
+        baseline_schema
+            .iter()
+            .enumerate()
+            .filter_map(|(baseline_idx, col)| {
+                let current_name = aliases.get(&col.name).unwrap_or(&col.name);
+                current_schema
+                    .iter()
+                    .position(|c| &c.name == current_name)
+                    .map(|current_idx| (baseline_idx, current_idx))
+            })
             .collect()
     }
     
@@ -344,21 +1696,19 @@ impl ChangeDetector {
         current_data: &[Vec<String>],
         removed_indices: &[u64],
         added_indices: &[u64],
-        common_columns: &[String],
+        common_columns: &[(usize, usize)],
+        match_threshold: f64,
+        cancellation: &crate::cancel::CancellationToken,
     ) -> Result<Vec<(u64, u64)>> {
         use rayon::prelude::*;
         
-        // Create column index mappings
-        let baseline_col_map: std::collections::HashMap<String, usize> = common_columns
-            .iter()
-            .enumerate()
-            .map(|(i, name)| (name.clone(), i))
-            .collect();
-        
-        // Parallel content matching with similarity scoring
+        // Parallel content matching with similarity scoring; once cancelled, remaining rows are skipped
         let matches: Vec<_> = removed_indices
             .par_iter()
             .filter_map(|&removed_idx| {
+                if cancellation.is_cancelled() {
+                    return None;
+                }
                 let removed_row = baseline_data.get(removed_idx as usize)?;
                 
                 // Find best match among added rows
@@ -370,7 +1720,6 @@ impl ChangeDetector {
                             removed_row,
                             added_row,
                             common_columns,
-                            &baseline_col_map,
                         );
                         Some((added_idx, similarity))
                     })
@@ -378,7 +1727,7 @@ impl ChangeDetector {
                 
                 // Only consider it a match if similarity is above threshold
                 if let Some((added_idx, similarity)) = best_match {
-                    if similarity > 0.5 { // At least 50% of key columns match
+                    if similarity > match_threshold {
                         Some((removed_idx, added_idx))
                     } else {
                         None
@@ -388,6 +1737,7 @@ impl ChangeDetector {
                 }
             })
             .collect();
+        cancellation.check()?;
         
         Ok(matches)
     }
@@ -396,19 +1746,16 @@ impl ChangeDetector {
     fn calculate_row_similarity(
         row1: &[String],
         row2: &[String],
-        common_columns: &[String],
-        col_map: &std::collections::HashMap<String, usize>,
+        common_columns: &[(usize, usize)],
     ) -> f64 {
         let mut matches = 0;
         let mut total = 0;
         
-        for col_name in common_columns {
-            if let Some(&col_idx) = col_map.get(col_name) {
-                if let (Some(val1), Some(val2)) = (row1.get(col_idx), row2.get(col_idx)) {
-                    total += 1;
-                    if val1 == val2 {
-                        matches += 1;
-                    }
+        for &(idx1, idx2) in common_columns {
+            if let (Some(val1), Some(val2)) = (row1.get(idx1), row2.get(idx2)) {
+                total += 1;
+                if val1 == val2 {
+                    matches += 1;
                 }
             }
         }
@@ -422,13 +1769,13 @@ impl ChangeDetector {
     
     /// Analyze modifications in parallel to detect cell-level changes
     fn analyze_modifications_parallel(
-        baseline_schema: &[ColumnInfo],
-        baseline_data: &[Vec<String>],
-        current_schema: &[ColumnInfo],
-        current_data: &[Vec<String>],
+        sides: ComparedData,
         modifications: &[(u64, u64)],
+        coercions: &HashMap<String, CoerceType>,
+        float_tolerance: Option<f64>,
     ) -> Result<Vec<RowModification>> {
         use rayon::prelude::*;
+        let ComparedData { baseline_schema, baseline_data, current_schema, current_data, aliases } = sides;
         
         // Create column mappings for schema-aware comparison
         let baseline_col_map: std::collections::HashMap<String, usize> = baseline_schema
@@ -455,6 +1802,9 @@ impl ChangeDetector {
                     current_row,
                     &baseline_col_map,
                     &current_col_map,
+                    aliases,
+                    coercions,
+                    float_tolerance,
                 );
                 
                 if !changes.is_empty() {
@@ -472,27 +1822,38 @@ impl ChangeDetector {
     }
     
     /// Compare two rows with schema awareness
+    ///
+    /// Renamed columns are compared through `aliases` and reported under their current name.
+    /// Columns in `coercions` are equal when both values normalize to the same canonical form,
+    /// and numeric values are equal when they differ by no more than `float_tolerance`.
     fn compare_rows_schema_aware(
         baseline_row: &[String],
         current_row: &[String],
         baseline_col_map: &std::collections::HashMap<String, usize>,
         current_col_map: &std::collections::HashMap<String, usize>,
+        aliases: &HashMap<String, String>,
+        coercions: &HashMap<String, CoerceType>,
+        float_tolerance: Option<f64>,
     ) -> HashMap<String, CellChange> {
         let mut changes = HashMap::new();
         
         // Compare common columns only
-        for col_name in baseline_col_map.keys() {
-            if let (Some(&baseline_idx), Some(&current_idx)) = 
-                (baseline_col_map.get(col_name), current_col_map.get(col_name)) {
+        for (col_name, &baseline_idx) in baseline_col_map {
+            let current_name = aliases.get(col_name).unwrap_or(col_name);
+            if let Some(&current_idx) = current_col_map.get(current_name) {
                 
                 let baseline_value = baseline_row.get(baseline_idx).map(|s| s.as_str()).unwrap_or("");
                 let current_value = current_row.get(current_idx).map(|s| s.as_str()).unwrap_or("");
                 
-                if baseline_value != current_value {
-                    changes.insert(col_name.clone(), CellChange {
-                        before: baseline_value.to_string(),
-                        after: current_value.to_string(),
-                    });
+                let coerced_equal = coercions
+                    .get(current_name)
+                    .is_some_and(|to| to.equivalent(baseline_value, current_value));
+
+                let within_tolerance = float_tolerance
+                    .is_some_and(|tolerance| within_float_tolerance(baseline_value, current_value, tolerance));
+
+                if baseline_value != current_value && !coerced_equal && !within_tolerance {
+                    changes.insert(current_name.clone(), CellChange::new(baseline_value, current_value));
                 }
             }
         }
@@ -545,26 +1906,343 @@ impl ChangeDetector {
                     if let Some(value) = row_data.get(col_idx) {
                         data.insert(col.name.clone(), value.clone());
                     }
-                }
-                
-                Some(RowRemoval { row_index: row_idx, data })
+                }
+                
+                Some(RowRemoval { row_index: row_idx, data })
+            })
+            .collect();
+        
+        Ok(removals)
+    }
+
+
+}
+
+impl SchemaChanges {
+    /// Check if there are any schema changes
+    pub fn has_changes(&self) -> bool {
+        self.column_order.is_some()
+            || !self.columns_added.is_empty()
+            || !self.columns_removed.is_empty()
+            || !self.columns_renamed.is_empty()
+            || !self.type_changes.is_empty()
+    }
+
+    /// Number of individual schema changes (a column reorder counts once)
+    pub fn change_count(&self) -> usize {
+        usize::from(self.column_order.is_some())
+            + self.columns_added.len()
+            + self.columns_removed.len()
+            + self.columns_renamed.len()
+            + self.type_changes.len()
+    }
+}
+
+impl ChangeDetectionResult {
+    /// One-line summary of the changes, e.g. "+3 rows, 2 modified, schema unchanged"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        
+        if !self.row_changes.added.is_empty() {
+            parts.push(format!("+{} rows", self.row_changes.added.len()));
+        }
+        if !self.row_changes.removed.is_empty() {
+            parts.push(format!("-{} rows", self.row_changes.removed.len()));
+        }
+        if !self.row_changes.modified.is_empty() {
+            parts.push(format!("{} modified", self.row_changes.modified.len()));
+        }
+        if parts.is_empty() {
+            parts.push("rows unchanged".to_string());
+        }
+        
+        if self.schema_changes.has_changes() {
+            parts.push("schema changed".to_string());
+        } else {
+            parts.push("schema unchanged".to_string());
+        }
+        
+        parts.join(", ")
+    }
+
+    /// Operations that turn the current data back into the baseline
+    ///
+    /// Expects a result detected with the snapshot as baseline, as `status` reports it.
+    pub fn generate_rollback_operations(&self) -> Vec<RollbackOperation> {
+        let mut operations = Vec::new();
+
+        let mut modified: Vec<&RowModification> = self.row_changes.modified.iter().collect();
+        modified.sort_by_key(|m| m.row_index);
+        for modification in modified {
+            let mut columns: Vec<(&String, &CellChange)> = modification.changes.iter().collect();
+            columns.sort_by_key(|(column, _)| column.as_str());
+            for (column, change) in columns {
+                operations.push(RollbackOperation::RestoreCell {
+                    row_index: modification.row_index,
+                    column: column.clone(),
+                    current: change.after.clone(),
+                    value: change.before.clone(),
+                });
+            }
+        }
+
+        let mut added: Vec<&RowAddition> = self.row_changes.added.iter().collect();
+        added.sort_by_key(|a| a.row_index);
+        operations.extend(added.into_iter().map(|addition| RollbackOperation::RemoveRow {
+            row_index: addition.row_index,
+            data: addition.data.clone(),
+        }));
+
+        let mut removed: Vec<&RowRemoval> = self.row_changes.removed.iter().collect();
+        removed.sort_by_key(|r| r.row_index);
+        operations.extend(removed.into_iter().map(|removal| RollbackOperation::RestoreRow {
+            row_index: removal.row_index,
+            data: removal.data.clone(),
+        }));
+
+        operations
+    }
+
+    /// The changes as if the two sides had been compared in the opposite order
+    ///
+    /// Cell values swap, added rows become removed rows and vice versa, and schema changes are
+    /// reversed. A modified row is reported at its position in `baseline_data`, the rows this
+    /// result was detected against, located the way `apply_to` does; without a matching row it
+    /// keeps its current position.
+    pub fn invert(&self, baseline_schema: &[ColumnInfo], baseline_data: &[Vec<String>]) -> ChangeDetectionResult {
+        let schema = &self.schema_changes;
+        // Cells are recorded under the name a column has on the side that becomes the baseline
+        let renamed_from: HashMap<&str, &str> = schema.columns_renamed.iter()
+            .map(|rename| (rename.to.as_str(), rename.from.as_str()))
+            .collect();
+        let schema_changes = SchemaChanges {
+            column_order: schema.column_order.as_ref().map(|order| ColumnOrderChange {
+                before: order.after.clone(),
+                after: order.before.clone(),
+            }),
+            columns_added: schema.columns_removed.iter()
+                .map(|removal| ColumnAddition {
+                    name: removal.name.clone(),
+                    data_type: removal.data_type.clone(),
+                    position: removal.position,
+                    nullable: removal.nullable,
+                    default_value: None,
+                })
+                .collect(),
+            columns_removed: schema.columns_added.iter()
+                .map(|addition| ColumnRemoval {
+                    name: addition.name.clone(),
+                    data_type: addition.data_type.clone(),
+                    position: addition.position,
+                    nullable: addition.nullable,
+                })
+                .collect(),
+            columns_renamed: schema.columns_renamed.iter()
+                .map(|rename| ColumnRename { from: rename.to.clone(), to: rename.from.clone(), confidence: rename.confidence })
+                .collect(),
+            type_changes: schema.type_changes.iter()
+                .map(|change| TypeChange { column: change.column.clone(), from: change.to.clone(), to: change.from.clone() })
+                .collect(),
+        };
+
+        let baseline_columns = BaselineColumns::new(baseline_schema, renamed_from.clone());
+        let mut used: HashSet<usize> = self.row_changes.removed.iter()
+            .map(|removal| removal.row_index as usize)
+            .collect();
+        let modified = self.row_changes.modified.iter()
+            .map(|modification| {
+                let row_index = match baseline_columns.row_of(modification, baseline_data, &used) {
+                    Some(baseline_index) => {
+                        used.insert(baseline_index);
+                        baseline_index as u64
+                    }
+                    None => modification.row_index,
+                };
+                let changes = modification.changes.iter()
+                    .map(|(column, change)| {
+                        let mut inverted = CellChange::new(change.after.clone(), change.before.clone());
+                        if change.delta.is_some() {
+                            inverted.add_numeric_delta();
+                        }
+                        let column = renamed_from.get(column.as_str()).copied().unwrap_or(column.as_str());
+                        (column.to_string(), inverted)
+                    })
+                    .collect();
+                RowModification { row_index, changes }
             })
             .collect();
-        
-        Ok(removals)
+        let row_changes = RowChanges {
+            modified,
+            added: self.row_changes.removed.iter()
+                .map(|removal| RowAddition { row_index: removal.row_index, data: removal.data.clone() })
+                .collect(),
+            removed: self.row_changes.added.iter()
+                .map(|addition| RowRemoval { row_index: addition.row_index, data: addition.data.clone() })
+                .collect(),
+        };
+
+        ChangeDetectionResult {
+            schema_changes,
+            row_changes,
+            coercion_failures: self.coercion_failures.clone(),
+            hash_quality: self.hash_quality.as_ref().map(|quality| crate::hash::HashQualityMetrics {
+                total_base_hashes: quality.total_compare_hashes,
+                unique_base_hashes: quality.unique_compare_hashes,
+                total_compare_hashes: quality.total_base_hashes,
+                unique_compare_hashes: quality.unique_base_hashes,
+                base_collision_count: quality.compare_collision_count,
+                compare_collision_count: quality.base_collision_count,
+                base_collision_rate: quality.compare_collision_rate,
+                compare_collision_rate: quality.base_collision_rate,
+            }),
+        }
     }
 
+    /// Rebuild the current rows from the baseline rows this result was detected against
+    ///
+    /// Unchanged rows are not recorded in a delta, so they fill the positions not taken by
+    /// added or modified rows in their baseline order. Values of added columns are only
+    /// recorded for added rows, so other rows need a default value for them.
+    pub fn apply_to(
+        &self,
+        baseline_schema: &[ColumnInfo],
+        baseline_data: &[Vec<String>],
+        current_schema: &[ColumnInfo],
+        current_row_count: usize,
+    ) -> Result<Vec<Vec<String>>> {
+        // Where each current column's value comes from in a baseline row. Columns are matched
+        // by name; renames are positional, so they are only trusted when no column was added.
+        let renamed_from: HashMap<&str, &str> = if self.schema_changes.columns_added.is_empty() {
+            self.schema_changes.columns_renamed.iter()
+                .map(|rename| (rename.to.as_str(), rename.from.as_str()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let defaults: HashMap<&str, Option<&String>> = self.schema_changes.columns_added.iter()
+            .map(|addition| (addition.name.as_str(), addition.default_value.as_ref()))
+            .collect();
+        let baseline_columns = BaselineColumns::new(baseline_schema, renamed_from);
+        let sources: Vec<Option<usize>> = current_schema.iter()
+            .map(|col| baseline_columns.position(&col.name))
+            .collect();
+
+        let carry_over = |baseline_row: &[String]| -> Result<Vec<String>> {
+            current_schema.iter().zip(&sources)
+                .map(|(col, source)| match source {
+                    Some(i) => Ok(baseline_row.get(*i).cloned().unwrap_or_default()),
+                    None => match defaults.get(col.name.as_str()) {
+                        Some(Some(default)) => Ok((*default).clone()),
+                        _ => Err(crate::error::TabdiffError::data_processing(format!(
+                            "Cannot reconstruct values of added column '{}' from a delta", col.name
+                        ))),
+                    },
+                })
+                .collect()
+        };
+
+        let mut rows: Vec<Option<Vec<String>>> = vec![None; current_row_count];
+        let mut used: HashSet<usize> = self.row_changes.removed.iter()
+            .map(|removal| removal.row_index as usize)
+            .collect();
+
+        let place = |rows: &mut Vec<Option<Vec<String>>>, index: u64, row: Vec<String>| -> Result<()> {
+            match rows.get_mut(index as usize) {
+                Some(slot) if slot.is_none() => {
+                    *slot = Some(row);
+                    Ok(())
+                }
+                _ => Err(crate::error::TabdiffError::data_processing(format!(
+                    "Delta places row {} outside the {} reconstructed rows", index, current_row_count
+                ))),
+            }
+        };
+
+        for addition in &self.row_changes.added {
+            let row = current_schema.iter()
+                .map(|col| addition.data.get(&col.name).cloned().unwrap_or_default())
+                .collect();
+            place(&mut rows, addition.row_index, row)?;
+        }
+
+        for modification in &self.row_changes.modified {
+            let baseline_index = baseline_columns.row_of(modification, baseline_data, &used).ok_or_else(|| {
+                crate::error::TabdiffError::data_processing(format!(
+                    "No baseline row matches modified row {}", modification.row_index
+                ))
+            })?;
+            used.insert(baseline_index);
+
+            let mut row = carry_over(&baseline_data[baseline_index])?;
+            for (col, value) in current_schema.iter().zip(row.iter_mut()) {
+                if let Some(change) = modification.changes.get(&col.name) {
+                    *value = change.after.clone();
+                }
+            }
+            place(&mut rows, modification.row_index, row)?;
+        }
+
+        // Unchanged rows keep their relative order
+        let mut unchanged = (0..baseline_data.len()).filter(|i| !used.contains(i));
+        for slot in rows.iter_mut().filter(|slot| slot.is_none()) {
+            let i = unchanged.next().ok_or_else(|| {
+                crate::error::TabdiffError::data_processing(format!(
+                    "Delta does not account for all {} rows", current_row_count
+                ))
+            })?;
+            *slot = Some(carry_over(&baseline_data[i])?);
+        }
+        if unchanged.next().is_some() {
+            return Err(crate::error::TabdiffError::data_processing(format!(
+                "Delta leaves more than {} rows", current_row_count
+            )));
+        }
+
+        Ok(rows.into_iter().flatten().collect())
+    }
+}
 
+/// Where the values of current columns are found in baseline rows
+struct BaselineColumns<'a> {
+    positions: HashMap<&'a str, usize>,
+    /// Baseline name of each renamed column, by its current name
+    renamed_from: HashMap<&'a str, &'a str>,
 }
 
-impl SchemaChanges {
-    /// Check if there are any schema changes
-    pub fn has_changes(&self) -> bool {
-        self.column_order.is_some()
-            || !self.columns_added.is_empty()
-            || !self.columns_removed.is_empty()
-            || !self.columns_renamed.is_empty()
-            || !self.type_changes.is_empty()
+impl<'a> BaselineColumns<'a> {
+    fn new(baseline_schema: &'a [ColumnInfo], renamed_from: HashMap<&'a str, &'a str>) -> Self {
+        let positions = baseline_schema.iter()
+            .enumerate()
+            .map(|(i, col)| (col.name.as_str(), i))
+            .collect();
+        Self { positions, renamed_from }
+    }
+
+    /// Position in a baseline row of the column named `column` in the current schema
+    fn position(&self, column: &str) -> Option<usize> {
+        self.positions.get(column)
+            .or_else(|| self.renamed_from.get(column).and_then(|from| self.positions.get(from)))
+            .copied()
+    }
+
+    /// Baseline row `modification` started from: its own position if that row holds the
+    /// recorded before values, else the first such row not in `used`
+    ///
+    /// Cell changes are only recorded for columns present in both schemas, under their current name.
+    fn row_of(&self, modification: &RowModification, baseline_data: &[Vec<String>], used: &HashSet<usize>) -> Option<usize> {
+        let matches_before = |i: usize| {
+            !used.contains(&i) && modification.changes.iter().all(|(column, change)| {
+                self.position(column)
+                    .and_then(|position| baseline_data[i].get(position))
+                    .is_some_and(|value| *value == change.before)
+            })
+        };
+        let same_position = modification.row_index as usize;
+        if same_position < baseline_data.len() && matches_before(same_position) {
+            Some(same_position)
+        } else {
+            (0..baseline_data.len()).find(|&i| matches_before(i))
+        }
     }
 }
 
@@ -578,6 +2256,101 @@ impl RowChanges {
     pub fn total_changes(&self) -> usize {
         self.modified.len() + self.added.len() + self.removed.len()
     }
+
+    /// Add the delta and percent change to every numeric cell change (`diff --numeric-delta`)
+    pub fn add_numeric_deltas(&mut self) {
+        for modification in &mut self.modified {
+            modification.changes.values_mut().for_each(CellChange::add_numeric_delta);
+        }
+    }
+
+    /// Indices of current rows whose hash matched a baseline row exactly
+    ///
+    /// Modified and added rows are keyed by their current position, so every other
+    /// current row was an identical hash match.
+    pub fn unchanged_row_indices(&self, current_row_count: usize) -> Vec<u64> {
+        let changed: HashSet<u64> = self.modified.iter().map(|m| m.row_index)
+            .chain(self.added.iter().map(|a| a.row_index))
+            .collect();
+        (0..current_row_count as u64)
+            .filter(|idx| !changed.contains(idx))
+            .collect()
+    }
+
+    /// Indices of unchanged current rows within `context` rows of a change, in order
+    ///
+    /// Removed rows no longer exist in the current data; their baseline index is used as the
+    /// position they were removed from. Overlapping windows are merged.
+    pub fn context_row_indices(&self, current_row_count: usize, context: usize) -> Vec<u64> {
+        let changed: HashSet<u64> = self.modified.iter().map(|m| m.row_index)
+            .chain(self.added.iter().map(|a| a.row_index))
+            .collect();
+        let context = context as u64;
+        let mut indices = std::collections::BTreeSet::new();
+        for &idx in changed.iter().chain(self.removed.iter().map(|r| &r.row_index)) {
+            let window = idx.saturating_sub(context)..=idx.saturating_add(context);
+            indices.extend(window.filter(|i| *i < current_row_count as u64 && !changed.contains(i)));
+        }
+        indices.into_iter().collect()
+    }
+
+    /// Count changed rows per value of `column`
+    ///
+    /// Modified and added rows are grouped by their current value, removed rows by their
+    /// baseline value. Row indices of modifications must still be positions in `current_rows`.
+    pub fn group_by(
+        &self,
+        column: &str,
+        baseline_schema: &[ColumnInfo],
+        current_schema: &[ColumnInfo],
+        current_rows: &[Vec<String>],
+    ) -> Result<ChangeGroups> {
+        let position = current_schema.iter().position(|col| col.name == column);
+        if position.is_none() && !baseline_schema.iter().any(|col| col.name == column) {
+            return Err(crate::error::TabdiffError::invalid_input(format!(
+                "Cannot group by '{}': no such column. Available columns: {}",
+                column,
+                current_schema.iter().map(|col| col.name.as_str()).collect::<Vec<_>>().join(", ")
+            )));
+        }
+
+        // Rows without the column (or beyond the loaded data) count towards the empty value
+        fn group<'a, 'g>(groups: &'g mut HashMap<&'a str, GroupChanges>, value: Option<&'a String>) -> &'g mut GroupChanges {
+            let value = value.map_or("", String::as_str);
+            groups.entry(value).or_insert_with(|| GroupChanges { value: value.to_string(), ..Default::default() })
+        }
+        let mut groups = HashMap::new();
+        for modification in &self.modified {
+            let value = position.and_then(|position| current_rows.get(modification.row_index as usize)?.get(position));
+            group(&mut groups, value).modified += 1;
+        }
+        for addition in &self.added {
+            group(&mut groups, addition.data.get(column)).added += 1;
+        }
+        for removal in &self.removed {
+            group(&mut groups, removal.data.get(column)).removed += 1;
+        }
+
+        let mut groups: Vec<GroupChanges> = groups.into_values().collect();
+        groups.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.value.cmp(&b.value)));
+        Ok(ChangeGroups { column: column.to_string(), groups })
+    }
+
+    /// Number of modified rows touching each column, most changed first (ties by name)
+    pub fn column_change_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for modification in &self.modified {
+            for column in modification.changes.keys() {
+                *counts.entry(column.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter()
+            .map(|(column, count)| (column.to_string(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
 }
 
 #[cfg(test)]
@@ -654,7 +2427,15 @@ mod tests {
             vec!["3".to_string(), "Charlie".to_string()],     // Added
         ];
 
-        let changes = ChangeDetector::detect_row_changes(&schema, &baseline_data, &schema, &current_data).unwrap();
+        let aliases = HashMap::new();
+        let sides = ComparedData {
+            baseline_schema: &schema,
+            baseline_data: &baseline_data,
+            current_schema: &schema,
+            current_data: &current_data,
+            aliases: &aliases,
+        };
+        let changes = ChangeDetector::detect_row_changes(sides, &HashMap::new(), &DetectionOptions::default()).unwrap().0;
 
         assert!(changes.has_changes());
         assert_eq!(changes.modified.len(), 1);
@@ -663,5 +2444,539 @@ mod tests {
         assert_eq!(changes.added.len(), 1);
         assert_eq!(changes.added[0].row_index, 2);
         assert_eq!(changes.removed.len(), 0);
+        assert_eq!(changes.unchanged_row_indices(current_data.len()), vec![1]);
+    }
+
+    #[test]
+    fn test_partial_rollback_operations() {
+        let schema = vec![
+            ColumnInfo { name: "id".to_string(), data_type: "INTEGER".to_string(), nullable: false },
+            ColumnInfo { name: "name".to_string(), data_type: "TEXT".to_string(), nullable: true },
+            ColumnInfo { name: "price".to_string(), data_type: "TEXT".to_string(), nullable: true },
+        ];
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let snapshot_data = vec![row(&["1", "Apple", "1.00"]), row(&["2", "Banana", "2.00"])];
+        let current_data = vec![
+            row(&["1", "Green Apple", "9.99"]),
+            row(&["2", "Banana", "9.99"]),
+            row(&["3", "Cherry", "3.00"]),
+        ];
+
+        let changes = ChangeDetector::detect_changes(&schema, &snapshot_data, &schema, &current_data).unwrap();
+        let operations = changes.generate_rollback_operations();
+
+        // Every operation together is a full rollback
+        assert_eq!(RollbackOperation::apply_all(&operations, &schema, &current_data), snapshot_data);
+
+        // Only the price column: names and the added row are kept
+        let columns = vec!["price".to_string()];
+        let price_only = RollbackFilter::new(&[], &columns).apply(operations.clone());
+        assert_eq!(price_only.len(), 2);
+        assert_eq!(RollbackOperation::apply_all(&price_only, &schema, &current_data), vec![
+            row(&["1", "Green Apple", "1.00"]),
+            row(&["2", "Banana", "2.00"]),
+            row(&["3", "Cherry", "3.00"]),
+        ]);
+
+        // Both cells of row 0 are one group; row 1's price and the added row are one each
+        let groups = RollbackOperation::group_by_row(operations.clone());
+        assert_eq!(groups.iter().map(|group| group.len()).collect::<Vec<_>>(), vec![2, 1, 1]);
+
+        // Only row 2: the added row is removed, edits elsewhere are kept
+        let rows = vec![RowRange::parse("2").unwrap()];
+        let row_only = RollbackFilter::new(&rows, &[]).apply(operations);
+        assert_eq!(RollbackOperation::apply_all(&row_only, &schema, &current_data), vec![
+            row(&["1", "Green Apple", "9.99"]),
+            row(&["2", "Banana", "9.99"]),
+        ]);
+    }
+
+    #[test]
+    fn test_partial_rollback_selects_removed_rows_by_snapshot_index() {
+        let schema = vec![
+            ColumnInfo { name: "id".to_string(), data_type: "INTEGER".to_string(), nullable: false },
+            ColumnInfo { name: "name".to_string(), data_type: "TEXT".to_string(), nullable: true },
+        ];
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let snapshot_data = vec![row(&["1", "Apple"]), row(&["2", "Banana"]), row(&["3", "Cherry"])];
+        let current_data = vec![row(&["1", "Apple"]), row(&["3", "Cherry"])];
+
+        let changes = ChangeDetector::detect_changes(&schema, &snapshot_data, &schema, &current_data).unwrap();
+        assert_eq!(changes.row_changes.removed.len(), 1);
+        assert_eq!(changes.row_changes.removed[0].row_index, 1);
+
+        // Banana is row 1 of the snapshot, which is what status reports and --rows selects
+        let rows = vec![RowRange::parse("1").unwrap()];
+        let selected = RollbackFilter::new(&rows, &[]).apply(changes.generate_rollback_operations());
+        assert!(matches!(selected.as_slice(), [RollbackOperation::RestoreRow { row_index: 1, .. }]));
+        assert_eq!(RollbackOperation::apply_all(&selected, &schema, &current_data), snapshot_data);
+    }
+
+    #[test]
+    fn test_row_range_parsing() {
+        assert_eq!(RowRange::parse("5").unwrap(), RowRange { start: 5, end: 5 });
+        assert_eq!(RowRange::parse("5-8").unwrap(), RowRange { start: 5, end: 8 });
+        assert!(RowRange::parse("8-5").is_err());
+        assert!(RowRange::parse("x").is_err());
+        assert!(RowRange::parse("-3").is_err());
+    }
+
+    #[test]
+    fn test_apply_reconstructs_current_rows() {
+        let column = |name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: "VARCHAR".to_string(),
+            nullable: true,
+        };
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let baseline_schema = vec![column("id"), column("name"), column("note")];
+        let current_schema = vec![column("name"), column("id")];
+
+        let baseline_data = vec![
+            row(&["1", "Alice", "a"]),
+            row(&["2", "Bob", "b"]),
+            row(&["3", "Carol", "c"]),
+            row(&["4", "Dave", "d"]),
+        ];
+        let current_data = vec![
+            row(&["Alice", "1"]),
+            row(&["Bobby", "2"]),
+            row(&["Dave", "4"]),
+            row(&["Erin", "5"]),
+        ];
+
+        let changes = ChangeDetector::detect_changes(&baseline_schema, &baseline_data, &current_schema, &current_data).unwrap();
+        let rebuilt = changes.apply_to(&baseline_schema, &baseline_data, &current_schema, current_data.len()).unwrap();
+        assert_eq!(rebuilt, current_data);
+
+        // Values of an added column cannot be recovered for carried-over rows
+        let widened_schema = vec![column("id"), column("name"), column("note"), column("extra")];
+        let widened_data: Vec<_> = baseline_data.iter()
+            .map(|r| r.iter().cloned().chain(["x".to_string()]).collect())
+            .collect();
+        let changes = ChangeDetector::detect_changes(&baseline_schema, &baseline_data, &widened_schema, &widened_data).unwrap();
+        assert!(changes.apply_to(&baseline_schema, &baseline_data, &widened_schema, widened_data.len()).is_err());
+    }
+
+    #[test]
+    fn test_rename_detection_with_moved_column() {
+        let column = |name: &str, data_type: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+        };
+        let baseline = vec![column("id", "INTEGER"), column("name", "VARCHAR"), column("price", "DOUBLE")];
+        // "price" renamed to "cost" and moved before "name"
+        let current = vec![column("id", "INTEGER"), column("cost", "DOUBLE"), column("name", "VARCHAR")];
+
+        let baseline_data = vec![
+            vec!["1".to_string(), "Apple".to_string(), "1.5".to_string()],
+            vec!["2".to_string(), "Banana".to_string(), "0.75".to_string()],
+            vec!["3".to_string(), "Cherry".to_string(), "2.25".to_string()],
+        ];
+        let current_data = vec![
+            vec!["1".to_string(), "1.5".to_string(), "Apple".to_string()],
+            vec!["2".to_string(), "0.75".to_string(), "Banana".to_string()],
+            vec!["3".to_string(), "2.25".to_string(), "Cherry".to_string()],
+        ];
+
+        let changes = ChangeDetector::detect_changes_with_renames(
+            &baseline, &baseline_data, &current, &current_data,
+        ).unwrap().schema_changes;
+
+        assert_eq!(changes.columns_renamed.len(), 1);
+        assert_eq!(changes.columns_renamed[0].from, "price");
+        assert_eq!(changes.columns_renamed[0].to, "cost");
+        assert_eq!(changes.columns_renamed[0].confidence, Some(1.0));
+        assert!(changes.columns_added.is_empty());
+        assert!(changes.columns_removed.is_empty());
+        assert!(changes.column_order.is_some());
+
+        // Positional detection misreports the same change as two renames
+        let positional = ChangeDetector::detect_schema_changes(&baseline, &current).unwrap();
+        assert_eq!(positional.columns_renamed.len(), 2);
+    }
+
+    #[test]
+    fn test_modification_in_renamed_column() {
+        let column = |name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: "VARCHAR".to_string(),
+            nullable: true,
+        };
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let baseline = vec![column("id"), column("name"), column("price")];
+        let current = vec![column("id"), column("name"), column("cost")];
+
+        let baseline_data = vec![row(&["1", "Apple", "1.5"]), row(&["2", "Banana", "0.75"])];
+        // "price" renamed to "cost" and Banana's value changed in the same edit
+        let current_data = vec![row(&["1", "Apple", "1.5"]), row(&["2", "Banana", "0.80"])];
+
+        let changes = ChangeDetector::detect_changes(&baseline, &baseline_data, &current, &current_data).unwrap();
+
+        assert!(changes.row_changes.added.is_empty());
+        assert!(changes.row_changes.removed.is_empty());
+        assert_eq!(changes.row_changes.modified.len(), 1);
+        let modification = &changes.row_changes.modified[0];
+        assert_eq!(modification.row_index, 1);
+        assert_eq!(modification.changes.len(), 1);
+        assert_eq!(modification.changes["cost"].before, "0.75");
+        assert_eq!(modification.changes["cost"].after, "0.80");
+
+        let rebuilt = changes.apply_to(&baseline, &baseline_data, &current, current_data.len()).unwrap();
+        assert_eq!(rebuilt, current_data);
+        let operations = changes.generate_rollback_operations();
+        assert_eq!(RollbackOperation::apply_all(&operations, &current, &current_data), baseline_data);
+    }
+
+    #[test]
+    fn test_bool_coercion_reports_no_changes() {
+        let column = |name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: "VARCHAR".to_string(),
+            nullable: true,
+        };
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let schema = vec![column("id"), column("active")];
+
+        let baseline_data = vec![row(&["1", "true"]), row(&["2", "false"]), row(&["3", "maybe"])];
+        let current_data = vec![row(&["1", "1"]), row(&["2", "0"]), row(&["3", "maybe"])];
+
+        let uncoerced = ChangeDetector::detect_changes(&schema, &baseline_data, &schema, &current_data).unwrap();
+        assert_eq!(uncoerced.row_changes.modified.len(), 2);
+
+        let options = DetectionOptions::default()
+            .with_coercions(vec![ColumnCoercion::parse("active:bool").unwrap()]);
+        let changes = ChangeDetector::detect_changes_with_options(
+            &schema, &baseline_data, &schema, &current_data, &options,
+        ).unwrap();
+
+        assert!(!changes.row_changes.has_changes());
+        assert_eq!(changes.coercion_failures.len(), 1);
+        assert_eq!(changes.coercion_failures[0].column, "active");
+        assert_eq!(changes.coercion_failures[0].value, "maybe");
+    }
+
+    #[test]
+    fn test_type_change_coercion_suppresses_rerendered_values() {
+        let column = |data_type: &str| ColumnInfo {
+            name: "id".to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+        };
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let baseline_schema = vec![column("VARCHAR")];
+        let current_schema = vec![column("BIGINT")];
+        let baseline_data = vec![row(&["001"]), row(&["002"]), row(&["abc"])];
+        let current_data = vec![row(&["1"]), row(&["2"]), row(&["3"])];
+
+        let options = DetectionOptions::default().with_row_identity(RowIdentity::Positional);
+        let changes = ChangeDetector::detect_changes_with_options(
+            &baseline_schema, &baseline_data, &current_schema, &current_data, &options,
+        ).unwrap();
+        assert_eq!(changes.row_changes.modified.len(), 3);
+
+        let options = options.with_type_change_coercion(true);
+        let changes = ChangeDetector::detect_changes_with_options(
+            &baseline_schema, &baseline_data, &current_schema, &current_data, &options,
+        ).unwrap();
+        assert_eq!(changes.schema_changes.type_changes.len(), 1);
+        assert_eq!(changes.row_changes.modified.len(), 1);
+        assert_eq!(changes.row_changes.modified[0].row_index, 2);
+        assert!(changes.coercion_failures.is_empty());
+    }
+
+    #[test]
+    fn test_coerce_type_for_type_change() {
+        assert_eq!(CoerceType::for_type_change("INTEGER", "VARCHAR"), Some(CoerceType::Integer));
+        assert_eq!(CoerceType::for_type_change("VARCHAR", "DATE"), Some(CoerceType::Date));
+        assert_eq!(CoerceType::for_type_change("BIGINT", "INTEGER"), Some(CoerceType::Integer));
+        assert_eq!(CoerceType::for_type_change("BOOLEAN", "BIGINT"), None);
+        assert_eq!(CoerceType::for_type_change("DOUBLE", "VARCHAR"), None);
+    }
+
+    #[test]
+    fn test_within_float_tolerance() {
+        assert!(within_float_tolerance("1.5", "1.501", 0.001));
+        assert!(within_float_tolerance("0.1", "0.101", 0.001));
+        assert!(within_float_tolerance(" 2 ", "2.0", 0.0));
+        assert!(!within_float_tolerance("1.5", "1.502", 0.001));
+        assert!(!within_float_tolerance("1.5", "n/a", 1.0));
+        assert!(!within_float_tolerance("NaN", "NaN", 1.0));
+    }
+
+    #[test]
+    fn test_coerce_type_normalization() {
+        assert_eq!(CoerceType::Integer.normalize("1.0").as_deref(), Some("1"));
+        assert_eq!(CoerceType::Integer.normalize("007").as_deref(), Some("7"));
+        assert_eq!(CoerceType::Integer.normalize("1.5"), None);
+        assert_eq!(CoerceType::Date.normalize("2024/03/05").as_deref(), Some("2024-03-05"));
+        assert_eq!(CoerceType::Date.normalize("2024-03-05T10:00:00").as_deref(), Some("2024-03-05"));
+        assert_eq!(CoerceType::Bool.normalize("").as_deref(), Some(""));
+        assert!(ColumnCoercion::parse("active").is_err());
+        assert!(ColumnCoercion::parse("active:float").is_err());
+    }
+
+    #[test]
+    fn test_row_numbering_prefers_source_lines() {
+        let zero_based = RowNumbering::default();
+        assert_eq!(zero_based.current(0), 0);
+
+        let numbering = RowNumbering::one_based().with_current_lines(Some(vec![2, 3, 6]));
+        assert_eq!(numbering.current(0), 2);
+        assert_eq!(numbering.current(2), 6);
+        // Rows without a known line, and the baseline side here, are numbered from 1
+        assert_eq!(numbering.current(3), 4);
+        assert_eq!(numbering.baseline(0), 1);
+    }
+
+    #[test]
+    fn test_numeric_delta() {
+        let delta = |before: &str, after: &str| {
+            let mut change = CellChange::new(before, after);
+            change.add_numeric_delta();
+            (change.delta, change.pct_change.map(|pct| (pct * 10.0).round() / 10.0))
+        };
+
+        assert_eq!(delta("1.50", "1.75"), (Some(0.25), Some(16.7)));
+        assert_eq!(delta("0.1", "0.3"), (Some(0.2), Some(200.0)));
+        assert_eq!(delta("-2", "-1"), (Some(1.0), Some(50.0)));
+        assert_eq!(delta("0", "5"), (Some(5.0), None));
+        assert_eq!(delta("Apple", "5"), (None, None));
+        assert_eq!(delta("1", "inf"), (None, None));
+    }
+
+    #[test]
+    fn test_cell_predicate_parse_and_match() {
+        let modification = |column: &str, before: &str, after: &str| RowModification {
+            row_index: 0,
+            changes: HashMap::from([(column.to_string(), CellChange::new(before, after))]),
+        };
+
+        let decreased = CellPredicate::parse("price decreased").unwrap();
+        assert_eq!(decreased.column, "price");
+        assert!(decreased.matches(&modification("price", "10", "9.5")));
+        // Numbers compare numerically, not as text
+        assert!(!decreased.matches(&modification("price", "9", "10")));
+        assert!(!decreased.matches(&modification("name", "10", "9")));
+
+        let at_least = CellPredicate::parse("price>=10").unwrap();
+        assert_eq!(at_least.condition, CellCondition::After(Comparison::Ge, "10".to_string()));
+        assert!(at_least.matches(&modification("price", "1", "10.0")));
+
+        let from = CellPredicate::parse("status changed-from 'open'").unwrap();
+        assert!(from.matches(&modification("status", "open", "closed")));
+        assert!(CellPredicate::parse("name != Bob").unwrap().matches(&modification("name", "Bob", "Robert")));
+
+        assert!(CellPredicate::parse("price").is_err());
+        assert!(CellPredicate::parse("> 10").is_err());
+    }
+
+    #[test]
+    fn test_column_change_counts_sorted_by_count() {
+        let modification = |columns: &[&str]| RowModification {
+            row_index: 0,
+            changes: columns.iter().map(|column| (column.to_string(), CellChange::new("a", "b"))).collect(),
+        };
+        let row_changes = RowChanges {
+            modified: vec![modification(&["price", "name"]), modification(&["price"]), modification(&["qty"])],
+            ..Default::default()
+        };
+
+        assert_eq!(row_changes.column_change_counts(), vec![
+            ("price".to_string(), 2),
+            ("name".to_string(), 1),
+            ("qty".to_string(), 1),
+        ]);
+        assert!(RowChanges::default().column_change_counts().is_empty());
+    }
+
+    #[test]
+    fn test_row_identity_strategies_on_reordered_rows() {
+        let column = |name: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: "VARCHAR".to_string(),
+            nullable: true,
+        };
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let schema = vec![column("id"), column("name")];
+
+        let baseline_data = vec![row(&["1", "Alice"]), row(&["2", "Bob"]), row(&["3", "Carol"])];
+        let reordered = vec![row(&["3", "Carol"]), row(&["1", "Alice"]), row(&["2", "Bob"])];
+        let detect = |current: &[Vec<String>], identity: RowIdentity| {
+            let options = DetectionOptions::default().with_row_identity(identity);
+            ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, current, &options)
+                .unwrap()
+                .row_changes
+        };
+
+        let by_key = detect(&reordered, RowIdentity::Key(vec!["id".to_string()]));
+        assert!(!by_key.has_changes());
+
+        let by_position = detect(&reordered, RowIdentity::Positional);
+        assert_eq!(by_position.modified.len(), 3);
+        assert!(by_position.added.is_empty() && by_position.removed.is_empty());
+
+        assert!(!detect(&reordered, RowIdentity::ContentHash).has_changes());
+
+        let edited = vec![row(&["1", "Alice"]), row(&["2", "Robert"]), row(&["3", "Carol"])];
+        let by_content = detect(&edited, RowIdentity::ContentHash);
+        assert!(by_content.modified.is_empty());
+        assert_eq!((by_content.added.len(), by_content.removed.len()), (1, 1));
+
+        let by_key = detect(&edited, RowIdentity::Key(vec!["id".to_string()]));
+        assert_eq!(by_key.modified.len(), 1);
+        assert_eq!(by_key.modified[0].changes["name"].after, "Robert");
+
+        let options = DetectionOptions::default().with_row_identity(RowIdentity::Key(vec!["missing".to_string()]));
+        assert!(ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, &edited, &options).is_err());
+    }
+
+    #[test]
+    fn test_single_thread_detection_matches_parallel() {
+        let schema = vec![
+            ColumnInfo { name: "id".to_string(), data_type: "BIGINT".to_string(), nullable: true },
+            ColumnInfo { name: "value".to_string(), data_type: "VARCHAR".to_string(), nullable: true },
+        ];
+        let baseline_data: Vec<Vec<String>> = (0..500)
+            .map(|i| vec![i.to_string(), format!("v{}", i)])
+            .collect();
+        let current_data: Vec<Vec<String>> = (0..520)
+            .filter(|i| i % 50 != 0)
+            .map(|i| vec![i.to_string(), if i % 7 == 0 { format!("w{}", i) } else { format!("v{}", i) }])
+            .collect();
+
+        let detect = |threads| {
+            let options = DetectionOptions::default().with_threads(threads);
+            let result = ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, &current_data, &options).unwrap();
+            serde_json::to_value(&result).unwrap()
+        };
+
+        assert_eq!(detect(Some(1)), detect(None));
+        assert_eq!(detect(Some(1)), detect(Some(4)));
+    }
+
+    #[test]
+    fn test_cancelled_detection_stops() {
+        let schema = vec![ColumnInfo { name: "id".to_string(), data_type: "BIGINT".to_string(), nullable: true }];
+        let baseline_data: Vec<Vec<String>> = (0..100).map(|i| vec![i.to_string()]).collect();
+        let current_data: Vec<Vec<String>> = (50..150).map(|i| vec![i.to_string()]).collect();
+
+        let cancellation = crate::cancel::CancellationToken::new();
+        let options = DetectionOptions::default().with_cancellation(cancellation.clone());
+        assert!(ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, &current_data, &options).is_ok());
+
+        cancellation.cancel();
+        let result = ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, &current_data, &options);
+        assert!(matches!(result, Err(crate::error::TabdiffError::Cancelled)));
+    }
+
+    #[test]
+    fn test_column_overlap() {
+        let schema = |names: &[&str]| -> Vec<ColumnInfo> {
+            names.iter()
+                .map(|name| ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+                .collect()
+        };
+
+        assert_eq!(ChangeDetector::column_overlap(&schema(&["id", "name"]), &schema(&["name", "id"])), 1.0);
+        assert_eq!(ChangeDetector::column_overlap(&schema(&["id", "name", "price"]), &schema(&["id", "name", "email"])), 0.5);
+        assert_eq!(ChangeDetector::column_overlap(&schema(&["order_id", "total"]), &schema(&["user_id", "email"])), 0.0);
+        assert_eq!(ChangeDetector::column_overlap(&[], &[]), 1.0);
+    }
+
+    #[test]
+    fn test_detect_appended_rows() {
+        let schema: Vec<ColumnInfo> = ["id", "event"].iter()
+            .map(|name| ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+            .collect();
+        let tail = vec![
+            vec!["2".to_string(), "logout".to_string()],
+            vec!["3".to_string(), "login".to_string()],
+        ];
+
+        // The tail starts at row 2 of the current side, after the two baseline rows
+        let appended = ChangeDetector::detect_appended_rows(&schema, &tail, 2).unwrap();
+        assert!(appended.row_changes.modified.is_empty() && appended.row_changes.removed.is_empty());
+        assert!(!appended.schema_changes.has_changes());
+        let mut added = appended.row_changes.added.clone();
+        added.sort_by_key(|addition| addition.row_index);
+        assert_eq!(added.iter().map(|addition| addition.row_index).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(added[0].data["event"], "logout");
+    }
+
+    #[test]
+    fn test_group_row_changes_by_column() {
+        let schema: Vec<ColumnInfo> = ["id", "category"].iter()
+            .map(|name| ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+            .collect();
+        let baseline_data = vec![
+            vec!["1".to_string(), "fruit".to_string()],
+            vec!["2".to_string(), "fruit".to_string()],
+            vec!["3".to_string(), "veg".to_string()],
+            vec!["4".to_string(), "veg".to_string()],
+        ];
+        // 1 moves to veg, 2 is unchanged, 3 is removed, 4 is unchanged, 5 and 6 are added
+        let current_data = vec![
+            vec!["1".to_string(), "veg".to_string()],
+            vec!["2".to_string(), "fruit".to_string()],
+            vec!["4".to_string(), "veg".to_string()],
+            vec!["5".to_string(), "fruit".to_string()],
+            vec!["6".to_string(), "".to_string()],
+        ];
+        let options = DetectionOptions::default().with_row_identity(RowIdentity::Key(vec!["id".to_string()]));
+        let changes = ChangeDetector::detect_changes_with_options(&schema, &baseline_data, &schema, &current_data, &options).unwrap();
+
+        let grouped = changes.row_changes.group_by("category", &schema, &schema, &current_data).unwrap();
+        let counts: Vec<(&str, usize, usize, usize)> = grouped.groups.iter()
+            .map(|group| (group.value.as_str(), group.modified, group.added, group.removed))
+            .collect();
+        assert_eq!(counts, vec![("veg", 1, 0, 1), ("", 0, 1, 0), ("fruit", 0, 1, 0)]);
+
+        assert!(changes.row_changes.group_by("region", &schema, &schema, &current_data).is_err());
+    }
+
+    #[test]
+    fn test_invert_matches_reverse_comparison() {
+        let schema = |names: &[&str]| -> Vec<ColumnInfo> {
+            names.iter()
+                .map(|name| ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+                .collect()
+        };
+        let rows = |rows: &[&[&str]]| -> Vec<Vec<String>> {
+            rows.iter().map(|row| row.iter().map(|value| value.to_string()).collect()).collect()
+        };
+        let a_schema = schema(&["id", "name", "price"]);
+        let a_rows = rows(&[&["1", "apple", "1.50"], &["2", "pear", "2.00"], &["3", "plum", "3.00"], &["4", "fig", "4.00"]]);
+        let b_schema = schema(&["id", "name", "price", "stock"]);
+        let b_rows = rows(&[&["1", "apple", "1.50", "7"], &["2", "pear", "2.00", "0"], &["4", "fig", "4.40", "3"], &["5", "kiwi", "5.00", "9"]]);
+
+        // Sorted, order-independent description of a result
+        fn describe(result: &ChangeDetectionResult) -> Vec<String> {
+            let sorted = |data: &HashMap<String, String>| format!("{:?}", data.iter().collect::<std::collections::BTreeMap<_, _>>());
+            let mut lines: Vec<String> = result.row_changes.modified.iter()
+                .map(|m| format!("modified {} {:?}", m.row_index, m.changes.iter()
+                    .map(|(column, change)| (column, &change.before, &change.after))
+                    .collect::<std::collections::BTreeSet<_>>()))
+                .chain(result.row_changes.added.iter().map(|a| format!("added {} {}", a.row_index, sorted(&a.data))))
+                .chain(result.row_changes.removed.iter().map(|r| format!("removed {} {}", r.row_index, sorted(&r.data))))
+                .chain(result.schema_changes.columns_added.iter().map(|c| format!("column added {} at {}", c.name, c.position)))
+                .chain(result.schema_changes.columns_removed.iter().map(|c| format!("column removed {} at {}", c.name, c.position)))
+                .collect();
+            lines.sort();
+            lines
+        }
+
+        for identity in [RowIdentity::Auto, RowIdentity::Key(vec!["id".to_string()])] {
+            let options = DetectionOptions::default().with_row_identity(identity);
+            let forward = ChangeDetector::detect_changes_with_options(&a_schema, &a_rows, &b_schema, &b_rows, &options).unwrap();
+            let backward = ChangeDetector::detect_changes_with_options(&b_schema, &b_rows, &a_schema, &a_rows, &options).unwrap();
+
+            let inverted = forward.invert(&a_schema, &a_rows);
+            assert_eq!(describe(&inverted), describe(&backward));
+            assert!(!describe(&inverted).is_empty());
+            // Inverting twice gives the original changes back
+            assert_eq!(describe(&inverted.invert(&b_schema, &b_rows)), describe(&forward));
+        }
     }
 }
@@ -0,0 +1,160 @@
+//! Checkpoints of partially computed row hashes, so an interrupted snapshot can resume
+//!
+//! A checkpoint file starts with a header line naming the input it belongs to, followed by
+//! one row hash per line in row order. Lines are only appended, so a run that dies mid-write
+//! leaves at most one incomplete trailing line, which is ignored on resume.
+
+use crate::error::Result;
+use crate::hash::RowHash;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const HEADER: &str = "tabdiff-hash-checkpoint v1";
+
+/// Where row hashing records its progress, and whether to continue from an earlier run
+#[derive(Debug, Clone)]
+pub struct HashCheckpoint {
+    path: PathBuf,
+    /// Identifies the input and hashing settings; checkpoints of anything else are discarded
+    fingerprint: String,
+    resume: bool,
+}
+
+impl HashCheckpoint {
+    pub fn new(path: PathBuf, fingerprint: String) -> Self {
+        Self {
+            path,
+            fingerprint,
+            resume: false,
+        }
+    }
+
+    /// Continue from the hashes an earlier run left in the checkpoint instead of starting over
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Row hashes recorded by an earlier run of the same input (empty unless resuming)
+    pub fn load(&self) -> Result<Vec<RowHash>> {
+        if !self.resume || !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        if line.trim_end() != self.header() {
+            log::warn!("Ignoring checkpoint {}: it belongs to a different input or settings", self.path.display());
+            return Ok(Vec::new());
+        }
+
+        let mut hashes = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            // An unterminated or garbled line is where the earlier run was interrupted
+            let hash = match line.strip_suffix('\n') {
+                Some(hash) if !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()) => hash,
+                _ => break,
+            };
+            hashes.push(RowHash {
+                row_index: hashes.len() as u64,
+                hash: hash.to_string(),
+            });
+        }
+        Ok(hashes)
+    }
+
+    /// Start recording, with the file holding exactly the `resumed` hashes
+    pub fn writer(&self, resumed: &[RowHash]) -> Result<CheckpointWriter> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = CheckpointWriter {
+            file: BufWriter::new(File::create(&self.path)?),
+        };
+        writeln!(writer.file, "{}", self.header())?;
+        for row_hash in resumed {
+            writer.record(row_hash)?;
+        }
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    /// Delete the checkpoint once the snapshot it was for has been written
+    pub fn remove(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn header(&self) -> String {
+        format!("{} {}", HEADER, self.fingerprint)
+    }
+}
+
+/// Appends row hashes to a checkpoint file
+pub struct CheckpointWriter {
+    file: BufWriter<File>,
+}
+
+impl CheckpointWriter {
+    pub fn record(&mut self, row_hash: &RowHash) -> Result<()> {
+        writeln!(self.file, "{}", row_hash.hash)?;
+        Ok(())
+    }
+
+    /// Make everything recorded so far durable for a later resume
+    pub fn flush(&mut self) -> Result<()> {
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn row_hash(row_index: u64, hash: &str) -> RowHash {
+        RowHash {
+            row_index,
+            hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip_ignores_partial_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let checkpoint = HashCheckpoint::new(temp_dir.path().join("v1.checkpoint"), "input-a".to_string());
+
+        let mut writer = checkpoint.writer(&[row_hash(0, "aa01")]).unwrap();
+        writer.record(&row_hash(1, "bb02")).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        // A run killed mid-write leaves an unterminated line behind
+        let mut file = fs::OpenOptions::new().append(true).open(checkpoint.path()).unwrap();
+        write!(file, "cc0").unwrap();
+
+        assert!(checkpoint.load().unwrap().is_empty());
+        let resumed = checkpoint.clone().with_resume(true).load().unwrap();
+        assert_eq!(resumed.len(), 2);
+        assert_eq!(resumed[1].row_index, 1);
+        assert_eq!(resumed[1].hash, "bb02");
+
+        let other_input = HashCheckpoint::new(checkpoint.path().to_path_buf(), "input-b".to_string()).with_resume(true);
+        assert!(other_input.load().unwrap().is_empty());
+
+        checkpoint.remove().unwrap();
+        assert!(!checkpoint.path().exists());
+    }
+}
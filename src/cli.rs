@@ -11,13 +11,25 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
     
-    /// Override workspace location
+    /// Look for the workspace from this directory instead of the current one (env: TABDIFF_WORKSPACE)
     #[arg(long, global = true)]
     pub workspace: Option<PathBuf>,
     
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     pub verbose: bool,
+    
+    /// DuckDB memory limit, e.g. "512MB" or "4GB" (env: TABDIFF_DUCKDB_MEMORY_LIMIT)
+    #[arg(long, global = true, value_parser = crate::duckdb_config::parse_memory_limit)]
+    pub duckdb_memory_limit: Option<String>,
+    
+    /// Number of DuckDB worker threads (env: TABDIFF_DUCKDB_THREADS)
+    #[arg(long, global = true, value_parser = crate::duckdb_config::parse_threads)]
+    pub duckdb_threads: Option<usize>,
+    
+    /// Progress output format: "text" or "json" (newline-delimited events on stderr)
+    #[arg(long, global = true, value_parser = crate::progress::ProgressFormat::parse)]
+    pub progress_format: Option<crate::progress::ProgressFormat>,
 }
 
 #[derive(Subcommand)]
@@ -27,16 +39,32 @@ pub enum Commands {
         /// Force initialization even if workspace exists
         #[arg(long)]
         force: bool,
+        
+        /// Name of the workspace directory (default: .tabdiff), recorded in .tabdiffrc
+        #[arg(long, value_parser = validate_workspace_dir)]
+        dir: Option<String>,
+        
+        /// Do not add snapshot archives to the project's .gitignore
+        #[arg(long)]
+        no_gitignore: bool,
     },
     
     /// Create a snapshot of structured data
+    #[command(group(clap::ArgGroup::new("replace").args(["overwrite", "update"])))]
     Snapshot {
-        /// Input file or directory path
-        input: String,
+        /// Input file or directory path, or git:<rev>:<path> for a file at a git revision
+        #[arg(required_unless_present = "from_query")]
+        input: Option<String>,
+        
+        /// Snapshot the result of this SQL query instead of an input file,
+        /// e.g. "SELECT * FROM read_csv('a.csv') WHERE x > 0"
+        #[arg(long, value_name = "SQL", conflicts_with_all = ["input", "no_header", "encoding", "json_path"])]
+        from_query: Option<String>,
         
-        /// Name for the snapshot
+        /// Name for the snapshot (default: the source's file name and its next sequence
+        /// number in the chain, e.g. sales_0003)
         #[arg(long)]
-        name: String,
+        name: Option<String>,
         
         /// Batch size for processing rows (must be > 0)
         #[arg(long, default_value = "10000", value_parser = validate_batch_size)]
@@ -51,6 +79,103 @@ pub enum Commands {
         /// Recommended for very large files (>1GB) to improve performance
         #[arg(long, conflicts_with = "full_data")]
         hash_only: bool,
+        
+        /// Record only the schema, row count and schema hash: no row hashes and no archive
+        /// The snapshot supports schema comparison only (for drift detection on very large data)
+        #[arg(long, conflicts_with_all = ["full_data", "hash_only"])]
+        metadata_only: bool,
+        
+        /// Treat the first row of a CSV/TSV file as data instead of a header
+        /// Column names are generated as column0, column1, ...
+        #[arg(long)]
+        no_header: bool,
+        
+        /// Sort rows by these columns before hashing so row indices are reproducible
+        /// Comma-separated, e.g. --order-by id,date; ties are broken by the remaining columns
+        #[arg(long, value_delimiter = ',')]
+        order_by: Vec<String>,
+        
+        /// Round floating-point values to this many decimal places before hashing (0-17)
+        /// Makes the same numbers hash identically across formats, e.g. CSV and Parquet
+        #[arg(long, value_parser = validate_float_precision)]
+        float_precision: Option<usize>,
+        
+        /// Read these strings as NULL (empty cells), e.g. --na-values NA,N/A,NULL,-
+        /// Recorded in the snapshot so status reads the source the same way
+        #[arg(long, value_name = "LIST", value_delimiter = ',')]
+        na_values: Vec<String>,
+        
+        /// Text encoding of a CSV/TSV/JSON input: utf-8 (default), latin-1 or windows-1252
+        #[arg(long, value_parser = crate::encoding::SourceEncoding::parse)]
+        encoding: Option<crate::encoding::SourceEncoding>,
+        
+        /// Describe why the snapshot was taken, e.g. "pre-migration baseline"
+        #[arg(short, long)]
+        message: Option<String>,
+        
+        /// Use the array at this JSON Pointer of a JSON input as the rows, e.g. /data/items
+        #[arg(long, value_name = "POINTER", value_parser = validate_json_pointer)]
+        json_path: Option<String>,
+        
+        /// Continue hashing from the checkpoint left by an interrupted run of this snapshot
+        #[arg(long, conflicts_with = "metadata_only")]
+        resume: bool,
+        
+        /// Archive compression from 0 (store only, fastest) to 9 (smallest, slowest); default 3
+        #[arg(long, value_name = "0-9", value_parser = crate::archive::CompressionLevel::parse, conflicts_with = "metadata_only")]
+        compression_level: Option<crate::archive::CompressionLevel>,
+        
+        /// Sign the archive with the secret in this key file, so `tabdiff verify --check-signature`
+        /// can detect later changes to it
+        #[arg(long, value_name = "FILE", conflicts_with = "metadata_only")]
+        sign_key: Option<PathBuf>,
+        
+        /// Continue this named chain instead of the chain of the input's path, so a dataset that
+        /// moved or lives at different paths on different machines keeps one history
+        #[arg(long, value_name = "NAME", conflicts_with = "all_tables")]
+        chain: Option<String>,
+        
+        /// Keep only this share of the rows, e.g. 0.1 for a tenth; rows are picked by a seeded
+        /// hash of their content, so unchanged rows stay in the sample from one snapshot to the next
+        #[arg(long, value_name = "FRACTION", value_parser = validate_sample_fraction, conflicts_with_all = ["from_query", "all_tables"])]
+        sample: Option<f64>,
+        
+        /// Seed of --sample; snapshots taken with the same fraction and seed keep the same rows
+        #[arg(long, value_name = "N", default_value_t = 0, requires = "sample")]
+        sample_seed: u64,
+        
+        /// Fail without writing the snapshot unless the rows pass this rule: no-nulls=COL,
+        /// unique=COL[,COL...], row-count-min=N or row-count-max=N (repeatable; all must hold)
+        #[arg(long = "assert", value_name = "RULE", value_parser = crate::assertions::DataAssertion::parse)]
+        assertions: Vec<crate::assertions::DataAssertion>,
+        
+        /// Snapshot every table of a .sql input (from its attached database or setup statements)
+        /// separately, as <name>::<table>; each table is chained on its own
+        #[arg(long, conflicts_with_all = ["from_query", "resume", "json_path", "no_header"])]
+        all_tables: bool,
+        
+        /// Load and hash the input and show the row count, parent and delta the snapshot would
+        /// record, without writing anything
+        #[arg(long, conflicts_with_all = ["metadata_only", "resume"])]
+        dry_run: bool,
+        
+        /// Replace the snapshot with this name if it exists, keeping its place in the chain
+        #[arg(long, requires = "name", conflicts_with_all = ["all_tables", "resume"])]
+        overwrite: bool,
+        
+        /// Like --overwrite, but leave the snapshot untouched when the source file's content
+        /// matches the one it was taken from
+        #[arg(long, requires = "name", conflicts_with_all = ["all_tables", "resume"])]
+        update: bool,
+        
+        /// With --overwrite or --update, also replace a snapshot that other snapshots are chained to,
+        /// recomputing their deltas against the new data
+        #[arg(long, requires = "replace")]
+        force: bool,
+        
+        /// Print the wall-clock time of each phase (load, hashing, delta, archive) to stderr
+        #[arg(long)]
+        timings: bool,
     },
     
     /// Compare two snapshots
@@ -59,15 +184,180 @@ pub enum Commands {
         snapshot1: String,
         
         /// Second snapshot name
-        snapshot2: String,
+        #[arg(required_unless_present_any = ["against_parent", "since"])]
+        snapshot2: Option<String>,
+        
+        /// Compare the first snapshot to its parent in the chain instead of to a second snapshot
+        /// (uses the cached delta when the comparison options allow it)
+        #[arg(long, conflicts_with_all = ["snapshot2", "baseline_format", "current_format"])]
+        against_parent: bool,
+        
+        /// Show the net changes since this ancestor of the first snapshot in its chain
+        #[arg(long, value_name = "SNAPSHOT", conflicts_with_all = ["snapshot2", "against_parent", "baseline_format", "current_format"])]
+        since: Option<String>,
+        
+        /// With --since, also summarize each step of the chain, including changes later undone
+        #[arg(long, requires = "since", conflicts_with_all = ["stat", "format"])]
+        steps: bool,
         
         /// Diff mode: "quick", "detailed", or "auto"
         #[arg(long, default_value = "auto")]
         mode: String,
         
         /// Custom output file for diff results
-        #[arg(long)]
+        /// The extension selects the format: .json (default), .csv or .parquet (one row per change)
+        #[arg(short, long)]
         output: Option<PathBuf>,
+        
+        /// Field delimiter for .csv/.tsv --output files (default: tab for .tsv, comma otherwise)
+        #[arg(long, value_name = "CHAR", value_parser = validate_delimiter)]
+        delimiter: Option<char>,
+        
+        /// Detect renamed columns by comparing their values instead of their positions
+        #[arg(long)]
+        detect_renames: bool,
+        
+        /// Only show these change categories: schema, rows, added, removed, modified (repeatable)
+        #[arg(long, value_parser = crate::output::ChangeCategory::parse)]
+        only: Vec<crate::output::ChangeCategory>,
+        
+        /// Also output the full state of every unchanged row (can be as large as the table)
+        #[arg(long)]
+        include_unchanged: bool,
+        
+        /// Maximum number of unchanged rows written by --include-unchanged
+        #[arg(long, default_value_t = 10000)]
+        max_unchanged: usize,
+        
+        /// Include N unchanged rows before and after each changed row as context
+        #[arg(long, value_name = "N")]
+        context: Option<usize>,
+        
+        /// Report the changes in the opposite direction, as if the two snapshots had been given the
+        /// other way round (reuses a cached delta instead of comparing again)
+        #[arg(long)]
+        invert: bool,
+        
+        /// Print only a git-style one-line summary of the change counts (the diff file is still written)
+        #[arg(long)]
+        stat: bool,
+        
+        /// Print the changes as a report in this format instead (junit: one failing test case per change;
+        /// csv: one row per change, also written to --output whatever its extension)
+        #[arg(long, value_parser = crate::output::ReportFormat::parse, conflicts_with = "stat")]
+        format: Option<crate::output::ReportFormat>,
+        
+        /// Classify each schema change as backward-compatible, forward-compatible or breaking
+        /// (also added to the JSON diff as "compatibility")
+        #[arg(long, conflicts_with_all = ["stat", "format"])]
+        compat: bool,
+        
+        /// For numeric cell changes, show after − before and the percent change, e.g.
+        /// 1.50 → 1.75 (+0.25, +16.7%) (also added to the JSON diff as "delta" and "pct_change")
+        #[arg(long, conflicts_with_all = ["stat", "format"])]
+        numeric_delta: bool,
+        
+        /// Width in characters of the changed-cells table (default: the terminal's width, or 100
+        /// when output isn't a terminal)
+        #[arg(long, value_name = "N", conflicts_with_all = ["stat", "format"])]
+        pretty_width: Option<usize>,
+        
+        /// Read the first argument as a data file in this format (csv, tsv, parquet, json or avro)
+        /// instead of resolving it as a snapshot
+        #[arg(long, value_parser = crate::data::InputFormat::parse)]
+        baseline_format: Option<crate::data::InputFormat>,
+        
+        /// Read the second argument as a data file in this format (csv, tsv, parquet, json or avro)
+        /// instead of resolving it as a snapshot
+        #[arg(long, value_parser = crate::data::InputFormat::parse)]
+        current_format: Option<crate::data::InputFormat>,
+        
+        /// Normalize a column to bool, integer or date on both sides before comparing, as column:type (repeatable)
+        #[arg(long, value_name = "COL:TYPE", value_parser = crate::change_detection::ColumnCoercion::parse)]
+        coerce: Vec<crate::change_detection::ColumnCoercion>,
+        
+        /// Treat numeric cells that differ by at most this much as unchanged, e.g. 0.001
+        /// (default: the workspace's `float-tolerance` config, else exact comparison)
+        #[arg(long, value_name = "EPSILON", value_parser = validate_float_tolerance)]
+        float_tolerance: Option<f64>,
+        
+        /// Compare the cells of a column whose type changed (e.g. INTEGER to VARCHAR) as that type,
+        /// so values that were only re-rendered, like 001 and 1, are not reported as modified
+        #[arg(long)]
+        report_schema_only_changes: bool,
+        
+        /// Pair an added and a removed row into a modification when more than this share of
+        /// their common columns are equal; lower pairs more eagerly, higher keeps more separate
+        /// additions and removals (rows at the same position always pair)
+        #[arg(long, value_name = "FRACTION", default_value_t = crate::change_detection::DEFAULT_MATCH_THRESHOLD, value_parser = validate_fraction)]
+        match_threshold: f64,
+        
+        /// When the snapshot holds a sample (`snapshot --sample`), sample the current file at the
+        /// same fraction and seed instead of refusing to compare a sample with complete data
+        #[arg(long)]
+        auto_sample: bool,
+        
+        /// For sources that only grow: when the second side starts with the first side's rows
+        /// (verified against the row order hash recorded in the first side, which must be a
+        /// snapshot), read and report only the rows after them, as additions; anything else
+        /// falls back to a full diff with a warning
+        #[arg(long, conflicts_with_all = ["key", "ignore_row_order", "include_unchanged", "context", "na_values"])]
+        append_only: bool,
+        
+        /// Compare these strings as empty cells, e.g. --na-values NA,N/A,NULL,-
+        #[arg(long, value_name = "LIST", value_delimiter = ',')]
+        na_values: Vec<String>,
+        
+        /// Match rows by these key columns instead of by position and content (comma-separated)
+        #[arg(long, value_name = "COLS", value_delimiter = ',')]
+        key: Vec<String>,
+        
+        /// Compare rows as a set: report only additions and removals, never modifications
+        /// For data whose row order carries no meaning, e.g. unordered exports
+        #[arg(long, conflicts_with = "key")]
+        ignore_row_order: bool,
+        
+        /// Accept a --key shared by several rows, pairing those rows by content and then in
+        /// file order (default: a non-unique key is an error)
+        #[arg(long, requires = "key")]
+        allow_duplicate_keys: bool,
+        
+        /// Keep only modified rows whose cell satisfies this predicate, e.g. "price decreased",
+        /// "price > 10" or "status changed-from open" (repeatable; all must hold)
+        #[arg(long = "where", value_name = "PREDICATE", value_parser = crate::change_detection::CellPredicate::parse)]
+        where_predicates: Vec<crate::change_detection::CellPredicate>,
+        
+        /// Number reported rows from 1, using the line each row starts on for CSV/TSV files
+        /// (default: zero-based row positions)
+        #[arg(long)]
+        line_numbers: bool,
+        
+        /// Also count changed rows per value of this column, e.g. --group-by category
+        /// (modified and added rows by their current value, removed rows by their old one)
+        #[arg(long, value_name = "COLUMN")]
+        group_by: Option<String>,
+        
+        /// Name changed rows by this column's value instead of their index, e.g. --label-column id
+        /// prints "Row id=42" (rows without the column keep their index)
+        #[arg(long, value_name = "COLUMN")]
+        label_column: Option<String>,
+        
+        /// Worker threads for change detection (0 or unset uses all cores)
+        #[arg(long, value_name = "N")]
+        threads: Option<usize>,
+        
+        /// Directory for the default diff file instead of .tabdiff/diffs
+        #[arg(long, value_name = "DIR", conflicts_with = "output")]
+        output_dir: Option<PathBuf>,
+        
+        /// Add "timestamp" or "source-hash" to the default diff filename so diffs
+        /// between equally named snapshots don't overwrite each other
+        #[arg(long, value_name = "SUFFIX", value_parser = crate::workspace::DiffNameSuffix::parse, conflicts_with = "output")]
+        name_suffix: Option<crate::workspace::DiffNameSuffix>,
+        
+        /// Print the wall-clock time of each phase (loading, schema, row and cell comparison) to stderr
+        #[arg(long)]
+        timings: bool,
     },
     
     /// Show snapshot information
@@ -79,9 +369,56 @@ pub enum Commands {
         #[arg(long)]
         detailed: bool,
         
+        /// Show the changes from the parent snapshot (uses the cached delta)
+        #[arg(long)]
+        diff_parent: bool,
+        
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        
+        /// Print only the column schema, as "jsonschema" or "ddl" (CREATE TABLE)
+        #[arg(long, value_name = "FORMAT", value_parser = crate::schema_export::SchemaFormat::parse, conflicts_with_all = ["detailed", "diff_parent", "json"])]
+        schema_format: Option<crate::schema_export::SchemaFormat>,
+        
+        /// List the files inside the snapshot archive with their sizes, for debugging
+        #[arg(long, conflicts_with_all = ["detailed", "diff_parent", "schema_format"])]
+        raw: bool,
+        
+        /// With --raw, print the content of this archive file instead, e.g. schema.json
+        #[arg(long, value_name = "NAME", requires = "raw")]
+        file: Option<String>,
+    },
+    
+    /// Write a snapshot's rows to a file (requires a snapshot with full data)
+    Export {
+        /// Snapshot to export
+        snapshot: String,
+        
+        /// File to write, relative to the workspace root unless absolute
+        output: PathBuf,
+        
+        /// Output format: csv, tsv, parquet or a registered format (default: from the file extension)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<String>,
+        
+        /// Field delimiter for delimited formats (default: tab for tsv, comma otherwise)
+        #[arg(long, value_name = "CHAR", value_parser = validate_delimiter)]
+        delimiter: Option<char>,
+    },
+    
+    /// Check that a snapshot's archive is intact: readable, with its shared payloads present
+    Verify {
+        /// Snapshot to verify
+        snapshot: String,
+        
+        /// Also check the signature recorded by `snapshot --sign-key`; fails for unsigned snapshots
+        #[arg(long, requires = "sign_key")]
+        check_signature: bool,
+        
+        /// Key file the snapshot was signed with
+        #[arg(long, value_name = "FILE", requires = "check_signature")]
+        sign_key: Option<PathBuf>,
     },
     
     /// Check current data against a snapshot
@@ -89,10 +426,20 @@ pub enum Commands {
         /// Input file or directory path
         input: String,
         
-        /// Snapshot to compare against (defaults to latest)
+        /// Snapshot to compare against (defaults to the latest snapshot of the same source)
         #[arg(long)]
         compare_to: Option<String>,
         
+        /// Check the file for internal consistency instead of comparing it to a snapshot:
+        /// mixed-type columns, rows with a deviating field count and columns without values
+        #[arg(long, conflicts_with_all = ["compare_to", "quiet", "format", "detect_renames", "only", "watch", "coerce", "float_tolerance", "report_schema_only_changes", "match_threshold", "auto_sample", "key", "ignore_row_order", "where_predicates", "line_numbers", "group_by", "output", "max_iterations", "threads", "min_column_overlap", "strict"])]
+        against_self: bool,
+        
+        /// Compare only the schemas: rows of the file and the snapshot are not loaded, and a
+        /// schema hash matching the snapshot's ends the check early
+        #[arg(long, conflicts_with_all = ["against_self", "only", "detect_renames", "coerce", "float_tolerance", "report_schema_only_changes", "match_threshold", "key", "ignore_row_order", "where_predicates", "line_numbers", "group_by"])]
+        schema_only: bool,
+        
         /// Quiet output (machine-readable)
         #[arg(long)]
         quiet: bool,
@@ -100,10 +447,140 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        
+        /// Also save the full change detection result as JSON to this file, whatever is printed
+        /// (relative paths are resolved against the workspace root; rewritten on every check with --watch)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Print the changes as a report in this format instead (junit: one failing test case per change)
+        #[arg(long, value_parser = crate::output::ReportFormat::parse, conflicts_with_all = ["quiet", "json", "watch"])]
+        format: Option<crate::output::ReportFormat>,
+        
+        /// Detect renamed columns by comparing their values instead of their positions
+        #[arg(long)]
+        detect_renames: bool,
+        
+        /// Only show these change categories: schema, rows, added, removed, modified (repeatable)
+        #[arg(long, value_parser = crate::output::ChangeCategory::parse)]
+        only: Vec<crate::output::ChangeCategory>,
+        
+        /// Re-check every N seconds, printing only when the status changes (Ctrl-C to stop)
+        #[arg(long, visible_alias = "follow", value_name = "SECONDS", value_parser = crate::watch::WatchOptions::parse_interval)]
+        watch: Option<std::time::Duration>,
+        
+        /// Stop watching after this many checks
+        #[arg(long, requires = "watch")]
+        max_iterations: Option<usize>,
+        
+        /// Text encoding of the input (defaults to the encoding recorded in the snapshot)
+        #[arg(long, value_parser = crate::encoding::SourceEncoding::parse)]
+        encoding: Option<crate::encoding::SourceEncoding>,
+        
+        /// Read the input in this format (csv, tsv, parquet, json or avro) instead of detecting it from the extension
+        #[arg(long, value_parser = crate::data::InputFormat::parse)]
+        current_format: Option<crate::data::InputFormat>,
+        
+        /// Use the array at this JSON Pointer as the rows (defaults to the path recorded in the snapshot)
+        #[arg(long, value_name = "POINTER", value_parser = validate_json_pointer)]
+        json_path: Option<String>,
+        
+        /// Normalize a column to bool, integer or date on both sides before comparing, as column:type (repeatable)
+        #[arg(long, value_name = "COL:TYPE", value_parser = crate::change_detection::ColumnCoercion::parse)]
+        coerce: Vec<crate::change_detection::ColumnCoercion>,
+        
+        /// Treat numeric cells that differ by at most this much as unchanged, e.g. 0.001
+        /// (default: the workspace's `float-tolerance` config, else exact comparison)
+        #[arg(long, value_name = "EPSILON", value_parser = validate_float_tolerance)]
+        float_tolerance: Option<f64>,
+        
+        /// Compare the cells of a column whose type changed (e.g. INTEGER to VARCHAR) as that type,
+        /// so values that were only re-rendered, like 001 and 1, are not reported as modified
+        #[arg(long)]
+        report_schema_only_changes: bool,
+        
+        /// Pair an added and a removed row into a modification when more than this share of
+        /// their common columns are equal; lower pairs more eagerly, higher keeps more separate
+        /// additions and removals (rows at the same position always pair)
+        #[arg(long, value_name = "FRACTION", default_value_t = crate::change_detection::DEFAULT_MATCH_THRESHOLD, value_parser = validate_fraction)]
+        match_threshold: f64,
+        
+        /// When the snapshot holds a sample (`snapshot --sample`), sample the current file at the
+        /// same fraction and seed instead of refusing to compare a sample with complete data
+        #[arg(long)]
+        auto_sample: bool,
+        
+        /// Compare these strings as empty cells, e.g. --na-values NA,N/A,NULL,-
+        #[arg(long, value_name = "LIST", value_delimiter = ',')]
+        na_values: Vec<String>,
+        
+        /// Match rows by these key columns instead of by position and content (comma-separated)
+        #[arg(long, value_name = "COLS", value_delimiter = ',')]
+        key: Vec<String>,
+        
+        /// Compare rows as a set: report only additions and removals, never modifications
+        /// For data whose row order carries no meaning, e.g. unordered exports
+        #[arg(long, conflicts_with = "key")]
+        ignore_row_order: bool,
+        
+        /// Accept a --key shared by several rows, pairing those rows by content and then in
+        /// file order (default: a non-unique key is an error)
+        #[arg(long, requires = "key")]
+        allow_duplicate_keys: bool,
+        
+        /// Keep only modified rows whose cell satisfies this predicate, e.g. "price decreased",
+        /// "price > 10" or "status changed-from open" (repeatable; all must hold)
+        #[arg(long = "where", value_name = "PREDICATE", value_parser = crate::change_detection::CellPredicate::parse)]
+        where_predicates: Vec<crate::change_detection::CellPredicate>,
+        
+        /// Number reported rows from 1, using the line each row starts on for CSV/TSV files
+        /// (default: zero-based row positions)
+        #[arg(long)]
+        line_numbers: bool,
+        
+        /// Also count changed rows per value of this column, e.g. --group-by category
+        /// (modified and added rows by their current value, removed rows by their old one)
+        #[arg(long, value_name = "COLUMN")]
+        group_by: Option<String>,
+        
+        /// Worker threads for change detection (0 or unset uses all cores)
+        #[arg(long, value_name = "N")]
+        threads: Option<usize>,
+        
+        /// Warn when the input and the snapshot share less than this fraction of their column
+        /// names, which suggests they are unrelated datasets
+        #[arg(long, value_name = "FRACTION", default_value_t = 0.5, value_parser = validate_fraction)]
+        min_column_overlap: f64,
+        
+        /// Fail instead of warning when the column overlap is below --min-column-overlap
+        #[arg(long)]
+        strict: bool,
+        
+        /// Print the wall-clock time of each phase (loading, schema, row and cell comparison) to stderr
+        #[arg(long, conflicts_with_all = ["watch", "against_self"])]
+        timings: bool,
+    },
+    
+    /// Print row and column counts of a file without hashing or snapshotting it
+    Stat {
+        /// Input file path
+        input: String,
+        
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
     
     /// List all snapshots
     List {
+        /// Only list snapshots whose name matches this glob, e.g. 'nightly_*' (`*` matches any
+        /// characters, `?` one)
+        pattern: Option<String>,
+        
+        /// Only list snapshots taken of this source file
+        #[arg(long, value_name = "FILE")]
+        source: Option<String>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -131,9 +608,64 @@ pub enum Commands {
         #[arg(long)]
         force: bool,
         
+        /// Ask about each changed row separately and roll back only the accepted ones
+        /// (ignored with --force)
+        #[arg(long, conflicts_with = "dry_run")]
+        interactive: bool,
+        
         /// Create backup before rollback
         #[arg(long, default_value = "true")]
         backup: bool,
+        
+        /// Only roll back these rows, e.g. --rows 3,10-12: edited and added rows by their index in
+        /// the current file, removed rows by their index in the snapshot (as `status` shows them)
+        #[arg(long, value_delimiter = ',', value_parser = crate::change_detection::RowRange::parse)]
+        rows: Vec<crate::change_detection::RowRange>,
+        
+        /// Only roll back cells in these columns, keeping added and removed rows as they are
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<String>,
+        
+        /// Field delimiter to write the file with (default: tab for .tsv, comma otherwise)
+        #[arg(long, value_name = "CHAR", value_parser = validate_delimiter)]
+        delimiter: Option<char>,
+    },
+    
+    /// Set the snapshot `status` compares a source file against when --compare-to is omitted
+    SetBaseline {
+        /// Source file the baseline applies to
+        input: String,
+        
+        /// Snapshot to use as the baseline
+        #[arg(required_unless_present = "clear", conflicts_with = "clear")]
+        snapshot: Option<String>,
+        
+        /// Remove the baseline, falling back to the latest snapshot of the source
+        #[arg(long)]
+        clear: bool,
+    },
+    
+    /// Show the snapshot history for a single source file
+    Log {
+        /// Input file whose history should be shown
+        input: String,
+        
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Squash a range of a source's snapshot chain into one standalone full-data snapshot
+    Merge {
+        /// First snapshot of the range to squash
+        from: String,
+        
+        /// Last snapshot of the range; its state becomes the new snapshot's data
+        to: String,
+        
+        /// Name for the squashed snapshot
+        #[arg(long)]
+        name: String,
     },
     
     /// Show snapshot chain and relationships
@@ -141,11 +673,15 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        
+        /// Print the chain as a graph to render with Graphviz (dot) or Mermaid (mermaid)
+        #[arg(long, value_name = "FORMAT", value_parser = crate::chain_graph::GraphFormat::parse, conflicts_with = "json")]
+        graph: Option<crate::chain_graph::GraphFormat>,
     },
     
     /// Clean up old snapshot archives to save space
     Cleanup {
-        /// Number of recent snapshots to keep full data for rollback capability (default: 5)
+        /// Number of recent snapshots of each source to keep full data for rollback capability (default: 5)
         /// Recent snapshots with full data can be rolled back, older ones become delta-only
         #[arg(long, default_value = "5")]
         keep_full: usize,
@@ -158,6 +694,37 @@ pub enum Commands {
         #[arg(long)]
         force: bool,
     },
+    
+    /// Remove diff files and archives left behind by deleted snapshots
+    Gc {
+        /// List the orphaned files without removing them
+        #[arg(long)]
+        dry_run: bool,
+        
+        /// Skip confirmation prompts
+        #[arg(long)]
+        force: bool,
+    },
+    
+    /// View or set workspace defaults for options (compression-level, float-precision,
+    /// float-tolerance, threads); a flag given on the command line always wins
+    Config {
+        /// Print the value of this key
+        #[arg(long, value_name = "KEY", conflicts_with_all = ["set", "unset", "list"])]
+        get: Option<String>,
+        
+        /// Set a default, as key=value (repeatable)
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_config_assignment)]
+        set: Vec<(String, String)>,
+        
+        /// Remove a default, falling back to the built-in one (repeatable)
+        #[arg(long, value_name = "KEY")]
+        unset: Vec<String>,
+        
+        /// List every key and its value (the default without other options)
+        #[arg(long)]
+        list: bool,
+    },
 }
 
 /// Parse diff mode string
@@ -191,6 +758,79 @@ fn validate_batch_size(s: &str) -> Result<usize, String> {
     Ok(batch_size)
 }
 
+/// Validate that a workspace directory name is a single plain path component
+fn validate_workspace_dir(s: &str) -> Result<String, String> {
+    if s.is_empty() || s == "." || s == ".." || s.contains(['/', '\\']) {
+        return Err(format!("Invalid workspace directory name: '{}'. Must be a single directory name.", s));
+    }
+    
+    Ok(s.to_string())
+}
+
+/// Validate a JSON Pointer such as "/data/items"
+fn validate_json_pointer(s: &str) -> Result<String, String> {
+    crate::data::DataProcessor::parse_json_pointer(s)
+        .map(|_| s.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a field delimiter: a single character, or "tab" / "\t" for a tab
+fn validate_delimiter(s: &str) -> Result<char, String> {
+    match s {
+        "tab" | "\\t" | "\t" => return Ok('\t'),
+        _ => {}
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(delimiter), None) if delimiter != '"' && delimiter != '\n' && delimiter != '\r' => Ok(delimiter),
+        _ => Err(format!("Invalid delimiter: '{}'. Must be a single character other than a quote or line break, or 'tab'.", s)),
+    }
+}
+
+/// Validate a fraction between 0 and 1
+fn validate_fraction(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        _ => Err(format!("Invalid fraction: '{}'. Must be a number between 0 and 1.", s)),
+    }
+}
+
+/// Validate a sample fraction above 0 and at most 1
+fn validate_sample_fraction(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(fraction) if fraction > 0.0 && fraction <= 1.0 => Ok(fraction),
+        _ => Err(format!("Invalid sample fraction: '{}'. Must be a number above 0 and at most 1.", s)),
+    }
+}
+
+/// Maximum meaningful decimal places for a 64-bit float
+const MAX_FLOAT_PRECISION: usize = 17;
+
+pub fn validate_float_precision(s: &str) -> Result<usize, String> {
+    let precision: usize = s.parse()
+        .map_err(|_| format!("Invalid float precision: '{}'. Must be a non-negative integer.", s))?;
+    
+    if precision > MAX_FLOAT_PRECISION {
+        return Err(format!("Float precision must be at most {}", MAX_FLOAT_PRECISION));
+    }
+    
+    Ok(precision)
+}
+
+fn parse_config_assignment(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("Invalid setting: '{}'. Use key=value, e.g. float-tolerance=0.001", s)),
+    }
+}
+
+pub fn validate_float_tolerance(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(tolerance) if tolerance.is_finite() && tolerance >= 0.0 => Ok(tolerance),
+        _ => Err(format!("Invalid float tolerance: '{}'. Must be a non-negative number.", s)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,4 +842,14 @@ mod tests {
         assert!(matches!(DiffMode::parse("auto"), Ok(DiffMode::Auto)));
         assert!(DiffMode::parse("invalid").is_err());
     }
+
+    #[test]
+    fn test_validate_delimiter() {
+        assert_eq!(validate_delimiter(";"), Ok(';'));
+        assert_eq!(validate_delimiter("tab"), Ok('\t'));
+        assert_eq!(validate_delimiter("\\t"), Ok('\t'));
+        assert!(validate_delimiter("\"").is_err());
+        assert!(validate_delimiter(",,").is_err());
+        assert!(validate_delimiter("").is_err());
+    }
 }
@@ -1,74 +1,371 @@
 //! Command implementations for tabdiff CLI
 
 use crate::cli::{Commands, DiffMode};
-use crate::data::DataProcessor;
+use crate::compatibility::SchemaCompatibility;
+use crate::data::{DataProcessor, InputFormat};
+use crate::encoding::SourceEncoding;
 use crate::error::Result;
-use crate::output::{PrettyPrinter, JsonFormatter};
+use crate::output::{ChangeCategory, ChangeFilter, JUnitFormatter, JsonFormatter, PrettyPrinter, ReportFormat, RowLabels, StatusPrinter};
 use crate::resolver::{SnapshotRef, SnapshotResolver};
+use crate::schema_export::SchemaFormat;
 use crate::snapshot::{SnapshotCreator, SnapshotLoader};
-use crate::workspace::TabdiffWorkspace;
-use crate::change_detection::ChangeDetector;
-use std::path::Path;
+use crate::workspace::{DiffNameSuffix, TabdiffWorkspace, WorkspaceDefaults};
+use crate::change_detection::{CellPredicate, ChangeDetectionResult, ChangeDetector, ChangeGroups, ColumnCoercion, DetectionOptions, RollbackFilter, RollbackOperation, RowIdentity, RowNumbering};
+use std::path::{Path, PathBuf};
+
+/// Settings given before the subcommand, applied to every data processor a command opens
+#[derive(Debug, Clone, Default)]
+pub struct EngineSettings {
+    pub duckdb: crate::duckdb_config::DuckDbSettings,
+    pub progress_format: crate::progress::ProgressFormat,
+}
+
+impl EngineSettings {
+    /// Data processor with these resource and progress settings
+    fn data_processor(&self) -> Result<DataProcessor> {
+        Ok(DataProcessor::new_with_settings(crate::DEFAULT_BATCH_SIZE, &self.duckdb)?
+            .with_progress_format(self.progress_format))
+    }
+}
 
 /// Execute a command
-pub fn execute_command(command: Commands, workspace_path: Option<&Path>) -> Result<()> {
+pub fn execute_command(command: Commands, workspace_path: Option<&Path>, engine: &EngineSettings) -> Result<()> {
     match command {
-        Commands::Init { force } => init_command(workspace_path, force),
+        Commands::Init { force, dir, no_gitignore } => init_command(workspace_path, force, dir, no_gitignore),
         Commands::Snapshot {
             input,
+            from_query,
             name,
             batch_size,
             full_data,
             hash_only,
+            metadata_only,
+            no_header,
+            order_by,
+            float_precision,
+            na_values,
+            encoding,
+            message,
+            json_path,
+            resume,
+            compression_level,
+            sign_key,
+            chain,
+            sample,
+            sample_seed,
+            assertions,
+            all_tables,
+            dry_run,
+            overwrite,
+            update,
+            force,
+            timings,
         } => {
             // Determine final full_data setting
-            let enable_full_data = if hash_only {
+            let enable_full_data = if hash_only || metadata_only {
                 false
             } else {
                 full_data
             };
-            snapshot_command(workspace_path, &input, &name, batch_size, enable_full_data)
+            // Metadata-only snapshots compute no row hashes for a configured precision to affect
+            let defaults = workspace_defaults(workspace_path)?;
+            let float_precision = float_precision.or(defaults.float_precision.filter(|_| !metadata_only));
+            let compression_level = compression_level.or(defaults.compression_level).unwrap_or_default();
+            let signing_key = sign_key.as_deref().map(crate::signing::SigningKey::from_file).transpose()?;
+            let sample = sample.map(|fraction| crate::data::SampleSpec { fraction, seed: sample_seed });
+            let options = SnapshotOptions {
+                from_query,
+                batch_size,
+                full_data: enable_full_data,
+                metadata_only,
+                no_header,
+                order_by,
+                float_precision,
+                na_values,
+                encoding,
+                message,
+                json_pointer: json_path,
+                resume,
+                compression_level,
+                signing_key,
+                chain,
+                sample,
+                assertions,
+                all_tables,
+                dry_run,
+                overwrite,
+                update,
+                force,
+            };
+            let timings = crate::timings::Timings::new(timings);
+            snapshot_command(workspace_path, input.as_deref(), name.as_deref(), options, &timings, engine)?;
+            timings.print();
+            Ok(())
         },
         Commands::Diff {
             snapshot1,
             snapshot2,
+            against_parent,
+            since,
+            steps,
             mode,
             output,
-        } => diff_command(workspace_path, &snapshot1, &snapshot2, &mode, output.as_deref()),
+            delimiter,
+            detect_renames,
+            only,
+            include_unchanged,
+            max_unchanged,
+            context,
+            invert,
+            stat,
+            format,
+            compat,
+            numeric_delta,
+            pretty_width,
+            baseline_format,
+            current_format,
+            coerce,
+            float_tolerance,
+            report_schema_only_changes,
+            match_threshold,
+            auto_sample,
+            append_only,
+            na_values,
+            key,
+            ignore_row_order,
+            allow_duplicate_keys,
+            where_predicates,
+            line_numbers,
+            group_by,
+            label_column,
+            threads,
+            output_dir,
+            name_suffix,
+            timings,
+        } => {
+            let defaults = workspace_defaults(workspace_path)?;
+            let (snapshot1, snapshot2, cached_changes) = if against_parent {
+                match parent_pair(workspace_path, &snapshot1)? {
+                    Some(pair) => pair,
+                    None => return Ok(()),
+                }
+            } else if let Some(since) = since {
+                // The aggregate is the diff of the range's endpoints, so undone changes cancel out
+                let range = chain_range(workspace_path, &since, &snapshot1)?;
+                if steps {
+                    PrettyPrinter::print_chain_steps(&range);
+                }
+                let first = range.first().map(|metadata| metadata.name.clone()).unwrap_or(since);
+                let last = range.last().map(|metadata| metadata.name.clone()).unwrap_or(snapshot1);
+                (first, last, None)
+            } else {
+                (snapshot1, snapshot2.unwrap_or_default(), None)
+            };
+            let options = DiffOptions {
+                mode: &mode,
+                output_path: output.as_deref(),
+                delimiter,
+                detect_renames,
+                only: &only,
+                unchanged_limit: include_unchanged.then_some(max_unchanged),
+                context: context.filter(|&n| n > 0),
+                invert,
+                stat,
+                format,
+                compat,
+                numeric_delta,
+                pretty_width,
+                baseline_format,
+                current_format,
+                coerce,
+                float_tolerance: float_tolerance.or(defaults.float_tolerance),
+                report_schema_only_changes,
+                match_threshold,
+                auto_sample,
+                append_only,
+                na_values,
+                row_identity: row_identity(key, ignore_row_order),
+                allow_duplicate_keys,
+                where_predicates,
+                line_numbers,
+                group_by: group_by.as_deref(),
+                label_column: label_column.as_deref(),
+                threads: threads.or(defaults.threads),
+                output_dir: output_dir.as_deref(),
+                name_suffix,
+            };
+            let timings = crate::timings::Timings::new(timings);
+            diff_command(workspace_path, &snapshot1, &snapshot2, options, cached_changes, &timings, engine)?;
+            timings.print();
+            Ok(())
+        }
         Commands::Show {
             snapshot,
             detailed,
+            diff_parent,
+            json,
+            schema_format,
+            raw,
+            file,
+        } => {
+            if raw {
+                show_raw_command(workspace_path, &snapshot, file.as_deref(), json)
+            } else {
+                show_command(workspace_path, &snapshot, detailed, diff_parent, json, schema_format)
+            }
+        }
+        Commands::Export {
+            snapshot,
+            output,
+            format,
+            delimiter,
+        } => export_command(workspace_path, &snapshot, &output, format.as_deref(), delimiter),
+        Commands::Verify {
+            snapshot,
+            check_signature,
+            sign_key,
+        } => {
+            let signing_key = sign_key.as_deref()
+                .filter(|_| check_signature)
+                .map(crate::signing::SigningKey::from_file)
+                .transpose()?;
+            verify_command(workspace_path, &snapshot, signing_key.as_ref())
+        }
+        Commands::Status {
+            input,
+            against_self: true,
             json,
-        } => show_command(workspace_path, &snapshot, detailed, json),
+            encoding,
+            current_format,
+            json_path,
+            na_values,
+            ..
+        } => {
+            let options = SelfCheckOptions { json, encoding, current_format, json_path, na_values };
+            self_check_command(workspace_path, &input, options, engine)
+        }
         Commands::Status {
             input,
             compare_to,
+            against_self: false,
+            schema_only,
             quiet,
             json,
-        } => status_command(workspace_path, &input, compare_to.as_deref(), quiet, json),
-        Commands::List { json } => list_command(workspace_path, json),
+            output,
+            format,
+            detect_renames,
+            only,
+            watch,
+            max_iterations,
+            encoding,
+            current_format,
+            json_path,
+            coerce,
+            float_tolerance,
+            report_schema_only_changes,
+            match_threshold,
+            auto_sample,
+            na_values,
+            key,
+            ignore_row_order,
+            allow_duplicate_keys,
+            where_predicates,
+            line_numbers,
+            group_by,
+            threads,
+            min_column_overlap,
+            strict,
+            timings,
+        } => {
+            let defaults = workspace_defaults(workspace_path)?;
+            let options = StatusOptions {
+                schema_only,
+                quiet,
+                json,
+                output_path: output.as_deref(),
+                format,
+                detect_renames,
+                only: &only,
+                watch: watch.map(|interval| crate::watch::WatchOptions { interval, max_iterations }),
+                encoding,
+                current_format,
+                json_path,
+                coerce,
+                float_tolerance: float_tolerance.or(defaults.float_tolerance),
+                report_schema_only_changes,
+                match_threshold,
+                auto_sample,
+                na_values,
+                row_identity: row_identity(key, ignore_row_order),
+                allow_duplicate_keys,
+                where_predicates,
+                line_numbers,
+                group_by: group_by.as_deref(),
+                threads: threads.or(defaults.threads),
+                overlap_check: ColumnOverlapCheck { min_overlap: min_column_overlap, strict },
+            };
+            let timings = crate::timings::Timings::new(timings);
+            status_command(workspace_path, &input, compare_to.as_deref(), options, &timings, engine)?;
+            timings.print();
+            Ok(())
+        }
+        Commands::Stat { input, json } => stat_command(workspace_path, &input, json, engine),
+        Commands::List { pattern, source, json } => list_command(workspace_path, pattern.as_deref(), source.as_deref(), json),
         Commands::Rollback {
             input,
             to,
             to_date,
             dry_run,
             force,
+            interactive,
             backup,
-        } => rollback_command(workspace_path, &input, to.as_deref(), to_date.as_deref(), dry_run, force, backup),
-        Commands::Chain { json } => chain_command(workspace_path, json),
+            rows,
+            columns,
+            delimiter,
+        } => {
+            let options = RollbackOptions {
+                dry_run,
+                force,
+                // --force skips every prompt, including the per-change ones
+                interactive: interactive && !force,
+                backup,
+                delimiter,
+                filter: RollbackFilter::new(&rows, &columns),
+            };
+            rollback_command(workspace_path, &input, to.as_deref(), to_date.as_deref(), options, engine)
+        }
+        Commands::SetBaseline { input, snapshot, clear } => {
+            set_baseline_command(workspace_path, &input, snapshot.as_deref(), clear)
+        }
+        Commands::Log { input, json } => log_command(workspace_path, &input, json),
+        Commands::Merge { from, to, name } => merge_command(workspace_path, &from, &to, &name),
+        Commands::Chain { json, graph } => chain_command(workspace_path, json, graph),
         Commands::Cleanup {
             keep_full,
             dry_run,
             force,
         } => cleanup_command(workspace_path, keep_full, dry_run, force),
+        Commands::Gc { dry_run, force } => gc_command(workspace_path, dry_run, force),
+        Commands::Config { get, set, unset, list } => config_command(workspace_path, get.as_deref(), &set, &unset, list),
     }
 }
 
 
 /// Initialize tabdiff workspace
-fn init_command(workspace_path: Option<&Path>, force: bool) -> Result<()> {
-    let current_dir = std::env::current_dir()?;
-    let root = workspace_path.unwrap_or(&current_dir);
+fn init_command(workspace_path: Option<&Path>, force: bool, dir: Option<String>, no_gitignore: bool) -> Result<()> {
+    let start_dir = TabdiffWorkspace::start_dir(workspace_path)?;
+    let root = start_dir.as_path();
+    
+    // Record a non-default layout so later commands find the workspace; without flags keep what was recorded
+    let mut settings = crate::workspace::WorkspaceSettings::load(root)?;
+    if let Some(dir) = dir {
+        settings.dir = dir;
+    }
+    if no_gitignore {
+        settings.gitignore = false;
+    }
+    std::fs::create_dir_all(root)?;
+    settings.save(root)?;
     
     let workspace = if force {
         // Force create new workspace, overwriting existing config
@@ -82,7 +379,9 @@ fn init_command(workspace_path: Option<&Path>, force: bool) -> Result<()> {
         workspace.create_config_with_force(true)?;
         
         // Update .gitignore
-        workspace.ensure_gitignore()?;
+        if settings.gitignore {
+            workspace.ensure_gitignore()?;
+        }
         
         workspace
     } else {
@@ -103,9 +402,8 @@ fn rollback_command(
     input: &str,
     to: Option<&str>,
     to_date: Option<&str>,
-    dry_run: bool,
-    force: bool,
-    backup: bool,
+    options: RollbackOptions,
+    engine: &EngineSettings,
 ) -> Result<()> {
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
     let resolver = SnapshotResolver::new(workspace.clone());
@@ -145,21 +443,33 @@ fn rollback_command(
     };
 
     // Load current data
-    let input_path = if Path::new(input).is_absolute() {
-        Path::new(input).to_path_buf()
-    } else {
-        workspace.root.join(input)
-    };
+    if crate::duckdb_config::is_remote_url(input) {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Cannot roll back '{}': only local files can be rewritten", input
+        )));
+    }
+    let input_path = resolve_input_path(&workspace, input);
 
     if !input_path.exists() {
+        return Err(crate::error::TabdiffError::file_not_found(&input_path));
+    }
+
+    // Read the current file the same way the target snapshot was read (header-less or not)
+    let target_metadata = SnapshotLoader::load_metadata(&target_snapshot.json_path)?;
+    if let Some(json_path) = &target_metadata.json_path {
         return Err(crate::error::TabdiffError::invalid_input(format!(
-            "Input file does not exist: {}", input_path.display()
+            "Cannot roll back '{}': its rows were read from {} within the JSON document, which rollback cannot rewrite",
+            input, json_path
         )));
     }
-
-    let mut data_processor = DataProcessor::new()?;
+    let mut data_processor = engine.data_processor()?
+        .with_no_header(target_metadata.synthetic_column_names)
+        .with_order_by(target_metadata.order_by.clone())
+        .with_float_precision(target_metadata.float_precision);
     let current_data_info = data_processor.load_file(&input_path)?;
     let current_row_data = data_processor.extract_all_data()?;
+    // The file is rewritten in its own format: tab-delimited for .tsv unless overridden
+    let delimiter = options.delimiter.unwrap_or_else(|| crate::writer::delimiter_for_path(&input_path));
 
     // Extract target schema from archive data
     let target_schema = if let Some(schema_data) = target_data.schema_data.get("columns") {
@@ -207,6 +517,23 @@ fn rollback_command(
         Vec::new()
     };
 
+    // Interactive rollback restores rows within the current schema, like a partial rollback
+    if options.interactive && target_schema.iter().map(|col| &col.name).ne(current_data_info.columns.iter().map(|col| &col.name)) {
+        return Err(crate::error::TabdiffError::invalid_input(
+            "--interactive cannot roll back schema changes; roll back without --interactive to restore the snapshot's columns"
+        ));
+    }
+
+    if options.filter.is_active() || options.interactive {
+        let target = RollbackTarget {
+            name: &target_snapshot.name,
+            schema: &target_schema,
+            rows: &target_row_data,
+            include_header: !target_metadata.synthetic_column_names,
+        };
+        return partial_rollback(&input_path, target, &current_data_info.columns, &current_row_data, options, delimiter);
+    }
+
     // Detect changes needed to rollback
     let changes = ChangeDetector::detect_changes(
         &current_data_info.columns,
@@ -222,7 +549,7 @@ fn rollback_command(
     }
 
     // Show what will be changed
-    if dry_run {
+    if options.dry_run {
         println!("🔍 Dry run - showing what would be changed:");
         PrettyPrinter::print_comprehensive_status_results(&changes, false);
         println!("\n💡 Use --force to apply these changes");
@@ -230,7 +557,7 @@ fn rollback_command(
     }
 
     // Show changes and ask for confirmation
-    if !force {
+    if !options.force {
         println!("📋 The following changes will be applied:");
         PrettyPrinter::print_comprehensive_status_results(&changes, false);
         
@@ -245,15 +572,20 @@ fn rollback_command(
     }
 
     // Create backup if requested
-    if backup {
+    if options.backup {
         let backup_path = format!("{}.backup", input_path.display());
         std::fs::copy(&input_path, &backup_path)?;
         println!("💾 Backup created: {}", backup_path);
     }
 
     // Apply the rollback by writing the target data
-    let target_csv_content = create_csv_content(&target_schema, &target_row_data)?;
-    std::fs::write(&input_path, target_csv_content)?;
+    // Header-less sources are restored without a header row
+    rollback_writer(&input_path, delimiter).write_rows(
+        &input_path,
+        &target_schema,
+        &target_row_data,
+        !target_metadata.synthetic_column_names,
+    )?;
 
     let snapshot_name = &target_snapshot.name;
     println!("✅ Rollback completed successfully!");
@@ -262,94 +594,526 @@ fn rollback_command(
     Ok(())
 }
 
-/// Create CSV content from schema and row data
-fn create_csv_content(schema: &[crate::hash::ColumnInfo], rows: &[Vec<String>]) -> Result<String> {
-    let mut content = String::new();
-    
-    // Write header
-    let headers: Vec<&str> = schema.iter().map(|col| col.name.as_str()).collect();
-    content.push_str(&headers.join(","));
-    content.push('\n');
-    
-    // Write rows
-    let empty_string = String::new();
-    for row in rows {
-        // Ensure row has the right number of columns
-        let mut row_values = Vec::new();
-        for (i, _col) in schema.iter().enumerate() {
-            let value = row.get(i).unwrap_or(&empty_string);
-            // Escape CSV values if they contain commas or quotes
-            if value.contains(',') || value.contains('"') || value.contains('\n') {
-                row_values.push(format!("\"{}\"", value.replace('"', "\"\"")));
-            } else {
-                row_values.push(value.clone());
+/// Writer that restores a rolled back file in its own format, as delimited text unless its
+/// extension names a registered output format
+fn rollback_writer(input_path: &Path, delimiter: char) -> Box<dyn crate::writer::DataWriter> {
+    crate::writer::writer_for_path(input_path, Some(delimiter))
+        .unwrap_or_else(|| Box::new(crate::writer::CsvWriter::new(delimiter)))
+}
+
+/// How a rollback is confirmed and written
+struct RollbackOptions<'a> {
+    dry_run: bool,
+    force: bool,
+    /// Ask about each change group instead of confirming all of them at once
+    interactive: bool,
+    backup: bool,
+    /// Field delimiter for delimited text (default: from the file's extension)
+    delimiter: Option<char>,
+    /// Restore only these rows/columns
+    filter: RollbackFilter<'a>,
+}
+
+/// The snapshot a rollback restores
+struct RollbackTarget<'a> {
+    name: &'a str,
+    schema: &'a [crate::hash::ColumnInfo],
+    rows: &'a [Vec<String>],
+    /// Header-less sources are restored without a header row
+    include_header: bool,
+}
+
+/// Roll back only the selected rows/columns, keeping every other current value
+fn partial_rollback(
+    input_path: &Path,
+    target: RollbackTarget,
+    current_schema: &[crate::hash::ColumnInfo],
+    current_row_data: &[Vec<String>],
+    options: RollbackOptions,
+    delimiter: char,
+) -> Result<()> {
+    let filter = options.filter;
+    if let Some(unknown) = filter.columns().iter().find(|c| !current_schema.iter().any(|col| col.name == **c)) {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Unknown column for --columns: '{}'", unknown
+        )));
+    }
+
+    // Detected in status direction so row indices match what `tabdiff status` shows
+    let changes = ChangeDetector::detect_changes(target.schema, target.rows, current_schema, current_row_data)?;
+    let mut operations = filter.apply(changes.generate_rollback_operations());
+
+    if operations.is_empty() {
+        println!("✅ The selected rows/columns already match the target snapshot. No rollback needed.");
+        return Ok(());
+    }
+
+    if options.dry_run {
+        println!("🔍 Dry run - showing what would be changed:");
+        PrettyPrinter::print_rollback_operations(&operations, current_schema);
+        println!("\n💡 Use --force to apply these changes");
+        return Ok(());
+    }
+
+    if options.interactive {
+        operations = choose_rollback_operations(operations, current_schema, std::io::stdin().lock())?;
+        if operations.is_empty() {
+            println!("❌ No changes accepted; rollback cancelled.");
+            return Ok(());
+        }
+    } else if !options.force {
+        println!("📋 The following changes will be applied:");
+        PrettyPrinter::print_rollback_operations(&operations, current_schema);
+
+        println!("\n⚠️  This will modify your file. Continue? (y/N)");
+        let mut user_input = String::new();
+        std::io::stdin().read_line(&mut user_input)?;
+
+        if !user_input.trim().to_lowercase().starts_with('y') {
+            println!("❌ Rollback cancelled.");
+            return Ok(());
+        }
+    }
+
+    if options.backup {
+        let backup_path = format!("{}.backup", input_path.display());
+        std::fs::copy(input_path, &backup_path)?;
+        println!("💾 Backup created: {}", backup_path);
+    }
+
+    let rows = RollbackOperation::apply_all(&operations, current_schema, current_row_data);
+    rollback_writer(input_path, delimiter).write_rows(input_path, current_schema, &rows, target.include_header)?;
+
+    println!("✅ Partial rollback completed: {} change(s) reverted to snapshot '{}'", operations.len(), target.name);
+
+    Ok(())
+}
+
+/// Ask about each change group, reading answers from `input`, and keep the accepted operations
+///
+/// y rolls a group back and n keeps it; a rolls back it and every remaining group, q keeps it
+/// and every remaining group. Running out of input keeps whatever is left.
+fn choose_rollback_operations(
+    operations: Vec<RollbackOperation>,
+    schema: &[crate::hash::ColumnInfo],
+    mut input: impl std::io::BufRead,
+) -> Result<Vec<RollbackOperation>> {
+    use std::io::Write;
+
+    let groups = RollbackOperation::group_by_row(operations);
+    let total = groups.len();
+    let mut accepted = Vec::new();
+    let mut accept_rest = false;
+    for (i, group) in groups.into_iter().enumerate() {
+        if !accept_rest {
+            println!("\n🔸 Change {}/{}:", i + 1, total);
+            PrettyPrinter::print_rollback_operations(&group, schema);
+            let answer = loop {
+                print!("Roll back this change? [y,n,a,q] ");
+                std::io::stdout().flush()?;
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    println!();
+                    break 'q';
+                }
+                match line.trim().to_lowercase().chars().next() {
+                    Some(answer @ ('y' | 'n' | 'a' | 'q')) => break answer,
+                    _ => println!("y: roll back, n: keep, a: roll back this and all remaining, q: keep this and all remaining"),
+                }
+            };
+            match answer {
+                'n' => continue,
+                'q' => break,
+                'a' => accept_rest = true,
+                _ => {}
             }
         }
-        content.push_str(&row_values.join(","));
-        content.push('\n');
+        accepted.extend(group);
     }
-    
-    Ok(content)
+    Ok(accepted)
+}
+
+/// How `tabdiff snapshot` reads its input and what it records
+struct SnapshotOptions {
+    /// Snapshot the result of this query instead of an input file
+    from_query: Option<String>,
+    batch_size: usize,
+    /// Store every row, not only their hashes
+    full_data: bool,
+    /// Record only the schema and row count
+    metadata_only: bool,
+    no_header: bool,
+    order_by: Vec<String>,
+    float_precision: Option<usize>,
+    na_values: Vec<String>,
+    encoding: Option<SourceEncoding>,
+    message: Option<String>,
+    /// JSON pointer to the array of rows within a JSON document
+    json_pointer: Option<String>,
+    resume: bool,
+    compression_level: crate::archive::CompressionLevel,
+    signing_key: Option<crate::signing::SigningKey>,
+    chain: Option<String>,
+    sample: Option<crate::data::SampleSpec>,
+    assertions: Vec<crate::assertions::DataAssertion>,
+    /// Snapshot every table of a `.sql` input separately
+    all_tables: bool,
+    dry_run: bool,
+    overwrite: bool,
+    update: bool,
+    /// Also replace a snapshot that others are chained to
+    force: bool,
+}
+
+/// Where a snapshot's rows are read from
+struct SnapshotSource<'a> {
+    /// The file DuckDB reads: the input itself, or its extracted or UTF-8 copy
+    read_path: &'a Path,
+    /// The input as given, for messages
+    input: &'a str,
+    /// Base of generated snapshot names
+    stem: String,
 }
 
 /// Create a snapshot
 fn snapshot_command(
     workspace_path: Option<&Path>,
-    input: &str,
-    name: &str,
-    batch_size: usize,
-    full_data: bool,
+    input: Option<&str>,
+    name: Option<&str>,
+    options: SnapshotOptions,
+    timings: &crate::timings::Timings,
+    engine: &EngineSettings,
 ) -> Result<()> {
+    let SnapshotOptions { full_data, metadata_only, dry_run, overwrite, update, force, .. } = options;
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
-    let (archive_path, json_path) = workspace.snapshot_paths(name);
     
-    // Check if snapshot already exists
-    if workspace.snapshot_exists(name) {
-        return Err(crate::error::TabdiffError::invalid_input(format!(
-            "Snapshot '{}' already exists. Use a different name or remove the existing snapshot.",
-            name
-        )));
+    // Check if snapshot already exists; --update decides once the source is known
+    let existing = name.filter(|name| workspace.snapshot_exists(name));
+    let mut replaced = None;
+    if let Some(name) = existing {
+        if !overwrite && !update {
+            return Err(crate::error::TabdiffError::invalid_input(format!(
+                "Snapshot '{}' already exists. Use a different name, remove the existing snapshot, or pass --overwrite (or --update) to replace it.",
+                name
+            )));
+        }
+        if overwrite {
+            replaced = Some(overwrite_target(&workspace, name, force)?);
+        }
     }
 
-    // Create snapshot
-    let input_path = if Path::new(input).is_absolute() {
-        Path::new(input).to_path_buf()
-    } else {
-        // Resolve relative paths relative to the workspace root
-        workspace.root.join(input)
-    };
+    if metadata_only {
+        if !options.order_by.is_empty() || options.float_precision.is_some() || !options.na_values.is_empty() {
+            return Err(crate::error::TabdiffError::invalid_input(
+                "--order-by, --float-precision and --na-values only affect row hashes, which --metadata-only does not compute"
+            ));
+        }
+        println!("ℹ️  Using metadata-only mode - only the schema and row count are recorded; rows cannot be compared");
+    } else if !full_data {
+        println!("ℹ️  Using hash-only mode - rollback and detailed diff capabilities disabled");
+    }
+    if let Some(sample) = &options.sample {
+        println!("ℹ️  Sampling {} of the rows - compare with --auto-sample to sample the current data the same way", sample.describe());
+    }
+
+    // DuckDB only reads UTF-8, so other encodings (and files with a BOM) are read from a UTF-8 copy
+    let encoding = options.encoding.unwrap_or_default();
+    let new_creator = || SnapshotCreator::new(options.batch_size, true)
+        .with_duckdb_settings(engine.duckdb.clone())
+        .with_progress_format(engine.progress_format)
+        .with_no_header(options.no_header)
+        .with_order_by(options.order_by.clone())
+        .with_float_precision(options.float_precision)
+        .with_na_values(options.na_values.clone())
+        .with_encoding(encoding)
+        .with_description(options.message.clone())
+        .with_json_path(options.json_pointer.clone())
+        .with_resume(options.resume)
+        .with_compression_level(options.compression_level)
+        .with_signing_key(options.signing_key.clone())
+        .with_chain(options.chain.clone())
+        .with_sample(options.sample)
+        .with_assertions(options.assertions.clone())
+        .with_timings(timings.clone());
+    let mut creator = new_creator();
+
+    // Temporary files that must live until the snapshot is written
+    let extracted;
+    let transcoded;
+    let input_path;
+    let source = match (input, &options.from_query) {
+        (_, Some(query)) => {
+            // An inline query has no file to extract, transcode or size up
+            creator = creator.with_query(query.clone());
+            SnapshotSource { read_path: Path::new(""), input: "inline query", stem: "query".to_string() }
+        }
+        (Some(input), None) => {
+            // git:REV:path inputs are extracted to a temporary file that lives until the snapshot is written
+            let git_source = crate::git::GitSource::parse(input)?;
+            extracted = match &git_source {
+                Some(source) => {
+                    let repo_root = crate::git::GitHelper::find_git_root(&workspace.root).ok_or_else(|| {
+                        crate::error::TabdiffError::git(format!("'{}' is not inside a git repository", workspace.root.display()))
+                    })?;
+                    Some(crate::git::GitHelper::extract_revision(&repo_root, source)?)
+                }
+                None => None,
+            };
+
+            // Create snapshot
+            input_path = if let Some(extracted) = &extracted {
+                extracted.path().to_path_buf()
+            } else {
+                resolve_input_path(&workspace, input)
+            };
+            let remote = crate::duckdb_config::is_remote_url(input);
     
-    // Check file size and provide warnings/recommendations
-    let file_size = std::fs::metadata(&input_path)?.len();
-    const LARGE_FILE_THRESHOLD: u64 = 100 * 1024 * 1024; // 100MB
-    const VERY_LARGE_FILE_THRESHOLD: u64 = 1024 * 1024 * 1024; // 1GB
+            if !remote && !input_path.exists() {
+                return Err(crate::error::TabdiffError::file_not_found(&input_path));
+            }
+
+            // Check file size and provide warnings/recommendations (remote sizes aren't known up front)
+            let file_size = if remote { 0 } else { std::fs::metadata(&input_path)?.len() };
+            const LARGE_FILE_THRESHOLD: u64 = 100 * 1024 * 1024; // 100MB
+            const VERY_LARGE_FILE_THRESHOLD: u64 = 1024 * 1024 * 1024; // 1GB
     
-    if file_size > VERY_LARGE_FILE_THRESHOLD && full_data {
-        println!("⚠️  WARNING: Large file detected ({:.1} GB)", file_size as f64 / (1024.0 * 1024.0 * 1024.0));
-        println!("   Consider using --hash-only for faster processing and smaller snapshots.");
-        println!("   This will disable rollback and detailed diff capabilities.");
-    } else if file_size > LARGE_FILE_THRESHOLD && full_data {
-        println!("ℹ️  INFO: Moderate file size ({:.1} MB) - using full data storage", file_size as f64 / (1024.0 * 1024.0));
-        println!("   Use --hash-only if you need faster processing.");
-    }
+            if file_size > VERY_LARGE_FILE_THRESHOLD && full_data {
+                println!("⚠️  WARNING: Large file detected ({:.1} GB)", file_size as f64 / (1024.0 * 1024.0 * 1024.0));
+                println!("   Consider using --hash-only for faster processing and smaller snapshots.");
+                println!("   This will disable rollback and detailed diff capabilities.");
+            } else if file_size > LARGE_FILE_THRESHOLD && full_data {
+                println!("ℹ️  INFO: Moderate file size ({:.1} MB) - using full data storage", file_size as f64 / (1024.0 * 1024.0));
+                println!("   Use --hash-only if you need faster processing.");
+            }
     
-    if !full_data {
-        println!("ℹ️  Using hash-only mode - rollback and detailed diff capabilities disabled");
-    }
+            transcoded = encoding.transcode(&input_path)?;
+    
+            if let Some(source) = &git_source {
+                // Group revisions of the same file into one chain by repo-relative path
+                creator = creator.with_source(source.label(), source.path.clone());
+            } else if transcoded.is_some() {
+                // Keep the chain on the original file rather than its UTF-8 copy
+                let canonical_source_path = crate::snapshot::canonical_source_path(&input_path);
+                creator = creator.with_source(input_path.to_string_lossy().to_string(), canonical_source_path);
+            }
+            let stem = Path::new(git_source.as_ref().map_or(input, |source| source.path.as_str()))
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            SnapshotSource { read_path: transcoded.as_ref().map_or(input_path.as_path(), |file| file.path()), input, stem }
+        }
+        (None, None) => {
+            return Err(crate::error::TabdiffError::invalid_input("Give an input file or --from-query"));
+        }
+    };
+    let (read_path, input) = (source.read_path, source.input);
     
-    let mut creator = SnapshotCreator::new(batch_size, true);
+    if let Some(name) = existing.filter(|_| update) {
+        let (_, json_path) = workspace.snapshot_paths(name);
+        if creator.is_unchanged(&SnapshotLoader::load_metadata(&json_path)?, read_path, full_data, metadata_only) {
+            println!("✅ Snapshot '{}' is unchanged: {} has the same content", name, input);
+            return Ok(());
+        }
+        replaced = Some(overwrite_target(&workspace, name, force)?);
+    }
+
+    if options.all_tables {
+        return snapshot_all_tables(&workspace, &source, name, &creator.chain_source_path(read_path), new_creator, &options, engine);
+    }
+
+    let generated_name;
+    let name = match name {
+        Some(name) => name,
+        None => {
+            generated_name = next_snapshot_name(&workspace, &creator.chain_source_path(read_path), &source.stem, "")?;
+            generated_name.as_str()
+        }
+    };
+
+    let Some((previous, children)) = replaced else {
+        return create_snapshot_and_report(&workspace, &mut creator, read_path, input, name, &options, false);
+    };
+
+    // The new snapshot takes the old one's place in the chain
+    creator = creator.with_parent(previous.parent_snapshot.clone());
+    if !dry_run && !children.is_empty() {
+        println!("⚠️  Overwriting '{}', the parent of {}; their deltas will be recomputed", name, describe_snapshots(&children));
+    }
+    create_snapshot_and_report(&workspace, &mut creator, read_path, input, name, &options, true)?;
+    if dry_run {
+        return Ok(());
+    }
+
+    for child in &children {
+        if creator.relink_child(&workspace, name, child)? {
+            println!("🔗 Recomputed the delta of '{}' against the new '{}'", child, name);
+        } else {
+            println!("⚠️  Warning: '{}' or the new '{}' has no full data, so '{}' can no longer be reconstructed from '{}'", child, name, child, name);
+        }
+    }
+    Ok(())
+}
+
+/// Metadata of the snapshot `--overwrite` would replace and the names of the snapshots chained to it
+///
+/// Replacing a parent changes the data its children's deltas were computed against, so that
+/// needs `--force`.
+fn overwrite_target(workspace: &TabdiffWorkspace, name: &str, force: bool) -> Result<(crate::snapshot::SnapshotMetadata, Vec<String>)> {
+    let chain = crate::snapshot::SnapshotChain::build_chain(workspace)?;
+    let children: Vec<String> = chain.get_children(name).iter().map(|child| child.name.clone()).collect();
+    if !children.is_empty() && !force {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' is the parent of {}; overwriting it would break reconstruction from their deltas. \
+             Pass --force with --overwrite to replace it and recompute their deltas, or use a different name.",
+            name,
+            describe_snapshots(&children)
+        )));
+    }
+
+    let (_, json_path) = workspace.snapshot_paths(name);
+    Ok((SnapshotLoader::load_metadata(&json_path)?, children))
+}
+
+/// `'a'` or `'a', 'b'` for messages about a list of snapshots
+fn describe_snapshots(names: &[String]) -> String {
+    names.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")
+}
+
+/// Snapshot every table of a `.sql` source as `<name>::<table>`, each in a chain of its own
+fn snapshot_all_tables(
+    workspace: &TabdiffWorkspace,
+    source: &SnapshotSource,
+    name: Option<&str>,
+    source_path: &str,
+    new_creator: impl Fn() -> SnapshotCreator,
+    options: &SnapshotOptions,
+    engine: &EngineSettings,
+) -> Result<()> {
+    let SnapshotSource { read_path, input, ref stem } = *source;
+    if !crate::sql::is_sql_file(read_path) {
+        return Err(crate::error::TabdiffError::invalid_input(
+            "--all-tables needs a .sql input whose database or setup statements provide the tables"
+        ));
+    }
+    let tables = engine.data_processor()?.sql_tables(read_path)?;
+    if tables.is_empty() {
+        return Err(crate::error::TabdiffError::invalid_input(format!("'{}' provides no tables", input)));
+    }
+
+    // Name every snapshot first so an existing name fails before any table is snapshotted
+    let mut planned = Vec::new();
+    for table in tables {
+        let label = table.label();
+        let creator = new_creator()
+            .with_source(format!("{}::{}", input, label), format!("{}::{}", source_path, label))
+            .with_table(table);
+        let table_name = match name {
+            Some(base) => format!("{}::{}", base, label),
+            None => next_snapshot_name(workspace, &creator.chain_source_path(read_path), stem, &format!("::{}", label))?,
+        };
+        if workspace.snapshot_exists(&table_name) {
+            return Err(crate::error::TabdiffError::invalid_input(format!(
+                "Snapshot '{}' already exists. Use a different name or remove the existing snapshot.",
+                table_name
+            )));
+        }
+        planned.push((creator, table_name, label));
+    }
+
+    println!("📚 Snapshotting {} tables of '{}'", planned.len(), input);
+    for (mut creator, table_name, label) in planned {
+        let label_source = format!("{} ({})", input, label);
+        create_snapshot_and_report(workspace, &mut creator, read_path, &label_source, &table_name, options, false)?;
+    }
+    Ok(())
+}
+
+/// Create one snapshot and print its summary; a dry run only prints what it would record
+///
+/// With `replacing`, the snapshot called `name` is only replaced once its successor has been
+/// written in full, so a failed run leaves it as it was.
+fn create_snapshot_and_report(
+    workspace: &TabdiffWorkspace,
+    creator: &mut SnapshotCreator,
+    read_path: &Path,
+    input: &str,
+    name: &str,
+    options: &SnapshotOptions,
+    replacing: bool,
+) -> Result<()> {
+    let SnapshotOptions { full_data, metadata_only, resume, dry_run, .. } = *options;
+    if dry_run {
+        println!("🔍 Dry run - analyzing '{}' for snapshot '{}'...", input, name);
+        let metadata = creator.preview_snapshot(read_path, name, full_data, Some(workspace))?;
+
+        println!("📋 Snapshot '{}' would record:", metadata.name);
+        println!("├─ Rows: {}", metadata.row_count);
+        println!("├─ Columns: {}", metadata.column_count);
+        if let Some(parent_name) = &metadata.parent_snapshot {
+            println!("├─ Parent: {}", parent_name);
+            println!("├─ Sequence: {}", metadata.sequence_number);
+            if let Some(delta) = &metadata.delta_from_parent {
+                println!("├─ Delta: {}", PrettyPrinter::format_diff_stat(&delta.changes));
+            }
+        } else {
+            println!("├─ Chain: First snapshot");
+        }
+        println!("└─ Nothing was written (dry run)");
+        return Ok(());
+    }
+
+    let (archive_path, json_path) = workspace.snapshot_paths(name);
     
     println!("📸 Creating snapshot '{}' from '{}'...", name, input);
+    if resume {
+        if workspace.checkpoint_path(name).exists() {
+            println!("↩️  Resuming from the checkpoint of an earlier run");
+        } else {
+            println!("ℹ️  No checkpoint found for '{}'; hashing from the start", name);
+        }
+    }
     
+    // A replacement is written next to the snapshot it replaces and moved into place afterwards
+    let (write_archive_path, write_json_path) = if replacing {
+        (crate::snapshot::staged_path(&archive_path), crate::snapshot::staged_path(&json_path))
+    } else {
+        (archive_path.clone(), json_path.clone())
+    };
+
     // Use enhanced snapshot creation with workspace context for chain management
-    let metadata = creator.create_snapshot_with_workspace(
-        &input_path,
-        name,
-        &archive_path,
-        &json_path,
-        full_data,
-        Some(&workspace),
-    )?;
+    let created = if metadata_only {
+        creator.create_metadata_only_snapshot(read_path, name, &write_json_path, Some(workspace))
+    } else {
+        creator.create_snapshot_with_workspace(
+            read_path,
+            name,
+            &write_archive_path,
+            &write_json_path,
+            full_data,
+            Some(workspace),
+        )
+    };
+    let metadata = match created {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            for path in [&write_archive_path, &write_json_path].into_iter().filter(|_| replacing) {
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+            }
+            return Err(e);
+        }
+    };
+    if replacing {
+        // Content other archives reference from the old archive moves out before it is replaced
+        workspace.release_archive(name)?;
+        if write_archive_path.exists() {
+            std::fs::rename(&write_archive_path, &archive_path)?;
+        } else if archive_path.exists() {
+            std::fs::remove_file(&archive_path)?;
+        }
+        std::fs::rename(&write_json_path, &json_path)?;
+    }
 
     println!("✅ Snapshot created successfully!");
     println!("├─ Name: {}", metadata.name);
@@ -367,151 +1131,412 @@ fn snapshot_command(
         println!("├─ Chain: First snapshot");
     }
     
-    println!("├─ Archive: {}", archive_path.display());
+    if metadata_only {
+        println!("├─ Archive: none (metadata-only)");
+    } else {
+        println!("├─ Archive: {}", archive_path.display());
+    }
     println!("└─ Metadata: {}", json_path.display());
 
     Ok(())
 }
 
-/// Compare two snapshots
-fn diff_command(
-    workspace_path: Option<&Path>,
-    snapshot1: &str,
-    snapshot2: &str,
-    mode: &str,
-    output_path: Option<&Path>,
-) -> Result<()> {
+/// Name for a snapshot taken without `--name`: the source's file stem and the sequence number
+/// the snapshot will get in its chain, e.g. `sales_0003`
+///
+/// The number is raised past any names already taken, so the result never collides.
+/// `suffix` is appended after the number, e.g. `::orders` for one table of a source.
+fn next_snapshot_name(workspace: &TabdiffWorkspace, source_path: &str, stem: &str, suffix: &str) -> Result<String> {
+    let chain = crate::snapshot::SnapshotChain::build_chain_for_source(workspace, source_path)?;
+    let mut sequence = chain.head.as_ref()
+        .and_then(|head| chain.snapshots.iter().find(|metadata| &metadata.name == head))
+        .map_or(0, |head| head.sequence_number + 1);
+
+    let stem: String = stem.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let stem = if stem.is_empty() { "snapshot".to_string() } else { stem };
+    loop {
+        let name = format!("{}_{:04}{}", stem, sequence, suffix);
+        if !workspace.snapshot_exists(&name) {
+            return Ok(name);
+        }
+        sequence += 1;
+    }
+}
+
+/// The parent of a snapshot, the snapshot's own name and the delta cached between them,
+/// for `diff --against-parent`
+///
+/// Prints a note and returns `None` when the snapshot is the first in its chain.
+fn parent_pair(workspace_path: Option<&Path>, snapshot: &str) -> Result<Option<(String, String, Option<ChangeDetectionResult>)>> {
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
-    let resolver = SnapshotResolver::new(workspace.clone());
+    let resolver = SnapshotResolver::new(workspace);
+    let resolved = resolver.resolve(&SnapshotRef::from_string(snapshot.to_string()))?;
+    let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
 
-    // Parse diff mode
-    let _diff_mode = DiffMode::parse(mode)
-        .map_err(|e| crate::error::TabdiffError::invalid_input(e))?;
+    let Some(parent) = metadata.parent_snapshot else {
+        println!("ℹ️  '{}': first snapshot, no parent to compare against", metadata.name);
+        return Ok(None);
+    };
+    let cached = metadata.delta_from_parent
+        .filter(|delta| delta.parent_name == parent)
+        .map(|delta| delta.changes);
+    Ok(Some((parent, metadata.name, cached)))
+}
 
-    // Resolve snapshots
-    let snap1_ref = SnapshotRef::from_string(snapshot1.to_string());
-    let snap2_ref = SnapshotRef::from_string(snapshot2.to_string());
-    
-    let resolved1 = resolver.resolve(&snap1_ref)?;
-    let resolved2 = resolver.resolve(&snap2_ref)?;
+/// Snapshots from `since` to `head` along `head`'s chain, both included, for `diff --since`
+fn chain_range(workspace_path: Option<&Path>, since: &str, head: &str) -> Result<Vec<crate::snapshot::SnapshotMetadata>> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace.clone());
+    let since_name = resolver.resolve(&SnapshotRef::from_string(since.to_string()))?.name;
+    let head_name = resolver.resolve(&SnapshotRef::from_string(head.to_string()))?.name;
 
-    println!("🔍 Comparing snapshots: {} → {}", resolved1.name, resolved2.name);
+    let chain = crate::snapshot::SnapshotChain::build_chain(&workspace)?;
+    let path = chain.find_path_to_snapshot(&head_name).ok_or_else(|| {
+        crate::error::TabdiffError::invalid_input(format!("The chain of '{}' has a missing snapshot", head_name))
+    })?;
+    let start = path.iter().position(|name| *name == since_name).ok_or_else(|| {
+        crate::error::TabdiffError::invalid_input(format!(
+            "'{}' is not an ancestor of '{}'; its chain is {}",
+            since_name, head_name, path.join(" → ")
+        ))
+    })?;
+
+    Ok(path[start..].iter()
+        .filter_map(|name| chain.snapshots.iter().find(|metadata| metadata.name == *name).cloned())
+        .collect())
+}
+
+/// How `tabdiff diff` compares its two sides and reports the changes
+struct DiffOptions<'a> {
+    mode: &'a str,
+    output_path: Option<&'a Path>,
+    delimiter: Option<char>,
+    detect_renames: bool,
+    only: &'a [ChangeCategory],
+    /// Also report unchanged rows, at most this many (`--include-unchanged`, `--max-unchanged`)
+    unchanged_limit: Option<usize>,
+    /// Unchanged rows shown before and after each changed row
+    context: Option<usize>,
+    invert: bool,
+    stat: bool,
+    format: Option<ReportFormat>,
+    compat: bool,
+    numeric_delta: bool,
+    pretty_width: Option<usize>,
+    /// Read the first side as a data file in this format instead of as a snapshot
+    baseline_format: Option<InputFormat>,
+    /// Read the second side as a data file in this format instead of as a snapshot
+    current_format: Option<InputFormat>,
+    coerce: Vec<ColumnCoercion>,
+    float_tolerance: Option<f64>,
+    report_schema_only_changes: bool,
+    match_threshold: f64,
+    auto_sample: bool,
+    append_only: bool,
+    na_values: Vec<String>,
+    row_identity: RowIdentity,
+    allow_duplicate_keys: bool,
+    where_predicates: Vec<CellPredicate>,
+    line_numbers: bool,
+    group_by: Option<&'a str>,
+    label_column: Option<&'a str>,
+    threads: Option<usize>,
+    output_dir: Option<&'a Path>,
+    name_suffix: Option<DiffNameSuffix>,
+}
 
-    // Load metadata for output formatting
-    let metadata2 = SnapshotLoader::load_metadata(&resolved2.json_path)?;
+/// Compare two snapshots
+fn diff_command(
+    workspace_path: Option<&Path>,
+    snapshot1: &str,
+    snapshot2: &str,
+    options: DiffOptions,
+    cached_changes: Option<ChangeDetectionResult>,
+    timings: &crate::timings::Timings,
+    engine: &EngineSettings,
+) -> Result<()> {
+    let DiffOptions {
+        mode,
+        output_path,
+        delimiter,
+        detect_renames,
+        only,
+        unchanged_limit,
+        context,
+        invert,
+        stat,
+        format,
+        compat,
+        numeric_delta,
+        pretty_width,
+        baseline_format,
+        current_format,
+        coerce,
+        float_tolerance,
+        report_schema_only_changes,
+        match_threshold,
+        auto_sample,
+        append_only,
+        na_values,
+        row_identity,
+        allow_duplicate_keys,
+        where_predicates,
+        line_numbers,
+        group_by,
+        label_column,
+        threads,
+        output_dir,
+        name_suffix,
+    } = options;
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace.clone());
+
+    // Parse diff mode
+    let _diff_mode = DiffMode::parse(mode)
+        .map_err(|e| crate::error::TabdiffError::invalid_input(e))?;
+    // A report is the whole output, so progress messages and warnings are suppressed
+    let report = format.is_some();
+    let quiet = stat || report;
 
-    // Load full snapshot data for comprehensive comparison
-    let snapshot1_data = if resolved1.has_archive() {
-        SnapshotLoader::load_full_snapshot(resolved1.require_archive()?)?
+    // Snapshots whose content hashes match are equal; their rows need not be extracted or compared
+    let identical = if baseline_format.is_none() && current_format.is_none() && unchanged_limit.is_none() {
+        DiffSide::identical_snapshots(&resolver, snapshot1, snapshot2)?
     } else {
-        return Err(crate::error::TabdiffError::archive("Baseline snapshot has no archive data"));
+        None
     };
-    
-    let snapshot2_data = if resolved2.has_archive() {
-        SnapshotLoader::load_full_snapshot(resolved2.require_archive()?)?
+    let content_identical = identical.is_some();
+
+    // --append-only reads only the rows after the baseline's, once the prefix digest matches
+    let appended = if identical.is_none() && append_only && baseline_format.is_none() {
+        let phase = std::time::Instant::now();
+        let appended = DiffSide::load_appended(&resolver, snapshot1, snapshot2, current_format, auto_sample, line_numbers, engine)?;
+        timings.record("prefix check", phase);
+        appended
     } else {
-        return Err(crate::error::TabdiffError::archive("Comparison snapshot has no archive data"));
+        None
     };
-    
-    // Extract baseline schema from archive data
-    let baseline_schema = if let Some(schema_data) = snapshot1_data.schema_data.get("columns") {
-        if let Some(columns_array) = schema_data.as_array() {
-            let mut baseline_columns = Vec::new();
-            for col_value in columns_array {
-                if let (Some(name), Some(data_type), Some(nullable)) = (
-                    col_value.get("name").and_then(|v| v.as_str()),
-                    col_value.get("data_type").and_then(|v| v.as_str()),
-                    col_value.get("nullable").and_then(|v| v.as_bool())
-                ) {
-                    baseline_columns.push(crate::hash::ColumnInfo {
-                        name: name.to_string(),
-                        data_type: data_type.to_string(),
-                        nullable,
-                    });
-                }
-            }
-            baseline_columns
-        } else {
-            Vec::new()
+    let appended_from = appended.as_ref().map(|(_, _, first_row)| *first_row);
+
+    let (baseline, current) = match (identical, appended) {
+        (Some(sides), _) => sides,
+        (None, Some((baseline, current, _))) => (baseline, current),
+        (None, None) => {
+            // Sides with an explicit format are data files; everything else is a snapshot
+            let phase = std::time::Instant::now();
+            let baseline = baseline_format.is_none()
+                .then(|| DiffSide::load_snapshot(&resolver, snapshot1, "Baseline"))
+                .transpose()?;
+            timings.record("load baseline", phase);
+            let phase = std::time::Instant::now();
+            let current = current_format.is_none()
+                .then(|| DiffSide::load_snapshot(&resolver, snapshot2, "Comparison"))
+                .transpose()?;
+            timings.record("load current", phase);
+            
+            // Data files are read the way the snapshot they are compared to was read
+            let reader_settings = baseline.as_ref().or(current.as_ref()).and_then(|side| side.metadata.clone());
+            let sample = reader_settings.as_ref()
+                .filter(|_| baseline.is_none() || current.is_none())
+                .map(|settings| matching_sample(settings, auto_sample, true))
+                .transpose()?
+                .flatten();
+            let phase = std::time::Instant::now();
+            let baseline = match baseline {
+                Some(side) => side,
+                None => DiffSide::load_file(&workspace, snapshot1, baseline_format, reader_settings.as_ref(), sample, line_numbers, engine)?,
+            };
+            timings.record("load baseline", phase);
+            let phase = std::time::Instant::now();
+            let current = match current {
+                Some(side) => side,
+                None => DiffSide::load_file(&workspace, snapshot2, current_format, reader_settings.as_ref(), sample, line_numbers, engine)?,
+            };
+            timings.record("load current", phase);
+            (baseline, current)
         }
-    } else {
-        Vec::new()
     };
-    
-    // Extract current schema from archive data
-    let current_schema = if let Some(schema_data) = snapshot2_data.schema_data.get("columns") {
-        if let Some(columns_array) = schema_data.as_array() {
-            let mut current_columns = Vec::new();
-            for col_value in columns_array {
-                if let (Some(name), Some(data_type), Some(nullable)) = (
-                    col_value.get("name").and_then(|v| v.as_str()),
-                    col_value.get("data_type").and_then(|v| v.as_str()),
-                    col_value.get("nullable").and_then(|v| v.as_bool())
-                ) {
-                    current_columns.push(crate::hash::ColumnInfo {
-                        name: name.to_string(),
-                        data_type: data_type.to_string(),
-                        nullable,
-                    });
-                }
-            }
-            current_columns
-        } else {
-            Vec::new()
+
+    // Metadata-only snapshots record no rows, so only their schemas can be compared
+    let metadata_only_side = [&baseline, &current].into_iter()
+        .find(|side| side.metadata.as_ref().is_some_and(|metadata| metadata.metadata_only))
+        .map(|side| side.name.clone());
+    if let Some(name) = &metadata_only_side {
+        require_row_data(name, &[
+            ("--only with a row category", only.iter().any(|category| *category != ChangeCategory::Schema)),
+            ("--include-unchanged", unchanged_limit.is_some()),
+            ("--context", context.is_some()),
+            ("--key", matches!(row_identity, RowIdentity::Key(_))),
+            ("--ignore-row-order", row_identity == RowIdentity::ContentHash),
+            ("--coerce", !coerce.is_empty()),
+            ("--where", !where_predicates.is_empty()),
+            ("--line-numbers", line_numbers),
+            ("--group-by", group_by.is_some()),
+            ("--detect-renames", detect_renames),
+        ])?;
+    }
+
+    if !quiet {
+        let (from, to) = if invert { (&current.name, &baseline.name) } else { (&baseline.name, &current.name) };
+        println!("🔍 Comparing snapshots: {} → {}", from, to);
+    }
+
+    if let (Some(metadata1), Some(metadata2)) = (&baseline.metadata, &current.metadata) {
+        if !report && metadata1.synthetic_column_names != metadata2.synthetic_column_names {
+            println!("⚠️  Warning: comparing a header-less snapshot against one with a header row.");
+            println!("   Column names will not match and the first data row may be treated as a header.");
         }
-    } else {
-        Vec::new()
-    };
 
-    // Extract baseline row data from archive
-    let baseline_rows = if let Some(rows_data) = snapshot1_data.row_data.get("rows") {
-        if let Some(rows_array) = rows_data.as_array() {
-            let mut baseline_rows = Vec::new();
-            for row_value in rows_array {
-                if let Some(row_array) = row_value.as_array() {
-                    let row: Vec<String> = row_array
-                        .iter()
-                        .map(|v| v.as_str().unwrap_or("").to_string())
-                        .collect();
-                    baseline_rows.push(row);
-                }
-            }
-            baseline_rows
-        } else {
-            Vec::new()
+        let rows_hashed = !metadata1.metadata_only && !metadata2.metadata_only;
+        if rows_hashed && !metadata1.has_hash_format_of(metadata2) {
+            return Err(crate::error::TabdiffError::hash_algorithm_mismatch(format!(
+                "Snapshots '{}' and '{}' hashed their rows with incompatible formats ({} vs {}). Re-create the older one with this version of tabdiff.",
+                baseline.name,
+                current.name,
+                metadata1.format_version,
+                metadata2.format_version,
+            )));
+        }
+        if rows_hashed && metadata1.float_precision != metadata2.float_precision {
+            return Err(crate::error::TabdiffError::hash_algorithm_mismatch(format!(
+                "Snapshots '{}' and '{}' were hashed with different float precision ({} vs {}). Re-create one with a matching --float-precision.",
+                baseline.name,
+                current.name,
+                describe_float_precision(metadata1.float_precision),
+                describe_float_precision(metadata2.float_precision),
+            )));
+        }
+        if rows_hashed && metadata1.na_values != metadata2.na_values {
+            return Err(crate::error::TabdiffError::hash_algorithm_mismatch(format!(
+                "Snapshots '{}' and '{}' were read with different --na-values ({:?} vs {:?}). Re-create one with matching --na-values.",
+                baseline.name,
+                current.name,
+                metadata1.na_values,
+                metadata2.na_values,
+            )));
+        }
+
+        if rows_hashed && metadata1.sample != metadata2.sample {
+            return Err(crate::error::TabdiffError::invalid_input(format!(
+                "Snapshots '{}' and '{}' hold different samples of their sources ({} vs {}), so rows outside either sample would be reported as changed. Re-create one with a matching --sample and --sample-seed.",
+                baseline.name,
+                current.name,
+                describe_sample(metadata1.sample),
+                describe_sample(metadata2.sample),
+            )));
         }
+
+        if !report && rows_hashed && metadata1.order_by != metadata2.order_by {
+            println!("⚠️  Warning: snapshots were hashed with different row ordering ({:?} vs {:?}).", metadata1.order_by, metadata2.order_by);
+            println!("   Row indices may not line up between the two snapshots.");
+        }
+    }
+
+    if !report {
+        warn_on_type_mismatches(&baseline.schema, &current.schema);
+    }
+
+    let row_count = |side: &DiffSide| side.metadata.as_ref().map_or(side.rows.len() as u64, |metadata| metadata.row_count);
+    let mut current_row_count = match appended_from {
+        // Only the rows after the shared prefix were read from a data file
+        Some(first_row) if current.metadata.is_none() => first_row + current.rows.len() as u64,
+        _ => row_count(&current),
+    };
+    let baseline_row_count = row_count(&baseline);
+    // A cached delta was detected with the default options, so any other option recomputes it
+    let cached_changes = cached_changes.filter(|_| {
+        !detect_renames && coerce.is_empty() && float_tolerance.is_none() && !report_schema_only_changes
+            && match_threshold == crate::change_detection::DEFAULT_MATCH_THRESHOLD && na_values.is_empty()
+            && row_identity == RowIdentity::default() && where_predicates.is_empty()
+    });
+    // --na-values replaces the sentinels recorded in the snapshot, as in status
+    let na_values = if na_values.is_empty() {
+        [&baseline, &current].into_iter()
+            .find_map(|side| side.metadata.as_ref())
+            .map(|metadata| metadata.na_values.clone())
+            .unwrap_or_default()
     } else {
-        Vec::new()
+        na_values
     };
+    let mut sources = [baseline.source(snapshot1), current.source(snapshot2)];
+    if invert {
+        sources.reverse();
+    }
+    let diff_suffix = name_suffix.map(|suffix| suffix.render(&sources[0], &sources[1]));
+    let DiffSide { name: mut baseline_name, schema: mut baseline_schema, rows: mut baseline_rows, source_lines: mut baseline_lines, .. } = baseline;
+    let DiffSide { name: mut current_name, schema: mut current_schema, rows: mut current_rows, source_lines: mut current_lines, .. } = current;
+    DataProcessor::blank_na_values(&mut baseline_rows, &na_values);
+    DataProcessor::blank_na_values(&mut current_rows, &na_values);
 
-    // Extract current row data from archive
-    let current_rows = if let Some(rows_data) = snapshot2_data.row_data.get("rows") {
-        if let Some(rows_array) = rows_data.as_array() {
-            let mut current_rows = Vec::new();
-            for row_value in rows_array {
-                if let Some(row_array) = row_value.as_array() {
-                    let row: Vec<String> = row_array
-                        .iter()
-                        .map(|v| v.as_str().unwrap_or("").to_string())
-                        .collect();
-                    current_rows.push(row);
-                }
-            }
-            current_rows
-        } else {
-            Vec::new()
+    // Use comprehensive change detection
+    let key_columns = match &row_identity {
+        RowIdentity::Key(columns) => columns.clone(),
+        _ => Vec::new(),
+    };
+    let options = DetectionOptions::default()
+        .with_renames(detect_renames)
+        .with_coercions(coerce)
+        .with_row_identity(row_identity)
+        .with_allow_duplicate_keys(allow_duplicate_keys)
+        .with_float_tolerance(float_tolerance)
+        .with_type_change_coercion(report_schema_only_changes)
+        .with_match_threshold(match_threshold)
+        .with_cell_predicates(where_predicates)
+        .with_threads(threads)
+        .with_timings(timings.clone());
+    let mut changes = if content_identical {
+        if !quiet {
+            println!("⚡ Content hashes match; skipped row comparison");
+        }
+        ChangeDetectionResult::default()
+    } else if let Some(name) = &metadata_only_side {
+        if !quiet {
+            println!("ℹ️  '{}' is a metadata-only snapshot; compared schemas only", name);
         }
+        ChangeDetector::detect_schema_only(&baseline_schema, &current_schema)?
+    } else if let Some(first_row) = appended_from {
+        if !quiet {
+            println!("⚡ '{}' starts with the {} rows of '{}'; compared only the {} appended rows", current_name, first_row, baseline_name, current_rows.len());
+        }
+        ChangeDetector::detect_appended_rows(&current_schema, &current_rows, first_row)?
+    } else if let Some(cached) = cached_changes {
+        if !quiet {
+            println!("⚡ Using the cached delta from '{}'", baseline_name);
+        }
+        cached
     } else {
-        Vec::new()
+        if append_only && !quiet {
+            println!("⚠️  Warning: '{}' does not start with the rows of '{}', so it was not only appended to; falling back to a full diff", current_name, baseline_name);
+        }
+        ChangeDetector::detect_changes_with_options(
+            &baseline_schema,
+            &baseline_rows,
+            &current_schema,
+            &current_rows,
+            &options,
+        )?
     };
-    
-    // Use comprehensive change detection
-    let changes = crate::change_detection::ChangeDetector::detect_changes(
-        &baseline_schema,
-        &baseline_rows,
-        &current_schema,
-        &current_rows,
-    )?;
+    // --invert reports the changes from the current side back to the baseline, so the two
+    // sides swap places for everything that follows
+    if invert {
+        changes = changes.invert(&baseline_schema, &baseline_rows);
+        std::mem::swap(&mut baseline_name, &mut current_name);
+        std::mem::swap(&mut baseline_schema, &mut current_schema);
+        std::mem::swap(&mut baseline_rows, &mut current_rows);
+        std::mem::swap(&mut baseline_lines, &mut current_lines);
+        current_row_count = baseline_row_count;
+    }
+    let numbering = if line_numbers {
+        RowNumbering::one_based()
+            .with_baseline_lines(baseline_lines)
+            .with_current_lines(current_lines)
+    } else {
+        RowNumbering::default()
+    };
+    if numeric_delta {
+        changes.row_changes.add_numeric_deltas();
+    }
     
     // Build comprehensive diff result
     let schema_changed = !changes.schema_changes.columns_added.is_empty() ||
@@ -521,17 +1546,41 @@ fn diff_command(
                         changes.schema_changes.column_order.is_some();
     let mut columns_changed = Vec::new();
     
+    // Details honour --only; the counts above and below stay complete
+    let filter = ChangeFilter::new(only);
+    let filtered = filter.apply(&changes);
+    // Row positions are needed for context lookups; reported changes use --line-numbers numbering
+    let shown = numbering.apply(&filtered);
+    let row_keys = filtered.row_changes.modified.iter()
+        .filter_map(|modification| current_rows.get(modification.row_index as usize).map(|row| (modification.row_index, row)))
+        .fold(crate::writer::RowKeys::new(key_columns), |keys, (row_idx, row)| {
+            keys.with_modified_row(numbering.current(row_idx), &current_schema, row)
+        });
+    let row_labels = match label_column {
+        Some(column) => {
+            if !quiet && ![&baseline_schema, &current_schema].iter().any(|schema| schema.iter().any(|col| col.name == column)) {
+                println!("⚠️  Warning: neither side has a column '{}', so rows are labeled by index", column);
+            }
+            filtered.row_changes.modified.iter()
+                .filter_map(|modification| current_rows.get(modification.row_index as usize).map(|row| (modification.row_index, row)))
+                .fold(RowLabels::new(column), |labels, (row_idx, row)| {
+                    labels.with_modified_row(numbering.current(row_idx), &current_schema, row)
+                })
+        }
+        None => RowLabels::default(),
+    };
+    
     // Process schema changes
-    for col_add in &changes.schema_changes.columns_added {
+    for col_add in &filtered.schema_changes.columns_added {
         columns_changed.push(format!("  {} (added)", col_add.name));
     }
-    for col_rem in &changes.schema_changes.columns_removed {
+    for col_rem in &filtered.schema_changes.columns_removed {
         columns_changed.push(format!("  {} (removed)", col_rem.name));
     }
-    for col_rename in &changes.schema_changes.columns_renamed {
+    for col_rename in &filtered.schema_changes.columns_renamed {
         columns_changed.push(format!("  {} → {} (renamed)", col_rename.from, col_rename.to));
     }
-    for type_change in &changes.schema_changes.type_changes {
+    for type_change in &filtered.schema_changes.type_changes {
         columns_changed.push(format!("  {} (type changed: {} → {})", 
             type_change.column, type_change.from, type_change.to));
     }
@@ -545,88 +1594,677 @@ fn diff_command(
     let mut sample_changes = Vec::new();
     
     // Add sample modifications
-    for (idx, modification) in changes.row_changes.modified.iter().enumerate() {
+    for (idx, modification) in shown.row_changes.modified.iter().enumerate() {
         if idx >= 5 { break; } // Limit to 5 samples
-        sample_changes.push(serde_json::json!({
+        let mut sample = serde_json::json!({
             "type": "modified",
             "row_index": modification.row_index,
             "changes": modification.changes
-        }));
+        });
+        if let Some(label) = row_labels.of_modified(modification.row_index) {
+            sample["label"] = serde_json::json!(label);
+        }
+        sample_changes.push(sample);
     }
     
     // Add sample additions
-    for (idx, addition) in changes.row_changes.added.iter().enumerate() {
+    for (idx, addition) in shown.row_changes.added.iter().enumerate() {
         if idx >= 5 || sample_changes.len() >= 5 { break; }
-        sample_changes.push(serde_json::json!({
+        let mut sample = serde_json::json!({
             "type": "added",
             "row_index": addition.row_index,
             "data": addition.data
-        }));
+        });
+        if let Some(label) = row_labels.of_data(&addition.data) {
+            sample["label"] = serde_json::json!(label);
+        }
+        sample_changes.push(sample);
     }
     
     // Add sample removals
-    for (idx, removal) in changes.row_changes.removed.iter().enumerate() {
+    for (idx, removal) in shown.row_changes.removed.iter().enumerate() {
         if idx >= 5 || sample_changes.len() >= 5 { break; }
-        sample_changes.push(serde_json::json!({
+        let mut sample = serde_json::json!({
             "type": "removed",
             "data": removal.data
-        }));
+        });
+        if let Some(label) = row_labels.of_data(&removal.data) {
+            sample["label"] = serde_json::json!(label);
+        }
+        sample_changes.push(sample);
     }
 
     // Create comprehensive diff result
-    let diff_result = serde_json::json!({
-        "base": resolved1.name,
-        "compare": resolved2.name,
+    let mut diff_result = serde_json::json!({
+        "base": baseline_name,
+        "compare": current_name,
         "schema_changed": schema_changed,
         "columns_changed": columns_changed,
-        "row_count": metadata2.row_count,
+        "row_count": current_row_count,
         "rows_changed": rows_changed,
         "sample_changes": sample_changes,
         "row_changes": {
             "modified": changes.row_changes.modified.len(),
             "added": changes.row_changes.added.len(),
             "removed": changes.row_changes.removed.len()
-        }
+        },
+        "columns_summary": JsonFormatter::columns_summary(&changes.row_changes)
     });
+    if filter.is_active() {
+        diff_result["only"] = serde_json::json!(only.iter().map(|c| c.as_str()).collect::<Vec<_>>());
+    }
+    if content_identical {
+        diff_result["content_identical"] = serde_json::json!(true);
+    }
+    if metadata_only_side.is_some() {
+        diff_result["schema_only"] = serde_json::json!(true);
+    }
+    if let Some(order_change) = &filtered.schema_changes.column_order {
+        diff_result["column_moves"] = serde_json::to_value(order_change.moves())?;
+    }
+    if !changes.coercion_failures.is_empty() {
+        diff_result["coercion_failures"] = serde_json::to_value(&changes.coercion_failures)?;
+    }
+    if let Some(hash_quality) = &changes.hash_quality {
+        diff_result["hash_quality"] = serde_json::to_value(hash_quality)?;
+    }
+    let groups = group_by
+        .map(|column| changes.row_changes.group_by(column, &baseline_schema, &current_schema, &current_rows))
+        .transpose()?;
+    if let Some(groups) = &groups {
+        diff_result["groups"] = serde_json::to_value(groups)?;
+    }
+    let compatibility = compat.then(|| SchemaCompatibility::analyze(&changes.schema_changes));
+    if let Some(compatibility) = &compatibility {
+        diff_result["compatibility"] = serde_json::to_value(compatibility)?;
+    }
+
+    let unchanged_row = |row_idx: u64| crate::change_detection::RowUnchanged {
+        row_index: numbering.current(row_idx),
+        data: current_schema
+            .iter()
+            .zip(current_rows[row_idx as usize].iter())
+            .map(|(col, value)| (col.name.clone(), value.clone()))
+            .collect(),
+    };
+
+    // Full state of rows that hashed identically, capped by --max-unchanged
+    let mut unchanged_events = Vec::new();
+    if let Some(limit) = unchanged_limit {
+        let unchanged_indices = changes.row_changes.unchanged_row_indices(current_rows.len());
+        let unchanged: Vec<crate::change_detection::RowUnchanged> = unchanged_indices
+            .iter()
+            .take(limit)
+            .map(|&row_idx| unchanged_row(row_idx))
+            .collect();
+        
+        diff_result["row_changes"]["unchanged"] = serde_json::json!(unchanged_indices.len());
+        diff_result["unchanged_truncated"] = serde_json::json!(unchanged.len() < unchanged_indices.len());
+        unchanged_events = crate::writer::ChangeEvent::flatten_unchanged(&unchanged, &row_keys);
+        diff_result["unchanged"] = serde_json::to_value(&unchanged)?;
+    }
+
+    // Unchanged rows surrounding the shown changes, like `diff -C`
+    if let Some(context) = context {
+        let context_rows: Vec<crate::change_detection::RowUnchanged> = filtered.row_changes
+            .context_row_indices(current_rows.len(), context)
+            .into_iter()
+            .map(unchanged_row)
+            .collect();
+        
+        // --include-unchanged already exports every unchanged row
+        if unchanged_limit.is_none() {
+            unchanged_events = crate::writer::ChangeEvent::flatten_unchanged(&context_rows, &row_keys);
+        }
+        diff_result["context"] = serde_json::to_value(&context_rows)?;
+    }
+
+    // Tabular exports get one row per change
+    let mut events = crate::writer::ChangeEvent::flatten(&shown, &row_keys);
+    events.extend(unchanged_events);
 
     // Output results
-    PrettyPrinter::print_diff_results(&diff_result);
+    if format == Some(ReportFormat::Junit) {
+        print!("{}", JUnitFormatter::format(&format!("{} → {}", baseline_name, current_name), &shown));
+    } else if format == Some(ReportFormat::Csv) {
+        crate::writer::CsvWriter::new(delimiter.unwrap_or(',')).write_to(&mut std::io::stdout().lock(), &events)?;
+    } else if stat {
+        PrettyPrinter::print_diff_stat(&changes);
+    } else {
+        PrettyPrinter::print_diff_results(&diff_result);
+        PrettyPrinter::print_change_table(&shown.row_changes, &row_labels, crate::output::output_width(pretty_width));
+        PrettyPrinter::print_columns_summary(&changes.row_changes);
+        if let Some(groups) = &groups {
+            PrettyPrinter::print_change_groups(groups);
+        }
+        if filter.is_active() {
+            println!("\n🔎 Showing only: {}", filter.describe());
+            PrettyPrinter::print_filtered_changes(&shown, filter);
+        }
+        if let Some(compatibility) = &compatibility {
+            PrettyPrinter::print_schema_compatibility(compatibility);
+        }
+        if numeric_delta {
+            PrettyPrinter::print_numeric_changes(&shown.row_changes, &row_labels);
+        }
+        PrettyPrinter::print_coercion_failures(&changes.coercion_failures);
+        if let Some(hash_quality) = &changes.hash_quality {
+            PrettyPrinter::print_hash_quality_warning(hash_quality);
+        }
+    }
 
     // Save diff result if requested
     if let Some(output_path) = output_path {
-        let diff_content = serde_json::to_string_pretty(&diff_result)?;
-        // If output path is relative, resolve it relative to workspace root
-        let final_output_path = if output_path.is_absolute() {
-            output_path.to_path_buf()
-        } else {
-            resolver.workspace().root.join(output_path)
-        };
+        let final_output_path = prepare_output_path(resolver.workspace(), output_path)?;
         
-        // Create parent directories if needed
-        if let Some(parent) = final_output_path.parent() {
-            std::fs::create_dir_all(parent)?;
+        // Tabular formats (--format csv or the extension) get one row per change; anything else is the JSON summary
+        let writer = match format {
+            Some(ReportFormat::Csv) => Some(crate::writer::writer_for_format(
+                "csv",
+                Some(delimiter.unwrap_or_else(|| crate::writer::delimiter_for_path(&final_output_path))),
+            )?),
+            _ => crate::writer::writer_for_path(&final_output_path, delimiter),
+        };
+        if let Some(writer) = writer {
+            writer.write_events(&final_output_path, &events)?;
+        } else {
+            let diff_content = serde_json::to_string_pretty(&diff_result)?;
+            std::fs::write(&final_output_path, diff_content)?;
+        }
+        if !quiet {
+            println!("\n💾 Diff saved to: {}", final_output_path.display());
         }
-        
-        std::fs::write(&final_output_path, diff_content)?;
-        println!("\n💾 Diff saved to: {}", final_output_path.display());
     } else {
-        // Save to default location
-        let diff_path = resolver.workspace().diff_path(&resolved1.name, &resolved2.name);
+        // Save to the default location, or the default filename in --output-dir
+        let diff_dir = match output_dir {
+            Some(dir) if dir.is_absolute() => dir.to_path_buf(),
+            Some(dir) => resolver.workspace().root.join(dir),
+            None => resolver.workspace().diffs_dir.clone(),
+        };
+        let diff_path = TabdiffWorkspace::diff_path_in(&diff_dir, &baseline_name, &current_name, diff_suffix.as_deref());
         let diff_content = serde_json::to_string_pretty(&diff_result)?;
         std::fs::create_dir_all(diff_path.parent().unwrap())?;
         std::fs::write(&diff_path, diff_content)?;
-        println!("\n💾 Diff saved to: {}", diff_path.display());
+        if !quiet {
+            println!("\n💾 Diff saved to: {}", diff_path.display());
+        }
     }
 
     Ok(())
 }
 
+/// Path an input is read from: URLs and absolute paths as given, relative paths resolved
+/// against the current directory when the file is there (a command run from a subdirectory of
+/// the workspace), and against the workspace root otherwise
+fn resolve_input_path(workspace: &TabdiffWorkspace, input: &str) -> PathBuf {
+    if crate::duckdb_config::is_remote_url(input) || Path::new(input).is_absolute() {
+        return PathBuf::from(input);
+    }
+    match std::env::current_dir() {
+        Ok(dir) if dir.join(input).exists() => dir.join(input),
+        _ => workspace.root.join(input),
+    }
+}
+
+/// Path an `--output` file is written to: relative paths are resolved against the workspace
+/// root, and missing parent directories are created
+fn prepare_output_path(workspace: &TabdiffWorkspace, output_path: &Path) -> Result<PathBuf> {
+    let final_output_path = if output_path.is_absolute() {
+        output_path.to_path_buf()
+    } else {
+        workspace.root.join(output_path)
+    };
+    if let Some(parent) = final_output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(final_output_path)
+}
+
+/// Row identity selected by `--key` or `--ignore-row-order` (the two conflict)
+fn row_identity(key: Vec<String>, ignore_row_order: bool) -> RowIdentity {
+    if ignore_row_order {
+        RowIdentity::ContentHash
+    } else {
+        RowIdentity::from_key_columns(key)
+    }
+}
+
+/// Fail when any of `row_options` (flag, requested) is requested against a metadata-only snapshot
+fn require_row_data(snapshot: &str, row_options: &[(&str, bool)]) -> Result<()> {
+    match row_options.iter().find(|(_, requested)| *requested) {
+        Some((flag, _)) => Err(crate::error::TabdiffError::invalid_input(format!(
+            "{} needs row data, but snapshot '{}' is metadata-only. Compare schemas only, or re-create the snapshot without --metadata-only",
+            flag, snapshot
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Human-readable float precision for messages ("default" when unset)
+fn describe_float_precision(float_precision: Option<usize>) -> String {
+    match float_precision {
+        Some(precision) => format!("{} decimal places", precision),
+        None => "default".to_string(),
+    }
+}
+
+/// Human-readable sample for messages ("complete data" when unsampled)
+fn describe_sample(sample: Option<crate::data::SampleSpec>) -> String {
+    match sample {
+        Some(sample) => format!("a {} sample", sample.describe()),
+        None => "complete data".to_string(),
+    }
+}
+
+/// Sample to read a data file compared with `snapshot` by, so both sides hold the same rows
+///
+/// Comparing a sampled snapshot with complete data would report every row outside the sample
+/// as added, so the file is only sampled to match with `--auto-sample`. Without `compares_rows`
+/// only the schema is compared, which sampling leaves as it is, so the file is read whole.
+fn matching_sample(snapshot: &crate::snapshot::SnapshotMetadata, auto_sample: bool, compares_rows: bool) -> Result<Option<crate::data::SampleSpec>> {
+    match snapshot.sample {
+        _ if !compares_rows => Ok(None),
+        Some(sample) if !auto_sample => Err(crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' holds {} of its source, so comparing it with the complete data would report every row outside the sample as added. Pass --auto-sample to sample the current data the same way.",
+            snapshot.name,
+            describe_sample(Some(sample)),
+        ))),
+        sample => Ok(sample),
+    }
+}
+
+/// Guard against comparing unrelated datasets, e.g. a file against another source's snapshot
+struct ColumnOverlapCheck {
+    /// Lowest fraction of shared column names that passes without a warning
+    min_overlap: f64,
+    /// Fail instead of warning
+    strict: bool,
+}
+
+impl ColumnOverlapCheck {
+    /// Warn, or fail when strict, if the schemas share too few column names; `label` names both sides
+    fn check(&self, label: &str, baseline_schema: &[crate::hash::ColumnInfo], current_schema: &[crate::hash::ColumnInfo], quiet: bool) -> Result<()> {
+        let overlap = ChangeDetector::column_overlap(baseline_schema, current_schema);
+        if overlap >= self.min_overlap {
+            return Ok(());
+        }
+        let message = format!(
+            "{} share only {:.0}% of their column names (minimum {:.0}%); they may be unrelated datasets",
+            label, overlap * 100.0, self.min_overlap * 100.0
+        );
+        if self.strict {
+            return Err(crate::error::TabdiffError::invalid_input(message));
+        }
+        if !quiet {
+            println!("⚠️  Warning: {}", message);
+            println!("   Pass --compare-to to choose the snapshot, or --strict to make this an error.");
+        }
+        Ok(())
+    }
+}
+
+/// Warn about shared columns whose types differ, which can show up as spurious value changes
+/// when the two sides come from different formats
+fn warn_on_type_mismatches(baseline_schema: &[crate::hash::ColumnInfo], current_schema: &[crate::hash::ColumnInfo]) {
+    let mismatches: Vec<String> = baseline_schema.iter()
+        .filter_map(|baseline_col| {
+            current_schema.iter()
+                .find(|col| col.name == baseline_col.name && col.data_type != baseline_col.data_type)
+                .map(|col| format!("{} ({} vs {})", col.name, baseline_col.data_type, col.data_type))
+        })
+        .collect();
+    if !mismatches.is_empty() {
+        println!("⚠️  Warning: column types differ between the two sources: {}", mismatches.join(", "));
+        println!("   Values are compared as text; read both sides with matching options if this reports spurious changes.");
+    }
+}
+
+/// One side of a diff: a stored snapshot, or a data file read directly
+struct DiffSide {
+    name: String,
+    metadata: Option<crate::snapshot::SnapshotMetadata>,
+    schema: Vec<crate::hash::ColumnInfo>,
+    rows: Vec<Vec<String>>,
+    /// Line each row starts on, for sides read from a CSV/TSV file with --line-numbers
+    source_lines: Option<Vec<u64>>,
+}
+
+impl DiffSide {
+    /// Where this side's data came from: the snapshot's source, or the data file `reference` names
+    fn source(&self, reference: &str) -> String {
+        self.metadata.as_ref().map_or_else(|| reference.to_string(), |metadata| metadata.source.clone())
+    }
+
+    /// Load a snapshot's full data; `role` names the side in errors
+    ///
+    /// Metadata-only snapshots have no rows; their schema comes from the metadata.
+    fn load_snapshot(resolver: &SnapshotResolver, reference: &str, role: &str) -> Result<Self> {
+        let resolved = resolver.resolve(&SnapshotRef::from_string(reference.to_string()))?;
+        let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+
+        if metadata.metadata_only {
+            return Ok(Self {
+                name: resolved.name,
+                schema: metadata.columns.clone(),
+                metadata: Some(metadata),
+                rows: Vec::new(),
+                source_lines: None,
+            });
+        }
+
+        if !resolved.has_archive() {
+            return Err(crate::error::TabdiffError::archive(format!("{} snapshot has no archive data", role)));
+        }
+        // Hash-only archives hold no rows, so only the schema is read from them
+        let (schema, rows) = if metadata.has_full_data {
+            let data = SnapshotLoader::load_full_snapshot(resolved.require_archive()?)?;
+            (SnapshotLoader::schema_columns(&data.schema_data), data.rows())
+        } else {
+            (SnapshotLoader::load_schema_only(resolved.require_archive()?)?, Vec::new())
+        };
+
+        Ok(Self {
+            name: resolved.name,
+            metadata: Some(metadata),
+            schema,
+            rows,
+            source_lines: None,
+        })
+    }
+
+    /// Both snapshots, without their rows, when their metadata proves they hold the same content
+    fn identical_snapshots(resolver: &SnapshotResolver, baseline: &str, current: &str) -> Result<Option<(Self, Self)>> {
+        let load = |reference: &str| -> Result<(String, crate::snapshot::SnapshotMetadata)> {
+            let resolved = resolver.resolve(&SnapshotRef::from_string(reference.to_string()))?;
+            let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+            Ok((resolved.name, metadata))
+        };
+        let (baseline_name, baseline_metadata) = load(baseline)?;
+        let (current_name, current_metadata) = load(current)?;
+
+        if !baseline_metadata.has_same_content(&current_metadata) {
+            return Ok(None);
+        }
+
+        let side = |name: String, metadata: crate::snapshot::SnapshotMetadata| Self {
+            name,
+            schema: metadata.columns.clone(),
+            metadata: Some(metadata),
+            rows: Vec::new(),
+            source_lines: None,
+        };
+        Ok(Some((side(baseline_name, baseline_metadata), side(current_name, current_metadata))))
+    }
+
+    /// Both sides for `diff --append-only`, when the current side starts with the baseline's rows
+    ///
+    /// The baseline is never read: the first `row_count` row hashes of the current side are
+    /// digested in order and checked against the baseline snapshot's row order hash, and only
+    /// the rows after them are kept on the current side. Returns the sides and that row count,
+    /// or `None` when the prefix cannot be verified and a full diff is needed.
+    fn load_appended(
+        resolver: &SnapshotResolver,
+        baseline: &str,
+        current: &str,
+        current_format: Option<InputFormat>,
+        auto_sample: bool,
+        line_numbers: bool,
+        engine: &EngineSettings,
+    ) -> Result<Option<(Self, Self, u64)>> {
+        let resolved = resolver.resolve(&SnapshotRef::from_string(baseline.to_string()))?;
+        let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+        // Snapshots created before the digest was recorded cannot be checked this way
+        let Some(expected) = metadata.row_order_hash.clone().filter(|_| !metadata.metadata_only) else {
+            return Ok(None);
+        };
+        let first_row = metadata.row_count;
+        let baseline_side = Self {
+            name: resolved.name,
+            schema: metadata.columns.clone(),
+            metadata: Some(metadata),
+            rows: Vec::new(),
+            source_lines: None,
+        };
+        let settings = baseline_side.metadata.as_ref().expect("baseline side is a snapshot");
+
+        let current_side = match current_format {
+            None => {
+                let side = Self::load_snapshot(resolver, current, "Comparison")?;
+                let comparable = side.metadata.as_ref().is_some_and(|other| {
+                    other.has_full_data && settings.has_hash_format_of(other)
+                        && other.float_precision == settings.float_precision
+                        && other.na_values == settings.na_values
+                        && other.sample == settings.sample
+                });
+                // Archived blobs hold their displayed form, not the digest they were hashed by
+                let has_blobs = side.schema.iter().any(|column| column.data_type.to_uppercase().contains("BLOB"));
+                if !comparable || has_blobs || (side.rows.len() as u64) < first_row
+                    || ChangeDetector::detect_schema_only(&settings.columns, &side.schema)?.schema_changes.has_changes() {
+                    return Ok(None);
+                }
+                let mut digest = crate::hash::RowOrderHasher::default();
+                for row in &side.rows[..first_row as usize] {
+                    digest.update(&crate::hash::HashComputer::hash_row(row));
+                }
+                if digest.finalize() != expected {
+                    return Ok(None);
+                }
+                Self { rows: side.rows[first_row as usize..].to_vec(), ..side }
+            }
+            Some(format) => {
+                let input_path = resolve_input_path(resolver.workspace(), current);
+                let sample = matching_sample(settings, auto_sample, true)?;
+                let mut data_processor = engine.data_processor()?
+                    .with_format(Some(format))
+                    .with_sample(sample)
+                    .with_no_header(settings.synthetic_column_names)
+                    .with_order_by(settings.order_by.clone())
+                    .with_float_precision(settings.float_precision)
+                    .with_na_values(settings.na_values.clone())
+                    .with_json_path(settings.json_path.clone());
+                let encoding = settings.encoding.as_deref()
+                    .and_then(|label| SourceEncoding::parse(label).ok())
+                    .unwrap_or_default();
+                let transcoded = encoding.transcode(&input_path)?;
+                let read_path = transcoded.as_ref().map_or(input_path.as_path(), |file| file.path());
+                let data_info = data_processor.load_file(read_path)?;
+                if data_info.row_count < first_row
+                    || ChangeDetector::detect_schema_only(&settings.columns, &data_info.columns)?.schema_changes.has_changes() {
+                    return Ok(None);
+                }
+
+                let mut digest = crate::hash::RowOrderHasher::default();
+                for row_hash in data_processor.row_hashes_iter()?.take(first_row as usize) {
+                    digest.update(&row_hash?.hash);
+                }
+                if digest.finalize() != expected {
+                    return Ok(None);
+                }
+                let rows = data_processor.extract_rows_from(first_row)?;
+                let source_lines = if line_numbers {
+                    data_processor.source_line_numbers(read_path)?
+                        .filter(|lines| lines.len() as u64 == first_row + rows.len() as u64)
+                } else {
+                    None
+                };
+
+                Self {
+                    name: input_path.file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .unwrap_or(current)
+                        .to_string(),
+                    metadata: None,
+                    schema: data_info.columns,
+                    rows,
+                    source_lines,
+                }
+            }
+        };
+        Ok(Some((baseline_side, current_side, first_row)))
+    }
+
+    /// Read a data file, using the reader settings recorded in `settings` when given
+    ///
+    /// With `sample`, only the rows that sample keeps are read. With `line_numbers`, the line
+    /// each row starts on is recorded for CSV/TSV files.
+    fn load_file(
+        workspace: &TabdiffWorkspace,
+        input: &str,
+        format: Option<InputFormat>,
+        settings: Option<&crate::snapshot::SnapshotMetadata>,
+        sample: Option<crate::data::SampleSpec>,
+        line_numbers: bool,
+        engine: &EngineSettings,
+    ) -> Result<Self> {
+        let input_path = resolve_input_path(workspace, input);
+
+        let mut data_processor = engine.data_processor()?.with_format(format).with_sample(sample);
+        let mut encoding = SourceEncoding::default();
+        if let Some(settings) = settings {
+            data_processor = data_processor
+                .with_no_header(settings.synthetic_column_names)
+                .with_order_by(settings.order_by.clone())
+                .with_float_precision(settings.float_precision)
+                .with_na_values(settings.na_values.clone())
+                .with_json_path(settings.json_path.clone());
+            encoding = settings.encoding.as_deref()
+                .and_then(|label| SourceEncoding::parse(label).ok())
+                .unwrap_or_default();
+        }
+
+        let transcoded = encoding.transcode(&input_path)?;
+        let read_path = transcoded.as_ref().map_or(input_path.as_path(), |file| file.path());
+        let data_info = data_processor.load_file(read_path)?;
+        let rows = data_processor.extract_all_data()?;
+        let source_lines = if line_numbers {
+            data_processor.source_line_numbers(read_path)?
+                .filter(|lines| lines.len() == rows.len())
+        } else {
+            None
+        };
+
+        let name = input_path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(input)
+            .to_string();
+
+        Ok(Self {
+            name,
+            metadata: None,
+            schema: data_info.columns,
+            rows,
+            source_lines,
+        })
+    }
+}
+
+/// Write a snapshot's rows to a file through the output format registry
+fn export_command(
+    workspace_path: Option<&Path>,
+    snapshot: &str,
+    output: &Path,
+    format: Option<&str>,
+    delimiter: Option<char>,
+) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace.clone());
+    let resolved = resolver.resolve(&SnapshotRef::from_string(snapshot.to_string()))?;
+    let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+
+    if !metadata.has_full_data || !resolved.has_archive() {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' has no row data to export; create it with --full-data", resolved.name
+        )));
+    }
+
+    let writer = match format {
+        Some(name) => crate::writer::writer_for_format(name, delimiter)?,
+        None => crate::writer::writer_for_path(output, delimiter).ok_or_else(|| {
+            crate::error::TabdiffError::invalid_input(format!(
+                "Cannot tell the output format of '{}' from its extension; use --format ({})",
+                output.display(),
+                crate::writer::writer_formats().join(", ")
+            ))
+        })?,
+    };
+
+    let output_path = prepare_output_path(&workspace, output)?;
+    let rows = SnapshotLoader::load_full_snapshot(resolved.require_archive()?)?.rows();
+    writer.write_rows(&output_path, &metadata.columns, &rows, !metadata.synthetic_column_names)?;
+
+    println!("📤 Exported {} rows of snapshot '{}' to {}", rows.len(), resolved.name, output_path.display());
+
+    Ok(())
+}
+
+/// Check that a snapshot's archive can be read and still holds the data it was created with
+///
+/// With a signing key, the signature recorded at creation must also match the archive.
+fn verify_command(
+    workspace_path: Option<&Path>,
+    snapshot: &str,
+    signing_key: Option<&crate::signing::SigningKey>,
+) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace);
+    let resolved = resolver.resolve(&SnapshotRef::from_string(snapshot.to_string()))?;
+    let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+
+    if metadata.metadata_only {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' is metadata-only and has no archive to verify", resolved.name
+        )));
+    }
+    let files = crate::archive::ArchiveManager::extract_archive(resolved.require_archive()?).map_err(|e| {
+        crate::error::TabdiffError::archive(format!("Archive of '{}' cannot be read: {}", resolved.name, e))
+    })?;
+    if let Some(data_hash) = &metadata.data_hash {
+        let intact = files.iter()
+            .find(|(name, _)| name == "data.parquet")
+            .is_some_and(|(_, content)| blake3::hash(content).to_hex().as_str() == data_hash.as_str());
+        if !intact {
+            return Err(crate::error::TabdiffError::archive(format!(
+                "Row data of '{}' does not match the hash recorded when it was created", resolved.name
+            )));
+        }
+    }
+    println!("✅ Archive of '{}' is intact ({} files)", resolved.name, files.len());
+
+    let Some(key) = signing_key else {
+        return Ok(());
+    };
+    let Some(signature) = &metadata.signature else {
+        return Err(crate::error::TabdiffError::archive(format!(
+            "Snapshot '{}' is not signed; create it with --sign-key", resolved.name
+        )));
+    };
+    let fingerprint = key.fingerprint();
+    if metadata.signed_by.as_deref().is_some_and(|signed_by| signed_by != fingerprint) {
+        return Err(crate::error::TabdiffError::archive(format!(
+            "Snapshot '{}' was signed with key {}, not with the given key {}",
+            resolved.name,
+            metadata.signed_by.as_deref().unwrap_or_default(),
+            fingerprint
+        )));
+    }
+    if !key.verify_files(&files, signature) {
+        return Err(crate::error::TabdiffError::archive(format!(
+            "Signature of '{}' does not match: its archive was changed after it was signed", resolved.name
+        )));
+    }
+    println!("🔏 Signature valid (key {})", fingerprint);
+
+    Ok(())
+}
+
 /// Show snapshot information
 fn show_command(
     workspace_path: Option<&Path>,
     snapshot: &str,
     detailed: bool,
+    diff_parent: bool,
     json: bool,
+    schema_format: Option<SchemaFormat>,
 ) -> Result<()> {
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
     let resolver = SnapshotResolver::new(workspace.clone());
@@ -637,172 +2275,787 @@ fn show_command(
 
     // Load metadata
     let metadata = SnapshotLoader::load_metadata(&resolved.json_path)?;
+
+    if diff_parent {
+        return show_parent_delta(&metadata, json);
+    }
+
+    if let Some(schema_format) = schema_format {
+        println!("{}", schema_format.render(&metadata.name, &metadata.columns)?);
+        return Ok(());
+    }
+
     let metadata_json = serde_json::to_value(&metadata)?;
 
     if json {
-        if detailed && resolved.has_archive() {
-            // Load full snapshot data
-            let full_data = SnapshotLoader::load_full_snapshot(resolved.require_archive()?)?;
-            let combined = serde_json::json!({
-                "metadata": metadata_json,
-                "archive_data": {
-                    "schema": full_data.schema_data,
-                    "rows": full_data.row_data
-                }
-            });
-            println!("{}", serde_json::to_string_pretty(&combined)?);
-        } else {
-            println!("{}", serde_json::to_string_pretty(&metadata_json)?);
-        }
+        if detailed && resolved.has_archive() {
+            // Load full snapshot data
+            let full_data = SnapshotLoader::load_full_snapshot(resolved.require_archive()?)?;
+            let combined = serde_json::json!({
+                "metadata": metadata_json,
+                "archive_data": {
+                    "schema": full_data.schema_data,
+                    "rows": full_data.row_data
+                }
+            });
+            println!("{}", serde_json::to_string_pretty(&combined)?);
+        } else {
+            println!("{}", serde_json::to_string_pretty(&metadata_json)?);
+        }
+    } else {
+        PrettyPrinter::print_snapshot_metadata(&metadata_json, detailed);
+    }
+
+    Ok(())
+}
+
+/// List the files inside a snapshot archive, or dump one of them
+fn show_raw_command(workspace_path: Option<&Path>, snapshot: &str, file: Option<&str>, json: bool) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace);
+    let resolved = resolver.resolve(&SnapshotRef::from_string(snapshot.to_string()))?;
+    let archive_path = resolved.require_archive()?;
+
+    if let Some(file) = file {
+        // Only this file is read, not the rest of the archive
+        let content = crate::archive::ArchiveManager::extract_file(archive_path, file)?.ok_or_else(|| {
+            let names = crate::archive::ArchiveManager::list_archive_contents(archive_path)
+                .map(|entries| entries.iter()
+                    .map(|entry| entry.path.strip_suffix(crate::archive::REFERENCE_SUFFIX).unwrap_or(&entry.path))
+                    .collect::<Vec<_>>()
+                    .join(", "))
+                .unwrap_or_default();
+            crate::error::TabdiffError::invalid_input(format!(
+                "Archive of '{}' has no file '{}'; it contains: {}",
+                resolved.name,
+                file,
+                names
+            ))
+        })?;
+        // Written as-is: archive files are not necessarily text
+        use std::io::Write;
+        std::io::stdout().write_all(&content)?;
+        return Ok(());
+    }
+    let files = crate::archive::ArchiveManager::extract_archive(archive_path)?;
+
+    if json {
+        let listing: Vec<serde_json::Value> = files.iter()
+            .map(|(name, content)| serde_json::json!({ "name": name, "size": content.len() }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&listing)?);
+    } else {
+        println!("📦 Archive of '{}': {}", resolved.name, archive_path.display());
+        for (i, (name, content)) in files.iter().enumerate() {
+            let branch = if i + 1 == files.len() { "└─" } else { "├─" };
+            println!("{} {} ({} bytes)", branch, name, content.len());
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the cached delta between a snapshot and its parent without reloading either archive
+fn show_parent_delta(metadata: &crate::snapshot::SnapshotMetadata, json: bool) -> Result<()> {
+    let delta = match (&metadata.parent_snapshot, &metadata.delta_from_parent) {
+        (Some(_), Some(delta)) => delta,
+        _ => {
+            if json {
+                let output = serde_json::json!({
+                    "snapshot": metadata.name,
+                    "parent": metadata.parent_snapshot,
+                    "changes": null
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("ℹ️  '{}': first snapshot / no delta", metadata.name);
+            }
+            return Ok(());
+        }
+    };
+
+    if json {
+        let output = serde_json::json!({
+            "snapshot": metadata.name,
+            "parent": delta.parent_name,
+            "changes": delta.changes
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        PrettyPrinter::print_snapshot_metadata(&metadata_json, detailed);
+        println!("🔍 Changes from '{}' → '{}' (cached delta)", delta.parent_name, metadata.name);
+        PrettyPrinter::print_comprehensive_status_results(&delta.changes, false);
     }
 
     Ok(())
 }
 
+/// How `tabdiff status` reads the current file and reports its changes
+struct StatusOptions<'a> {
+    /// Compare only the schema, not the rows
+    schema_only: bool,
+    quiet: bool,
+    json: bool,
+    output_path: Option<&'a Path>,
+    format: Option<ReportFormat>,
+    detect_renames: bool,
+    only: &'a [ChangeCategory],
+    /// Re-check whenever the file changes instead of once
+    watch: Option<crate::watch::WatchOptions>,
+    encoding: Option<SourceEncoding>,
+    current_format: Option<InputFormat>,
+    /// JSON pointer to the array of rows (default: the snapshot's)
+    json_path: Option<String>,
+    coerce: Vec<ColumnCoercion>,
+    float_tolerance: Option<f64>,
+    report_schema_only_changes: bool,
+    match_threshold: f64,
+    auto_sample: bool,
+    na_values: Vec<String>,
+    row_identity: RowIdentity,
+    allow_duplicate_keys: bool,
+    where_predicates: Vec<CellPredicate>,
+    line_numbers: bool,
+    group_by: Option<&'a str>,
+    threads: Option<usize>,
+    overlap_check: ColumnOverlapCheck,
+}
+
 /// Check status against a snapshot
 fn status_command(
     workspace_path: Option<&Path>,
     input: &str,
     compare_to: Option<&str>,
-    quiet: bool,
-    json: bool,
+    options: StatusOptions,
+    timings: &crate::timings::Timings,
+    engine: &EngineSettings,
 ) -> Result<()> {
+    let StatusOptions {
+        schema_only,
+        quiet,
+        json,
+        output_path,
+        format,
+        detect_renames,
+        only,
+        watch,
+        encoding,
+        current_format,
+        json_path,
+        coerce,
+        float_tolerance,
+        report_schema_only_changes,
+        match_threshold,
+        auto_sample,
+        na_values,
+        row_identity,
+        allow_duplicate_keys,
+        where_predicates,
+        line_numbers,
+        group_by,
+        threads,
+        overlap_check,
+    } = options;
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
     let resolver = SnapshotResolver::new(workspace.clone());
+    let junit = format == Some(ReportFormat::Junit);
+    if format == Some(ReportFormat::Csv) {
+        return Err(crate::error::TabdiffError::invalid_input(
+            "--format csv is only supported by diff; use --json for machine-readable status"
+        ));
+    }
+
+    let input_path = resolve_input_path(&workspace, input);
 
-    // Resolve comparison snapshot
+    // Resolve comparison snapshot (default: the source's baseline, else its latest snapshot)
     let comparison_snapshot = if let Some(name) = compare_to {
         let snap_ref = SnapshotRef::from_string(name.to_string());
         resolver.resolve(&snap_ref)?
     } else {
-        resolver.resolve_latest()?.ok_or_else(|| {
-            crate::error::TabdiffError::workspace("No snapshots found to compare against")
+        let canonical_source_path = crate::snapshot::canonical_source_path(&input_path);
+        resolver.resolve_default_for_source(&canonical_source_path)?.ok_or_else(|| {
+            crate::error::TabdiffError::workspace(format!(
+                "No snapshots found for '{}'. Create one with 'tabdiff snapshot {} --name <name>' or pass --compare-to",
+                input, input
+            ))
         })?
     };
 
-    if !json {
+    if !json && !junit {
         println!("📊 Checking status of '{}' against snapshot '{}'...", input, comparison_snapshot.name);
     }
 
     // Load baseline snapshot metadata and data
     let baseline_metadata = SnapshotLoader::load_metadata(&comparison_snapshot.json_path)?;
     
-    // Check if baseline snapshot has full data for rollback capability
-    if !baseline_metadata.has_full_data {
-        return Err(crate::error::TabdiffError::invalid_input(
-            "Cannot rollback from hash-only snapshot. Use --full-data when creating snapshots for rollback capability."
-        ));
-    }
-    
-    let baseline_data = if comparison_snapshot.has_archive() {
-        SnapshotLoader::load_full_snapshot(comparison_snapshot.require_archive()?)?
+    // Metadata-only snapshots record no rows, so only the schemas can be compared
+    let phase = std::time::Instant::now();
+    let (baseline_schema, baseline_row_data) = if baseline_metadata.metadata_only {
+        require_row_data(&comparison_snapshot.name, &[
+            ("--only with a row category", only.iter().any(|category| *category != ChangeCategory::Schema)),
+            ("--key", matches!(row_identity, RowIdentity::Key(_))),
+            ("--ignore-row-order", row_identity == RowIdentity::ContentHash),
+            ("--coerce", !coerce.is_empty()),
+            ("--where", !where_predicates.is_empty()),
+            ("--line-numbers", line_numbers),
+            ("--group-by", group_by.is_some()),
+            ("--detect-renames", detect_renames),
+        ])?;
+        if !json && !quiet && !junit {
+            println!("ℹ️  '{}' is a metadata-only snapshot; comparing schemas only", comparison_snapshot.name);
+        }
+        (baseline_metadata.columns.clone(), Vec::new())
+    } else if schema_only {
+        // The metadata records the columns, so the archive is not opened
+        (baseline_metadata.columns.clone(), Vec::new())
     } else {
-        return Err(crate::error::TabdiffError::archive("Baseline snapshot has no archive data"));
-    };
+        // Check if baseline snapshot has full data for rollback capability
+        if !baseline_metadata.has_full_data {
+            return Err(crate::error::TabdiffError::invalid_input(
+                "Cannot rollback from hash-only snapshot. Use --full-data when creating snapshots for rollback capability."
+            ));
+        }
 
-    // Load current data
-    let input_path = if Path::new(input).is_absolute() {
-        Path::new(input).to_path_buf()
-    } else {
-        // Resolve relative paths relative to the workspace root
-        workspace.root.join(input)
-    };
+        let baseline_data = if comparison_snapshot.has_archive() {
+            SnapshotLoader::load_full_snapshot(comparison_snapshot.require_archive()?)?
+        } else {
+            return Err(crate::error::TabdiffError::archive("Baseline snapshot has no archive data"));
+        };
 
-    let mut data_processor = DataProcessor::new()?;
-    let current_data_info = data_processor.load_file(&input_path)?;
-    let current_row_data = data_processor.extract_all_data()?;
+        // Extract baseline schema from archive data
+        let baseline_schema = if let Some(schema_data) = baseline_data.schema_data.get("columns") {
+            if let Some(columns_array) = schema_data.as_array() {
+                let mut baseline_columns = Vec::new();
+                for col_value in columns_array {
+                    if let (Some(name), Some(data_type), Some(nullable)) = (
+                        col_value.get("name").and_then(|v| v.as_str()),
+                        col_value.get("data_type").and_then(|v| v.as_str()),
+                        col_value.get("nullable").and_then(|v| v.as_bool())
+                    ) {
+                        baseline_columns.push(crate::hash::ColumnInfo {
+                            name: name.to_string(),
+                            data_type: data_type.to_string(),
+                            nullable,
+                        });
+                    }
+                }
+                baseline_columns
+            } else {
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        };
 
-    // Extract baseline schema from archive data
-    let baseline_schema = if let Some(schema_data) = baseline_data.schema_data.get("columns") {
-        if let Some(columns_array) = schema_data.as_array() {
-            let mut baseline_columns = Vec::new();
-            for col_value in columns_array {
-                if let (Some(name), Some(data_type), Some(nullable)) = (
-                    col_value.get("name").and_then(|v| v.as_str()),
-                    col_value.get("data_type").and_then(|v| v.as_str()),
-                    col_value.get("nullable").and_then(|v| v.as_bool())
-                ) {
-                    baseline_columns.push(crate::hash::ColumnInfo {
-                        name: name.to_string(),
-                        data_type: data_type.to_string(),
-                        nullable,
-                    });
+        // Extract baseline row data from archive
+        let baseline_row_data = if let Some(rows_data) = baseline_data.row_data.get("rows") {
+            if let Some(rows_array) = rows_data.as_array() {
+                let mut baseline_rows = Vec::new();
+                for row_value in rows_array {
+                    if let Some(row_array) = row_value.as_array() {
+                        let row: Vec<String> = row_array
+                            .iter()
+                            .map(|v| v.as_str().unwrap_or("").to_string())
+                            .collect();
+                        baseline_rows.push(row);
+                    }
                 }
+                baseline_rows
+            } else {
+                Vec::new()
             }
-            baseline_columns
         } else {
             Vec::new()
+        };
+        (baseline_schema, baseline_row_data)
+    };
+    timings.record("load baseline", phase);
+    let schema_only = schema_only || baseline_metadata.metadata_only;
+
+    // --na-values replaces the sentinels recorded in the snapshot; rows the snapshot stored
+    // before they were configured are normalized too
+    let na_values = if na_values.is_empty() { baseline_metadata.na_values.clone() } else { na_values };
+    let mut baseline_row_data = baseline_row_data;
+    DataProcessor::blank_na_values(&mut baseline_row_data, &na_values);
+
+    // Read the current file the same way the baseline was read (header-less or not)
+    let mut data_processor = engine.data_processor()?
+        .with_no_header(baseline_metadata.synthetic_column_names)
+        .with_order_by(baseline_metadata.order_by.clone())
+        .with_float_precision(baseline_metadata.float_precision)
+        .with_na_values(na_values)
+        .with_format(current_format)
+        .with_json_path(json_path.or_else(|| baseline_metadata.json_path.clone()))
+        .with_sample(matching_sample(&baseline_metadata, auto_sample, !schema_only)?);
+    let encoding = encoding.unwrap_or_else(|| {
+        baseline_metadata.encoding.as_deref()
+            .and_then(|label| SourceEncoding::parse(label).ok())
+            .unwrap_or_default()
+    });
+
+    // Compare the current file against the baseline (re-run on every tick in watch mode)
+    let options = DetectionOptions::default()
+        .with_renames(detect_renames)
+        .with_coercions(coerce)
+        .with_row_identity(row_identity)
+        .with_allow_duplicate_keys(allow_duplicate_keys)
+        .with_float_tolerance(float_tolerance)
+        .with_type_change_coercion(report_schema_only_changes)
+        .with_match_threshold(match_threshold)
+        .with_cell_predicates(where_predicates)
+        .with_threads(threads)
+        .with_timings(timings.clone());
+    let hash_computer = crate::hash::HashComputer::new(crate::DEFAULT_BATCH_SIZE);
+    let mut warned_types = quiet || json || junit;
+    let announce_schema_change = !(quiet || json || junit) && watch.is_none();
+    let mut checked_overlap = false;
+    // Groups are counted before --line-numbers renumbers rows away from their positions
+    let mut check_status = || -> Result<(ChangeDetectionResult, Option<ChangeGroups>)> {
+        let transcoded = encoding.transcode(&input_path)?;
+        let read_path = transcoded.as_ref().map_or(input_path.as_path(), |file| file.path());
+        let phase = std::time::Instant::now();
+        let current_data_info = data_processor.load_file(read_path)?;
+        timings.record("load current", phase);
+        let group = |changes: &ChangeDetectionResult, current_rows: &[Vec<String>]| group_by
+            .map(|column| changes.row_changes.group_by(column, &baseline_schema, &current_data_info.columns, current_rows))
+            .transpose();
+
+        if !checked_overlap {
+            let label = format!("'{}' and snapshot '{}'", input, comparison_snapshot.name);
+            overlap_check.check(&label, &baseline_schema, &current_data_info.columns, quiet || json || junit)?;
+            checked_overlap = true;
         }
-    } else {
-        Vec::new()
+        if !warned_types {
+            warn_on_type_mismatches(&baseline_schema, &current_data_info.columns);
+            warned_types = true;
+        }
+
+        // A schema hash matching the snapshot's means there are no schema changes to detect
+        let schema_matches = hash_computer.hash_schema(&current_data_info.columns)?.hash == baseline_metadata.schema_hash;
+        if schema_only {
+            if schema_matches {
+                return Ok((ChangeDetectionResult::default(), None));
+            }
+            return Ok((ChangeDetector::detect_schema_only(&baseline_schema, &current_data_info.columns)?, None));
+        }
+
+        // Matching schema and content hashes mean nothing changed; skip extracting and comparing rows.
+        // Content hashes of an older hash format are not comparable, so the rows are compared instead.
+        if baseline_metadata.content_hash.is_some()
+            && crate::hash_format(&baseline_metadata.format_version) == crate::hash_format(crate::FORMAT_VERSION)
+            && current_data_info.row_count == baseline_metadata.row_count
+            && schema_matches
+        {
+            let row_hashes = data_processor.compute_row_hashes_sql()?;
+            let content_hash = hash_computer.content_hash(row_hashes.iter().map(|rh| rh.hash.as_str()));
+            if baseline_metadata.content_hash.as_deref() == Some(content_hash.as_str()) {
+                log::debug!("Content hash matches snapshot '{}'; skipped row comparison", comparison_snapshot.name);
+                ChangeDetector::validate_options(&current_data_info.columns, &options)?;
+                let changes = ChangeDetectionResult::default();
+                let groups = group(&changes, &[])?;
+                return Ok((changes, groups));
+            }
+        }
+
+        // Report a schema change before extracting rows, which can take a while for large files
+        if announce_schema_change && !schema_matches {
+            let schema_changes = ChangeDetector::detect_schema_only(&baseline_schema, &current_data_info.columns)?.schema_changes;
+            println!("🧬 Schema differs from snapshot '{}' ({} schema changes); comparing rows...", comparison_snapshot.name, schema_changes.change_count());
+        }
+
+        let phase = std::time::Instant::now();
+        let current_row_data = data_processor.extract_all_data()?;
+        timings.record("load current", phase);
+        let changes = ChangeDetector::detect_changes_with_options(
+            &baseline_schema,
+            &baseline_row_data,
+            &current_data_info.columns,
+            &current_row_data,
+            &options,
+        )?;
+        let groups = group(&changes, &current_row_data)?;
+        if !line_numbers {
+            return Ok((changes, groups));
+        }
+        // The baseline's rows come from the snapshot, so only the current side has source lines
+        let current_lines = data_processor.source_line_numbers(read_path)?
+            .filter(|lines| lines.len() == current_row_data.len());
+        Ok((RowNumbering::one_based().with_current_lines(current_lines).apply(&changes), groups))
     };
 
-    // Extract baseline row data from archive
-    let baseline_row_data = if let Some(rows_data) = baseline_data.row_data.get("rows") {
-        if let Some(rows_array) = rows_data.as_array() {
-            let mut baseline_rows = Vec::new();
-            for row_value in rows_array {
-                if let Some(row_array) = row_value.as_array() {
-                    let row: Vec<String> = row_array
-                        .iter()
-                        .map(|v| v.as_str().unwrap_or("").to_string())
-                        .collect();
-                    baseline_rows.push(row);
+    // --output keeps the complete result, independent of what is printed
+    let output_path = output_path.map(|path| prepare_output_path(&workspace, path)).transpose()?;
+    let save_status = |changes: &ChangeDetectionResult| -> Result<()> {
+        if let Some(output_path) = &output_path {
+            std::fs::write(output_path, JsonFormatter::format_comprehensive_status_results(changes)?)?;
+        }
+        Ok(())
+    };
+
+    let filter = ChangeFilter::new(only);
+    let mut printer = StatusPrinter::new(quiet, json);
+
+    let watch = match watch {
+        Some(watch) => watch,
+        None if junit => {
+            let (changes, _) = check_status()?;
+            save_status(&changes)?;
+            let suite = format!("{} → {}", comparison_snapshot.name, input);
+            print!("{}", JUnitFormatter::format(&suite, &filter.apply(&changes)));
+            return Ok(());
+        }
+        None => {
+            let (changes, groups) = check_status()?;
+            save_status(&changes)?;
+            printer.print_if_changed(&changes, filter, groups.as_ref())?;
+            if let Some(output_path) = output_path.as_ref().filter(|_| !quiet && !json) {
+                println!("\n💾 Status saved to: {}", output_path.display());
+            }
+            return Ok(());
+        }
+    };
+
+    if !json {
+        println!("👀 Watching every {:.1}s (Ctrl-C to stop)...", watch.interval.as_secs_f64());
+    }
+
+    // Keep watching through transient errors (e.g. a half-written file); exit with the last result
+    let mut last_result = Ok(());
+    let mut last_error = None;
+    let mut iterations = 0;
+    loop {
+        // Ctrl-C cancels the process-wide token; any earlier cancellation already ended the loop
+        crate::cancel::CancellationToken::global().reset();
+        crate::watch::wait_for_stable(&input_path, crate::watch::DEBOUNCE);
+        if crate::watch::interrupted() {
+            break;
+        }
+
+        match check_status() {
+            Ok((changes, groups)) => {
+                save_status(&changes)?;
+                printer.print_if_changed(&changes, filter, groups.as_ref())?;
+                last_error = None;
+                last_result = Ok(());
+            }
+            // Ctrl-C stopped this check part-way; keep the last complete result
+            Err(crate::error::TabdiffError::Cancelled) => break,
+            Err(e) => {
+                let message = e.to_string();
+                if last_error.as_deref() != Some(message.as_str()) {
+                    eprintln!("⚠️  {}", message);
                 }
+                printer.reset();
+                last_error = Some(message);
+                last_result = Err(e);
             }
-            baseline_rows
-        } else {
-            Vec::new()
         }
+
+        iterations += 1;
+        if watch.max_iterations.is_some_and(|max| iterations >= max) {
+            break;
+        }
+        if !crate::watch::sleep_interruptible(watch.interval) {
+            break;
+        }
+    }
+
+    last_result
+}
+
+/// How `tabdiff status --against-self` reads the file and reports its issues
+struct SelfCheckOptions {
+    json: bool,
+    encoding: Option<SourceEncoding>,
+    current_format: Option<InputFormat>,
+    json_path: Option<String>,
+    /// Strings read as empty cells, so a numeric column holding `NA` is not mixed-type
+    na_values: Vec<String>,
+}
+
+/// Check a file for internal consistency, without a snapshot to compare against
+fn self_check_command(
+    workspace_path: Option<&Path>,
+    input: &str,
+    options: SelfCheckOptions,
+    engine: &EngineSettings,
+) -> Result<()> {
+    let SelfCheckOptions { json, encoding, current_format, json_path, na_values } = options;
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+
+    let input_path = resolve_input_path(&workspace, input);
+
+    let transcoded = encoding.unwrap_or_default().transcode(&input_path)?;
+    let read_path = transcoded.as_ref().map_or(input_path.as_path(), |file| file.path());
+    let mut data_processor = engine.data_processor()?
+        .with_format(current_format)
+        .with_json_path(json_path)
+        .with_na_values(na_values);
+    let report = crate::consistency::ConsistencyReport::check(&mut data_processor, read_path)?;
+
+    if json {
+        let mut output = serde_json::to_value(&report)?;
+        output["source"] = serde_json::json!(input);
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        Vec::new()
-    };
+        PrettyPrinter::print_consistency_report(input, &report);
+    }
 
-    // Use comprehensive change detection
-    let changes = ChangeDetector::detect_changes(
-        &baseline_schema,
-        &baseline_row_data,
-        &current_data_info.columns,
-        &current_row_data,
-    )?;
+    Ok(())
+}
+
+/// Print basic file statistics from a dry read (no hashing or archiving)
+fn stat_command(workspace_path: Option<&Path>, input: &str, json: bool, engine: &EngineSettings) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+
+    let input_path = resolve_input_path(&workspace, input);
+
+    let mut data_processor = engine.data_processor()?;
+    let data_info = data_processor.load_file(&input_path)?;
 
-    // Output results
     if json {
-        let status_json = JsonFormatter::format_comprehensive_status_results(&changes)?;
-        println!("{}", status_json);
+        let output = serde_json::json!({
+            "source": input,
+            "row_count": data_info.row_count,
+            "column_count": data_info.column_count(),
+            "columns": data_info.columns,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        PrettyPrinter::print_comprehensive_status_results(&changes, quiet);
+        println!("📄 {}", input);
+        println!("├─ Rows: {}", data_info.row_count);
+        println!("├─ Columns: {}", data_info.column_count());
+        println!("└─ Schema:");
+        for (i, column) in data_info.columns.iter().enumerate() {
+            let prefix = if i == data_info.columns.len() - 1 { "   └─" } else { "   ├─" };
+            println!("{} {} ({})", prefix, column.name, column.data_type);
+        }
     }
 
     Ok(())
 }
 
-/// List all snapshots
-fn list_command(workspace_path: Option<&Path>, json: bool) -> Result<()> {
+/// List all snapshots, or only those taken of `source`
+fn list_command(workspace_path: Option<&Path>, pattern: Option<&str>, source: Option<&str>, json: bool) -> Result<()> {
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
-    let resolver = SnapshotResolver::new(workspace);
 
-    let snapshots = resolver.list_snapshots()?;
-    
+    let entries: Vec<(String, Option<crate::snapshot::SnapshotMetadata>)> = match source {
+        Some(input) => {
+            let input_path = resolve_input_path(&workspace, input);
+
+            let canonical_source_path = crate::snapshot::canonical_source_path(&input_path);
+
+            crate::snapshot::SnapshotChain::build_chain_for_source(&workspace, &canonical_source_path)?
+                .snapshots
+                .into_iter()
+                .filter(|metadata| crate::resolver::glob_matches(pattern.unwrap_or("*"), &metadata.name))
+                .map(|metadata| (metadata.name.clone(), Some(metadata)))
+                .collect()
+        }
+        None => {
+            let resolver = SnapshotResolver::new(workspace.clone());
+            // Details are best-effort: a snapshot whose metadata can't be read is still listed
+            resolver.list_snapshots_matching(pattern.unwrap_or("*"))?
+                .into_iter()
+                .map(|name| {
+                    let (_, json_path) = workspace.snapshot_paths(&name);
+                    let metadata = SnapshotLoader::load_metadata(&json_path).ok();
+                    (name, metadata)
+                })
+                .collect()
+        }
+    };
+
+    if json {
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(name, metadata)| match metadata {
+                Some(metadata) => serde_json::json!({
+                    "name": name,
+                    "description": metadata.description,
+                    "created": metadata.created,
+                    "row_count": metadata.row_count,
+                    "has_full_data": metadata.has_full_data,
+                    "metadata_only": metadata.metadata_only,
+                    "parent": metadata.parent_snapshot,
+                    "archive_size": metadata.archive_size,
+                    "source": metadata.source_path.as_deref().unwrap_or(&metadata.source),
+                }),
+                None => serde_json::json!({ "name": name }),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        PrettyPrinter::print_snapshot_list(&entries);
+    }
+
+    Ok(())
+}
+
+/// Record or clear the snapshot that `status` compares a source against by default
+fn set_baseline_command(workspace_path: Option<&Path>, input: &str, snapshot: Option<&str>, clear: bool) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace.clone());
+
+    let input_path = resolve_input_path(&workspace, input);
+
+    let canonical_source_path = crate::snapshot::canonical_source_path(&input_path);
+
+    if clear {
+        workspace.set_baseline(&canonical_source_path, None)?;
+        println!("📌 Cleared baseline for '{}'", input);
+        return Ok(());
+    }
+
+    let snapshot = snapshot.ok_or_else(|| {
+        crate::error::TabdiffError::invalid_input("A snapshot name is required unless --clear is given")
+    })?;
+    let resolved = resolver.resolve(&SnapshotRef::from_string(snapshot.to_string()))?;
+    workspace.set_baseline(&canonical_source_path, Some(&resolved.name))?;
+    println!("📌 Baseline for '{}' set to '{}'", input, resolved.name);
+
+    Ok(())
+}
+
+/// Show the snapshot history for a single source file
+fn log_command(workspace_path: Option<&Path>, input: &str, json: bool) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+
+    let input_path = resolve_input_path(&workspace, input);
+
+    let canonical_source_path = crate::snapshot::canonical_source_path(&input_path);
+
+    let chain = crate::snapshot::SnapshotChain::build_chain_for_source(&workspace, &canonical_source_path)?;
+
     if json {
-        println!("{}", serde_json::to_string_pretty(&snapshots)?);
+        let entries: Vec<serde_json::Value> = chain.snapshots
+            .iter()
+            .map(|snapshot| serde_json::json!({
+                "name": snapshot.name,
+                "created": snapshot.created,
+                "sequence_number": snapshot.sequence_number,
+                "row_count": snapshot.row_count,
+                "column_count": snapshot.column_count,
+                "parent": snapshot.parent_snapshot,
+                "summary": log_summary(snapshot),
+            }))
+            .collect();
+        let log_json = serde_json::json!({
+            "source": canonical_source_path,
+            "snapshots": entries,
+        });
+        println!("{}", serde_json::to_string_pretty(&log_json)?);
     } else {
-        PrettyPrinter::print_snapshot_list(&snapshots);
+        println!("📜 History for '{}'", input);
+
+        if chain.snapshots.is_empty() {
+            println!("No snapshots found for this source.");
+            return Ok(());
+        }
+
+        for (i, snapshot) in chain.snapshots.iter().enumerate() {
+            let prefix = if i == chain.snapshots.len() - 1 { "└─" } else { "├─" };
+            println!(
+                "{} {} ({}) - {} rows - {}",
+                prefix,
+                snapshot.name,
+                snapshot.created.format("%Y-%m-%d %H:%M:%S UTC"),
+                snapshot.row_count,
+                log_summary(snapshot),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// One-line delta summary for a snapshot in the log
+fn log_summary(snapshot: &crate::snapshot::SnapshotMetadata) -> String {
+    match (&snapshot.delta_from_parent, &snapshot.parent_snapshot) {
+        (Some(delta), _) => delta.changes.summary(),
+        (None, Some(_)) => "no delta recorded".to_string(),
+        (None, None) => "initial snapshot".to_string(),
+    }
+}
+
+/// Squash the chain range `from`..=`to` into a standalone full-data snapshot
+fn merge_command(workspace_path: Option<&Path>, from: &str, to: &str, name: &str) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let resolver = SnapshotResolver::new(workspace.clone());
+
+    if workspace.snapshot_exists(name) {
+        return Err(crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' already exists. Use a different name or remove the existing snapshot.",
+            name
+        )));
+    }
+
+    let from_snapshot = resolver.resolve(&SnapshotRef::from_string(from.to_string()))?;
+    let to_snapshot = resolver.resolve(&SnapshotRef::from_string(to.to_string()))?;
+    let to_metadata = SnapshotLoader::load_metadata(&to_snapshot.json_path)?;
+
+    // Both endpoints must be on the same source chain, with `from` at or before `to`
+    let source_path = to_metadata.source_path.clone()
+        .unwrap_or_else(|| crate::snapshot::canonical_source_path(Path::new(&to_metadata.source)));
+    let chain = crate::snapshot::SnapshotChain::build_chain_for_source(&workspace, &source_path)?;
+    let path = chain.find_path_to_snapshot(&to_snapshot.name).unwrap_or_default();
+    let from_position = path.iter().position(|n| *n == from_snapshot.name).ok_or_else(|| {
+        crate::error::TabdiffError::invalid_input(format!(
+            "Snapshot '{}' is not an ancestor of '{}' in the same source chain",
+            from_snapshot.name, to_snapshot.name
+        ))
+    })?;
+    let squashed = &path[from_position..];
+    let parent = chain.get_parent(&from_snapshot.name).map(|p| p.name.clone());
+
+    println!("🔀 Squashing {} snapshot(s) '{}' → '{}' into '{}'...", squashed.len(), from_snapshot.name, to_snapshot.name, name);
+
+    let rows = chain.reconstruct_rows(&workspace, &to_snapshot.name)?;
+
+    let creator = SnapshotCreator::new(10000, false);
+    // The squashed snapshot is the newest in the chain, so it takes the next free sequence number
+    let sequence_number = chain.snapshots.iter().map(|s| s.sequence_number).max().unwrap_or(0) + 1;
+    let metadata = creator.create_squashed_snapshot(&workspace, &to_metadata, name, parent, sequence_number, &rows)?;
+
+    // Snapshots built on `to` now build on the squashed snapshot, which holds the same data; they
+    // and their descendants are renumbered after it so sequence numbers still grow along the chain
+    let mut descendants = chain.get_children(&to_snapshot.name);
+    let mut next = 0;
+    while next < descendants.len() {
+        descendants.extend(chain.get_children(&descendants[next].name));
+        next += 1;
     }
+    descendants.sort_by_key(|descendant| descendant.sequence_number);
+    for (offset, descendant) in descendants.into_iter().enumerate() {
+        let mut descendant = descendant.clone();
+        descendant.sequence_number = sequence_number + 1 + offset as u64;
+        if descendant.parent_snapshot.as_deref() == Some(to_snapshot.name.as_str()) {
+            descendant.parent_snapshot = Some(name.to_string());
+            if let Some(delta) = descendant.delta_from_parent.as_mut() {
+                delta.parent_name = name.to_string();
+            }
+        }
+        let (_, descendant_json_path) = workspace.snapshot_paths(&descendant.name);
+        std::fs::write(&descendant_json_path, serde_json::to_string_pretty(&descendant)?)?;
+    }
+
+    println!("✅ Created squashed snapshot '{}' ({} rows, parent: {})",
+             name, metadata.row_count, metadata.parent_snapshot.as_deref().unwrap_or("none"));
+    println!("🧹 No longer part of the chain: {}", squashed.join(", "));
+    println!("   Their archives can be removed once you no longer need them individually.");
 
     Ok(())
 }
 
 /// Show snapshot chain and relationships
-fn chain_command(workspace_path: Option<&Path>, json: bool) -> Result<()> {
+fn chain_command(workspace_path: Option<&Path>, json: bool, graph: Option<crate::chain_graph::GraphFormat>) -> Result<()> {
     let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
 
     // Build snapshot chain
     let chain = crate::snapshot::SnapshotChain::build_chain(&workspace)?;
 
-    if json {
+    if let Some(graph) = graph {
+        println!("{}", graph.render(&chain.snapshots));
+    } else if json {
         let chain_json = serde_json::json!({
             "snapshots": chain.snapshots,
             "head": chain.head,
@@ -838,6 +3091,10 @@ fn chain_command(workspace_path: Option<&Path>, json: bool) -> Result<()> {
             
             println!("{} {} (seq: {})", prefix, snapshot.name, snapshot.sequence_number);
             
+            if let Some(description) = &snapshot.description {
+                println!("   └─ Description: {}", description);
+            }
+            
             if let Some(parent) = &snapshot.parent_snapshot {
                 println!("   └─ Parent: {}", parent);
             }
@@ -899,7 +3156,7 @@ fn cleanup_command(
     if candidates_for_cleanup.is_empty() {
         println!("✅ No snapshots need data cleanup.");
         println!("   • Total archives: {}", full_archives_count);
-        println!("   • Keep full data for: {}", keep_full);
+        println!("   • Keep full data for: {} per source", keep_full);
         return Ok(());
     }
 
@@ -917,7 +3174,7 @@ fn cleanup_command(
     println!("📊 Cleanup analysis:");
     println!("   • Total archives: {}", full_archives_count);
     println!("   • Snapshots for data cleanup: {}", candidates_for_cleanup.len());
-    println!("   • Keep full data for: {}", keep_full);
+    println!("   • Keep full data for: {} per source", keep_full);
     println!("   • Estimated space savings: {} bytes", total_space_saved);
     println!("   • Archives will retain deltas for reconstruction");
 
@@ -941,7 +3198,7 @@ fn cleanup_command(
             let newest_cleanup = &candidates_for_cleanup[0];
             println!("   • Rollback will NOT work for snapshots: {} to {}", oldest_cleanup.name, newest_cleanup.name);
         }
-        println!("   • Rollback WILL work for the {} most recent snapshots", keep_full);
+        println!("   • Rollback WILL work for the {} most recent snapshots of each source", keep_full);
         println!("   • Full data will be removed from {} snapshots (deltas preserved)", candidates_for_cleanup.len());
         println!("\n❓ Continue with cleanup? (y/N)");
         
@@ -981,3 +3238,97 @@ fn cleanup_command(
 
     Ok(())
 }
+
+/// Remove orphaned diff files and archives
+fn gc_command(workspace_path: Option<&Path>, dry_run: bool, force: bool) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let orphans = workspace.find_orphaned_files()?;
+
+    if orphans.is_empty() {
+        println!("✅ No orphaned files found.");
+        return Ok(());
+    }
+
+    let total_size: u64 = orphans.iter().map(|orphan| orphan.size).sum();
+    println!("🗑️  Found {} orphaned file(s) ({} bytes):", orphans.len(), total_size);
+    for orphan in &orphans {
+        let display_path = orphan.path.strip_prefix(&workspace.root).unwrap_or(&orphan.path);
+        println!("   • {} ({})", display_path.display(), orphan.reason);
+    }
+
+    if dry_run {
+        println!("\n💡 Run without --dry-run to remove these files");
+        return Ok(());
+    }
+
+    // Ask for confirmation unless force is used
+    if !force {
+        println!("\n❓ Remove these files? (y/N)");
+        
+        let mut user_input = String::new();
+        std::io::stdin().read_line(&mut user_input)?;
+        
+        if !user_input.trim().to_lowercase().starts_with('y') {
+            println!("❌ Garbage collection cancelled.");
+            return Ok(());
+        }
+    }
+
+    let stats = workspace.remove_orphaned_files(&orphans)?;
+    println!("✅ Removed {} file(s), reclaimed {} bytes", stats.files_removed, stats.bytes_freed);
+
+    Ok(())
+}
+
+/// Option defaults of the workspace, used where a flag was not given
+fn workspace_defaults(workspace_path: Option<&Path>) -> Result<WorkspaceDefaults> {
+    TabdiffWorkspace::find_or_create(workspace_path)?.defaults()
+}
+
+/// View or change the workspace's option defaults
+fn config_command(
+    workspace_path: Option<&Path>,
+    get: Option<&str>,
+    set: &[(String, String)],
+    unset: &[String],
+    list: bool,
+) -> Result<()> {
+    let workspace = TabdiffWorkspace::find_or_create(workspace_path)?;
+    let mut defaults = workspace.defaults()?;
+
+    if let Some(key) = get {
+        match defaults.get(key)? {
+            Some(value) => println!("{}", value),
+            None => eprintln!("ℹ️  '{}' is not set; the built-in default applies", key),
+        }
+        return Ok(());
+    }
+
+    // Validate every change before saving any of them
+    for (key, value) in set {
+        defaults.set(key, Some(value))?;
+    }
+    for key in unset {
+        defaults.set(key, None)?;
+    }
+    if !set.is_empty() || !unset.is_empty() {
+        workspace.save_defaults(&defaults)?;
+        for (key, value) in set {
+            println!("⚙️  Set {} = {}", key, value);
+        }
+        for key in unset {
+            println!("⚙️  Unset {}", key);
+        }
+        if !list {
+            return Ok(());
+        }
+    }
+
+    for key in WorkspaceDefaults::KEYS {
+        match defaults.get(key)? {
+            Some(value) => println!("{} = {}", key, value),
+            None => println!("{} (not set)", key),
+        }
+    }
+    Ok(())
+}
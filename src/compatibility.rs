@@ -0,0 +1,226 @@
+//! Schema-evolution compatibility of detected schema changes, for `diff --compat`
+//!
+//! Levels follow the usual schema-registry meaning: a backward-compatible change lets readers
+//! of the new schema read data written with the old one, a forward-compatible change lets
+//! readers of the old schema read new data, and a breaking change allows neither.
+
+use crate::change_detection::SchemaChanges;
+use serde::Serialize;
+
+/// How a schema change affects readers of the data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    /// Readers of either schema can read data written with the other
+    Full,
+    /// Readers of the new schema can read old data
+    Backward,
+    /// Readers of the old schema can read new data
+    Forward,
+    Breaking,
+}
+
+impl Compatibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "fully compatible",
+            Self::Backward => "backward-compatible",
+            Self::Forward => "forward-compatible",
+            Self::Breaking => "breaking",
+        }
+    }
+
+    /// The level that holds for both changes, e.g. backward and forward together hold for neither
+    fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Full, level) | (level, Self::Full) => level,
+            (a, b) if a == b => a,
+            _ => Self::Breaking,
+        }
+    }
+}
+
+/// Verdict on one schema change
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeVerdict {
+    /// "column_added", "column_removed", "column_renamed", "type_changed" or "column_order"
+    pub kind: &'static str,
+    pub column: Option<String>,
+    pub compatibility: Compatibility,
+    pub reason: String,
+}
+
+/// Compatibility of every schema change and of all of them together
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaCompatibility {
+    pub level: Compatibility,
+    pub changes: Vec<ChangeVerdict>,
+}
+
+impl SchemaCompatibility {
+    pub fn analyze(schema_changes: &SchemaChanges) -> Self {
+        let mut changes = Vec::new();
+
+        for addition in &schema_changes.columns_added {
+            let (compatibility, reason) = if addition.nullable || addition.default_value.is_some() {
+                (Compatibility::Backward, "old rows read the new column as null".to_string())
+            } else {
+                (Compatibility::Forward, "old rows have no value for the required new column".to_string())
+            };
+            changes.push(ChangeVerdict { kind: "column_added", column: Some(addition.name.clone()), compatibility, reason });
+        }
+
+        for removal in &schema_changes.columns_removed {
+            changes.push(ChangeVerdict {
+                kind: "column_removed",
+                column: Some(removal.name.clone()),
+                compatibility: Compatibility::Breaking,
+                reason: "readers of the column no longer find it".to_string(),
+            });
+        }
+
+        for rename in &schema_changes.columns_renamed {
+            changes.push(ChangeVerdict {
+                kind: "column_renamed",
+                column: Some(rename.to.clone()),
+                compatibility: Compatibility::Breaking,
+                reason: format!("readers looking up '{}' by name no longer find it", rename.from),
+            });
+        }
+
+        for type_change in &schema_changes.type_changes {
+            let (compatibility, reason) = if widens(&type_change.from, &type_change.to) {
+                (Compatibility::Backward, format!("{} → {} widens the type; old values still fit", type_change.from, type_change.to))
+            } else {
+                (Compatibility::Breaking, format!("{} → {} narrows or changes the type; values may not convert", type_change.from, type_change.to))
+            };
+            changes.push(ChangeVerdict { kind: "type_changed", column: Some(type_change.column.clone()), compatibility, reason });
+        }
+
+        if schema_changes.column_order.is_some() {
+            changes.push(ChangeVerdict {
+                kind: "column_order",
+                column: None,
+                compatibility: Compatibility::Full,
+                reason: "readers that address columns by name are unaffected".to_string(),
+            });
+        }
+
+        let level = changes.iter().fold(Compatibility::Full, |level, verdict| level.combine(verdict.compatibility));
+        Self { level, changes }
+    }
+}
+
+/// Kinds of values a type holds; only types of one kind can widen into each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeFamily {
+    Numeric,
+    Temporal,
+    Text,
+}
+
+/// DuckDB types (and their aliases) ranked within their family from narrowest to widest
+const TYPE_RANKS: &[(&str, TypeFamily, u8)] = &[
+    ("BOOLEAN", TypeFamily::Numeric, 0),
+    ("BOOL", TypeFamily::Numeric, 0),
+    ("TINYINT", TypeFamily::Numeric, 1),
+    ("INT1", TypeFamily::Numeric, 1),
+    ("SMALLINT", TypeFamily::Numeric, 2),
+    ("INT2", TypeFamily::Numeric, 2),
+    ("INTEGER", TypeFamily::Numeric, 3),
+    ("INT", TypeFamily::Numeric, 3),
+    ("INT4", TypeFamily::Numeric, 3),
+    ("BIGINT", TypeFamily::Numeric, 4),
+    ("INT8", TypeFamily::Numeric, 4),
+    ("HUGEINT", TypeFamily::Numeric, 5),
+    ("FLOAT", TypeFamily::Numeric, 6),
+    ("REAL", TypeFamily::Numeric, 6),
+    ("DECIMAL", TypeFamily::Numeric, 7),
+    ("NUMERIC", TypeFamily::Numeric, 7),
+    ("DOUBLE", TypeFamily::Numeric, 8),
+    ("DATE", TypeFamily::Temporal, 0),
+    ("TIMESTAMP", TypeFamily::Temporal, 1),
+    ("DATETIME", TypeFamily::Temporal, 1),
+    ("TIMESTAMP WITH TIME ZONE", TypeFamily::Temporal, 2),
+    ("TIMESTAMPTZ", TypeFamily::Temporal, 2),
+    ("VARCHAR", TypeFamily::Text, 0),
+    ("TEXT", TypeFamily::Text, 0),
+    ("STRING", TypeFamily::Text, 0),
+];
+
+/// Family and rank of a type, ignoring parameters such as `DECIMAL(10,2)`
+fn type_rank(data_type: &str) -> Option<(TypeFamily, u8)> {
+    let upper = data_type.trim().to_uppercase();
+    let base = upper.split('(').next().unwrap_or_default().trim();
+    TYPE_RANKS.iter()
+        .find(|(name, _, _)| *name == base)
+        .map(|&(_, family, rank)| (family, rank))
+}
+
+/// Whether every value of `from` is also a value of `to`; text holds any value
+fn widens(from: &str, to: &str) -> bool {
+    match (type_rank(from), type_rank(to)) {
+        (_, Some((TypeFamily::Text, _))) => true,
+        (Some((from_family, from_rank)), Some((to_family, to_rank))) => from_family == to_family && from_rank < to_rank,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::change_detection::{ColumnAddition, ColumnRemoval, TypeChange};
+
+    fn addition(name: &str, nullable: bool) -> ColumnAddition {
+        ColumnAddition { name: name.to_string(), data_type: "VARCHAR".to_string(), position: 2, nullable, default_value: None }
+    }
+
+    #[test]
+    fn test_adding_nullable_column_is_backward_compatible() {
+        let changes = SchemaChanges { columns_added: vec![addition("email", true)], ..SchemaChanges::default() };
+        let compatibility = SchemaCompatibility::analyze(&changes);
+
+        assert_eq!(compatibility.level, Compatibility::Backward);
+        assert_eq!(compatibility.changes.len(), 1);
+        assert_eq!(compatibility.changes[0].column.as_deref(), Some("email"));
+
+        let changes = SchemaChanges { columns_added: vec![addition("email", false)], ..SchemaChanges::default() };
+        assert_eq!(SchemaCompatibility::analyze(&changes).level, Compatibility::Forward);
+    }
+
+    #[test]
+    fn test_dropping_column_is_breaking() {
+        let changes = SchemaChanges {
+            columns_added: vec![addition("email", true)],
+            columns_removed: vec![ColumnRemoval { name: "phone".to_string(), data_type: "VARCHAR".to_string(), position: 1, nullable: true }],
+            ..SchemaChanges::default()
+        };
+        let compatibility = SchemaCompatibility::analyze(&changes);
+
+        assert_eq!(compatibility.level, Compatibility::Breaking);
+        assert_eq!(compatibility.changes[1].kind, "column_removed");
+        assert_eq!(compatibility.changes[1].compatibility, Compatibility::Breaking);
+    }
+
+    #[test]
+    fn test_type_changes_are_ranked() {
+        let type_change = |from: &str, to: &str| SchemaChanges {
+            type_changes: vec![TypeChange { column: "price".to_string(), from: from.to_string(), to: to.to_string() }],
+            ..SchemaChanges::default()
+        };
+
+        assert_eq!(SchemaCompatibility::analyze(&type_change("INTEGER", "BIGINT")).level, Compatibility::Backward);
+        assert_eq!(SchemaCompatibility::analyze(&type_change("DECIMAL(10,2)", "DOUBLE")).level, Compatibility::Backward);
+        assert_eq!(SchemaCompatibility::analyze(&type_change("DATE", "VARCHAR")).level, Compatibility::Backward);
+        assert_eq!(SchemaCompatibility::analyze(&type_change("DOUBLE", "INTEGER")).level, Compatibility::Breaking);
+        assert_eq!(SchemaCompatibility::analyze(&type_change("VARCHAR", "DATE")).level, Compatibility::Breaking);
+        assert_eq!(SchemaCompatibility::analyze(&type_change("BIGINT", "DATE")).level, Compatibility::Breaking);
+    }
+
+    #[test]
+    fn test_no_schema_changes_are_fully_compatible() {
+        let compatibility = SchemaCompatibility::analyze(&SchemaChanges::default());
+        assert_eq!(compatibility.level, Compatibility::Full);
+        assert!(compatibility.changes.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+//! Internal consistency checks of a single file, for `status --against-self`
+//!
+//! No snapshot is involved: the file is checked for text columns whose values mostly, but not
+//! all, share a type, records with a different number of fields than the first one, and
+//! columns without any values.
+
+use crate::data::DataProcessor;
+use crate::error::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// Text column whose values mostly parse as one type
+#[derive(Debug, Clone, Serialize)]
+pub struct MixedTypeColumn {
+    pub column: String,
+    pub inferred_type: String,
+    pub matching: u64,
+    pub mismatched: u64,
+    pub examples: Vec<String>,
+}
+
+/// Record with a different number of fields than the first record (usually the header)
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldCountAnomaly {
+    pub line: u64,
+    pub expected: usize,
+    pub found: usize,
+}
+
+/// Everything `status --against-self` found in one file
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyReport {
+    pub row_count: u64,
+    pub column_count: usize,
+    pub mixed_type_columns: Vec<MixedTypeColumn>,
+    pub field_count_anomalies: Vec<FieldCountAnomaly>,
+    pub null_columns: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// Check the file at `path`; CSV/TSV files are read as text so their types can be inferred
+    pub fn check(processor: &mut DataProcessor, path: &Path) -> Result<Self> {
+        let field_counts = processor.record_field_counts(path)?;
+        let data_info = match field_counts {
+            Some(_) => processor.load_delimited_as_text(path)?,
+            None => processor.load_file(path)?,
+        };
+
+        let mut report = Self {
+            row_count: data_info.row_count,
+            column_count: data_info.column_count(),
+            ..Self::default()
+        };
+
+        if let Some((_, expected)) = field_counts.as_ref().and_then(|counts| counts.first().copied()) {
+            report.field_count_anomalies = field_counts.iter()
+                .flatten()
+                .filter(|(_, found)| *found != expected)
+                .map(|&(line, found)| FieldCountAnomaly { line, expected, found })
+                .collect();
+        }
+
+        for profile in processor.column_profiles()? {
+            if data_info.row_count > 0 && profile.present == 0 {
+                report.null_columns.push(profile.name.clone());
+            }
+            if profile.is_mixed() {
+                report.mixed_type_columns.push(MixedTypeColumn {
+                    inferred_type: profile.dominant_type.clone().unwrap_or_default(),
+                    matching: profile.present - profile.nonconforming,
+                    mismatched: profile.nonconforming,
+                    examples: profile.examples,
+                    column: profile.name,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub fn has_issues(&self) -> bool {
+        !self.mixed_type_columns.is_empty() || !self.field_count_anomalies.is_empty() || !self.null_columns.is_empty()
+    }
+}
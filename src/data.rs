@@ -1,13 +1,19 @@
 //! Data processing utilities using DuckDB
 
+use crate::duckdb_config::DuckDbSettings;
 use crate::error::Result;
 use crate::hash::ColumnInfo;
+use crate::progress::ProgressFormat;
 use crate::sql;
 use blake3;
 use duckdb::Connection;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Only files up to this size are checked for being header-only; a header alone is far smaller
+const HEADER_ONLY_PROBE_BYTES: u64 = 64 * 1024;
+
 fn get_duckdb_install_instructions() -> String {
     if cfg!(target_os = "windows") {
         r#"  Windows:
@@ -50,15 +56,116 @@ fn get_duckdb_install_instructions() -> String {
     }
 }
 
+/// Reader used for an input file instead of detecting the format from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Csv,
+    Tsv,
+    Parquet,
+    Json,
+    Avro,
+}
+
+impl InputFormat {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "parquet" => Ok(Self::Parquet),
+            "json" | "jsonl" => Ok(Self::Json),
+            "avro" => Ok(Self::Avro),
+            _ => Err(format!("Invalid format: {}. Use 'csv', 'tsv', 'parquet', 'json' or 'avro'", s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Parquet => "parquet",
+            Self::Json => "json",
+            Self::Avro => "avro",
+        }
+    }
+
+    /// Format implied by the extension of a URL's path, ignoring any query string or fragment
+    fn of_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let extension = Path::new(path).extension()?.to_str()?;
+        Self::parse(extension).ok()
+    }
+
+    /// DuckDB table function call that reads `path` in this format
+    ///
+    /// `null_strings` is appended to the options of the CSV/TSV readers (see `nullstr_option`).
+    fn reader_expr(&self, path: &str, no_header: bool, null_strings: &str) -> String {
+        let header = if no_header { ", header=false" } else { "" };
+        match self {
+            Self::Csv => format!("read_csv('{}'{}{})", path, header, null_strings),
+            Self::Tsv => format!("read_csv('{}', delim='\\t'{}{})", path, header, null_strings),
+            Self::Parquet => format!("read_parquet('{}')", path),
+            Self::Json => format!("read_json_auto('{}')", path),
+            Self::Avro => format!("read_avro('{}')", path),
+        }
+    }
+}
+
+/// Share of rows kept by `snapshot --sample`, picked by a seeded hash of each row's content
+///
+/// Picking by content rather than position keeps every unchanged row in or out of the sample
+/// however the file is reordered, so two samples taken with the same fraction and seed compare
+/// like the complete data; a modified row may enter or leave the sample. The hash is tabdiff's
+/// own blake3 over the row's canonical values, so samples stay the same across DuckDB versions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SampleSpec {
+    pub fraction: f64,
+    pub seed: u64,
+}
+
+impl SampleSpec {
+    /// Row hashes are compared in steps of one millionth of the rows
+    const RESOLUTION: u64 = 1_000_000;
+
+    /// Whether the row with these canonical values (as they are hashed) is in the sample
+    pub fn keeps(&self, row_values: &[String]) -> bool {
+        let threshold = (self.fraction * Self::RESOLUTION as f64).round() as u64;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.seed.to_le_bytes());
+        hasher.update(crate::hash::HashComputer::hash_row(row_values).as_bytes());
+        let digest = hasher.finalize();
+        let bucket = u64::from_le_bytes(digest.as_bytes()[..8].try_into().expect("blake3 digests are 32 bytes"));
+        bucket % Self::RESOLUTION < threshold
+    }
+
+    /// Fraction and seed for messages, e.g. "10% (seed 42)"
+    pub fn describe(&self) -> String {
+        let percent = (self.fraction * Self::RESOLUTION as f64).round() / (Self::RESOLUTION / 100) as f64;
+        format!("{}% (seed {})", percent, self.seed)
+    }
+}
+
 /// Data processor for various file formats
 pub struct DataProcessor {
     connection: Connection,
     chunk_size: usize,
     cached_columns: Option<Vec<ColumnInfo>>,
     streaming_query: Option<String>,
+    no_header: bool,
+    order_by: Vec<String>,
+    float_precision: Option<usize>,
+    na_values: Vec<String>,
+    format: Option<InputFormat>,
+    json_path: Option<String>,
+    sample: Option<SampleSpec>,
+    hash_checkpoint: Option<crate::checkpoint::HashCheckpoint>,
+    progress_format: ProgressFormat,
+    cancellation: crate::cancel::CancellationToken,
 }
 
 impl DataProcessor {
+    /// Column numbering the rows of a source while `--sample` picks from it
+    const SAMPLE_ORDINAL: &'static str = "__tabdiff_sample_row";
+
     /// Create a new data processor with default settings
     pub fn new() -> Result<Self> {
         Self::new_with_config(10000) // Default chunk size of 10K rows
@@ -66,6 +173,12 @@ impl DataProcessor {
 
     /// Create a new data processor with custom configuration
     pub fn new_with_config(chunk_size: usize) -> Result<Self> {
+        let settings = DuckDbSettings::from_env()?;
+        Self::new_with_settings(chunk_size, &settings)
+    }
+
+    /// Create a new data processor with explicit DuckDB resource settings
+    pub fn new_with_settings(chunk_size: usize, settings: &DuckDbSettings) -> Result<Self> {
         let connection = match Connection::open_in_memory() {
             Ok(conn) => conn,
             Err(e) => {
@@ -93,13 +206,22 @@ impl DataProcessor {
         };
         
         // Optimize DuckDB for large datasets and performance
-        connection.execute("SET memory_limit='8GB'", [])?;  // Increased from 4GB
-        // DuckDB auto-detects optimal thread count by default, no need to set explicitly
+        connection.execute(&format!("SET memory_limit='{}'", settings.memory_limit()), [])?;
+        // DuckDB auto-detects optimal thread count unless explicitly configured
+        if let Some(threads) = settings.threads {
+            connection.execute(&format!("SET threads={}", threads), [])?;
+        }
+        // Extensions downloaded once stay in this directory for later runs
+        if let Some(directory) = &settings.extension_directory {
+            connection.execute(
+                &format!("SET extension_directory='{}'", directory.display().to_string().replace('\'', "''")),
+                [],
+            )?;
+        }
         connection.execute("SET enable_progress_bar=false", [])?; // Disable for performance
         connection.execute("SET preserve_insertion_order=false", [])?; // Allow reordering for performance
         connection.execute("SET enable_object_cache=true", [])?; // Enable object caching
         connection.execute("SET temp_directory='/tmp'", [])?; // Use fast temp storage
-        connection.execute("SET max_memory='8GB'", [])?; // Set max memory usage
         connection.execute("SET force_compression='auto'", [])?; // Enable compression for temp data
         
         Ok(Self { 
@@ -107,35 +229,354 @@ impl DataProcessor {
             chunk_size, 
             cached_columns: None,
             streaming_query: None,
+            no_header: false,
+            order_by: Vec::new(),
+            float_precision: None,
+            na_values: Vec::new(),
+            format: None,
+            json_path: None,
+            sample: None,
+            hash_checkpoint: None,
+            progress_format: ProgressFormat::from_env(),
+            cancellation: crate::cancel::CancellationToken::global(),
         })
     }
 
+    /// Treat CSV/TSV input as header-less so DuckDB generates column names (column0, column1, ...)
+    pub fn with_no_header(mut self, no_header: bool) -> Self {
+        self.no_header = no_header;
+        self
+    }
+
+    /// Sort rows by the given columns before hashing and extraction so row indices are reproducible
+    pub fn with_order_by(mut self, order_by: Vec<String>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// Round fractional numbers to this many decimal places when stringifying values
+    pub fn with_float_precision(mut self, float_precision: Option<usize>) -> Self {
+        self.float_precision = float_precision;
+        self
+    }
+
+    /// Read these strings (e.g. `NA`, `N/A`, `-`) as NULL, i.e. as empty cells
+    pub fn with_na_values(mut self, na_values: Vec<String>) -> Self {
+        self.na_values = na_values;
+        self
+    }
+
+    /// Read input with this format's reader instead of detecting it from the file extension
+    pub fn with_format(mut self, format: Option<InputFormat>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Read the rows of JSON input from the array at this JSON Pointer, e.g. "/data/items"
+    pub fn with_json_path(mut self, json_path: Option<String>) -> Self {
+        self.json_path = json_path;
+        self
+    }
+
+    /// Keep only this sample of the rows of data files
+    pub fn with_sample(mut self, sample: Option<SampleSpec>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Record row hashes to this checkpoint while hashing, and skip rows it already holds when resuming
+    pub fn with_hash_checkpoint(mut self, checkpoint: Option<crate::checkpoint::HashCheckpoint>) -> Self {
+        self.hash_checkpoint = checkpoint;
+        self
+    }
+
+    /// Stop hashing and extraction with `TabdiffError::Cancelled` once this token is cancelled
+    /// (default: the process-wide token set by Ctrl-C)
+    pub fn with_cancellation(mut self, cancellation: crate::cancel::CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Report hashing progress in this format (default: from TABDIFF_PROGRESS_FORMAT)
+    pub fn with_progress_format(mut self, progress_format: ProgressFormat) -> Self {
+        self.progress_format = progress_format;
+        self
+    }
+
+    /// Hashes to resume from and a writer that records new ones, when hashing is checkpointed
+    fn open_hash_checkpoint(&self) -> Result<(Vec<crate::hash::RowHash>, Option<crate::checkpoint::CheckpointWriter>)> {
+        match &self.hash_checkpoint {
+            Some(checkpoint) => {
+                let resumed = checkpoint.load()?;
+                if !resumed.is_empty() {
+                    log::info!("Resuming row hashing after {} rows from {}", resumed.len(), checkpoint.path().display());
+                }
+                let writer = checkpoint.writer(&resumed)?;
+                Ok((resumed, Some(writer)))
+            }
+            None => Ok((Vec::new(), None)),
+        }
+    }
+
+    /// ` OFFSET n` clause skipping rows that were hashed before a resume
+    fn offset_clause(skip: u64) -> String {
+        if skip > 0 {
+            format!(" OFFSET {}", skip)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Canonical string form of a DuckDB value, used for extraction and hashing alike
+    ///
+    /// FLOAT, DOUBLE and (with a precision) DECIMAL values share one numeric formatting, so the
+    /// same number hashes identically regardless of the physical type a source format uses.
+    /// Blobs show their length and a short content hash; hashing uses their full digest instead.
+    pub fn format_value(value: duckdb::types::ValueRef<'_>, float_precision: Option<usize>) -> String {
+        match value {
+            duckdb::types::ValueRef::Null => String::new(),
+            duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
+            duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
+            duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
+            duckdb::types::ValueRef::Int(i) => i.to_string(),
+            duckdb::types::ValueRef::BigInt(i) => i.to_string(),
+            duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
+            duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
+            duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
+            duckdb::types::ValueRef::UInt(i) => i.to_string(),
+            duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
+            // Widen through the shortest decimal form so FLOAT 1.1 matches DOUBLE 1.1
+            duckdb::types::ValueRef::Float(f) => Self::format_float(f.to_string().parse().unwrap_or(f as f64), float_precision),
+            duckdb::types::ValueRef::Double(f) => Self::format_float(f, float_precision),
+            duckdb::types::ValueRef::Decimal(d) => match (float_precision, d.to_string().parse::<f64>()) {
+                (Some(_), Ok(f)) => Self::format_float(f, float_precision),
+                _ => d.to_string(),
+            },
+            duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
+            duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes {}>", b.len(), &Self::blob_digest(b)[..8]),
+            duckdb::types::ValueRef::Date32(days) => Self::format_date(days),
+            duckdb::types::ValueRef::Time64(unit, value) => Self::format_time(unit, value),
+            duckdb::types::ValueRef::Timestamp(unit, value) => Self::format_timestamp(unit, value),
+            _ => "<unknown>".to_string(),
+        }
+    }
+
+    /// ISO-8601 date of a DuckDB DATE, stored as days since 1970-01-01
+    fn format_date(days: i32) -> String {
+        chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::TimeDelta::days(days.into())))
+            .map_or_else(|| days.to_string(), |date| date.format("%Y-%m-%d").to_string())
+    }
+
+    /// ISO-8601 time of day of a DuckDB TIME, with fractional seconds only when present
+    fn format_time(unit: duckdb::types::TimeUnit, value: i64) -> String {
+        let (seconds, nanos) = Self::split_seconds(unit, value);
+        u32::try_from(seconds).ok()
+            .and_then(|seconds| chrono::NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos))
+            .map_or_else(|| value.to_string(), |time| time.format("%H:%M:%S%.f").to_string())
+    }
+
+    /// ISO-8601 date and time of a DuckDB TIMESTAMP, in UTC for TIMESTAMP WITH TIME ZONE
+    fn format_timestamp(unit: duckdb::types::TimeUnit, value: i64) -> String {
+        let (seconds, nanos) = Self::split_seconds(unit, value);
+        chrono::DateTime::from_timestamp(seconds, nanos)
+            .map_or_else(|| value.to_string(), |timestamp| timestamp.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+    }
+
+    /// Whole seconds and the remaining nanoseconds of a time value counted in `unit`
+    fn split_seconds(unit: duckdb::types::TimeUnit, value: i64) -> (i64, u32) {
+        let per_second: i64 = match unit {
+            duckdb::types::TimeUnit::Second => 1,
+            duckdb::types::TimeUnit::Millisecond => 1_000,
+            duckdb::types::TimeUnit::Microsecond => 1_000_000,
+            duckdb::types::TimeUnit::Nanosecond => 1_000_000_000,
+        };
+        let nanos = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+        (value.div_euclid(per_second), nanos as u32)
+    }
+
+    /// `format_value`, with `--na-values` sentinels read as NULL
+    fn format_cell(value: duckdb::types::ValueRef<'_>, float_precision: Option<usize>, na_values: &[String]) -> String {
+        let formatted = Self::format_value(value, float_precision);
+        if na_values.contains(&formatted) {
+            String::new()
+        } else {
+            formatted
+        }
+    }
+
+    /// Blank the cells of `rows` that hold one of the `na_values` sentinels
+    pub fn blank_na_values(rows: &mut [Vec<String>], na_values: &[String]) {
+        if na_values.is_empty() {
+            return;
+        }
+        for cell in rows.iter_mut().flatten() {
+            if na_values.contains(cell) {
+                cell.clear();
+            }
+        }
+    }
+
+    /// `read_csv` option that reads empty fields and the `--na-values` sentinels as NULL
+    /// (empty when no sentinels are configured, leaving DuckDB's default)
+    fn nullstr_option(na_values: &[String]) -> String {
+        if na_values.is_empty() {
+            return String::new();
+        }
+        let strings: Vec<String> = std::iter::once("")
+            .chain(na_values.iter().map(|value| value.as_str()))
+            .map(|value| format!("'{}'", value.replace('\'', "''")))
+            .collect();
+        format!(", nullstr=[{}]", strings.join(", "))
+    }
+
+    /// Blake3 hex digest of a blob's bytes
+    fn blob_digest(bytes: &[u8]) -> String {
+        blake3::hash(bytes).to_hex().to_string()
+    }
+
+    /// Format a number as its shortest round-trip decimal, or rounded to `float_precision`
+    /// places with trailing zeros removed (so 1.50 and 1.5 agree)
+    pub fn format_float(value: f64, float_precision: Option<usize>) -> String {
+        let precision = match float_precision {
+            Some(precision) if value.is_finite() => precision,
+            _ => return value.to_string(),
+        };
+        let rounded = format!("{:.*}", precision, value);
+        let trimmed = if rounded.contains('.') {
+            rounded.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            rounded.as_str()
+        };
+        // Rounding can leave "-0" for tiny negative numbers
+        if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+    }
+
+    /// Build the ORDER BY clause for the configured sort columns (empty when unordered)
+    ///
+    /// The remaining `columns` follow as tiebreakers: DuckDB runs with preserve_insertion_order
+    /// off, so rows tied on the sort columns would otherwise come back in any order. Rows tied on
+    /// every column are identical, so their order cannot change a hash.
+    fn order_by_clause(&self, columns: &[ColumnInfo]) -> String {
+        if self.order_by.is_empty() {
+            return String::new();
+        }
+        
+        let tiebreakers = columns.iter()
+            .map(|c| &c.name)
+            .filter(|name| !self.order_by.contains(name));
+        let columns = self.order_by.iter()
+            .chain(tiebreakers)
+            .map(|c| sql::quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(" ORDER BY {}", columns)
+    }
+
+    /// Ensure every sort column exists in the loaded data
+    fn validate_order_by(&self, columns: &[ColumnInfo]) -> Result<()> {
+        for name in &self.order_by {
+            if !columns.iter().any(|c| &c.name == name) {
+                return Err(crate::error::TabdiffError::invalid_input(
+                    format!("Unknown --order-by column: '{}'", name)
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Load data from file and return basic info
     pub fn load_file(&mut self, file_path: &Path) -> Result<DataInfo> {
+        // A reused processor must not keep the previous file's schema or query
+        self.cached_columns = None;
+        self.streaming_query = None;
+        
         // Check if this is a SQL file
         if sql::is_sql_file(file_path) {
+            if self.sample.is_some() {
+                return Err(crate::error::TabdiffError::invalid_input(
+                    format!("--sample is only supported for data files, not SQL files: {}", file_path.display())
+                ));
+            }
             return self.load_sql_file(file_path);
         }
         
-        // Validate file exists and is readable
-        if !file_path.exists() {
-            return Err(crate::error::TabdiffError::invalid_input(
-                format!("File not found: {}", file_path.display())
-            ));
-        }
+        let path_str = file_path.to_string_lossy();
+        let remote = crate::duckdb_config::is_remote_url(&path_str);
+
+        if remote {
+            crate::duckdb_config::enable_remote_access(&self.connection)?;
+        } else {
+            // Validate file exists and is readable
+            if !file_path.exists() {
+                return Err(crate::error::TabdiffError::file_not_found(file_path));
+            }
 
-        if !file_path.is_file() && !file_path.is_dir() {
+            if !file_path.is_file() && !file_path.is_dir() {
+                return Err(crate::error::TabdiffError::invalid_input(
+                    format!("Path is neither a file nor a directory: {}", file_path.display())
+                ));
+            }
+        }
+        
+        // Header-less files must be read explicitly so the first row is kept as data
+        let delimited = match self.format {
+            Some(format) => matches!(format, InputFormat::Csv | InputFormat::Tsv),
+            None => Self::is_delimited_format(file_path),
+        };
+        if self.no_header && !delimited {
             return Err(crate::error::TabdiffError::invalid_input(
-                format!("Path is neither a file nor a directory: {}", file_path.display())
+                format!("--no-header is only supported for CSV/TSV files: {}", file_path.display())
             ));
         }
-
-        let path_str = file_path.to_string_lossy();
+        // DuckDB only infers readers for its built-in formats, so Avro is always read explicitly,
+        // as are URLs, whose extension may be followed by a query string
+        let format = self.format.or_else(|| if remote {
+            InputFormat::of_url(&path_str)
+        } else {
+            Self::is_avro_format(file_path).then_some(InputFormat::Avro)
+        });
+        let header_only = self.header_only_delimiter(file_path)?;
+        let null_strings = Self::nullstr_option(&self.na_values);
+        if format == Some(InputFormat::Avro) {
+            crate::duckdb_config::load_extension(&self.connection, "avro")?;
+        }
+        let source_expr = match (format, &self.json_path) {
+            (_, Some(pointer)) => {
+                let is_json = format.map_or_else(|| Self::is_json_format(file_path), |f| f == InputFormat::Json);
+                if !is_json {
+                    return Err(crate::error::TabdiffError::invalid_input(
+                        format!("--json-path is only supported for JSON files: {}", file_path.display())
+                    ));
+                }
+                Self::json_pointer_source(&path_str, pointer)?
+            }
+            (_, None) if header_only.is_some() => format!(
+                "read_csv('{}', header=true{}{})",
+                path_str,
+                if header_only == Some(b'\t') { ", delim='\\t'" } else { "" },
+                null_strings
+            ),
+            (Some(format), None) => format.reader_expr(&path_str, self.no_header, &null_strings),
+            (None, None) if self.no_header => format!("read_csv('{}', header=false{})", path_str, null_strings),
+            // Sentinels are read as NULL so columns get the types they would have with empty cells
+            (None, None) if delimited && !null_strings.is_empty() => format!("read_csv('{}'{})", path_str, null_strings),
+            (None, None) => format!("'{}'", path_str),
+        };
         
+        let source_expr = match self.sample {
+            Some(sample) => self.sample_source(&source_expr, sample)
+                .map_err(|e| match e {
+                    crate::error::TabdiffError::DuckDb(e) => self.convert_duckdb_error(e, file_path),
+                    e => e,
+                })?,
+            None => source_expr,
+        };
+
         // Create a view of the file with proper error handling
         let create_view_sql = format!(
-            "CREATE OR REPLACE VIEW data_view AS SELECT * FROM '{}'",
-            path_str
+            "CREATE OR REPLACE VIEW data_view AS SELECT * FROM {}",
+            source_expr
         );
         
         self.connection.execute(&create_view_sql, [])
@@ -153,7 +594,11 @@ impl DataProcessor {
             ))?;
         
         // Get column information
-        let columns = self.get_column_info()?;
+        let mut columns = self.get_column_info()?;
+        if format == Some(InputFormat::Avro) {
+            columns = self.stringify_nested_columns(&source_expr, columns)?;
+        }
+        self.validate_order_by(&columns)?;
         
         Ok(DataInfo {
             source: file_path.to_path_buf(),
@@ -162,31 +607,119 @@ impl DataProcessor {
         })
     }
 
+    /// Source expression for the rows of `source_expr` that `sample` keeps
+    ///
+    /// The source is copied into a temporary table with an ordinal per row; every row is read
+    /// once and hashed the way row hashes are computed, and the ordinals of the kept rows select
+    /// them from that table.
+    fn sample_source(&mut self, source_expr: &str, sample: SampleSpec) -> Result<String> {
+        self.connection.execute_batch(&format!(
+            "CREATE OR REPLACE TEMP TABLE sample_source AS SELECT row_number() OVER () AS {ordinal}, * FROM {source};
+             CREATE OR REPLACE TEMP TABLE sample_kept ({ordinal} BIGINT);",
+            ordinal = Self::SAMPLE_ORDINAL,
+            source = source_expr,
+        ))?;
+        let columns: Vec<ColumnInfo> = self.get_column_info_from_view("sample_source")?
+            .into_iter()
+            .filter(|col| col.name != Self::SAMPLE_ORDINAL)
+            .collect();
+        let column_list = columns.iter()
+            .map(|c| sql::quote_identifier(&c.name))
+            .chain(std::iter::once(Self::SAMPLE_ORDINAL.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut kept = Vec::new();
+        {
+            let mut stmt = self.connection.prepare(&format!("SELECT {} FROM sample_source", column_list))?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                if sample.keeps(&self.extract_row_values_for_hashing(row, &columns)?) {
+                    kept.push(row.get::<_, i64>(columns.len())?);
+                }
+            }
+        }
+        let mut appender = self.connection.appender("sample_kept")?;
+        for ordinal in kept {
+            appender.append_row([ordinal])?;
+        }
+        appender.flush()?;
+
+        Ok(format!(
+            "(SELECT * EXCLUDE ({ordinal}) FROM sample_source WHERE {ordinal} IN (SELECT {ordinal} FROM sample_kept))",
+            ordinal = Self::SAMPLE_ORDINAL,
+        ))
+    }
+
     /// Load data from SQL file with database connection
     pub fn load_sql_file(&mut self, file_path: &Path) -> Result<DataInfo> {
+        let select_query = self.run_sql_setup(file_path)?;
+        
+        // For SQL queries, use streaming approach to handle large datasets efficiently
+        if select_query.trim().is_empty() {
+            return Err(crate::error::TabdiffError::invalid_input(
+                format!("No SELECT query found in SQL file '{}'", file_path.display())
+            ));
+        }
+        
+        self.load_select_query(select_query.trim(), file_path.to_path_buf())
+    }
+
+    /// Tables and views a SQL file makes available: those of its attached database, or
+    /// those its setup statements create
+    pub fn sql_tables(&mut self, file_path: &Path) -> Result<Vec<sql::SourceTable>> {
+        self.run_sql_setup(file_path)?;
+        
+        let mut stmt = self.connection.prepare(
+            "SELECT table_catalog, table_schema, table_name FROM information_schema.tables \
+             WHERE table_catalog NOT IN ('system', 'temp') \
+             AND table_schema NOT IN ('information_schema', 'pg_catalog') \
+             ORDER BY table_catalog, table_schema, table_name"
+        )?;
+        let tables = stmt
+            .query_map([], |row| Ok(sql::SourceTable {
+                catalog: row.get(0)?,
+                schema: row.get(1)?,
+                name: row.get(2)?,
+            }))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(tables)
+    }
+
+    /// Load one table of a SQL file instead of the file's SELECT query
+    pub fn load_sql_table(&mut self, file_path: &Path, table: &sql::SourceTable) -> Result<DataInfo> {
+        self.cached_columns = None;
+        self.streaming_query = None;
+        self.run_sql_setup(file_path)?;
+        self.load_select_query(&format!("SELECT * FROM {}", table.qualified()), file_path.to_path_buf())
+    }
+
+    /// Attach the SQL file's database and run its setup statements, returning its SELECT query
+    /// (empty when the file has none)
+    fn run_sql_setup(&mut self, file_path: &Path) -> Result<String> {
         // Load environment variables
         sql::load_env_file()?;
         
-        // Parse the SQL file
-        let sql_file = sql::parse_sql_file(file_path)?;
+        let content = std::fs::read_to_string(file_path)
+            .map_err(|e| crate::error::TabdiffError::invalid_input(
+                format!("Failed to read SQL file '{}': {}", file_path.display(), e)
+            ))?;
         
         // Substitute environment variables in the connection string
-        let connection_string = sql::substitute_env_vars(&sql_file.connection_string)?;
+        let connection_string = sql::substitute_env_vars(&sql::find_connection_string(&content))?;
         
         // Execute the connection string to attach the database (if provided)
         if !connection_string.is_empty() {
             self.connection.execute(&connection_string, [])
                 .map_err(|e| crate::error::TabdiffError::data_processing(
-                    format!("Failed to execute connection string '{}': {}", connection_string, e)
+                    format!(
+                        "Failed to execute connection string '{}': {}",
+                        sql::mask_secrets(&connection_string),
+                        sql::mask_secrets(&e.to_string())
+                    )
                 ))?;
         }
         
-        // Parse the content again to get setup statements and the SELECT query
-        let content = std::fs::read_to_string(file_path)
-            .map_err(|e| crate::error::TabdiffError::invalid_input(
-                format!("Failed to read SQL file '{}': {}", file_path.display(), e)
-            ))?;
-        
         // Split by semicolons to get individual statements
         let statements: Vec<&str> = content.split(';').collect();
         let mut setup_statements = Vec::new();
@@ -227,18 +760,30 @@ impl DataProcessor {
             if !statement.is_empty() {
                 self.connection.execute(&statement, [])
                     .map_err(|e| crate::error::TabdiffError::data_processing(
-                        format!("Failed to execute setup statement '{}': {}", statement, e)
+                        format!(
+                            "Failed to execute setup statement '{}': {}",
+                            sql::mask_secrets(&statement),
+                            sql::mask_secrets(&e.to_string())
+                        )
                     ))?;
             }
         }
         
-        // For SQL queries, use streaming approach to handle large datasets efficiently
-        if select_query.trim().is_empty() {
-            return Err(crate::error::TabdiffError::invalid_input(
-                format!("No SELECT query found in SQL file '{}'", file_path.display())
-            ));
+        Ok(select_query)
+    }
+
+    /// Load the result of an inline SELECT query, read lazily like a `.sql` file's query
+    pub fn load_query(&mut self, query: &str) -> Result<DataInfo> {
+        let select_query = query.trim().trim_end_matches(';').trim();
+        if select_query.is_empty() {
+            return Err(crate::error::TabdiffError::invalid_input("The query is empty"));
         }
         
+        self.load_select_query(select_query, std::path::PathBuf::from("<query>"))
+    }
+
+    /// Count and describe the rows of a SELECT query and keep it for streaming
+    fn load_select_query(&mut self, select_query: &str, source: std::path::PathBuf) -> Result<DataInfo> {
         // First, get the row count and column info without materializing all data
         let count_query = format!("SELECT COUNT(*) FROM ({})", select_query.trim());
         let row_count: u64 = self.connection
@@ -274,11 +819,17 @@ impl DataProcessor {
                 format!("Failed to drop temporary view: {}", e)
             ))?;
         
-        // Store the original SELECT query for streaming use
-        self.streaming_query = Some(select_query.trim().to_string());
+        self.validate_order_by(&columns)?;
+        
+        // Store the original SELECT query for streaming use, sorted when requested
+        self.streaming_query = Some(if self.order_by.is_empty() {
+            select_query.trim().to_string()
+        } else {
+            format!("SELECT * FROM ({}) AS query_result{}", select_query.trim(), self.order_by_clause(&columns))
+        });
         
         Ok(DataInfo {
-            source: file_path.to_path_buf(),
+            source,
             row_count,
             columns,
         })
@@ -293,25 +844,25 @@ impl DataProcessor {
            error_msg.contains("Could not convert") ||
            error_msg.contains("Invalid CSV") ||
            error_msg.contains("Unterminated quoted field") {
-            crate::error::TabdiffError::invalid_input(
-                format!("Malformed CSV file '{}': {}", file_path.display(), error_msg)
+            crate::error::TabdiffError::malformed_data(
+                format!("CSV file '{}': {}", file_path.display(), error_msg)
             )
         } else if error_msg.contains("JSON") || error_msg.contains("Malformed JSON") {
-            crate::error::TabdiffError::invalid_input(
-                format!("Malformed JSON file '{}': {}", file_path.display(), error_msg)
+            crate::error::TabdiffError::malformed_data(
+                format!("JSON file '{}': {}", file_path.display(), error_msg)
             )
         } else if error_msg.contains("No files found") || error_msg.contains("does not exist") {
-            crate::error::TabdiffError::invalid_input(
-                format!("File not found: {}", file_path.display())
-            )
+            crate::error::TabdiffError::file_not_found(file_path)
         } else if error_msg.contains("Permission denied") {
             crate::error::TabdiffError::invalid_input(
                 format!("Permission denied accessing file: {}", file_path.display())
             )
         } else if error_msg.contains("UTF-8") || error_msg.contains("encoding") {
-            crate::error::TabdiffError::invalid_input(
-                format!("File encoding error '{}': {}", file_path.display(), error_msg)
+            crate::error::TabdiffError::malformed_data(
+                format!("encoding error in '{}': {}", file_path.display(), error_msg)
             )
+        } else if error_msg.contains("No extension found") || error_msg.contains("not supported") {
+            crate::error::TabdiffError::unsupported_format(file_path)
         } else {
             // For other DuckDB errors, pass through the original error message
             crate::error::TabdiffError::DuckDb(error)
@@ -323,6 +874,38 @@ impl DataProcessor {
         self.get_column_info_from_view("data_view")
     }
 
+    /// Redefine data_view with nested columns (records, arrays, maps, unions) cast to VARCHAR
+    ///
+    /// `format_value` has no representation for nested values, so they are rendered once by
+    /// DuckDB as text and then hash, extract and diff like any other string column.
+    fn stringify_nested_columns(&mut self, source_expr: &str, columns: Vec<ColumnInfo>) -> Result<Vec<ColumnInfo>> {
+        let is_nested = |data_type: &str| {
+            let upper = data_type.to_uppercase();
+            upper.ends_with(']') || ["STRUCT", "MAP", "UNION"].iter().any(|kind| upper.starts_with(kind))
+        };
+        if !columns.iter().any(|col| is_nested(&col.data_type)) {
+            return Ok(columns);
+        }
+        
+        let select_list = columns.iter()
+            .map(|col| if is_nested(&col.data_type) {
+                format!("CAST({0} AS VARCHAR) AS {0}", sql::quote_identifier(&col.name))
+            } else {
+                sql::quote_identifier(&col.name)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.connection.execute(
+            &format!("CREATE OR REPLACE VIEW data_view AS SELECT {} FROM {}", select_list, source_expr),
+            [],
+        ).map_err(|e| crate::error::TabdiffError::data_processing(
+            format!("Failed to stringify nested Avro columns: {}", e)
+        ))?;
+        
+        self.cached_columns = None;
+        self.get_column_info()
+    }
+
     /// Get column information from a specific view (cached to avoid repeated calls)
     fn get_column_info_from_view(&mut self, view_name: &str) -> Result<Vec<ColumnInfo>> {
         // Return cached columns if available (for data_view only)
@@ -369,6 +952,44 @@ impl DataProcessor {
         self.extract_data_chunked_with_progress(None)
     }
 
+    /// Rows from `first_row` on, in the order they are hashed; earlier rows are skipped by the
+    /// query rather than read
+    pub fn extract_rows_from(&mut self, first_row: u64) -> Result<Vec<Vec<String>>> {
+        let columns = self.get_column_info()?;
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+        let source = match &self.streaming_query {
+            Some(query) => format!("({})", query),
+            None => "data_view".to_string(),
+        };
+        let data_sql = format!(
+            "SELECT * FROM {}{}{}",
+            source,
+            self.order_by_clause(&columns),
+            Self::offset_clause(first_row)
+        );
+        let float_precision = self.float_precision;
+        let na_values = self.na_values.clone();
+        let cancellation = self.cancellation.clone();
+        let mut stmt = self.connection.prepare(&data_sql)
+            .map_err(|e| crate::error::TabdiffError::data_processing(
+                format!("Failed to prepare data query: {}", e)
+            ))?;
+        let rows = stmt.query_map([], |row| {
+            (0..columns.len())
+                .map(|i| Ok(Self::format_cell(row.get_ref(i)?, float_precision, &na_values)))
+                .collect::<duckdb::Result<Vec<String>>>()
+        })?;
+
+        let mut data = Vec::new();
+        for row in rows {
+            cancellation.check()?;
+            data.push(row?);
+        }
+        Ok(data)
+    }
+
     /// Extract data in chunks with progress reporting for better memory efficiency
     pub fn extract_data_chunked_with_progress(
         &mut self,
@@ -412,7 +1033,11 @@ impl DataProcessor {
         // No chunking needed - use streaming approach
 
         // Execute the full query once and stream through results (no LIMIT/OFFSET)
-        let mut stmt = self.connection.prepare("SELECT * FROM data_view")
+        let data_sql = format!("SELECT * FROM data_view{}", self.order_by_clause(&columns));
+        let float_precision = self.float_precision;
+        let na_values = self.na_values.clone();
+        let cancellation = self.cancellation.clone();
+        let mut stmt = self.connection.prepare(&data_sql)
             .map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to prepare streaming data query: {}", e)
             ))?;
@@ -420,28 +1045,7 @@ impl DataProcessor {
         let rows = stmt.query_map([], |row| {
             let mut string_row = Vec::with_capacity(column_count);
             for i in 0..column_count {
-                let value: String = match row.get_ref(i)? {
-                    duckdb::types::ValueRef::Null => String::new(),
-                    duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                    duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Int(i) => i.to_string(),
-                    duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Float(f) => f.to_string(),
-                    duckdb::types::ValueRef::Double(f) => f.to_string(),
-                    duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                    duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                    duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                    duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                    duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                    duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                    _ => "<unknown>".to_string(),
-                };
+                let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                 string_row.push(value);
             }
             Ok(string_row)
@@ -450,6 +1054,7 @@ impl DataProcessor {
         ))?;
 
         for row_result in rows {
+            cancellation.check()?;
             let row = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to process streaming row: {}", e)
             ))?;
@@ -497,6 +1102,9 @@ impl DataProcessor {
 
         // For better performance with large datasets, execute the full query once 
         // and stream through the result set instead of using LIMIT/OFFSET
+        let float_precision = self.float_precision;
+        let na_values = self.na_values.clone();
+        let cancellation = self.cancellation.clone();
         let mut stmt = self.connection.prepare(query)
             .map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to prepare streaming query: {}", e)
@@ -505,28 +1113,7 @@ impl DataProcessor {
         let rows = stmt.query_map([], |row| {
             let mut string_row = Vec::with_capacity(column_count);
             for i in 0..column_count {
-                let value: String = match row.get_ref(i)? {
-                    duckdb::types::ValueRef::Null => String::new(),
-                    duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                    duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Int(i) => i.to_string(),
-                    duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Float(f) => f.to_string(),
-                    duckdb::types::ValueRef::Double(f) => f.to_string(),
-                    duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                    duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                    duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                    duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                    duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                    duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                    _ => "<unknown>".to_string(),
-                };
+                let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                 string_row.push(value);
             }
             Ok(string_row)
@@ -538,6 +1125,7 @@ impl DataProcessor {
         let update_frequency = 50_000; // Report progress every 50K rows for smooth updates
 
         for row_result in rows {
+            cancellation.check()?;
             let row = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to process streaming row: {}", e)
             ))?;
@@ -558,6 +1146,9 @@ impl DataProcessor {
 
     /// Execute a chunk query and return the results as string vectors
     fn execute_chunk_query(&mut self, sql: &str, column_count: usize) -> Result<Vec<Vec<String>>> {
+        self.cancellation.check()?;
+        let float_precision = self.float_precision;
+        let na_values = self.na_values.clone();
         let mut stmt = self.connection.prepare(sql)
             .map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to prepare chunk query: {}", e)
@@ -566,28 +1157,7 @@ impl DataProcessor {
         let rows = stmt.query_map([], |row| {
             let mut string_row = Vec::with_capacity(column_count); // Pre-allocate
             for i in 0..column_count {
-                let value: String = match row.get_ref(i)? {
-                    duckdb::types::ValueRef::Null => String::new(),
-                    duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                    duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Int(i) => i.to_string(),
-                    duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Float(f) => f.to_string(),
-                    duckdb::types::ValueRef::Double(f) => f.to_string(),
-                    duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                    duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                    duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                    duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                    duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                    duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                    _ => "<unknown>".to_string(),
-                };
+                let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                 string_row.push(value);
             }
             Ok(string_row)
@@ -640,42 +1210,25 @@ impl DataProcessor {
         }
 
         let mut processed_rows = 0u64;
+        let cancellation = self.cancellation.clone();
 
         if let Some(ref query) = self.streaming_query.clone() {
             // Stream SQL query results
+            let float_precision = self.float_precision;
+            let na_values = self.na_values.clone();
             let mut stmt = self.connection.prepare(query)?;
             
             let rows = stmt.query_map([], |row| {
                 let mut string_row = Vec::with_capacity(column_count);
                 for i in 0..column_count {
-                    let value: String = match row.get_ref(i)? {
-                        duckdb::types::ValueRef::Null => String::new(),
-                        duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                        duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::Int(i) => i.to_string(),
-                        duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::Float(f) => f.to_string(),
-                        duckdb::types::ValueRef::Double(f) => f.to_string(),
-                        duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                        duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                        duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                        duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                        duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                        duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                        _ => "<unknown>".to_string(),
-                    };
+                    let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                     string_row.push(value);
                 }
                 Ok(string_row)
             })?;
             
             for row_result in rows {
+                cancellation.check()?;
                 let row = row_result?;
                 row_callback(row)?;
                 processed_rows += 1;
@@ -689,39 +1242,22 @@ impl DataProcessor {
             }
         } else {
             // Stream from data_view for regular files
-            let mut stmt = self.connection.prepare("SELECT * FROM data_view")?;
+            let data_sql = format!("SELECT * FROM data_view{}", self.order_by_clause(&columns));
+            let float_precision = self.float_precision;
+            let na_values = self.na_values.clone();
+            let mut stmt = self.connection.prepare(&data_sql)?;
             
             let rows = stmt.query_map([], |row| {
                 let mut string_row = Vec::with_capacity(column_count);
                 for i in 0..column_count {
-                    let value: String = match row.get_ref(i)? {
-                        duckdb::types::ValueRef::Null => String::new(),
-                        duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                        duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::Int(i) => i.to_string(),
-                        duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                        duckdb::types::ValueRef::Float(f) => f.to_string(),
-                        duckdb::types::ValueRef::Double(f) => f.to_string(),
-                        duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                        duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                        duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                        duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                        duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                        duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                        _ => "<unknown>".to_string(),
-                    };
+                    let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                     string_row.push(value);
                 }
                 Ok(string_row)
             })?;
             
             for row_result in rows {
+                cancellation.check()?;
                 let row = row_result?;
                 row_callback(row)?;
                 processed_rows += 1;
@@ -752,7 +1288,11 @@ impl DataProcessor {
             .collect();
         
         // Single query to get all columns at once (much more efficient than N queries)
-        let mut stmt = self.connection.prepare("SELECT * FROM data_view")
+        let data_sql = format!("SELECT * FROM data_view{}", self.order_by_clause(&columns));
+        let float_precision = self.float_precision;
+        let na_values = self.na_values.clone();
+        let cancellation = self.cancellation.clone();
+        let mut stmt = self.connection.prepare(&data_sql)
             .map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to prepare column data query: {}", e)
             ))?;
@@ -760,28 +1300,7 @@ impl DataProcessor {
         let rows = stmt.query_map([], |row| {
             let mut row_values = Vec::with_capacity(columns.len());
             for i in 0..columns.len() {
-                let value: String = match row.get_ref(i)? {
-                    duckdb::types::ValueRef::Null => String::new(),
-                    duckdb::types::ValueRef::Boolean(b) => if b { "true".to_string() } else { "false".to_string() },
-                    duckdb::types::ValueRef::TinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::SmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Int(i) => i.to_string(),
-                    duckdb::types::ValueRef::BigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::HugeInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UTinyInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::USmallInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::UBigInt(i) => i.to_string(),
-                    duckdb::types::ValueRef::Float(f) => f.to_string(),
-                    duckdb::types::ValueRef::Double(f) => f.to_string(),
-                    duckdb::types::ValueRef::Decimal(d) => d.to_string(),
-                    duckdb::types::ValueRef::Text(s) => String::from_utf8_lossy(s).into_owned(),
-                    duckdb::types::ValueRef::Blob(b) => format!("<blob:{} bytes>", b.len()),
-                    duckdb::types::ValueRef::Date32(d) => format!("{:?}", d),
-                    duckdb::types::ValueRef::Time64(t, _) => format!("{:?}", t),
-                    duckdb::types::ValueRef::Timestamp(ts, _) => format!("{:?}", ts),
-                    _ => "<unknown>".to_string(),
-                };
+                let value = Self::format_cell(row.get_ref(i)?, float_precision, &na_values);
                 row_values.push(value);
             }
             Ok(row_values)
@@ -791,6 +1310,7 @@ impl DataProcessor {
         
         // Process each row and distribute values to appropriate columns
         for row_result in rows {
+            cancellation.check()?;
             let row_values = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to process column data row: {}", e)
             ))?;
@@ -839,6 +1359,55 @@ impl DataProcessor {
         self.compute_regular_row_hashes_with_progress(progress_callback)
     }
 
+    /// Lazily hash rows one chunk at a time, in the same order and with the same hashes as
+    /// `compute_row_hashes_sql`, which is the collecting counterpart
+    ///
+    /// Each chunk is a separate `LIMIT`/`OFFSET` query of `chunk_size` rows, so at most one
+    /// chunk of hashes is held in memory. Progress reporting and checkpoints are not used.
+    pub fn row_hashes_iter(&mut self) -> Result<RowHashIter<'_>> {
+        let columns = self.get_column_info()?;
+        let column_list = columns.iter()
+            .map(|c| sql::quote_identifier(&c.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let source = match &self.streaming_query {
+            Some(query) => format!("({})", query),
+            None => "data_view".to_string(),
+        };
+        let source_sql = format!("SELECT {} FROM {}{}", column_list, source, self.order_by_clause(&columns));
+
+        Ok(RowHashIter {
+            done: columns.is_empty(),
+            processor: self,
+            source_sql,
+            columns,
+            next_row: 0,
+            buffer: Vec::new().into_iter(),
+        })
+    }
+
+    /// Hash the rows of one chunk query, numbering them from `offset`
+    fn hash_chunk_query(&self, sql: &str, columns: &[ColumnInfo], offset: u64) -> Result<Vec<crate::hash::RowHash>> {
+        self.cancellation.check()?;
+        let mut stmt = self.connection.prepare(sql)
+            .map_err(|e| crate::error::TabdiffError::data_processing(
+                format!("Failed to prepare chunk hash query: {}", e)
+            ))?;
+        let rows = stmt.query_map([], |row| {
+            self.extract_row_values_for_hashing(row, columns)
+        }).map_err(|e| crate::error::TabdiffError::data_processing(
+            format!("Failed to create chunk row iterator: {}", e)
+        ))?;
+
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        for row_result in rows {
+            chunk.push(row_result.map_err(|e| crate::error::TabdiffError::data_processing(
+                format!("Failed to process row {}: {}", offset + chunk.len() as u64, e)
+            ))?);
+        }
+        Ok(Self::hash_chunk_parallel(&chunk, offset))
+    }
+
     /// Compute row hashes for regular files with chunking
     fn compute_regular_row_hashes_with_progress(
         &mut self,
@@ -859,20 +1428,24 @@ impl DataProcessor {
             return Ok(Vec::new());
         }
 
-        let mut all_hashes = Vec::new();
-        let mut processed_rows = 0u64;
+        // Rows already in a resumed checkpoint are skipped rather than hashed again
+        let (mut all_hashes, mut checkpoint) = self.open_hash_checkpoint()?;
+        let skipped_rows = all_hashes.len() as u64;
+        let mut processed_rows = skipped_rows;
         let start_time = std::time::Instant::now();
         
-        // Use natural file order - no ORDER BY clause needed
+        // Use natural file order unless --order-by was given
         // DuckDB preserves the original row order from CSV files
         let column_list = columns.iter()
-            .map(|c| format!("\"{}\"", c.name))
+            .map(|c| sql::quote_identifier(&c.name))
             .collect::<Vec<_>>()
             .join(", ");
         
         let natural_order_sql = format!(
-            "SELECT {} FROM data_view",
-            column_list
+            "SELECT {} FROM data_view{}{}",
+            column_list,
+            self.order_by_clause(&columns),
+            Self::offset_clause(skipped_rows)
         );
         
         let mut stmt = self.connection.prepare(&natural_order_sql)
@@ -887,25 +1460,48 @@ impl DataProcessor {
         ))?;
 
         // Process each row individually with immediate progress updates
-        for (row_index, row_result) in rows.enumerate() {
+        let checkpoint_interval = self.chunk_size.max(1) as u64;
+        for row_result in rows {
+            if self.cancellation.is_cancelled() {
+                // Keep the rows hashed so far so `--resume` can continue from here
+                if let Some(writer) = &mut checkpoint {
+                    writer.flush()?;
+                }
+                return Err(crate::error::TabdiffError::Cancelled);
+            }
+            let row_index = processed_rows;
             let row_values = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to process row {}: {}", row_index, e)
             ))?;
             
-            let hash_hex = self.compute_row_hash(&row_values);
+            let hash_hex = Self::compute_row_hash(&row_values);
             
-            all_hashes.push(crate::hash::RowHash {
-                row_index: row_index as u64,
+            let row_hash = crate::hash::RowHash {
+                row_index,
                 hash: hash_hex,
-            });
+            };
+            if let Some(writer) = &mut checkpoint {
+                writer.record(&row_hash)?;
+            }
+            all_hashes.push(row_hash);
             
             processed_rows += 1;
+            if processed_rows.is_multiple_of(checkpoint_interval) {
+                if let Some(writer) = &mut checkpoint {
+                    writer.flush()?;
+                }
+            }
             
             self.report_hash_progress(processed_rows, total_rows, start_time, &progress_callback);
         }
+        if let Some(writer) = &mut checkpoint {
+            writer.flush()?;
+        }
         
-        // Final newline after progress
-        eprintln!();
+        // Final newline after text progress
+        if self.progress_format == ProgressFormat::Text {
+            eprintln!();
+        }
         
         Ok(all_hashes)
     }
@@ -932,20 +1528,25 @@ impl DataProcessor {
             return Ok(Vec::new());
         }
 
-        let mut all_hashes = Vec::new();
-        all_hashes.reserve(total_rows as usize); // Pre-allocate to prevent reallocations
-        let mut processed_rows = 0u64;
+        // Rows already in a resumed checkpoint are skipped rather than hashed again
+        let (mut all_hashes, mut checkpoint) = self.open_hash_checkpoint()?;
+        all_hashes.reserve((total_rows as usize).saturating_sub(all_hashes.len())); // Pre-allocate to prevent reallocations
+        let mut processed_rows = all_hashes.len() as u64;
         let start_time = std::time::Instant::now();
 
-        // Execute the full query once and stream through results (no chunking needed)
-
         let column_list = columns.iter()
-            .map(|c| format!("\"{}\"", c.name))
+            .map(|c| sql::quote_identifier(&c.name))
             .collect::<Vec<_>>()
             .join(", ");
 
-        // Execute the full query once and stream through results (no LIMIT/OFFSET)
-        let streaming_sql = format!("SELECT {} FROM ({})", column_list, query);
+        // Execute the query once and stream through results, only offset to skip resumed rows
+        let streaming_sql = format!(
+            "SELECT {} FROM ({}){}{}",
+            column_list,
+            query,
+            self.order_by_clause(&columns),
+            Self::offset_clause(processed_rows)
+        );
         let mut stmt = self.connection.prepare(&streaming_sql)
             .map_err(|e| crate::error::TabdiffError::data_processing(
                 format!("Failed to prepare streaming hash query: {}", e)
@@ -957,78 +1558,117 @@ impl DataProcessor {
             format!("Failed to create streaming row iterator: {}", e)
         ))?;
 
-        // Process each row from the single streaming query
-        for row_result in rows {
-            let row_values = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
-                format!("Failed to process streaming row {}: {}", processed_rows, e)
-            ))?;
-            
-            let hash_hex = self.compute_row_hash(&row_values);
-            
-            all_hashes.push(crate::hash::RowHash {
-                row_index: processed_rows,
-                hash: hash_hex,
+        // A worker hashes each full chunk in parallel while the next one streams; the channel
+        // holds no chunk of its own, so at most one chunk is buffered besides the one hashing
+        let chunk_size = self.chunk_size.max(1);
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<(u64, Vec<Vec<String>>)>(0);
+        let all_hashes = std::thread::scope(|scope| -> Result<Vec<crate::hash::RowHash>> {
+            let worker = scope.spawn(move || -> Result<Vec<crate::hash::RowHash>> {
+                for (offset, chunk) in receiver {
+                    let hashes = Self::hash_chunk_parallel(&chunk, offset);
+                    Self::checkpoint_hashes(&mut checkpoint, &hashes)?;
+                    all_hashes.extend(hashes);
+                }
+                Ok(all_hashes)
             });
 
-            processed_rows += 1;
-            
-            // Report progress at regular intervals for better performance
-            if processed_rows % 10000 == 0 || processed_rows >= total_rows {
-                self.report_hash_progress(processed_rows, total_rows, start_time, &progress_callback);
-            }
+            let streamed = (|| -> Result<()> {
+                let mut chunk: Vec<Vec<String>> = Vec::with_capacity(chunk_size);
+                for row_result in rows {
+                    self.cancellation.check()?;
+                    let row_values = row_result.map_err(|e| crate::error::TabdiffError::data_processing(
+                        format!("Failed to process streaming row {}: {}", processed_rows + chunk.len() as u64, e)
+                    ))?;
+                    chunk.push(row_values);
+
+                    if chunk.len() >= chunk_size {
+                        let full = std::mem::replace(&mut chunk, Vec::with_capacity(chunk_size));
+                        let offset = processed_rows;
+                        processed_rows += full.len() as u64;
+                        // A closed channel means the worker stopped with an error, reported below
+                        if sender.send((offset, full)).is_err() {
+                            return Ok(());
+                        }
+                        self.report_hash_progress(processed_rows, total_rows, start_time, &progress_callback);
+                    }
+                }
+
+                // Hash the final partial chunk
+                if !chunk.is_empty() {
+                    let offset = processed_rows;
+                    processed_rows += chunk.len() as u64;
+                    let _ = sender.send((offset, chunk));
+                }
+                Ok(())
+            })();
+            drop(sender);
+
+            let hashed = worker.join().expect("row hash worker panicked");
+            streamed?;
+            hashed
+        })?;
+
+        self.report_hash_progress(processed_rows, total_rows, start_time, &progress_callback);
+
+        // Final newline after text progress
+        if self.progress_format == ProgressFormat::Text {
+            eprintln!();
         }
         
-        // Final newline after progress
-        eprintln!();
-        
         Ok(all_hashes)
     }
 
+    /// Record a hashed chunk in the checkpoint, if any, and flush it
+    fn checkpoint_hashes(
+        checkpoint: &mut Option<crate::checkpoint::CheckpointWriter>,
+        hashes: &[crate::hash::RowHash],
+    ) -> Result<()> {
+        if let Some(writer) = checkpoint {
+            for row_hash in hashes {
+                writer.record(row_hash)?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Hash a chunk of rows in parallel, offsetting row indices by the rows already processed
+    fn hash_chunk_parallel(chunk: &[Vec<String>], offset: u64) -> Vec<crate::hash::RowHash> {
+        use rayon::prelude::*;
+
+        chunk
+            .par_iter()
+            .enumerate()
+            .map(|(idx, row_values)| crate::hash::RowHash {
+                row_index: offset + idx as u64,
+                hash: Self::compute_row_hash(row_values),
+            })
+            .collect()
+    }
+
     /// Extract row values for hashing with consistent formatting
+    ///
+    /// Blobs contribute the digest of their full contents rather than their displayed form, so
+    /// blobs of equal length but different bytes hash differently.
     fn extract_row_values_for_hashing(&self, row: &duckdb::Row, columns: &[ColumnInfo]) -> duckdb::Result<Vec<String>> {
-        let mut row_values = Vec::new();
+        let mut row_values = Vec::with_capacity(columns.len());
         for i in 0..columns.len() {
-            let value: String = match row.get_ref(i) {
-                Ok(duckdb::types::ValueRef::Null) => String::new(),
-                Ok(duckdb::types::ValueRef::Boolean(b)) => b.to_string(),
-                Ok(duckdb::types::ValueRef::TinyInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::SmallInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::Int(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::BigInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::HugeInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::UTinyInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::USmallInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::UInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::UBigInt(i)) => i.to_string(),
-                Ok(duckdb::types::ValueRef::Float(f)) => {
-                    // Use consistent float formatting to avoid precision issues
-                    format!("{:.10}", f)
-                },
-                Ok(duckdb::types::ValueRef::Double(f)) => {
-                    // Use consistent double formatting to avoid precision issues
-                    format!("{:.15}", f)
-                },
-                Ok(duckdb::types::ValueRef::Decimal(d)) => d.to_string(),
-                Ok(duckdb::types::ValueRef::Text(s)) => String::from_utf8_lossy(s).to_string(),
-                Ok(duckdb::types::ValueRef::Blob(b)) => format!("<blob:{} bytes>", b.len()),
-                Ok(duckdb::types::ValueRef::Date32(d)) => format!("{:?}", d),
-                Ok(duckdb::types::ValueRef::Time64(t, _)) => format!("{:?}", t),
-                Ok(duckdb::types::ValueRef::Timestamp(ts, _)) => format!("{:?}", ts),
-                _ => String::new(), // Handle any other types or errors
-            };
+            // Values that cannot be read hash as empty, like NULL
+            let value = row.get_ref(i)
+                .map(|value| match value {
+                    duckdb::types::ValueRef::Blob(bytes) => format!("blob:{}", Self::blob_digest(bytes)),
+                    value => Self::format_cell(value, self.float_precision, &self.na_values),
+                })
+                .unwrap_or_default();
             row_values.push(value);
         }
         Ok(row_values)
     }
 
     /// Compute hash for a row's values
-    fn compute_row_hash(&self, row_values: &[String]) -> String {
-        // Hash the row values using Blake3 with consistent separator
-        let row_content = row_values.join("||"); // Use || to avoid conflicts with | in data
-        let hash = blake3::hash(row_content.as_bytes());
-        
-        // Use full Blake3 hash for maximum collision resistance
-        hash.to_hex().to_string()
+    fn compute_row_hash(row_values: &[String]) -> String {
+        // Length-prefixed fields, so values containing separators cannot collide
+        crate::hash::HashComputer::hash_row(row_values)
     }
 
     /// Report progress for hash computation
@@ -1046,12 +1686,8 @@ impl DataProcessor {
             // Always print to stderr for immediate feedback
             let elapsed = start_time.elapsed().as_secs_f64();
             let rate = processed_rows as f64 / elapsed;
-            let percent = (processed_rows as f64 / total_rows as f64) * 100.0;
             
-            use std::io::Write;
-            eprint!("\rProcessed: {}/{} rows ({:.1}%) - {:.0} rows/sec", 
-                   processed_rows, total_rows, percent, rate);
-            let _ = std::io::stderr().flush();
+            self.progress_format.report("hashing", processed_rows, total_rows, rate);
             
             // Also call the progress callback if provided
             if let Some(callback) = progress_callback {
@@ -1070,11 +1706,11 @@ impl DataProcessor {
 
         // Fast column hashing - just hash the column metadata, not all the data
         // This is much more efficient and still provides change detection for schema changes
-        self.compute_column_metadata_hashes(&columns)
+        Self::compute_column_metadata_hashes(&columns)
     }
 
     /// Efficient column hash computation - hash only metadata, not data content
-    fn compute_column_metadata_hashes(&self, columns: &[ColumnInfo]) -> Result<Vec<crate::hash::ColumnHash>> {
+    pub fn compute_column_metadata_hashes(columns: &[ColumnInfo]) -> Result<Vec<crate::hash::ColumnHash>> {
         let mut column_hashes = Vec::new();
 
         for column in columns {
@@ -1106,17 +1742,398 @@ impl DataProcessor {
 
 
 
+    /// Check if file is a delimited text format (CSV/TSV)
+    pub fn is_delimited_format(file_path: &Path) -> bool {
+        if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
+            matches!(extension.to_lowercase().as_str(), "csv" | "tsv")
+        } else {
+            false
+        }
+    }
+
+    /// Line (1-based) on which each data row of a CSV/TSV file starts
+    ///
+    /// None when the input isn't a delimited file or its rows are sorted rather than read in
+    /// file order. Quoted fields may span lines; empty lines hold no row.
+    pub fn source_line_numbers(&self, file_path: &Path) -> Result<Option<Vec<u64>>> {
+        let delimited = match self.format {
+            Some(format) => matches!(format, InputFormat::Csv | InputFormat::Tsv),
+            None => Self::is_delimited_format(file_path),
+        };
+        if !delimited || !self.order_by.is_empty() || self.streaming_query.is_some() || !file_path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read(file_path)?;
+        let mut lines = Vec::new();
+        let mut line = 1u64;
+        let mut in_quotes = false;
+        let mut record_start: Option<u64> = None;
+        for &byte in &content {
+            match byte {
+                b'\n' => {
+                    if !in_quotes {
+                        lines.extend(record_start.take());
+                    }
+                    line += 1;
+                }
+                b'\r' => {}
+                b'"' => {
+                    in_quotes = !in_quotes;
+                    record_start.get_or_insert(line);
+                }
+                _ => {
+                    record_start.get_or_insert(line);
+                }
+            }
+        }
+        lines.extend(record_start);
+
+        if !self.no_header && !lines.is_empty() {
+            lines.remove(0);
+        }
+        Ok(Some(lines))
+    }
+
+    /// Field delimiter of a CSV/TSV input, or None for other formats
+    fn delimiter_of(&self, file_path: &Path) -> Option<u8> {
+        match self.format {
+            Some(InputFormat::Csv) => Some(b','),
+            Some(InputFormat::Tsv) => Some(b'\t'),
+            Some(_) => None,
+            None if !Self::is_delimited_format(file_path) => None,
+            None => {
+                let is_tsv = file_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("tsv"));
+                Some(if is_tsv { b'\t' } else { b',' })
+            }
+        }
+    }
+
+    /// Delimiter of a CSV/TSV file whose only record is its header, or None
+    ///
+    /// With no data rows DuckDB's sniffer cannot tell the header apart from data, so such a
+    /// file is read with an explicit header to give zero rows under the named columns.
+    fn header_only_delimiter(&self, file_path: &Path) -> Result<Option<u8>> {
+        if self.no_header || !std::fs::metadata(file_path).is_ok_and(|metadata| metadata.len() <= HEADER_ONLY_PROBE_BYTES) {
+            return Ok(None);
+        }
+        let records = self.record_field_counts(file_path)?;
+        Ok(records.filter(|records| records.len() == 1).and(self.delimiter_of(file_path)))
+    }
+
+    /// Field count of every record in a CSV/TSV file, with the line the record starts on
+    ///
+    /// None when the input isn't a delimited file. The header counts as a record; quoted fields
+    /// may span lines and contain delimiters, and empty lines hold no record.
+    pub fn record_field_counts(&self, file_path: &Path) -> Result<Option<Vec<(u64, usize)>>> {
+        let delimiter = match self.delimiter_of(file_path) {
+            Some(delimiter) if file_path.is_file() => delimiter,
+            _ => return Ok(None),
+        };
+
+        let content = std::fs::read(file_path)?;
+        let mut records = Vec::new();
+        let mut line = 1u64;
+        let mut in_quotes = false;
+        let mut record: Option<(u64, usize)> = None;
+        for &byte in &content {
+            match byte {
+                b'\n' => {
+                    if !in_quotes {
+                        records.extend(record.take());
+                    }
+                    line += 1;
+                }
+                b'\r' => {}
+                b'"' => {
+                    in_quotes = !in_quotes;
+                    record.get_or_insert((line, 1));
+                }
+                byte if byte == delimiter && !in_quotes => {
+                    record.get_or_insert((line, 1)).1 += 1;
+                }
+                _ => {
+                    record.get_or_insert((line, 1));
+                }
+            }
+        }
+        records.extend(record);
+        Ok(Some(records))
+    }
+
+    /// Load a CSV/TSV file with every column read as text, tolerating malformed rows
+    ///
+    /// For inspecting a file's own consistency: types are left for `column_profiles` to infer,
+    /// short rows are padded with NULLs and rows that still cannot be parsed are skipped.
+    pub fn load_delimited_as_text(&mut self, file_path: &Path) -> Result<DataInfo> {
+        self.cached_columns = None;
+        self.streaming_query = None;
+
+        let delimiter = self.delimiter_of(file_path).ok_or_else(|| crate::error::TabdiffError::invalid_input(
+            format!("Only CSV/TSV files can be read as text: {}", file_path.display())
+        ))?;
+        if !file_path.is_file() {
+            return Err(crate::error::TabdiffError::file_not_found(file_path));
+        }
+
+        let create_view_sql = format!(
+            "CREATE OR REPLACE VIEW data_view AS SELECT * FROM read_csv('{}', header={}, all_varchar=true, null_padding=true, ignore_errors=true{}{})",
+            file_path.to_string_lossy(),
+            !self.no_header,
+            if delimiter == b'\t' { ", delim='\\t'" } else { "" },
+            Self::nullstr_option(&self.na_values)
+        );
+        self.connection.execute(&create_view_sql, [])
+            .map_err(|e| self.convert_duckdb_error(e, file_path))?;
+
+        let row_count: u64 = self.connection
+            .prepare("SELECT COUNT(*) FROM data_view")?
+            .query_row([], |row| row.get(0))?;
+        let columns = self.get_column_info()?;
+
+        Ok(DataInfo {
+            source: file_path.to_path_buf(),
+            row_count,
+            columns,
+        })
+    }
+
+    /// Descriptions of the `assertions` the loaded rows violate, one per failed rule
+    pub fn assertion_violations(&mut self, assertions: &[crate::assertions::DataAssertion]) -> Result<Vec<String>> {
+        let columns = self.get_column_info()?;
+        let source = match &self.streaming_query {
+            Some(query) => format!("({})", query),
+            None => "data_view".to_string(),
+        };
+
+        let mut violations = Vec::new();
+        for assertion in assertions {
+            if let Some(missing) = assertion.columns().iter().find(|name| !columns.iter().any(|col| &col.name == *name)) {
+                violations.push(format!("{}: there is no column '{}'", assertion, missing));
+                continue;
+            }
+            let measure: u64 = self.connection
+                .prepare(&assertion.measure_sql(&source))?
+                .query_row([], |row| row.get(0))?;
+            violations.extend(assertion.violation(measure));
+        }
+        Ok(violations)
+    }
+
+    /// Count the present values of every column and, for text columns, how consistently they
+    /// parse as one type
+    pub fn column_profiles(&mut self) -> Result<Vec<ColumnProfile>> {
+        let columns = self.get_column_info()?;
+        let source = match &self.streaming_query {
+            Some(query) => format!("({})", query),
+            None => "data_view".to_string(),
+        };
+
+        let mut profiles = Vec::with_capacity(columns.len());
+        for column in columns {
+            let values = format!("(SELECT {} AS v FROM {})", sql::quote_identifier(&column.name), source);
+            if column.data_type != "VARCHAR" {
+                let present: u64 = self.connection
+                    .prepare(&format!("SELECT COUNT(v) FROM {}", values))?
+                    .query_row([], |row| row.get(0))?;
+                profiles.push(ColumnProfile::untyped(column, present));
+                continue;
+            }
+
+            let casts = ColumnProfile::CANDIDATE_TYPES.iter()
+                .map(|data_type| format!(", COUNT(TRY_CAST(v AS {}))", data_type))
+                .collect::<String>();
+            let counts: Vec<u64> = self.connection
+                .prepare(&format!("SELECT COUNT(*) FILTER (WHERE trim(v) <> ''){} FROM {}", casts, values))?
+                .query_row([], |row| {
+                    (0..=ColumnProfile::CANDIDATE_TYPES.len()).map(|i| row.get(i)).collect()
+                })?;
+            let present = counts[0];
+
+            // The most common parseable type (the narrowest on ties), if most values have it
+            let best = ColumnProfile::CANDIDATE_TYPES.iter()
+                .zip(&counts[1..])
+                .rev()
+                .max_by_key(|(_, &count)| count);
+            let (dominant_type, conforming) = match best {
+                Some((data_type, &count)) if count * 2 > present => (data_type, count),
+                _ => {
+                    profiles.push(ColumnProfile::untyped(column, present));
+                    continue;
+                }
+            };
+
+            let mut examples = Vec::new();
+            if conforming < present {
+                let mut stmt = self.connection.prepare(&format!(
+                    "SELECT DISTINCT v FROM {} WHERE trim(v) <> '' AND TRY_CAST(v AS {}) IS NULL ORDER BY v LIMIT {}",
+                    values, dominant_type, ColumnProfile::MAX_EXAMPLES
+                ))?;
+                let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+                for value in rows {
+                    examples.push(value?);
+                }
+            }
+            profiles.push(ColumnProfile {
+                name: column.name,
+                data_type: column.data_type,
+                present,
+                dominant_type: Some(dominant_type.to_string()),
+                nonconforming: present - conforming,
+                examples,
+            });
+        }
+        Ok(profiles)
+    }
+
+    /// Check if file is JSON or newline-delimited JSON
+    fn is_json_format(file_path: &Path) -> bool {
+        file_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|extension| matches!(extension.to_lowercase().as_str(), "json" | "jsonl"))
+    }
+
+    /// Split a JSON Pointer (RFC 6901) into its unescaped reference tokens
+    ///
+    /// "" and "/" both select the document root.
+    pub fn parse_json_pointer(pointer: &str) -> Result<Vec<String>> {
+        if pointer.is_empty() || pointer == "/" {
+            return Ok(Vec::new());
+        }
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            return Err(crate::error::TabdiffError::invalid_input(
+                format!("Invalid JSON pointer '{}': it must start with '/', e.g. /data/items", pointer)
+            ));
+        };
+        Ok(tokens.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+    }
+
+    /// Table expression whose rows are the records of the array at `pointer` in a JSON file
+    ///
+    /// Numeric tokens after the first index into arrays, e.g. "/pages/0/items".
+    fn json_pointer_source(path: &str, pointer: &str) -> Result<String> {
+        let tokens = Self::parse_json_pointer(pointer)?;
+        if tokens.is_empty() {
+            return Ok(format!("read_json_auto('{}')", path));
+        }
+        
+        let mut array_expr = String::new();
+        for (i, token) in tokens.iter().enumerate() {
+            match token.parse::<usize>() {
+                // DuckDB lists are 1-based
+                Ok(index) if i > 0 => array_expr.push_str(&format!("[{}]", index + 1)),
+                _ => {
+                    if i > 0 {
+                        array_expr.push('.');
+                    }
+                    array_expr.push_str(&sql::quote_identifier(token));
+                }
+            }
+        }
+        Ok(format!(
+            "(SELECT json_record.* FROM (SELECT UNNEST({}) AS json_record FROM read_json_auto('{}')))",
+            array_expr, path
+        ))
+    }
+
+    /// Check if file is Avro (read through DuckDB's avro extension)
+    fn is_avro_format(file_path: &Path) -> bool {
+        file_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("avro"))
+    }
+
     /// Check if file format is supported
     pub fn is_supported_format(file_path: &Path) -> bool {
         if let Some(extension) = file_path.extension().and_then(|s| s.to_str()) {
             matches!(extension.to_lowercase().as_str(), 
-                     "csv" | "parquet" | "json" | "jsonl" | "tsv" | "sql")
+                     "csv" | "parquet" | "json" | "jsonl" | "tsv" | "avro" | "sql")
         } else {
             false
         }
     }
 }
 
+/// Iterator over row hashes returned by `DataProcessor::row_hashes_iter`
+///
+/// After an error the iterator is exhausted.
+pub struct RowHashIter<'a> {
+    processor: &'a mut DataProcessor,
+    source_sql: String,
+    columns: Vec<ColumnInfo>,
+    next_row: u64,
+    buffer: std::vec::IntoIter<crate::hash::RowHash>,
+    done: bool,
+}
+
+impl Iterator for RowHashIter<'_> {
+    type Item = Result<crate::hash::RowHash>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(row_hash) = self.buffer.next() {
+            return Some(Ok(row_hash));
+        }
+        if self.done {
+            return None;
+        }
+
+        let chunk_size = self.processor.chunk_size.max(1);
+        let sql = format!("{} LIMIT {} OFFSET {}", self.source_sql, chunk_size, self.next_row);
+        match self.processor.hash_chunk_query(&sql, &self.columns, self.next_row) {
+            Ok(hashes) => {
+                // A short chunk is the last one
+                self.done = hashes.len() < chunk_size;
+                self.next_row += hashes.len() as u64;
+                self.buffer = hashes.into_iter();
+                self.buffer.next().map(Ok)
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Value counts of one column, gathered by `DataProcessor::column_profiles`
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub data_type: String,
+    /// Values that are neither NULL nor blank
+    pub present: u64,
+    /// For text columns, the type most present values parse as (if more than half do)
+    pub dominant_type: Option<String>,
+    /// Present values that don't parse as `dominant_type`
+    pub nonconforming: u64,
+    /// A few distinct nonconforming values, in sorted order
+    pub examples: Vec<String>,
+}
+
+impl ColumnProfile {
+    /// Types tried for text columns, narrowest first
+    pub const CANDIDATE_TYPES: [&'static str; 5] = ["BIGINT", "DOUBLE", "DATE", "TIMESTAMP", "BOOLEAN"];
+    pub const MAX_EXAMPLES: usize = 3;
+
+    fn untyped(column: ColumnInfo, present: u64) -> Self {
+        Self {
+            name: column.name,
+            data_type: column.data_type,
+            present,
+            dominant_type: None,
+            nonconforming: 0,
+            examples: Vec::new(),
+        }
+    }
+
+    /// Whether most, but not all, present values parse as one type
+    pub fn is_mixed(&self) -> bool {
+        self.dominant_type.is_some() && self.nonconforming > 0
+    }
+}
+
 /// Information about loaded data
 #[derive(Debug, Clone)]
 pub struct DataInfo {
@@ -1148,16 +2165,69 @@ mod tests {
         assert!(true);
     }
 
+    #[test]
+    fn test_connection_uses_the_given_settings() {
+        let settings = DuckDbSettings { memory_limit: Some("1GB".to_string()), threads: Some(1), extension_directory: None };
+        let processor = DataProcessor::new_with_settings(100, &settings).unwrap();
+        let threads: i64 = processor.connection
+            .query_row("SELECT current_setting('threads')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(threads, 1);
+    }
+
+    #[test]
+    fn test_format_value_renders_temporal_values_as_iso_8601() {
+        use duckdb::types::{TimeUnit, ValueRef};
+
+        assert_eq!(DataProcessor::format_value(ValueRef::Date32(19723), None), "2024-01-01");
+        assert_eq!(DataProcessor::format_value(ValueRef::Date32(-1), None), "1969-12-31");
+        assert_eq!(DataProcessor::format_value(ValueRef::Time64(TimeUnit::Microsecond, 37_800_000_000), None), "10:30:00");
+        assert_eq!(DataProcessor::format_value(ValueRef::Time64(TimeUnit::Microsecond, 37_800_250_000), None), "10:30:00.250");
+        assert_eq!(
+            DataProcessor::format_value(ValueRef::Timestamp(TimeUnit::Microsecond, 1_704_103_200_000_000), None),
+            "2024-01-01T10:00:00"
+        );
+        assert_eq!(
+            DataProcessor::format_value(ValueRef::Timestamp(TimeUnit::Millisecond, 1_704_103_200_123), None),
+            "2024-01-01T10:00:00.123"
+        );
+        assert_eq!(DataProcessor::format_value(ValueRef::Timestamp(TimeUnit::Second, -1), None), "1969-12-31T23:59:59");
+    }
+
     #[test]
     fn test_supported_formats() {
         assert!(DataProcessor::is_supported_format(Path::new("test.csv")));
         assert!(DataProcessor::is_supported_format(Path::new("test.parquet")));
         assert!(DataProcessor::is_supported_format(Path::new("test.json")));
         assert!(DataProcessor::is_supported_format(Path::new("test.sql")));
+        assert!(DataProcessor::is_supported_format(Path::new("test.avro")));
         assert!(!DataProcessor::is_supported_format(Path::new("test.txt")));
         assert!(!DataProcessor::is_supported_format(Path::new("test")));
     }
 
+    #[test]
+    #[ignore = "downloads the postgres extension; run with --ignored when online"]
+    fn test_sql_connection_errors_mask_passwords() {
+        let temp_dir = TempDir::new().unwrap();
+        let sql_path = temp_dir.path().join("query.sql");
+        fs::write(&sql_path, "-- ATTACH 'host=127.0.0.1 port=1 user=admin password=hunter2 dbname=mydb' AS db (TYPE postgres);\nSELECT * FROM db.users;\n").unwrap();
+        
+        let mut processor = DataProcessor::new().unwrap();
+        let error = processor.load_file(&sql_path).unwrap_err().to_string();
+        assert!(error.contains("Failed to execute connection string"), "Unexpected error: {}", error);
+        assert!(error.contains("password=****"), "Unexpected error: {}", error);
+        assert!(!error.contains("hunter2"), "Password leaked: {}", error);
+    }
+
+    #[test]
+    fn test_parse_json_pointer() {
+        assert!(DataProcessor::parse_json_pointer("").unwrap().is_empty());
+        assert!(DataProcessor::parse_json_pointer("/").unwrap().is_empty());
+        assert_eq!(DataProcessor::parse_json_pointer("/data/items").unwrap(), vec!["data", "items"]);
+        assert_eq!(DataProcessor::parse_json_pointer("/a~1b/c~0d").unwrap(), vec!["a/b", "c~d"]);
+        assert!(DataProcessor::parse_json_pointer("data/items").is_err());
+    }
+
     #[test]
     fn test_csv_loading() {
         let temp_dir = TempDir::new().unwrap();
@@ -1174,4 +2244,308 @@ mod tests {
         assert_eq!(data_info.column_count(), 3);
         assert_eq!(data_info.column_names(), vec!["name", "age", "city"]);
     }
+
+    #[test]
+    fn test_parallel_streaming_hashes_match_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("data.csv");
+        let sql_path = temp_dir.path().join("query.sql");
+
+        let mut csv_content = String::from("id,name,score\n");
+        for i in 0..25 {
+            csv_content.push_str(&format!("{},name_{},{}\n", i, i, i * 7));
+        }
+        fs::write(&csv_path, csv_content).unwrap();
+        fs::write(&sql_path, format!("SELECT * FROM read_csv('{}');\n", csv_path.display())).unwrap();
+
+        // Sequential path: regular file hashing
+        let mut file_processor = DataProcessor::new().unwrap();
+        file_processor.load_file(&csv_path).unwrap();
+        let sequential = file_processor.compute_row_hashes_sql().unwrap();
+
+        // Parallel path: streaming SQL hashing with chunks that don't divide the row count evenly
+        let mut sql_processor = DataProcessor::new_with_config(4).unwrap();
+        sql_processor.load_file(&sql_path).unwrap();
+        let parallel = sql_processor.compute_row_hashes_sql().unwrap();
+
+        assert_eq!(sequential.len(), 25);
+        assert_eq!(parallel.len(), sequential.len());
+        for (seq, par) in sequential.iter().zip(parallel.iter()) {
+            assert_eq!(seq.row_index, par.row_index);
+            assert_eq!(seq.hash, par.hash);
+        }
+    }
+
+    #[test]
+    fn test_row_hashes_iter_matches_collected_hashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("data.csv");
+        let rows: String = (0..25).map(|i| format!("{},name_{},{}\n", i, i, i * 7)).collect();
+        fs::write(&csv_path, format!("id,name,score\n{}", rows)).unwrap();
+
+        let mut processor = DataProcessor::new_with_config(4).unwrap();
+        processor.load_file(&csv_path).unwrap();
+        let collected = processor.compute_row_hashes_sql().unwrap();
+
+        // Chunks of 4 don't divide 25 rows evenly
+        let mut iter = processor.row_hashes_iter().unwrap();
+        let first = iter.next().unwrap().unwrap();
+        assert_eq!(first.row_index, 0);
+        let streamed: Vec<_> = std::iter::once(Ok(first)).chain(iter).collect::<Result<_>>().unwrap();
+
+        assert_eq!(streamed.len(), 25);
+        for (a, b) in collected.iter().zip(streamed.iter()) {
+            assert_eq!(a.row_index, b.row_index);
+            assert_eq!(a.hash, b.hash);
+        }
+    }
+
+    #[test]
+    fn test_order_by_makes_row_hashes_independent_of_physical_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let sorted_path = temp_dir.path().join("sorted.csv");
+        let shuffled_path = temp_dir.path().join("shuffled.csv");
+        fs::write(&sorted_path, "id,name\n1,a\n2,b\n3,c\n4,d\n").unwrap();
+        fs::write(&shuffled_path, "id,name\n3,c\n1,a\n4,d\n2,b\n").unwrap();
+
+        let mut sorted = DataProcessor::new().unwrap().with_order_by(vec!["id".to_string()]);
+        sorted.load_file(&sorted_path).unwrap();
+        let sorted_hashes = sorted.compute_row_hashes_sql().unwrap();
+
+        let mut shuffled = DataProcessor::new().unwrap().with_order_by(vec!["id".to_string()]);
+        shuffled.load_file(&shuffled_path).unwrap();
+        let shuffled_hashes = shuffled.compute_row_hashes_sql().unwrap();
+
+        assert_eq!(sorted_hashes.len(), 4);
+        for (a, b) in sorted_hashes.iter().zip(shuffled_hashes.iter()) {
+            assert_eq!(a.row_index, b.row_index);
+            assert_eq!(a.hash, b.hash);
+        }
+        assert_eq!(sorted.extract_all_data().unwrap(), shuffled.extract_all_data().unwrap());
+    }
+
+    #[test]
+    fn test_order_by_breaks_ties_on_the_remaining_columns() {
+        let temp_dir = TempDir::new().unwrap();
+        let first_path = temp_dir.path().join("first.csv");
+        let second_path = temp_dir.path().join("second.csv");
+        // Every row shares its region with another one
+        fs::write(&first_path, "region,name\neu,b\nus,d\neu,a\nus,c\n").unwrap();
+        fs::write(&second_path, "region,name\nus,c\neu,a\nus,d\neu,b\n").unwrap();
+
+        let mut first = DataProcessor::new().unwrap().with_order_by(vec!["region".to_string()]);
+        first.load_file(&first_path).unwrap();
+        let mut second = DataProcessor::new().unwrap().with_order_by(vec!["region".to_string()]);
+        second.load_file(&second_path).unwrap();
+
+        let expected: Vec<Vec<String>> = [["eu", "a"], ["eu", "b"], ["us", "c"], ["us", "d"]].iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+        assert_eq!(first.extract_all_data().unwrap(), expected);
+        assert_eq!(second.extract_all_data().unwrap(), expected);
+        let hashes = |processor: &mut DataProcessor| processor.compute_row_hashes_sql().unwrap()
+            .into_iter()
+            .map(|row| row.hash)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes(&mut first), hashes(&mut second));
+    }
+
+    #[test]
+    fn test_resumed_hashing_matches_uninterrupted_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("rows.csv");
+        let rows: String = (0..25).map(|i| format!("{},name{}\n", i, i)).collect();
+        fs::write(&csv_path, format!("id,name\n{}", rows)).unwrap();
+        let checkpoint = crate::checkpoint::HashCheckpoint::new(temp_dir.path().join("v1.checkpoint"), "rows".to_string());
+        let hash_rows = |checkpoint: crate::checkpoint::HashCheckpoint| {
+            let mut processor = DataProcessor::new_with_config(10).unwrap()
+                .with_order_by(vec!["id".to_string()])
+                .with_hash_checkpoint(Some(checkpoint));
+            processor.load_file(&csv_path).unwrap();
+            processor.compute_row_hashes_sql().unwrap()
+        };
+
+        let uninterrupted = hash_rows(checkpoint.clone());
+
+        // Simulate a run killed after 12 rows, halfway through writing the 13th
+        let content = fs::read_to_string(checkpoint.path()).unwrap();
+        let mut lines: Vec<&str> = content.lines().take(13).collect();
+        lines.push("ab");
+        fs::write(checkpoint.path(), lines.join("\n")).unwrap();
+        assert_eq!(checkpoint.clone().with_resume(true).load().unwrap().len(), 12);
+
+        let resumed = hash_rows(checkpoint.clone().with_resume(true));
+        assert_eq!(resumed.len(), 25);
+        for (a, b) in uninterrupted.iter().zip(resumed.iter()) {
+            assert_eq!(a.row_index, b.row_index);
+            assert_eq!(a.hash, b.hash);
+        }
+        assert_eq!(checkpoint.with_resume(true).load().unwrap().len(), 25);
+    }
+
+    #[test]
+    fn test_source_line_numbers_follow_multiline_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("notes.csv");
+        fs::write(&csv_path, "id,note\r\n1,plain\r\n2,\"two\nlines\"\r\n\r\n3,last").unwrap();
+
+        let processor = DataProcessor::new().unwrap();
+        assert_eq!(processor.source_line_numbers(&csv_path).unwrap(), Some(vec![2, 3, 6]));
+
+        let header_less = DataProcessor::new().unwrap().with_no_header(true);
+        assert_eq!(header_less.source_line_numbers(&csv_path).unwrap(), Some(vec![1, 2, 3, 6]));
+
+        // Sorted rows no longer follow the file
+        let sorted = DataProcessor::new().unwrap().with_order_by(vec!["id".to_string()]);
+        assert_eq!(sorted.source_line_numbers(&csv_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_record_field_counts_respect_quotes() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("data.csv");
+        fs::write(&csv_path, "id,name,note\n1,\"Smith, J\",ok\n2,Bob\n\n3,\"multi\nline\",x,extra\n").unwrap();
+
+        let processor = DataProcessor::new().unwrap();
+        let counts = processor.record_field_counts(&csv_path).unwrap().unwrap();
+        assert_eq!(counts, vec![(1, 3), (2, 3), (3, 2), (5, 4)]);
+        assert!(processor.record_field_counts(&temp_dir.path().join("data.parquet")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_format_float() {
+        assert_eq!(DataProcessor::format_float(1.5, None), "1.5");
+        assert_eq!(DataProcessor::format_float(0.1 + 0.2, None), "0.30000000000000004");
+        assert_eq!(DataProcessor::format_float(0.1 + 0.2, Some(10)), "0.3");
+        assert_eq!(DataProcessor::format_float(2.0, Some(4)), "2");
+        assert_eq!(DataProcessor::format_float(-0.00001, Some(2)), "0");
+        assert_eq!(DataProcessor::format_float(f64::NAN, Some(2)), "NaN");
+    }
+
+    #[test]
+    fn test_float_precision_makes_hashes_type_independent() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("prices.csv");
+        let sql_path = temp_dir.path().join("prices.sql");
+        fs::write(&csv_path, "id,price,ratio\n1,1.5,0.1\n2,2.25,0.3\n").unwrap();
+        // Same numbers as DECIMAL and FLOAT instead of DOUBLE
+        fs::write(&sql_path, "SELECT * FROM (VALUES (1, 1.50::DECIMAL(10,2), 0.1::FLOAT), (2, 2.25::DECIMAL(10,2), (0.1 + 0.2)::FLOAT)) AS t(id, price, ratio);").unwrap();
+
+        let hashes = |path: &Path, float_precision: Option<usize>| {
+            let mut processor = DataProcessor::new().unwrap().with_float_precision(float_precision);
+            processor.load_file(path).unwrap();
+            processor.compute_row_hashes_sql().unwrap()
+                .into_iter()
+                .map(|row_hash| row_hash.hash)
+                .collect::<Vec<_>>()
+        };
+
+        assert_ne!(hashes(&csv_path, None), hashes(&sql_path, None));
+        assert_eq!(hashes(&csv_path, Some(6)), hashes(&sql_path, Some(6)));
+    }
+
+    #[test]
+    fn test_blobs_of_equal_length_are_compared_by_content() {
+        let load = |payload: &str| {
+            let mut processor = DataProcessor::new().unwrap();
+            processor.load_query(&format!("SELECT 1 AS id, '{}'::BLOB AS payload", payload)).unwrap();
+            let hashes = processor.compute_row_hashes_sql().unwrap();
+            (hashes[0].hash.clone(), processor.extract_all_data().unwrap())
+        };
+
+        let (hash_a, rows_a) = load("\\xAA\\xBB\\xCC");
+        let (hash_b, rows_b) = load("\\xAA\\xBB\\xCD");
+        assert_ne!(hash_a, hash_b);
+        assert!(rows_a[0][1].starts_with("<blob:3 bytes "), "Unexpected blob display: {}", rows_a[0][1]);
+        assert_ne!(rows_a[0][1], rows_b[0][1]);
+
+        let columns = vec![
+            ColumnInfo { name: "id".to_string(), data_type: "INTEGER".to_string(), nullable: false },
+            ColumnInfo { name: "payload".to_string(), data_type: "BLOB".to_string(), nullable: true },
+        ];
+        let changes = crate::change_detection::ChangeDetector::detect_changes(&columns, &rows_a, &columns, &rows_b).unwrap();
+        assert_eq!(changes.row_changes.modified.len(), 1);
+        assert!(changes.row_changes.modified[0].changes.contains_key("payload"));
+    }
+
+    #[test]
+    fn test_values_containing_separators_hash_differently() {
+        let mut processor = DataProcessor::new().unwrap();
+        processor.load_query("SELECT 'a||b' AS x, 'c' AS y UNION ALL SELECT 'a', 'b||c' ORDER BY y").unwrap();
+        let hashes = processor.compute_row_hashes_sql().unwrap();
+
+        assert_eq!(hashes.len(), 2);
+        assert_ne!(hashes[0].hash, hashes[1].hash);
+    }
+
+    #[test]
+    fn test_order_by_unknown_column_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("data.csv");
+        fs::write(&csv_path, "id,name\n1,a\n").unwrap();
+
+        let mut processor = DataProcessor::new().unwrap().with_order_by(vec!["missing".to_string()]);
+        assert!(processor.load_file(&csv_path).is_err());
+    }
+
+    #[test]
+    fn test_duckdb_settings_applied() {
+        let extensions = TempDir::new().unwrap();
+        let settings = DuckDbSettings {
+            memory_limit: Some("256MB".to_string()),
+            threads: Some(2),
+            extension_directory: Some(extensions.path().to_path_buf()),
+        };
+        let processor = DataProcessor::new_with_settings(10000, &settings).unwrap();
+        
+        let threads: i64 = processor.connection
+            .query_row("SELECT current_setting('threads')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(threads, 2);
+        
+        let memory_limit: String = processor.connection
+            .query_row("SELECT current_setting('memory_limit')", [], |row| row.get(0))
+            .unwrap();
+        // DuckDB reports the limit in binary units, e.g. "244.1 MiB"
+        assert!(memory_limit.contains("MiB"), "Unexpected memory limit: {}", memory_limit);
+        
+        let extension_directory: String = processor.connection
+            .query_row("SELECT current_setting('extension_directory')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(extension_directory, extensions.path().display().to_string());
+    }
+
+    #[test]
+    fn test_small_memory_limit_completes_snapshot_workload() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("small.csv");
+        fs::write(&csv_path, "id,name\n1,a\n2,b\n3,c\n").unwrap();
+        
+        let settings = DuckDbSettings {
+            memory_limit: Some("128MB".to_string()),
+            threads: Some(1),
+            extension_directory: None,
+        };
+        let mut processor = DataProcessor::new_with_settings(10000, &settings).unwrap();
+        let data_info = processor.load_file(&csv_path).unwrap();
+        let hashes = processor.compute_row_hashes_sql().unwrap();
+        let rows = processor.extract_all_data().unwrap();
+        
+        assert_eq!(data_info.row_count, 3);
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn test_sample_keeps_rows_by_content_and_seed() {
+        let rows: Vec<Vec<String>> = (0..2000).map(|id| vec![id.to_string(), format!("item-{}", id)]).collect();
+        let sample = SampleSpec { fraction: 0.1, seed: 7 };
+        let kept: Vec<&Vec<String>> = rows.iter().filter(|row| sample.keeps(row)).collect();
+        assert!(kept.len() > 120 && kept.len() < 280, "Unexpected sample size: {}", kept.len());
+
+        // Membership depends only on the row and the seed
+        assert!(kept.iter().all(|row| sample.keeps(row)));
+        let reseeded = SampleSpec { seed: 8, ..sample };
+        assert!(kept.iter().any(|row| !reseeded.keeps(row)));
+    }
 }
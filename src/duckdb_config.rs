@@ -4,6 +4,191 @@ use crate::error::{Result, TabdiffError};
 use std::env;
 use std::path::{Path, PathBuf};
 
+/// Environment variable overriding the DuckDB memory limit (e.g. "2GB")
+pub const MEMORY_LIMIT_ENV: &str = "TABDIFF_DUCKDB_MEMORY_LIMIT";
+
+/// Environment variable overriding the DuckDB worker thread count
+pub const THREADS_ENV: &str = "TABDIFF_DUCKDB_THREADS";
+
+/// Environment variable naming the directory DuckDB extensions are installed into and loaded
+/// from (defaults to DuckDB's own `~/.duckdb/extensions`)
+pub const EXTENSION_DIR_ENV: &str = "TABDIFF_DUCKDB_EXTENSION_DIR";
+
+/// Memory limit used when none is configured
+pub const DEFAULT_MEMORY_LIMIT: &str = "8GB";
+
+/// Resource settings applied to every DuckDB connection
+#[derive(Debug, Clone, Default)]
+pub struct DuckDbSettings {
+    /// Memory limit such as "512MB" or "4GB" (defaults to DEFAULT_MEMORY_LIMIT)
+    pub memory_limit: Option<String>,
+    /// Worker thread count (defaults to DuckDB's auto-detection)
+    pub threads: Option<usize>,
+    /// Persistent directory for downloaded extensions (defaults to DuckDB's own)
+    pub extension_directory: Option<PathBuf>,
+}
+
+impl DuckDbSettings {
+    /// Read settings from the environment, validating any values present
+    pub fn from_env() -> Result<Self> {
+        let memory_limit = match env::var(MEMORY_LIMIT_ENV) {
+            Ok(value) => Some(parse_memory_limit(&value).map_err(|e| {
+                TabdiffError::config(format!("{}: {}", MEMORY_LIMIT_ENV, e))
+            })?),
+            Err(_) => None,
+        };
+        
+        let threads = match env::var(THREADS_ENV) {
+            Ok(value) => Some(parse_threads(&value).map_err(|e| {
+                TabdiffError::config(format!("{}: {}", THREADS_ENV, e))
+            })?),
+            Err(_) => None,
+        };
+        
+        let extension_directory = env::var_os(EXTENSION_DIR_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+        
+        Ok(Self { memory_limit, threads, extension_directory })
+    }
+
+    /// Effective memory limit
+    pub fn memory_limit(&self) -> &str {
+        self.memory_limit.as_deref().unwrap_or(DEFAULT_MEMORY_LIMIT)
+    }
+}
+
+/// Validate a memory limit such as "512MB", "4GB" or "1.5GiB"
+pub fn parse_memory_limit(s: &str) -> std::result::Result<String, String> {
+    let trimmed = s.trim();
+    let split_at = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split_at);
+    
+    let value: f64 = number.parse()
+        .map_err(|_| format!("Invalid memory limit: '{}'. Use a size such as '512MB' or '4GB'", s))?;
+    
+    if value <= 0.0 {
+        return Err("Memory limit must be greater than 0".to_string());
+    }
+    
+    let valid_units = ["B", "KB", "MB", "GB", "TB", "KIB", "MIB", "GIB", "TIB"];
+    if !valid_units.contains(&unit.trim().to_uppercase().as_str()) {
+        return Err(format!("Invalid memory limit unit in '{}'. Use B, KB, MB, GB or TB", s));
+    }
+    
+    Ok(format!("{}{}", number, unit.trim()))
+}
+
+/// Validate a DuckDB thread count
+pub fn parse_threads(s: &str) -> std::result::Result<usize, String> {
+    let threads: usize = s.trim().parse()
+        .map_err(|_| format!("Invalid thread count: '{}'. Must be a positive integer.", s))?;
+    
+    if threads == 0 {
+        return Err("Thread count must be greater than 0".to_string());
+    }
+    
+    Ok(threads)
+}
+
+/// How far a DuckDB extension is available to a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionState {
+    /// Not in the extension directory, so it has to be downloaded
+    NotInstalled,
+    /// In the extension directory (or built in) but not loaded into this connection
+    Installed,
+    /// Already loaded into this connection
+    Loaded,
+}
+
+impl ExtensionState {
+    /// Statements that make the extension usable, if any are needed; only a missing
+    /// extension is installed, which is the step that goes to the network
+    pub fn statements(self, name: &str) -> Option<String> {
+        match self {
+            ExtensionState::NotInstalled => Some(format!("INSTALL {0}; LOAD {0};", name)),
+            ExtensionState::Installed => Some(format!("LOAD {};", name)),
+            ExtensionState::Loaded => None,
+        }
+    }
+}
+
+/// Look up an extension in `duckdb_extensions()`; unknown names count as not installed
+pub fn extension_state(connection: &duckdb::Connection, name: &str) -> Result<ExtensionState> {
+    let mut statement = connection.prepare(
+        "SELECT installed, loaded FROM duckdb_extensions() WHERE extension_name = ?"
+    )?;
+    let mut rows = statement.query([name])?;
+    let Some(row) = rows.next()? else {
+        return Ok(ExtensionState::NotInstalled);
+    };
+    let installed: Option<bool> = row.get(0)?;
+    let loaded: Option<bool> = row.get(1)?;
+    Ok(match (installed.unwrap_or(false), loaded.unwrap_or(false)) {
+        (_, true) => ExtensionState::Loaded,
+        (true, false) => ExtensionState::Installed,
+        (false, false) => ExtensionState::NotInstalled,
+    })
+}
+
+/// Install (if needed) and load a DuckDB extension into a connection
+///
+/// Extensions are downloaded into the extension directory the first time they are used, so
+/// only inputs that need them pay the cost, and later runs just load the installed copy.
+pub fn load_extension(connection: &duckdb::Connection, name: &str) -> Result<()> {
+    let state = extension_state(connection, name)?;
+    let Some(statements) = state.statements(name) else {
+        return Ok(());
+    };
+    log::debug!("DuckDB extension '{}' is {:?}; running: {}", name, state, statements);
+    connection
+        .execute_batch(&statements)
+        .map_err(|e| TabdiffError::data_processing(format!(
+            "Failed to load the DuckDB '{}' extension (it is downloaded on first use, so network access may be required): {}",
+            name, e
+        )))
+}
+
+/// URL schemes DuckDB reads over the network through its httpfs extension
+const REMOTE_SCHEMES: [&str; 5] = ["http://", "https://", "s3://", "gs://", "r2://"];
+
+/// Whether an input names a remote object such as `https://host/data.csv` or
+/// `s3://bucket/data.parquet` rather than a local path
+pub fn is_remote_url(input: &str) -> bool {
+    let lower = input.trim_start().to_lowercase();
+    REMOTE_SCHEMES.iter().any(|scheme| lower.starts_with(scheme))
+}
+
+/// Prepare a connection to read remote URLs: load httpfs and, when the standard AWS environment
+/// variables are set, register them as the S3 credentials
+///
+/// Without those variables DuckDB falls back to any persistent secrets it has been given.
+pub fn enable_remote_access(connection: &duckdb::Connection) -> Result<()> {
+    load_extension(connection, "httpfs")?;
+
+    let (Ok(key_id), Ok(secret)) = (env::var("AWS_ACCESS_KEY_ID"), env::var("AWS_SECRET_ACCESS_KEY")) else {
+        return Ok(());
+    };
+    let literal = |value: &str| format!("'{}'", value.replace('\'', "''"));
+    let mut options = vec![
+        "TYPE S3".to_string(),
+        format!("KEY_ID {}", literal(&key_id)),
+        format!("SECRET {}", literal(&secret)),
+    ];
+    if let Ok(token) = env::var("AWS_SESSION_TOKEN") {
+        options.push(format!("SESSION_TOKEN {}", literal(&token)));
+    }
+    if let Ok(region) = env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")) {
+        options.push(format!("REGION {}", literal(&region)));
+    }
+    connection
+        .execute_batch(&format!("CREATE OR REPLACE TEMPORARY SECRET tabdiff_env_s3 ({})", options.join(", ")))
+        .map_err(|e| TabdiffError::config(format!("Failed to register S3 credentials from the environment: {}", e)))
+}
+
 /// DuckDB configuration manager
 pub struct DuckDbConfig {
     pub library_path: Option<PathBuf>,
@@ -210,11 +395,18 @@ impl Default for DuckDbConfig {
     }
 }
 
-/// Initialize DuckDB configuration and validate it
-pub fn init_duckdb() -> Result<DuckDbConfig> {
+/// Initialize DuckDB configuration and validate it against the settings connections will use
+pub fn init_duckdb(settings: &DuckDbSettings) -> Result<DuckDbConfig> {
     let config = DuckDbConfig::new();
     config.validate()?;
     
+    // A configured extension directory must exist before DuckDB installs into it
+    if let Some(directory) = &settings.extension_directory {
+        std::fs::create_dir_all(directory).map_err(|e| TabdiffError::config(format!(
+            "{}: cannot create '{}': {}", EXTENSION_DIR_ENV, directory.display(), e
+        )))?;
+    }
+    
     // Log the configuration being used
     if config.use_bundled() {
         log::info!("Using bundled DuckDB library");
@@ -229,6 +421,16 @@ pub fn init_duckdb() -> Result<DuckDbConfig> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_remote_url() {
+        assert!(is_remote_url("https://example.com/data.csv"));
+        assert!(is_remote_url("HTTP://example.com/data.csv"));
+        assert!(is_remote_url("s3://bucket/data.parquet"));
+        assert!(!is_remote_url("data/s3://not-a-url.csv"));
+        assert!(!is_remote_url("/tmp/data.csv"));
+        assert!(!is_remote_url("https.csv"));
+    }
+
     #[test]
     fn test_config_creation() {
         let _config = DuckDbConfig::new();
@@ -250,6 +452,78 @@ mod tests {
         println!("Uses bundled DuckDB: {}", uses_bundled);
     }
 
+    #[test]
+    fn test_parse_memory_limit() {
+        assert_eq!(parse_memory_limit("512MB").unwrap(), "512MB");
+        assert_eq!(parse_memory_limit("1.5GiB").unwrap(), "1.5GiB");
+        assert!(parse_memory_limit("lots").is_err());
+        assert!(parse_memory_limit("0GB").is_err());
+        assert!(parse_memory_limit("4 parsecs").is_err());
+        assert!(parse_memory_limit("100").is_err());
+    }
+
+    #[test]
+    fn test_parse_threads() {
+        assert_eq!(parse_threads("4").unwrap(), 4);
+        assert!(parse_threads("0").is_err());
+        assert!(parse_threads("-1").is_err());
+        assert!(parse_threads("many").is_err());
+    }
+
+    #[test]
+    fn test_extension_statements_only_install_missing_extensions() {
+        let install = ExtensionState::NotInstalled.statements("httpfs").unwrap();
+        assert!(install.contains("INSTALL httpfs") && install.contains("LOAD httpfs"));
+        assert_eq!(ExtensionState::Installed.statements("httpfs").unwrap(), "LOAD httpfs;");
+        assert_eq!(ExtensionState::Loaded.statements("httpfs"), None);
+    }
+
+    #[test]
+    fn test_loading_an_available_extension_does_not_install() {
+        let connection = duckdb::Connection::open_in_memory().unwrap();
+        let loaded: Option<String> = connection
+            .query_row("SELECT extension_name FROM duckdb_extensions() WHERE loaded LIMIT 1", [], |row| row.get(0))
+            .ok();
+        let Some(name) = loaded else {
+            return; // this DuckDB build has no extension loaded by default
+        };
+        assert_eq!(extension_state(&connection, &name).unwrap(), ExtensionState::Loaded);
+
+        // An install would have to reach this repository and fail
+        connection.execute_batch(
+            "SET custom_extension_repository = 'http://127.0.0.1:9'; SET autoinstall_known_extensions = false;"
+        ).unwrap();
+        load_extension(&connection, &name).unwrap();
+        load_extension(&connection, &name).unwrap();
+        assert_eq!(
+            extension_state(&connection, "no_such_extension").unwrap(),
+            ExtensionState::NotInstalled
+        );
+    }
+
+    #[test]
+    fn test_loading_an_installed_extension_only_loads_it() {
+        let connection = duckdb::Connection::open_in_memory().unwrap();
+        let installed: Option<String> = connection
+            .query_row(
+                "SELECT extension_name FROM duckdb_extensions() WHERE installed AND NOT loaded LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+        let Some(name) = installed else {
+            return; // nothing is installed without being loaded in this DuckDB build
+        };
+        assert_eq!(extension_state(&connection, &name).unwrap(), ExtensionState::Installed);
+
+        // LOAD works offline, while an install would have to reach this repository and fail
+        connection.execute_batch(
+            "SET custom_extension_repository = 'http://127.0.0.1:9'; SET autoinstall_known_extensions = false;"
+        ).unwrap();
+        load_extension(&connection, &name).unwrap();
+        assert_eq!(extension_state(&connection, &name).unwrap(), ExtensionState::Loaded);
+    }
+
     #[test]
     fn test_error_message_generation() {
         let config = DuckDbConfig {
@@ -0,0 +1,108 @@
+//! Text encodings for input files that are not plain UTF-8
+//!
+//! DuckDB only reads UTF-8, so other encodings are transcoded into a temporary UTF-8 copy.
+
+use crate::data::DataProcessor;
+use crate::error::{Result, TabdiffError};
+use crate::git::ExtractedFile;
+use std::path::Path;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Encoding of a text input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceEncoding {
+    #[default]
+    Utf8,
+    Latin1,
+    Windows1252,
+}
+
+impl SourceEncoding {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" | "iso8859-1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            _ => Err(format!("Unsupported encoding: {}. Use 'utf-8', 'latin-1' or 'windows-1252'", s)),
+        }
+    }
+
+    /// Canonical label recorded in snapshot metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Latin1 => "iso-8859-1",
+            Self::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Decode file contents to a string, dropping a leading UTF-8 byte order mark
+    pub fn decode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Self::Utf8 => {
+                let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+                String::from_utf8(bytes.to_vec()).map_err(|e| {
+                    TabdiffError::malformed_data(format!("invalid UTF-8 ({}); use --encoding to read other encodings", e))
+                })
+            }
+            Self::Latin1 => Ok(encoding_rs::mem::decode_latin1(bytes).into_owned()),
+            Self::Windows1252 => Ok(encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned()),
+        }
+    }
+
+    /// Write a UTF-8 copy of a text file for DuckDB, or return None when it can be read as is
+    ///
+    /// UTF-8 files are only copied when they start with a byte order mark.
+    pub fn transcode(&self, input_path: &Path) -> Result<Option<ExtractedFile>> {
+        if !input_path.is_file() {
+            if *self == Self::Utf8 {
+                return Ok(None);
+            }
+            return Err(TabdiffError::invalid_input(format!(
+                "--encoding is only supported for single text files: {}", input_path.display()
+            )));
+        }
+
+        if !DataProcessor::is_delimited_format(input_path) && !Self::is_json(input_path) {
+            if *self == Self::Utf8 {
+                return Ok(None);
+            }
+            return Err(TabdiffError::invalid_input(format!(
+                "--encoding is only supported for CSV, TSV and JSON files: {}", input_path.display()
+            )));
+        }
+
+        let bytes = std::fs::read(input_path)?;
+        if *self == Self::Utf8 && !bytes.starts_with(UTF8_BOM) {
+            return Ok(None);
+        }
+
+        let text = self.decode(&bytes)
+            .map_err(|e| TabdiffError::malformed_data(format!("'{}': {}", input_path.display(), e)))?;
+        let key = format!("{}:{}", self.as_str(), input_path.display());
+        ExtractedFile::write("encoding", &key, input_path, text.as_bytes()).map(Some)
+    }
+
+    fn is_json(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "json" | "jsonl"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(SourceEncoding::Utf8.decode(b"\xEF\xBB\xBFid,name").unwrap(), "id,name");
+        assert!(SourceEncoding::Utf8.decode(b"Jos\xE9").is_err());
+        assert_eq!(SourceEncoding::Latin1.decode(b"Jos\xE9 M\xFCller").unwrap(), "José Müller");
+        assert_eq!(SourceEncoding::Windows1252.decode(b"\x80 \x93quoted\x94 \xE9").unwrap(), "€ “quoted” é");
+        assert_eq!(SourceEncoding::parse("ISO-8859-1").unwrap(), SourceEncoding::Latin1);
+        assert_eq!(SourceEncoding::parse("cp1252").unwrap(), SourceEncoding::Windows1252);
+        assert!(SourceEncoding::parse("ebcdic").is_err());
+    }
+}
@@ -34,6 +34,18 @@ pub enum TabdiffError {
     #[error("Schema mismatch: {message}")]
     SchemaMismatch { message: String },
 
+    #[error("File not found: {path}")]
+    FileNotFound { path: PathBuf },
+
+    #[error("Unsupported file format: {path}")]
+    UnsupportedFormat { path: PathBuf },
+
+    #[error("Malformed data: {message}")]
+    MalformedData { message: String },
+
+    #[error("Hash algorithm mismatch: {message}")]
+    HashAlgorithmMismatch { message: String },
+
     #[error("Invalid sampling strategy: {strategy}")]
     InvalidSampling { strategy: String },
 
@@ -66,6 +78,36 @@ pub enum TabdiffError {
 }
 
 impl TabdiffError {
+    /// Stable numeric code identifying the error kind, for programmatic consumers.
+    /// Codes are never reused or renumbered; new variants get new codes.
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Generic(_) => 1,
+            Self::Cancelled => 2,
+            Self::InvalidInput { .. } => 10,
+            Self::FileNotFound { .. } => 11,
+            Self::UnsupportedFormat { .. } => 12,
+            Self::MalformedData { .. } => 13,
+            Self::InvalidSampling { .. } => 14,
+            Self::Config { .. } => 15,
+            Self::Workspace(_) => 20,
+            Self::SnapshotNotFound { .. } => 21,
+            Self::InvalidSnapshot { .. } => 22,
+            Self::Archive(_) => 23,
+            Self::SchemaMismatch { .. } => 30,
+            Self::HashAlgorithmMismatch { .. } => 31,
+            Self::Hash { .. } => 32,
+            Self::DataProcessing { .. } => 40,
+            Self::DuckDb(_) => 41,
+            Self::Parquet(_) => 42,
+            Self::Io(_) => 50,
+            Self::Json(_) => 51,
+            Self::WalkDir(_) => 52,
+            Self::StringConversion(_) => 53,
+            Self::Git { .. } => 60,
+        }
+    }
+
     pub fn workspace(msg: impl Into<String>) -> Self {
         Self::Workspace(msg.into())
     }
@@ -80,6 +122,26 @@ impl TabdiffError {
         }
     }
 
+    pub fn file_not_found(path: impl Into<PathBuf>) -> Self {
+        Self::FileNotFound { path: path.into() }
+    }
+
+    pub fn unsupported_format(path: impl Into<PathBuf>) -> Self {
+        Self::UnsupportedFormat { path: path.into() }
+    }
+
+    pub fn malformed_data(msg: impl Into<String>) -> Self {
+        Self::MalformedData {
+            message: msg.into(),
+        }
+    }
+
+    pub fn hash_algorithm_mismatch(msg: impl Into<String>) -> Self {
+        Self::HashAlgorithmMismatch {
+            message: msg.into(),
+        }
+    }
+
     pub fn invalid_sampling(strategy: impl Into<String>) -> Self {
         Self::InvalidSampling {
             strategy: strategy.into(),
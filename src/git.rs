@@ -1,6 +1,88 @@
 //! Git integration utilities
 
-use std::path::Path;
+use crate::error::{Result, TabdiffError};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix for inputs that name a file at a git revision, e.g. `git:HEAD~3:data/sales.csv`
+pub const GIT_SOURCE_PREFIX: &str = "git:";
+
+/// A file as it existed at a specific git revision
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitSource {
+    /// Revision understood by `git show` (commit, branch, tag, `HEAD~1`, ...)
+    pub rev: String,
+    /// Path of the file relative to the repository root
+    pub path: String,
+}
+
+impl GitSource {
+    /// Parse a `git:REV:path` input; returns None for ordinary file paths
+    pub fn parse(input: &str) -> Result<Option<Self>> {
+        let spec = match input.strip_prefix(GIT_SOURCE_PREFIX) {
+            Some(spec) => spec,
+            None => return Ok(None),
+        };
+        match spec.split_once(':') {
+            Some((rev, path)) if !rev.is_empty() && !path.is_empty() => Ok(Some(Self {
+                rev: rev.to_string(),
+                path: path.trim_start_matches("./").to_string(),
+            })),
+            _ => Err(TabdiffError::invalid_input(format!(
+                "Invalid git source '{}'. Expected git:<revision>:<path>, e.g. git:HEAD~1:data.csv",
+                input
+            ))),
+        }
+    }
+
+    /// Rev-qualified source label recorded in snapshot metadata
+    pub fn label(&self) -> String {
+        format!("{}{}:{}", GIT_SOURCE_PREFIX, self.rev, self.path)
+    }
+}
+
+/// A temporary copy of an input file (a git blob or transcoded text), removed again when dropped
+#[derive(Debug)]
+pub struct ExtractedFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl ExtractedFile {
+    /// Write `contents` to a private temp directory under the file name of `original`
+    ///
+    /// `kind` and `key` make the directory unique per purpose and source.
+    pub(crate) fn write(kind: &str, key: &str, original: &Path, contents: &[u8]) -> Result<Self> {
+        let file_name = original
+            .file_name()
+            .ok_or_else(|| TabdiffError::invalid_input(format!("Invalid source path: {}", original.display())))?;
+        let dir = std::env::temp_dir().join(format!(
+            "tabdiff-{}-{}-{}",
+            kind,
+            std::process::id(),
+            blake3::hash(key.as_bytes()).to_hex()
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let extracted = Self {
+            path: dir.join(file_name),
+            dir,
+        };
+        std::fs::write(&extracted.path, contents)?;
+
+        Ok(extracted)
+    }
+
+    /// Path of the extracted file (keeps the original file name so format detection works)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ExtractedFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
 
 /// Git integration helper
 pub struct GitHelper;
@@ -34,6 +116,27 @@ impl GitHelper {
         }
     }
 
+    /// Extract a file at a git revision into a temporary file
+    pub fn extract_revision(repo_root: &Path, source: &GitSource) -> Result<ExtractedFile> {
+        let output = Command::new("git")
+            .arg("show")
+            .arg(format!("{}:{}", source.rev, source.path))
+            .current_dir(repo_root)
+            .output()
+            .map_err(|e| TabdiffError::git(format!("Failed to run git: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(TabdiffError::git(format!(
+                "Cannot read '{}' at revision '{}': {}",
+                source.path,
+                source.rev,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        ExtractedFile::write("git", &source.label(), Path::new(&source.path), &output.stdout)
+    }
+
     /// Suggest DVC commands for tracking large files
     pub fn suggest_dvc_commands() -> Vec<String> {
         vec![
@@ -61,4 +164,17 @@ mod tests {
         fs::create_dir(temp_dir.path().join(".git")).unwrap();
         assert!(GitHelper::is_git_repo(temp_dir.path()));
     }
+
+    #[test]
+    fn test_git_source_parsing() {
+        assert!(GitSource::parse("data/sales.csv").unwrap().is_none());
+        
+        let source = GitSource::parse("git:HEAD~3:data/sales.csv").unwrap().unwrap();
+        assert_eq!(source.rev, "HEAD~3");
+        assert_eq!(source.path, "data/sales.csv");
+        assert_eq!(source.label(), "git:HEAD~3:data/sales.csv");
+        
+        assert!(GitSource::parse("git:HEAD").is_err());
+        assert!(GitSource::parse("git::data.csv").is_err());
+    }
 }
@@ -40,6 +40,26 @@ pub struct ColumnInfo {
     pub nullable: bool,
 }
 
+/// Order-dependent digest of row hashes, fed one row at a time
+///
+/// Two row sequences digest equally exactly when they hold the same rows in the same order, so
+/// a snapshot's digest lets `diff --append-only` check that another source starts with its rows.
+#[derive(Default)]
+pub struct RowOrderHasher {
+    hasher: Hasher,
+}
+
+impl RowOrderHasher {
+    pub fn update(&mut self, row_hash: &str) {
+        self.hasher.update(row_hash.as_bytes());
+        self.hasher.update(b"\n");
+    }
+
+    pub fn finalize(&self) -> HashValue {
+        self.hasher.finalize().to_hex().to_string()
+    }
+}
+
 /// Hash computer for various data structures
 pub struct HashComputer {
     #[allow(dead_code)] // Used for potential future batching optimizations
@@ -60,14 +80,44 @@ impl HashComputer {
 
     /// Compute hash for multiple values (e.g., a row)
     pub fn hash_values(&self, values: &[String]) -> HashValue {
+        Self::hash_row(values)
+    }
+
+    /// Hash of a row's values, as recorded in snapshots
+    ///
+    /// Each value is prefixed with its byte length instead of being joined with a separator,
+    /// so no value can contain a field boundary: `["a|b", "c"]` and `["a", "b|c"]` differ.
+    pub fn hash_row(values: &[String]) -> HashValue {
         let mut hasher = Hasher::new();
         for value in values {
+            hasher.update(&(value.len() as u64).to_le_bytes());
             hasher.update(value.as_bytes());
-            hasher.update(b"|"); // Separator to avoid hash collisions
         }
         hasher.finalize().to_hex().to_string()
     }
 
+    /// Order-independent digest of row hashes, equal exactly when the rows are equal as a multiset
+    pub fn content_hash<'a>(&self, row_hashes: impl IntoIterator<Item = &'a str>) -> HashValue {
+        let mut sorted: Vec<&str> = row_hashes.into_iter().collect();
+        sorted.sort_unstable();
+        
+        let mut hasher = Hasher::new();
+        for hash in sorted {
+            hasher.update(hash.as_bytes());
+            hasher.update(b"\n");
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Order-dependent digest of row hashes, see `RowOrderHasher`
+    pub fn row_order_hash<'a>(&self, row_hashes: impl IntoIterator<Item = &'a str>) -> HashValue {
+        let mut hasher = RowOrderHasher::default();
+        for hash in row_hashes {
+            hasher.update(hash);
+        }
+        hasher.finalize()
+    }
+
     /// Compute schema hash from column information
     pub fn hash_schema(&self, columns: &[ColumnInfo]) -> Result<SchemaHash> {
         let mut hasher = Hasher::new();
@@ -244,9 +294,6 @@ impl HashComputer {
             compare_collision_rate,
         };
         
-        // Print diagnostics for debugging
-        // hash_quality.print_diagnostics();
-        
         // Create maps from content hash to row indices for tracking which rows changed
         let mut base_content_to_indices: HashMap<&str, Vec<u64>> = HashMap::new();
         for rh in base_hashes {
@@ -353,8 +400,11 @@ impl RowHashComparison {
     }
 }
 
-/// Hash quality metrics for debugging
-#[derive(Debug, Clone)]
+/// Hash quality metrics: how many rows share a hash with another row on each side
+///
+/// Rows with equal hashes can't be told apart, so a high rate makes content-based matching
+/// guess which copies were added, removed or modified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HashQualityMetrics {
     pub total_base_hashes: u64,
     pub unique_base_hashes: u64,
@@ -434,6 +484,50 @@ mod tests {
         assert_ne!(hash1, hash3); // Order matters
     }
 
+    #[test]
+    fn test_field_boundaries_cannot_be_forged() {
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        // These collided when fields were joined with "||" (both read "a||b||c")
+        let first = row(&["a||b", "c"]);
+        let second = row(&["a", "b||c"]);
+        assert_eq!(first.join("||"), second.join("||"));
+        assert_ne!(HashComputer::hash_row(&first), HashComputer::hash_row(&second));
+
+        // ... and these when each field was followed by "|"
+        assert_ne!(HashComputer::hash_row(&row(&["a|", ""])), HashComputer::hash_row(&row(&["a", "|"])));
+        // Trailing empty fields still count
+        assert_ne!(HashComputer::hash_row(&row(&["a"])), HashComputer::hash_row(&row(&["a", ""])));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_row_order_but_not_duplicates() {
+        let computer = HashComputer::new(1000);
+        
+        let hash1 = computer.content_hash(["a", "b", "c"]);
+        let hash2 = computer.content_hash(["c", "a", "b"]);
+        let hash3 = computer.content_hash(["a", "b", "c", "c"]);
+        
+        assert_eq!(hash1, hash2);
+        assert_ne!(hash1, hash3);
+    }
+
+    #[test]
+    fn test_row_order_hash_depends_on_row_order() {
+        let computer = HashComputer::new(1000);
+
+        let hash1 = computer.row_order_hash(["a", "b", "c"]);
+        let hash2 = computer.row_order_hash(["c", "a", "b"]);
+        assert_ne!(hash1, hash2);
+
+        // Digesting a prefix incrementally gives the hash of that prefix alone
+        let mut hasher = RowOrderHasher::default();
+        hasher.update("a");
+        hasher.update("b");
+        assert_eq!(hasher.finalize(), computer.row_order_hash(["a", "b"]));
+        assert_ne!(computer.row_order_hash(["ab"]), computer.row_order_hash(["a", "b"]));
+    }
+
     #[test]
     fn test_hash_rows() {
         let computer = HashComputer::new(1000);
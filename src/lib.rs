@@ -18,13 +18,37 @@ pub mod progress;
 pub mod git;
 pub mod change_detection;
 pub mod sql;
+pub mod writer;
+pub mod watch;
+pub mod encoding;
+pub mod store;
+pub mod schema_export;
+pub mod chain_graph;
+pub mod assertions;
+pub mod checkpoint;
+pub mod consistency;
+pub mod compatibility;
+pub mod cancel;
+pub mod signing;
+pub mod timings;
 
 pub use error::{Result, TabdiffError};
 pub use workspace::TabdiffWorkspace;
 pub use resolver::SnapshotResolver;
 
 /// Current format version for tabdiff files
-pub const FORMAT_VERSION: &str = "1.0.0";
+///
+/// The major version changes whenever row hashes are computed differently. Version 2
+/// length-prefixes each field instead of joining fields with a separator, and also covers the
+/// switch from fixed `{:.15}`/`{:.10}` float formatting to the shortest round-trip
+/// representation (rounded by `--float-precision` when given); version 3 renders
+/// dates, times and timestamps as ISO-8601 instead of their raw DuckDB representation.
+pub const FORMAT_VERSION: &str = "3.0.0";
+
+/// Major part of a format version; row hashes are only comparable within one
+pub fn hash_format(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
 
 /// Default batch size for processing rows
 pub const DEFAULT_BATCH_SIZE: usize = 10000;
@@ -2,8 +2,9 @@
 
 use clap::Parser;
 use tabdiff::cli::Cli;
-use tabdiff::commands::execute_command;
+use tabdiff::commands::{execute_command, EngineSettings};
 use tabdiff::duckdb_config;
+use tabdiff::progress;
 
 fn main() {
     // Initialize logging
@@ -19,15 +20,42 @@ fn main() {
         log::set_max_level(log::LevelFilter::Debug);
     }
 
+    // Flags override the environment; every data processor the command opens uses the result
+    let engine = match engine_settings(&cli) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
     // Initialize and validate DuckDB configuration
-    if let Err(e) = duckdb_config::init_duckdb() {
+    if let Err(e) = duckdb_config::init_duckdb(&engine.duckdb) {
         eprintln!("{}", e);
         std::process::exit(1);
     }
 
+    // First Ctrl-C cancels the running operation cleanly, a second one exits immediately
+    tabdiff::cancel::install_interrupt_handler();
+
     // Execute the command
-    if let Err(e) = execute_command(cli.command, cli.workspace.as_deref()) {
+    if let Err(e) = execute_command(cli.command, cli.workspace.as_deref(), &engine) {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
 }
+
+/// DuckDB and progress settings from the global flags, falling back to the environment
+fn engine_settings(cli: &Cli) -> tabdiff::Result<EngineSettings> {
+    let mut duckdb = duckdb_config::DuckDbSettings::from_env()?;
+    if let Some(memory_limit) = &cli.duckdb_memory_limit {
+        duckdb.memory_limit = Some(memory_limit.clone());
+    }
+    if let Some(threads) = cli.duckdb_threads {
+        duckdb.threads = Some(threads);
+    }
+    Ok(EngineSettings {
+        duckdb,
+        progress_format: cli.progress_format.unwrap_or_else(progress::ProgressFormat::from_env),
+    })
+}
@@ -1,15 +1,189 @@
 //! Output formatting utilities
 
 use crate::error::Result;
-use crate::hash::RowHashComparison;
+use crate::hash::{ColumnInfo, HashQualityMetrics, RowHashComparison};
+use crate::snapshot::SnapshotMetadata;
 use crate::workspace::WorkspaceStats;
-use crate::change_detection::{ChangeDetectionResult, SchemaChanges, RowChanges};
+use crate::consistency::ConsistencyReport;
+use crate::compatibility::{Compatibility, SchemaCompatibility};
+use crate::change_detection::{CellChange, ChangeDetectionResult, ChangeGroups, CoercionFailure, ColumnMove, SchemaChanges, RowChanges, RollbackOperation};
 use serde_json::Value;
 
+/// Change category selectable with `--only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeCategory {
+    Schema,
+    Rows,
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeCategory {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "schema" => Ok(Self::Schema),
+            "rows" => Ok(Self::Rows),
+            "added" => Ok(Self::Added),
+            "removed" => Ok(Self::Removed),
+            "modified" => Ok(Self::Modified),
+            _ => Err(format!("Invalid change category: {}. Use 'schema', 'rows', 'added', 'removed', or 'modified'", s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schema => "schema",
+            Self::Rows => "rows",
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+        }
+    }
+}
+
+/// Alternative report printed by `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML for CI test-report dashboards
+    Junit,
+    /// One CSV row per change, for spreadsheets
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "junit" => Ok(Self::Junit),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("Invalid report format: {}. Use 'junit' or 'csv'", s)),
+        }
+    }
+}
+
+/// Selection of change categories to display; an empty selection shows everything
+#[derive(Debug, Clone, Copy)]
+pub struct ChangeFilter<'a> {
+    only: &'a [ChangeCategory],
+}
+
+impl<'a> ChangeFilter<'a> {
+    pub fn new(only: &'a [ChangeCategory]) -> Self {
+        Self { only }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.only.is_empty()
+    }
+
+    pub fn includes_schema(&self) -> bool {
+        !self.is_active() || self.only.contains(&ChangeCategory::Schema)
+    }
+
+    pub fn includes_added(&self) -> bool {
+        self.includes_rows_of(ChangeCategory::Added)
+    }
+
+    pub fn includes_removed(&self) -> bool {
+        self.includes_rows_of(ChangeCategory::Removed)
+    }
+
+    pub fn includes_modified(&self) -> bool {
+        self.includes_rows_of(ChangeCategory::Modified)
+    }
+
+    pub fn includes_any_rows(&self) -> bool {
+        self.includes_added() || self.includes_removed() || self.includes_modified()
+    }
+
+    fn includes_rows_of(&self, category: ChangeCategory) -> bool {
+        !self.is_active() || self.only.contains(&ChangeCategory::Rows) || self.only.contains(&category)
+    }
+
+    /// Comma-separated names of the selected categories
+    pub fn describe(&self) -> String {
+        self.only.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Copy of the detection result with unselected categories emptied
+    pub fn apply(&self, changes: &ChangeDetectionResult) -> ChangeDetectionResult {
+        let mut filtered = changes.clone();
+        if !self.includes_schema() {
+            filtered.schema_changes = SchemaChanges {
+                column_order: None,
+                columns_added: Vec::new(),
+                columns_removed: Vec::new(),
+                columns_renamed: Vec::new(),
+                type_changes: Vec::new(),
+            };
+        }
+        if !self.includes_added() {
+            filtered.row_changes.added.clear();
+        }
+        if !self.includes_removed() {
+            filtered.row_changes.removed.clear();
+        }
+        if !self.includes_modified() {
+            filtered.row_changes.modified.clear();
+        }
+        filtered
+    }
+}
+
+/// Names changed rows by the value of a display column (`diff --label-column`), e.g. `id=42`
+///
+/// Rows without a label, and every row when no column is set, are named by their index.
+#[derive(Debug, Clone, Default)]
+pub struct RowLabels {
+    column: Option<String>,
+    modified: std::collections::HashMap<u64, String>,
+}
+
+impl RowLabels {
+    pub fn new(column: &str) -> Self {
+        Self { column: Some(column.to_string()), modified: std::collections::HashMap::new() }
+    }
+
+    /// Record the label of the modified row reported as `row_index` from its current values
+    pub fn with_modified_row(mut self, row_index: u64, schema: &[ColumnInfo], row: &[String]) -> Self {
+        let Some(column) = &self.column else {
+            return self;
+        };
+        let value = schema.iter().position(|col| &col.name == column).and_then(|i| row.get(i));
+        if let Some(value) = value {
+            let label = format!("{}={}", column, value);
+            self.modified.insert(row_index, label);
+        }
+        self
+    }
+
+    /// Label of a modified row, if its current values have the column
+    pub fn of_modified(&self, row_index: u64) -> Option<String> {
+        self.modified.get(&row_index).cloned()
+    }
+
+    /// Label of an added or removed row from its data, if it has the column
+    pub fn of_data(&self, data: &std::collections::HashMap<String, String>) -> Option<String> {
+        let column = self.column.as_ref()?;
+        data.get(column).map(|value| format!("{}={}", column, value))
+    }
+
+    /// Label of a modified row, falling back to its index
+    pub fn modified_or_index(&self, row_index: u64) -> String {
+        self.of_modified(row_index).unwrap_or_else(|| row_index.to_string())
+    }
+}
+
 /// Pretty printer for tabdiff output
 pub struct PrettyPrinter;
 
 impl PrettyPrinter {
+    /// Numeric changes listed by `print_numeric_changes` before the rest are summarized
+    pub const MAX_NUMERIC_CHANGES: usize = 20;
+
+    /// Modified cells listed by `print_change_table` before the rest are summarized
+    pub const MAX_TABLE_ROWS: usize = 20;
+
     /// Print workspace statistics
     pub fn print_workspace_stats(stats: &WorkspaceStats) {
         println!("📊 Tabdiff Workspace Statistics");
@@ -20,23 +194,56 @@ impl PrettyPrinter {
         println!("└─ Diff size: {}", format_bytes(stats.total_diff_size));
     }
 
-    /// Print snapshot list
-    pub fn print_snapshot_list(snapshots: &[String]) {
+    /// Print snapshot list; snapshots whose metadata couldn't be read are listed by name only
+    pub fn print_snapshot_list(snapshots: &[(String, Option<SnapshotMetadata>)]) {
         if snapshots.is_empty() {
             println!("No snapshots found.");
             return;
         }
 
         println!("📸 Available Snapshots:");
-        for (i, snapshot) in snapshots.iter().enumerate() {
-            let prefix = if i == snapshots.len() - 1 { "└─" } else { "├─" };
-            println!("{} {}", prefix, snapshot);
+        for (i, (snapshot, metadata)) in snapshots.iter().enumerate() {
+            let last = i == snapshots.len() - 1;
+            let prefix = if last { "└─" } else { "├─" };
+            let Some(metadata) = metadata else {
+                println!("{} {}", prefix, snapshot);
+                continue;
+            };
+
+            match &metadata.description {
+                Some(description) => println!("{} {} - {}", prefix, snapshot, description),
+                None => println!("{} {}", prefix, snapshot),
+            }
+
+            let data = if metadata.metadata_only {
+                "metadata only"
+            } else if metadata.has_full_data {
+                "full data"
+            } else {
+                "hash only"
+            };
+            let mut details = vec![
+                metadata.created.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                format!("{} rows", metadata.row_count),
+                data.to_string(),
+            ];
+            if let Some(parent) = &metadata.parent_snapshot {
+                details.push(format!("parent {}", parent));
+            }
+            if let Some(archive_size) = metadata.archive_size {
+                details.push(format!("archive {}", format_bytes(archive_size)));
+            }
+            let continuation = if last { "  " } else { "│ " };
+            println!("{}   {}", continuation, details.join(" · "));
         }
     }
 
     /// Print snapshot metadata
     pub fn print_snapshot_metadata(metadata: &Value, detailed: bool) {
         println!("📸 Snapshot: {}", metadata.get("name").unwrap_or(&Value::Null));
+        if let Some(description) = metadata.get("description").and_then(|v| v.as_str()) {
+            println!("├─ Description: {}", description);
+        }
         println!("├─ Created: {}", metadata.get("created").unwrap_or(&Value::Null));
         println!("├─ Source: {}", metadata.get("source").unwrap_or(&Value::Null));
         println!("├─ Rows: {}", metadata.get("row_count").unwrap_or(&Value::Null));
@@ -77,6 +284,12 @@ impl PrettyPrinter {
                                 .collect::<Vec<_>>()
                                 .join(", "));
             }
+            if let Some(moves) = diff.get("column_moves").and_then(|v| v.as_array()) {
+                println!("│  └─ Columns moved: {}", moves.len());
+                for column_move in moves.iter().filter_map(|v| serde_json::from_value::<ColumnMove>(v.clone()).ok()) {
+                    println!("│     └─ {}", Self::format_column_move(&column_move));
+                }
+            }
         } else {
             println!("├─ ✅ Schema: unchanged");
         }
@@ -95,6 +308,15 @@ impl PrettyPrinter {
             println!("├─ ✅ Rows: unchanged");
         }
         
+        if let Some(unchanged) = diff.get("row_changes").and_then(|r| r.get("unchanged")).and_then(|v| v.as_u64()) {
+            let included = diff.get("unchanged").and_then(|v| v.as_array()).map(|rows| rows.len()).unwrap_or(0);
+            println!("├─ Unchanged rows: {} ({} included)", unchanged, included);
+        }
+        
+        if let Some(context) = diff.get("context").and_then(|v| v.as_array()) {
+            println!("├─ Context rows: {}", context.len());
+        }
+        
         println!("└─ Total rows: {}", diff.get("row_count").unwrap_or(&Value::Null));
     }
 
@@ -188,12 +410,202 @@ impl PrettyPrinter {
         }
     }
 
+    /// Print the operations of a partial rollback, one line per operation
+    pub fn print_rollback_operations(operations: &[RollbackOperation], columns: &[ColumnInfo]) {
+        let format_row = |data: &std::collections::HashMap<String, String>| {
+            columns.iter()
+                .map(|col| format!("{}: '{}'", col.name, data.get(&col.name).map(|v| v.as_str()).unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        println!("📋 Rollback operations: {}", operations.len());
+        for (i, operation) in operations.iter().enumerate() {
+            let branch = if i + 1 == operations.len() { "└─" } else { "├─" };
+            match operation {
+                RollbackOperation::RestoreCell { row_index, column, current, value } => {
+                    println!("{} Row {}: {} '{}' → '{}'", branch, row_index, column, current, value);
+                }
+                RollbackOperation::RemoveRow { row_index, data } => {
+                    println!("{} Remove row {}: {}", branch, row_index, format_row(data));
+                }
+                RollbackOperation::RestoreRow { row_index, data } => {
+                    println!("{} Restore snapshot row {}: {}", branch, row_index, format_row(data));
+                }
+            }
+        }
+    }
+
+    /// Print change detection results restricted to the selected categories
+    /// The summary header always reports the complete counts
+    pub fn print_filtered_status_results(
+        changes: &ChangeDetectionResult,
+        filter: ChangeFilter,
+        quiet: bool,
+    ) {
+        if !filter.is_active() {
+            Self::print_comprehensive_status_results(changes, quiet);
+        } else if quiet {
+            Self::print_comprehensive_status_results(&filter.apply(changes), true);
+        } else {
+            println!("📊 tabdiff status (only: {})", filter.describe());
+            println!("├─ Summary: {}", changes.summary());
+            Self::print_filtered_changes(&filter.apply(changes), filter);
+        }
+
+        if !quiet {
+            Self::print_coercion_failures(&changes.coercion_failures);
+            if let Some(hash_quality) = &changes.hash_quality {
+                Self::print_hash_quality_warning(hash_quality);
+            }
+        }
+    }
+
+    /// Git-style one-line summary, e.g. "1 file changed, +3 rows, -1 rows, ~2 modified, 0 schema changes"
+    pub fn format_diff_stat(changes: &ChangeDetectionResult) -> String {
+        let files_changed = usize::from(changes.row_changes.has_changes() || changes.schema_changes.has_changes());
+        format!(
+            "{} {} changed, +{} rows, -{} rows, ~{} modified, {} schema changes",
+            files_changed,
+            if files_changed == 1 { "file" } else { "files" },
+            changes.row_changes.added.len(),
+            changes.row_changes.removed.len(),
+            changes.row_changes.modified.len(),
+            changes.schema_changes.change_count(),
+        )
+    }
+
+    /// Print one `--stat` line per step of a chain range, from the deltas cached in the snapshots
+    pub fn print_chain_steps(range: &[SnapshotMetadata]) {
+        let step_count = range.len().saturating_sub(1);
+        println!("🔗 {} step(s) from '{}':", step_count, range.first().map_or("", |metadata| metadata.name.as_str()));
+        for (i, step) in range.windows(2).enumerate() {
+            let summary = match &step[1].delta_from_parent {
+                Some(delta) => Self::format_diff_stat(&delta.changes),
+                None => "no cached delta".to_string(),
+            };
+            let branch = if i + 1 == step_count { "└─" } else { "├─" };
+            println!("{} {} → {}: {}", branch, step[0].name, step[1].name, summary);
+        }
+        println!();
+    }
+
+    /// Print the `--stat` summary line
+    pub fn print_diff_stat(changes: &ChangeDetectionResult) {
+        println!("{}", Self::format_diff_stat(changes));
+    }
+
+    /// Print the findings of `status --against-self`
+    pub fn print_consistency_report(source: &str, report: &ConsistencyReport) {
+        println!("🔎 Consistency of '{}' ({} rows, {} columns)", source, report.row_count, report.column_count);
+        if !report.has_issues() {
+            println!("└─ ✅ No inconsistencies found");
+            return;
+        }
+
+        if report.mixed_type_columns.is_empty() {
+            println!("├─ ✅ Column types: consistent");
+        } else {
+            println!("├─ ❌ Mixed-type columns: {}", report.mixed_type_columns.len());
+            for column in &report.mixed_type_columns {
+                println!("│  {}: {} of {} values are {}, not: {}",
+                    column.column,
+                    column.mismatched,
+                    column.matching + column.mismatched,
+                    column.inferred_type,
+                    column.examples.iter().map(|value| format!("'{}'", value)).collect::<Vec<_>>().join(", "));
+            }
+        }
+
+        if report.field_count_anomalies.is_empty() {
+            println!("├─ ✅ Field counts: consistent");
+        } else {
+            println!("├─ ❌ Rows with a deviating field count: {}", report.field_count_anomalies.len());
+            for anomaly in report.field_count_anomalies.iter().take(10) {
+                println!("│  line {}: {} fields, expected {}", anomaly.line, anomaly.found, anomaly.expected);
+            }
+            if report.field_count_anomalies.len() > 10 {
+                println!("│  ... and {} more", report.field_count_anomalies.len() - 10);
+            }
+        }
+
+        if report.null_columns.is_empty() {
+            println!("└─ ✅ Empty columns: none");
+        } else {
+            println!("└─ ❌ Columns without any values: {}", report.null_columns.join(", "));
+        }
+    }
+
+    /// Warn that duplicate row hashes make content-based row matching unreliable
+    pub fn print_hash_quality_warning(hash_quality: &HashQualityMetrics) {
+        println!();
+        println!(
+            "⚠️  Warning: many rows share a hash ({:.1}% of baseline rows, {:.1}% of current rows)",
+            hash_quality.base_collision_rate * 100.0,
+            hash_quality.compare_collision_rate * 100.0
+        );
+        println!("   Identical rows can't be told apart, so reported additions, removals and modifications may be unreliable.");
+        println!("   Use --key <cols> to match rows by a unique key instead.");
+    }
+
+    /// Warn about values in `--coerce` columns that did not parse and were compared as-is
+    pub fn print_coercion_failures(failures: &[CoercionFailure]) {
+        if failures.is_empty() {
+            return;
+        }
+        println!();
+        println!("⚠️  Warning: {} value(s) could not be coerced and were compared as-is:", failures.len());
+        for failure in failures {
+            println!("   {}: '{}' is not a valid {}", failure.column, failure.value, failure.coerce_to);
+        }
+    }
+
+    /// Print the compatibility verdict of each schema change and the overall level
+    pub fn print_schema_compatibility(compatibility: &SchemaCompatibility) {
+        let icon = |level: Compatibility| match level {
+            Compatibility::Full | Compatibility::Backward | Compatibility::Forward => "✅",
+            Compatibility::Breaking => "❌",
+        };
+        println!();
+        println!("{} Schema compatibility: {}", icon(compatibility.level), compatibility.level.as_str());
+        for verdict in &compatibility.changes {
+            let subject = verdict.column.as_deref().map_or_else(String::new, |column| format!(" '{}'", column));
+            println!("   {} {}{}: {} ({})", icon(verdict.compatibility), verdict.kind.replace('_', " "), subject, verdict.compatibility.as_str(), verdict.reason);
+        }
+    }
+
+    /// Print the details of the selected change categories
+    pub fn print_filtered_changes(filtered: &ChangeDetectionResult, filter: ChangeFilter) {
+        if filter.includes_schema() {
+            if filtered.schema_changes.has_changes() {
+                println!("├─ ❌ Schema: CHANGED");
+                Self::print_schema_changes(&filtered.schema_changes, "│  ");
+            } else {
+                println!("├─ ✅ Schema: unchanged");
+            }
+        }
+
+        if filter.includes_any_rows() {
+            if filtered.row_changes.has_changes() {
+                println!("└─ ❌ Rows shown: {}", filtered.row_changes.total_changes());
+                Self::print_row_changes(&filtered.row_changes, "   ");
+            } else {
+                println!("└─ ✅ Rows: no matching changes");
+            }
+        }
+    }
+
     /// Print schema changes details
     fn print_schema_changes(schema_changes: &SchemaChanges, prefix: &str) {
         if let Some(order_change) = &schema_changes.column_order {
             println!("{}├─ Column order changed", prefix);
             println!("{}│  ├─ Before: [{}]", prefix, order_change.before.join(", "));
-            println!("{}│  └─ After:  [{}]", prefix, order_change.after.join(", "));
+            println!("{}│  ├─ After:  [{}]", prefix, order_change.after.join(", "));
+            let moves = order_change.moves();
+            println!("{}│  └─ Moved: {}", prefix, moves.len());
+            for column_move in &moves {
+                println!("{}│     └─ {}", prefix, Self::format_column_move(column_move));
+            }
         }
         
         if !schema_changes.columns_added.is_empty() {
@@ -210,6 +622,16 @@ impl PrettyPrinter {
             }
         }
         
+        if !schema_changes.columns_renamed.is_empty() {
+            println!("{}├─ Columns renamed: {}", prefix, schema_changes.columns_renamed.len());
+            for rename in &schema_changes.columns_renamed {
+                match rename.confidence {
+                    Some(confidence) => println!("{}│  └─ {} → {} ({:.0}% match)", prefix, rename.from, rename.to, confidence * 100.0),
+                    None => println!("{}│  └─ {} → {}", prefix, rename.from, rename.to),
+                }
+            }
+        }
+        
         if !schema_changes.type_changes.is_empty() {
             println!("{}└─ Type changes: {}", prefix, schema_changes.type_changes.len());
             for type_change in &schema_changes.type_changes {
@@ -218,6 +640,165 @@ impl PrettyPrinter {
         }
     }
 
+    /// Render a column move as `price: position 2 → 0`
+    pub fn format_column_move(column_move: &ColumnMove) -> String {
+        format!("{}: position {} → {}", column_move.column, column_move.from, column_move.to)
+    }
+
+    /// Render a cell change as `'before' → 'after'`
+    ///
+    /// When the values differ only in whitespace, or contain control or zero-width characters,
+    /// spaces are shown as `␣` and other invisible characters as `[U+XXXX]` so the change can be seen.
+    ///
+    /// Changes enriched by `--numeric-delta` render as `1.50 → 1.75 (+0.25, +16.7%)` instead.
+    pub fn format_cell_change(change: &CellChange) -> String {
+        if let Some(delta) = change.delta {
+            let pct = change.pct_change.map(|pct| format!(", {:+.1}%", pct)).unwrap_or_default();
+            return format!("{} → {} ({:+.*}{})", change.before, change.after, change.delta_decimals(), delta, pct);
+        }
+        let has_invisible = [&change.before, &change.after]
+            .iter()
+            .any(|value| value.chars().any(is_invisible_char));
+        let visible = |value: &str| value.chars().filter(|c| !c.is_whitespace() && !is_invisible_char(*c)).collect::<String>();
+        let whitespace_only = visible(&change.before) == visible(&change.after);
+
+        if !has_invisible && !whitespace_only {
+            return format!("'{}' → '{}'", change.before, change.after);
+        }
+        let note = match (whitespace_only, has_invisible) {
+            (true, false) => "whitespace only",
+            (true, true) => "invisible characters only",
+            (false, _) => "contains invisible characters",
+        };
+        format!("'{}' → '{}' ({})", reveal_invisible(&change.before), reveal_invisible(&change.after), note)
+    }
+
+    /// Print the numeric cell changes of modified rows with their delta (`diff --numeric-delta`)
+    pub fn print_numeric_changes(row_changes: &RowChanges, labels: &RowLabels) {
+        let mut numeric = row_changes.modified.iter().flat_map(|modification| {
+            let mut columns: Vec<(&String, &CellChange)> = modification.changes.iter()
+                .filter(|(_, change)| change.delta.is_some())
+                .collect();
+            columns.sort_by(|a, b| a.0.cmp(b.0));
+            columns.into_iter().map(move |(column, change)| (labels.modified_or_index(modification.row_index), column, change))
+        }).peekable();
+        if numeric.peek().is_none() {
+            return;
+        }
+
+        println!("\n📈 Numeric changes:");
+        for (row, column, change) in numeric.by_ref().take(Self::MAX_NUMERIC_CHANGES) {
+            println!("   Row {} {}: {}", row, column, Self::format_cell_change(change));
+        }
+        let remaining = numeric.count();
+        if remaining > 0 {
+            println!("   ... and {} more", remaining);
+        }
+    }
+
+    /// Print the modified cells as a table fitted to `width` characters
+    pub fn print_change_table(row_changes: &RowChanges, labels: &RowLabels, width: usize) {
+        if let Some(table) = Self::render_change_table(row_changes, labels, width) {
+            println!("\n📋 Changed cells:");
+            print!("{}", table);
+        }
+    }
+
+    /// Aligned `Row`, `Column`, `Before`, `After` table of the modified cells, at most `width`
+    /// characters wide (None when no row was modified); rows are named by their label
+    ///
+    /// Column names get up to a quarter of the width and the values share the rest; longer
+    /// text is cut with `…` instead of wrapping, so every line keeps its alignment.
+    pub fn render_change_table(row_changes: &RowChanges, labels: &RowLabels, width: usize) -> Option<String> {
+        const INDENT: &str = "   ";
+        const GAP: &str = "  ";
+        const MIN_WIDTH: usize = 6;
+
+        let cells: Vec<(String, &String, &CellChange)> = row_changes.modified.iter().flat_map(|modification| {
+            let mut columns: Vec<(&String, &CellChange)> = modification.changes.iter().collect();
+            columns.sort_by(|a, b| a.0.cmp(b.0));
+            columns.into_iter().map(move |(column, change)| (labels.modified_or_index(modification.row_index), column, change))
+        }).collect();
+        if cells.is_empty() {
+            return None;
+        }
+        let shown = &cells[..cells.len().min(Self::MAX_TABLE_ROWS)];
+
+        fn widest<'a>(texts: impl Iterator<Item = &'a str>) -> usize {
+            texts.map(|text| text.chars().count()).max().unwrap_or(0)
+        }
+        let row_width = widest(shown.iter().map(|(row, _, _)| row.as_str())).max("Row".len());
+        let available = width.saturating_sub(INDENT.len() + row_width + 3 * GAP.len());
+        let column_width = widest(shown.iter().map(|(_, column, _)| column.as_str()))
+            .clamp(MIN_WIDTH, (available / 4).max(MIN_WIDTH));
+        let value_width = (available.saturating_sub(column_width) / 2).max(MIN_WIDTH);
+        let before_width = widest(shown.iter().map(|(_, _, change)| change.before.as_str())).clamp(MIN_WIDTH, value_width);
+
+        let line = |row: &str, column: &str, before: &str, after: &str| {
+            let line = format!(
+                "{}{:<row_width$}{GAP}{:<column_width$}{GAP}{:<before_width$}{GAP}{}",
+                INDENT,
+                row,
+                truncate_cell(column, column_width),
+                truncate_cell(before, before_width),
+                truncate_cell(after, value_width),
+            );
+            format!("{}\n", line.trim_end())
+        };
+        let mut table = line("Row", "Column", "Before", "After");
+        for (row, column, change) in shown {
+            table.push_str(&line(row, column, &change.before, &change.after));
+        }
+        if cells.len() > shown.len() {
+            table.push_str(&format!("{}... and {} more changed cells\n", INDENT, cells.len() - shown.len()));
+        }
+        Some(table)
+    }
+
+    /// Print the changed-row counts of each `--group-by` value, most changed first
+    pub fn print_change_groups(groups: &ChangeGroups) {
+        println!("\n📊 Changes by {}:", groups.column);
+        if groups.groups.is_empty() {
+            println!("   No rows changed");
+            return;
+        }
+
+        let shown = &groups.groups[..groups.groups.len().min(Self::MAX_TABLE_ROWS)];
+        let label = |value: &str| if value.is_empty() { "(empty)".to_string() } else { value.to_string() };
+        let label_width = shown.iter().map(|group| label(&group.value).chars().count()).max().unwrap_or(0);
+        for group in shown {
+            println!(
+                "   {:<label_width$}  {} rows ({} modified, {} added, {} removed)",
+                label(&group.value),
+                group.total(),
+                group.modified,
+                group.added,
+                group.removed,
+            );
+        }
+        if groups.groups.len() > shown.len() {
+            println!("   ... and {} more groups", groups.groups.len() - shown.len());
+        }
+    }
+
+    /// Print how many modified rows touched each column, most changed first
+    pub fn print_columns_summary(row_changes: &RowChanges) {
+        let counts = row_changes.column_change_counts();
+        if counts.is_empty() {
+            return;
+        }
+
+        let shown = &counts[..counts.len().min(Self::MAX_TABLE_ROWS)];
+        let listed = shown.iter()
+            .map(|(column, count)| format!("{}: {} {}", column, count, if *count == 1 { "change" } else { "changes" }))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("\n🧮 Changes by column: {}", listed);
+        if counts.len() > shown.len() {
+            println!("   ... and {} more columns", counts.len() - shown.len());
+        }
+    }
+
     /// Print row changes details
     fn print_row_changes(row_changes: &RowChanges, prefix: &str) {
         if !row_changes.modified.is_empty() {
@@ -231,7 +812,7 @@ impl PrettyPrinter {
                     let is_last_change = j == std::cmp::min(1, modification.changes.len() - 1);
                     let change_prefix = if is_last { "   " } else { "│  " };
                     let change_marker = if is_last_change { "└─" } else { "├─" };
-                    println!("{}{}   {} {}: '{}' → '{}'", prefix, change_prefix, change_marker, col, change.before, change.after);
+                    println!("{}{}   {} {}: {}", prefix, change_prefix, change_marker, col, Self::format_cell_change(change));
                 }
                 
                 if modification.changes.len() > 2 {
@@ -319,6 +900,305 @@ impl JsonFormatter {
     ) -> Result<String> {
         Ok(serde_json::to_string_pretty(changes)?)
     }
+
+    /// Format change detection results restricted to the selected categories as JSON
+    /// A `summary` object keeps the complete counts
+    pub fn format_filtered_status_results(
+        changes: &ChangeDetectionResult,
+        filter: ChangeFilter,
+    ) -> Result<String> {
+        if !filter.is_active() {
+            return Self::format_comprehensive_status_results(changes);
+        }
+
+        let mut json = serde_json::to_value(filter.apply(changes))?;
+        if let Some(object) = json.as_object_mut() {
+            if !filter.includes_schema() {
+                object.remove("schema_changes");
+            }
+            if !filter.includes_any_rows() {
+                object.remove("row_changes");
+            }
+            object.insert("summary".to_string(), Self::change_summary(changes));
+        }
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+
+    /// Modified-row count of each column, as `columns_summary` in `diff` and `status` JSON
+    pub fn columns_summary(row_changes: &RowChanges) -> Value {
+        Value::Object(row_changes.column_change_counts()
+            .into_iter()
+            .map(|(column, count)| (column, Value::from(count)))
+            .collect())
+    }
+
+    /// Complete change counts, independent of any `--only` filter
+    pub fn change_summary(changes: &ChangeDetectionResult) -> Value {
+        serde_json::json!({
+            "schema_changed": changes.schema_changes.has_changes(),
+            "added": changes.row_changes.added.len(),
+            "removed": changes.row_changes.removed.len(),
+            "modified": changes.row_changes.modified.len()
+        })
+    }
+}
+
+/// One JUnit test case; failing cases carry a message
+struct JUnitCase {
+    classname: &'static str,
+    name: String,
+    failure: Option<String>,
+}
+
+/// JUnit XML report in which every detected change is a failing test case
+///
+/// Rows are listed up to `MAX_ROW_CASES` per category; the rest are summarized in one more
+/// failing case. A comparison without changes yields a single passing case.
+pub struct JUnitFormatter;
+
+impl JUnitFormatter {
+    pub const MAX_ROW_CASES: usize = 100;
+
+    /// Report `changes` as a test suite called `suite`
+    pub fn format(suite: &str, changes: &ChangeDetectionResult) -> String {
+        let cases = Self::cases(changes);
+        let failures = cases.iter().filter(|case| case.failure.is_some()).count();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<testsuites name=\"tabdiff\" tests=\"{}\" failures=\"{}\">\n", cases.len(), failures));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            xml_escape(suite),
+            cases.len(),
+            failures
+        ));
+        for case in &cases {
+            let open = format!("    <testcase classname=\"{}\" name=\"{}\"", case.classname, xml_escape(&case.name));
+            match &case.failure {
+                Some(message) => {
+                    let kind = case.classname.rsplit('.').next().unwrap_or(case.classname);
+                    xml.push_str(&format!("{}>\n", open));
+                    xml.push_str(&format!("      <failure type=\"{}\" message=\"{}\"/>\n", kind, xml_escape(message)));
+                    xml.push_str("    </testcase>\n");
+                }
+                None => xml.push_str(&format!("{}/>\n", open)),
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+
+    fn cases(changes: &ChangeDetectionResult) -> Vec<JUnitCase> {
+        let failing = |classname: &'static str, name: String, message: String| JUnitCase {
+            classname,
+            name,
+            failure: Some(message),
+        };
+        let mut cases = Vec::new();
+
+        let schema = &changes.schema_changes;
+        if let Some(order) = &schema.column_order {
+            cases.push(failing("schema", "column order".to_string(), format!(
+                "Column order changed: [{}] → [{}]", order.before.join(", "), order.after.join(", ")
+            )));
+        }
+        for column in &schema.columns_added {
+            cases.push(failing("schema", format!("column {}", column.name), format!("Column '{}' added ({})", column.name, column.data_type)));
+        }
+        for column in &schema.columns_removed {
+            cases.push(failing("schema", format!("column {}", column.name), format!("Column '{}' removed ({})", column.name, column.data_type)));
+        }
+        for rename in &schema.columns_renamed {
+            cases.push(failing("schema", format!("column {}", rename.from), format!("Column '{}' renamed to '{}'", rename.from, rename.to)));
+        }
+        for change in &schema.type_changes {
+            cases.push(failing("schema", format!("column {}", change.column), format!(
+                "Column '{}' type changed: {} → {}", change.column, change.from, change.to
+            )));
+        }
+
+        let rows = &changes.row_changes;
+        Self::push_row_cases(&mut cases, "rows.modified", "modified", rows.modified.iter().map(|modification| {
+            let mut cells: Vec<_> = modification.changes.iter().collect();
+            cells.sort_by(|a, b| a.0.cmp(b.0));
+            let message = cells.iter()
+                .map(|(column, change)| format!("{}: '{}' → '{}'", column, change.before, change.after))
+                .collect::<Vec<_>>()
+                .join("; ");
+            (modification.row_index, message)
+        }));
+        Self::push_row_cases(&mut cases, "rows.added", "added", rows.added.iter().map(|addition| {
+            (addition.row_index, format!("Row added: {}", format_row_data(&addition.data)))
+        }));
+        Self::push_row_cases(&mut cases, "rows.removed", "removed", rows.removed.iter().map(|removal| {
+            (removal.row_index, format!("Row removed: {}", format_row_data(&removal.data)))
+        }));
+
+        if cases.is_empty() {
+            cases.push(JUnitCase {
+                classname: "tabdiff",
+                name: "no changes".to_string(),
+                failure: None,
+            });
+        }
+        cases
+    }
+
+    /// One failing case per row, then a single case summarizing rows past the cap
+    fn push_row_cases(
+        cases: &mut Vec<JUnitCase>,
+        classname: &'static str,
+        kind: &str,
+        rows: impl ExactSizeIterator<Item = (u64, String)>,
+    ) {
+        let total = rows.len();
+        for (row_index, message) in rows.take(Self::MAX_ROW_CASES) {
+            cases.push(JUnitCase {
+                classname,
+                name: format!("row {}", row_index),
+                failure: Some(message),
+            });
+        }
+        if total > Self::MAX_ROW_CASES {
+            let more = total - Self::MAX_ROW_CASES;
+            cases.push(JUnitCase {
+                classname,
+                name: format!("{} more {} rows", more, kind),
+                failure: Some(format!("{} more {} rows not listed", more, kind)),
+            });
+        }
+    }
+}
+
+/// Cells of a row as `column='value'`, in column name order
+fn format_row_data(data: &std::collections::HashMap<String, String>) -> String {
+    let mut cells: Vec<_> = data.iter().collect();
+    cells.sort_by(|a, b| a.0.cmp(b.0));
+    cells.iter()
+        .map(|(column, value)| format!("{}='{}'", column, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Escape text for an XML attribute, dropping characters XML 1.0 cannot represent
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\t' => escaped.push_str("&#9;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Prints status results, skipping output identical to the previous print (for watch loops)
+#[derive(Debug)]
+pub struct StatusPrinter {
+    quiet: bool,
+    json: bool,
+    last_status: Option<serde_json::Value>,
+}
+
+impl StatusPrinter {
+    pub fn new(quiet: bool, json: bool) -> Self {
+        Self {
+            quiet,
+            json,
+            last_status: None,
+        }
+    }
+
+    /// Print the status unless it matches the last one printed; returns whether it was printed
+    ///
+    /// `groups` are the `--group-by` counts, added to the JSON and printed after the changes.
+    pub fn print_if_changed(&mut self, changes: &ChangeDetectionResult, filter: ChangeFilter, groups: Option<&ChangeGroups>) -> Result<bool> {
+        let mut status = JsonFormatter::format_filtered_status_results(changes, filter)?;
+        // Compare parsed values: row data comes from hash maps, so key order varies between checks
+        let mut value: serde_json::Value = serde_json::from_str(&status)?;
+        value["columns_summary"] = JsonFormatter::columns_summary(&changes.row_changes);
+        if let Some(groups) = groups {
+            value["groups"] = serde_json::to_value(groups)?;
+        }
+        status = serde_json::to_string_pretty(&value)?;
+        if self.last_status.as_ref() == Some(&value) {
+            return Ok(false);
+        }
+
+        if self.json {
+            println!("{}", status);
+        } else {
+            if self.last_status.is_some() && !self.quiet {
+                println!("\n🔄 Status changed at {}", chrono::Local::now().format("%H:%M:%S"));
+            }
+            PrettyPrinter::print_filtered_status_results(changes, filter, self.quiet);
+            if !self.quiet {
+                PrettyPrinter::print_columns_summary(&changes.row_changes);
+            }
+            if let Some(groups) = groups.filter(|_| !self.quiet) {
+                PrettyPrinter::print_change_groups(groups);
+            }
+        }
+
+        self.last_status = Some(value);
+        Ok(true)
+    }
+
+    /// Forget the last status so the next one is printed even if it is unchanged
+    pub fn reset(&mut self) {
+        self.last_status = None;
+    }
+}
+
+/// Characters that render as nothing (or as a plain space) in a terminal, other than the space itself
+fn is_invisible_char(c: char) -> bool {
+    c != ' ' && (c.is_whitespace() || c.is_control() || matches!(
+        c,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    ))
+}
+
+/// Show spaces as `␣` and other invisible characters as their code point
+fn reveal_invisible(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            ' ' => "␣".to_string(),
+            c if is_invisible_char(c) => format!("[U+{:04X}]", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Width of table output when neither `--pretty-width` nor the terminal gives one
+pub const DEFAULT_OUTPUT_WIDTH: usize = 100;
+
+/// Width to fit table output to: `pretty_width` if given, else the terminal's width
+pub fn output_width(pretty_width: Option<usize>) -> usize {
+    pretty_width
+        .or_else(|| terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| width as usize))
+        .unwrap_or(DEFAULT_OUTPUT_WIDTH)
+}
+
+/// Fit a value on one line of at most `width` characters: control characters become spaces,
+/// and longer values are cut with `…`
+fn truncate_cell(value: &str, width: usize) -> String {
+    let single_line = value.chars().map(|c| if c.is_control() { ' ' } else { c });
+    if value.chars().count() <= width {
+        return single_line.collect();
+    }
+    let mut truncated: String = single_line.take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
 }
 
 /// Format bytes in human-readable format
@@ -352,6 +1232,115 @@ mod tests {
         assert_eq!(format_bytes(1048576), "1.0 MB");
     }
 
+    #[test]
+    fn test_change_table_truncates_long_values_and_aligns_columns() {
+        let mut row_changes = RowChanges::default();
+        row_changes.modified.push(crate::change_detection::RowModification {
+            row_index: 7,
+            changes: std::collections::HashMap::from([("description".to_string(), CellChange::new("x".repeat(100), "y".repeat(100)))]),
+        });
+        row_changes.modified.push(crate::change_detection::RowModification {
+            row_index: 12,
+            changes: std::collections::HashMap::from([("qty".to_string(), CellChange::new("1", "2"))]),
+        });
+
+        let table = PrettyPrinter::render_change_table(&row_changes, &RowLabels::default(), 60).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines, vec![
+            format!("   Row  Column{}Before{}After", " ".repeat(7), " ".repeat(14)),
+            format!("   7    description  {}…  {}…", "x".repeat(17), "y".repeat(17)),
+            format!("   12   qty{}1{}2", " ".repeat(10), " ".repeat(19)),
+        ]);
+        assert!(lines.iter().all(|line| line.chars().count() <= 60));
+
+        assert!(PrettyPrinter::render_change_table(&RowChanges::default(), &RowLabels::default(), 60).is_none());
+        assert_eq!(truncate_cell("two\nlines", 20), "two lines");
+        assert_eq!(truncate_cell("abcdef", 4), "abc…");
+    }
+
+    #[test]
+    fn test_row_labels_fall_back_to_index() {
+        let schema = vec![
+            ColumnInfo { name: "id".to_string(), data_type: "INTEGER".to_string(), nullable: false },
+            ColumnInfo { name: "qty".to_string(), data_type: "INTEGER".to_string(), nullable: false },
+        ];
+        let labels = RowLabels::new("id").with_modified_row(12, &schema, &["42".to_string(), "2".to_string()]);
+        assert_eq!(labels.modified_or_index(12), "id=42");
+        assert_eq!(labels.modified_or_index(7), "7");
+        assert_eq!(labels.of_data(&std::collections::HashMap::from([("id".to_string(), "5".to_string())])).unwrap(), "id=5");
+
+        let missing = RowLabels::new("sku").with_modified_row(12, &schema, &["42".to_string(), "2".to_string()]);
+        assert_eq!(missing.modified_or_index(12), "12");
+        assert!(missing.of_data(&std::collections::HashMap::new()).is_none());
+        assert_eq!(RowLabels::default().modified_or_index(3), "3");
+    }
+
+    #[test]
+    fn test_format_cell_change_reveals_invisible_differences() {
+        let change = CellChange::new;
+
+        assert_eq!(PrettyPrinter::format_cell_change(&change("Apple", "Apple ")), "'Apple' → 'Apple␣' (whitespace only)");
+        assert_eq!(PrettyPrinter::format_cell_change(&change("Apple", "Ap\u{200B}ple")), "'Apple' → 'Ap[U+200B]ple' (invisible characters only)");
+        assert_eq!(PrettyPrinter::format_cell_change(&change("a\tb", "a b c")), "'a[U+0009]b' → 'a␣b␣c' (contains invisible characters)");
+        // Ordinary changes are left as they are, spaces included
+        assert_eq!(PrettyPrinter::format_cell_change(&change("New York", "Boston")), "'New York' → 'Boston'");
+    }
+
+    #[test]
+    fn test_format_cell_change_with_numeric_delta() {
+        let format = |before: &str, after: &str| {
+            let mut change = CellChange::new(before, after);
+            change.add_numeric_delta();
+            PrettyPrinter::format_cell_change(&change)
+        };
+
+        assert_eq!(format("1.50", "1.75"), "1.50 → 1.75 (+0.25, +16.7%)");
+        assert_eq!(format("10", "8"), "10 → 8 (-2, -20.0%)");
+        assert_eq!(format("0", "3"), "0 → 3 (+3)");
+        // Non-numeric changes keep the quoted form
+        assert_eq!(format("New York", "Boston"), "'New York' → 'Boston'");
+    }
+
+    #[test]
+    fn test_junit_report_fails_one_case_per_change() {
+        let clean = JUnitFormatter::format("v1 → v2", &ChangeDetectionResult::default());
+        assert!(clean.contains("tests=\"1\" failures=\"0\""));
+        assert!(clean.contains("<testcase classname=\"tabdiff\" name=\"no changes\"/>"));
+
+        let mut changes = ChangeDetectionResult::default();
+        changes.row_changes.modified.push(crate::change_detection::RowModification {
+            row_index: 1,
+            changes: std::collections::HashMap::from([("name".to_string(), CellChange::new("Bob & co", "<Robert>"))]),
+        });
+        for row_index in 0..JUnitFormatter::MAX_ROW_CASES as u64 + 5 {
+            changes.row_changes.added.push(crate::change_detection::RowAddition {
+                row_index,
+                data: std::collections::HashMap::from([("id".to_string(), row_index.to_string())]),
+            });
+        }
+
+        let report = JUnitFormatter::format("v1 → v2", &changes);
+        let expected_cases = 1 + JUnitFormatter::MAX_ROW_CASES + 1;
+        assert!(report.contains(&format!("tests=\"{}\" failures=\"{}\"", expected_cases, expected_cases)));
+        assert!(report.contains("message=\"name: &apos;Bob &amp; co&apos; → &apos;&lt;Robert&gt;&apos;\""));
+        assert!(report.contains("name=\"5 more added rows\""));
+    }
+
+    #[test]
+    fn test_change_category_parse() {
+        assert_eq!(ChangeCategory::parse("removed"), Ok(ChangeCategory::Removed));
+        assert_eq!(ChangeCategory::parse("Schema"), Ok(ChangeCategory::Schema));
+        assert!(ChangeCategory::parse("everything").is_err());
+    }
+
+    #[test]
+    fn test_format_diff_stat_without_changes() {
+        assert_eq!(
+            PrettyPrinter::format_diff_stat(&ChangeDetectionResult::default()),
+            "0 files changed, +0 rows, -0 rows, ~0 modified, 0 schema changes"
+        );
+    }
+
     #[test]
     fn test_json_formatter() {
         let data = serde_json::json!({"test": "value"});
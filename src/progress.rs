@@ -1,7 +1,67 @@
 //! Progress reporting utilities
 
 use indicatif::{ProgressBar, ProgressStyle};
-use std::time::Duration;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Environment variable selecting the progress output format ("text" or "json")
+pub const PROGRESS_FORMAT_ENV: &str = "TABDIFF_PROGRESS_FORMAT";
+
+/// How progress updates are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressFormat {
+    /// Human-readable spinners and `Processed:` lines
+    #[default]
+    Text,
+    /// Newline-delimited JSON events: {phase, processed, total, rate}
+    Json,
+}
+
+impl ProgressFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid progress format: {}. Use 'text' or 'json'", s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        }
+    }
+
+    /// Read the format from the environment, falling back to text
+    pub fn from_env() -> Self {
+        std::env::var(PROGRESS_FORMAT_ENV)
+            .ok()
+            .and_then(|value| Self::parse(&value).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write a single progress event for the given phase
+    pub fn report(&self, phase: &str, processed: u64, total: u64, rate: f64) {
+        match self {
+            Self::Text => {
+                let percent = if total > 0 { (processed as f64 / total as f64) * 100.0 } else { 100.0 };
+                eprint!("\rProcessed: {}/{} rows ({:.1}%) - {:.0} rows/sec",
+                    processed, total, percent, rate);
+            }
+            Self::Json => {
+                let event = serde_json::json!({
+                    "phase": phase,
+                    "processed": processed,
+                    "total": total,
+                    "rate": rate,
+                });
+                eprintln!("{}", event);
+            }
+        }
+        let _ = std::io::stderr().flush();
+    }
+}
 
 /// Progress reporter for tabdiff operations
 #[derive(Debug)]
@@ -12,21 +72,26 @@ pub struct ProgressReporter {
     pub archive_pb: Option<ProgressBar>,
     estimated_rows: u64,
     show_progress: bool,
+    format: ProgressFormat,
 }
 
 impl ProgressReporter {
     /// Create progress reporter for snapshot creation
-    pub fn new_for_snapshot(estimated_rows: u64) -> Self {
-        // Only create the first progress bar (schema analysis)
-        let schema_pb = create_spinner("Analyzing schema...");
+    pub fn new_for_snapshot(estimated_rows: u64, format: ProgressFormat) -> Self {
+        // Only create the first progress bar (schema analysis); JSON mode never draws spinners
+        let schema_pb = match format {
+            ProgressFormat::Text => Some(create_spinner("Analyzing schema...")),
+            ProgressFormat::Json => None,
+        };
 
         Self {
-            schema_pb: Some(schema_pb),
+            schema_pb,
             rows_pb: None,
             columns_pb: None,
             archive_pb: None,
             estimated_rows,
-            show_progress: true,
+            show_progress: format == ProgressFormat::Text,
+            format,
         }
     }
 
@@ -43,6 +108,7 @@ impl ProgressReporter {
             archive_pb: None,
             estimated_rows: 0,
             show_progress: true,
+            format: ProgressFormat::Text,
         }
     }
 
@@ -55,6 +121,7 @@ impl ProgressReporter {
             archive_pb: None,
             estimated_rows: 0,
             show_progress: false,
+            format: ProgressFormat::Text,
         }
     }
 
@@ -123,6 +190,24 @@ impl ProgressReporter {
         }
     }
 
+    /// Report archive-phase progress, as a spinner message or a JSON event
+    pub fn report_archive(&self, phase: &str, processed: u64, total: u64, message: &str, start_time: Instant) {
+        match self.format {
+            ProgressFormat::Text => {
+                if let Some(pb) = &self.archive_pb {
+                    pb.set_length(total);
+                    pb.set_position(processed);
+                    pb.set_message(message.to_string());
+                }
+            }
+            ProgressFormat::Json => {
+                let elapsed = start_time.elapsed().as_secs_f64();
+                let rate = if elapsed > 0.0 { processed as f64 / elapsed } else { 0.0 };
+                self.format.report(phase, processed, total, rate);
+            }
+        }
+    }
+
     /// Finish archive creation
     pub fn finish_archive(&mut self, message: &str) {
         if let Some(pb) = self.archive_pb.take() {
@@ -191,7 +276,7 @@ mod tests {
 
     #[test]
     fn test_progress_reporter_creation() {
-        let reporter = ProgressReporter::new_for_snapshot(1000);
+        let reporter = ProgressReporter::new_for_snapshot(1000, ProgressFormat::Text);
         assert!(reporter.schema_pb.is_some());
         // These are now created lazily, so they start as None
         assert!(reporter.rows_pb.is_none());
@@ -199,6 +284,13 @@ mod tests {
         assert!(reporter.archive_pb.is_none());
     }
 
+    #[test]
+    fn test_progress_format_parse() {
+        assert_eq!(ProgressFormat::parse("text"), Ok(ProgressFormat::Text));
+        assert_eq!(ProgressFormat::parse("JSON"), Ok(ProgressFormat::Json));
+        assert!(ProgressFormat::parse("xml").is_err());
+    }
+
     #[test]
     fn test_minimal_progress_reporter() {
         let reporter = ProgressReporter::new_minimal();
@@ -6,13 +6,15 @@ use crate::snapshot::SnapshotLoader;
 use chrono::{DateTime, Utc, NaiveDateTime, TimeZone};
 use std::path::{Path, PathBuf};
 
-/// Reference to a snapshot (by name or path)
+/// Reference to a snapshot (by name or path) or to a group of snapshots (by pattern)
 #[derive(Debug, Clone)]
 pub enum SnapshotRef {
     /// Snapshot name (e.g., "v1")
     Name(String),
     /// Direct path to .tabdiff or .json file
     Path(PathBuf),
+    /// Glob over snapshot names (e.g., "nightly_*"): `*` matches any characters, `?` one
+    Pattern(String),
 }
 
 impl SnapshotRef {
@@ -20,12 +22,45 @@ impl SnapshotRef {
         let path = Path::new(&s);
         if path.exists() || s.contains('/') || s.contains('\\') {
             Self::Path(PathBuf::from(s))
+        } else if s.contains(['*', '?']) {
+            Self::Pattern(s)
         } else {
             Self::Name(s)
         }
     }
 }
 
+/// Whether `name` matches the glob `pattern`, where `*` matches any characters and `?` one
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried against, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// Resolves snapshot references to actual file paths
 #[derive(Debug)]
 pub struct SnapshotResolver {
@@ -42,7 +77,50 @@ impl SnapshotResolver {
         match snapshot_ref {
             SnapshotRef::Name(name) => self.resolve_by_name(name),
             SnapshotRef::Path(path) => self.resolve_by_path(path),
+            SnapshotRef::Pattern(pattern) => {
+                let mut matches = self.resolve_pattern(pattern)?;
+                if matches.len() > 1 {
+                    return Err(TabdiffError::invalid_input(format!(
+                        "'{}' matches {} snapshots ({}); name a single snapshot",
+                        pattern,
+                        matches.len(),
+                        matches.iter().map(|resolved| resolved.name.as_str()).collect::<Vec<_>>().join(", ")
+                    )));
+                }
+                Ok(matches.remove(0))
+            }
+        }
+    }
+
+    /// Resolve several references in one call, expanding each pattern to every snapshot it matches
+    ///
+    /// Results follow the order of `snapshot_refs`, with a pattern's matches sorted by name. A
+    /// snapshot referenced more than once is returned once, and a pattern matching nothing is an error.
+    pub fn resolve_many(&self, snapshot_refs: &[SnapshotRef]) -> Result<Vec<ResolvedSnapshot>> {
+        let mut resolved: Vec<ResolvedSnapshot> = Vec::new();
+        for snapshot_ref in snapshot_refs {
+            let batch = match snapshot_ref {
+                SnapshotRef::Pattern(pattern) => self.resolve_pattern(pattern)?,
+                _ => vec![self.resolve(snapshot_ref)?],
+            };
+            for snapshot in batch {
+                if !resolved.iter().any(|seen| seen.json_path == snapshot.json_path) {
+                    resolved.push(snapshot);
+                }
+            }
         }
+        Ok(resolved)
+    }
+
+    /// Every snapshot matching `pattern`, sorted by name; an error when there is none
+    fn resolve_pattern(&self, pattern: &str) -> Result<Vec<ResolvedSnapshot>> {
+        let names = self.list_snapshots_matching(pattern)?;
+        if names.is_empty() {
+            return Err(TabdiffError::SnapshotNotFound {
+                name: format!("No snapshots match '{}'", pattern),
+            });
+        }
+        names.iter().map(|name| self.resolve_by_name(name)).collect()
     }
 
     /// Resolve snapshot by name
@@ -128,6 +206,14 @@ impl SnapshotResolver {
         self.workspace.list_snapshots()
     }
 
+    /// List the snapshots whose name matches the glob `pattern`, sorted by name
+    pub fn list_snapshots_matching(&self, pattern: &str) -> Result<Vec<String>> {
+        Ok(self.list_snapshots()?
+            .into_iter()
+            .filter(|name| glob_matches(pattern, name))
+            .collect())
+    }
+
     /// Find the latest snapshot
     pub fn latest_snapshot(&self) -> Result<Option<String>> {
         self.workspace.latest_snapshot()
@@ -158,6 +244,28 @@ impl SnapshotResolver {
         }
     }
 
+    /// Resolve the snapshot a source is compared against when none is named
+    ///
+    /// A baseline recorded with `set-baseline` takes precedence over the head of the source's
+    /// snapshot chain. Returns None when the source has neither.
+    pub fn resolve_default_for_source(&self, source_path: &str) -> Result<Option<ResolvedSnapshot>> {
+        if let Some(baseline) = self.workspace.baseline_for_source(source_path)? {
+            return match self.resolve_by_name(&baseline) {
+                Ok(resolved) => Ok(Some(resolved)),
+                Err(TabdiffError::SnapshotNotFound { name }) => Err(TabdiffError::workspace(format!(
+                    "Baseline snapshot '{}' no longer exists. Run 'tabdiff set-baseline' again or pass --compare-to",
+                    name
+                ))),
+                Err(e) => Err(e),
+            };
+        }
+        
+        let chain = crate::snapshot::SnapshotChain::build_chain_for_source(&self.workspace, source_path)?;
+        chain.head
+            .map(|head| self.resolve(&SnapshotRef::from_string(head)))
+            .transpose()
+    }
+
     /// Get workspace reference
     pub fn workspace(&self) -> &TabdiffWorkspace {
         &self.workspace
@@ -291,6 +399,24 @@ mod tests {
 
         let ref3 = SnapshotRef::from_string("./file.tabdiff".to_string());
         assert!(matches!(ref3, SnapshotRef::Path(_)));
+
+        // Wildcards make a pattern
+        let ref4 = SnapshotRef::from_string("nightly_*".to_string());
+        assert!(matches!(ref4, SnapshotRef::Pattern(_)));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("nightly_*", "nightly_2024_01"));
+        assert!(glob_matches("nightly_*", "nightly_"));
+        assert!(!glob_matches("nightly_*", "weekly_1"));
+        assert!(glob_matches("v?", "v1"));
+        assert!(!glob_matches("v?", "v10"));
+        assert!(glob_matches("*_01*", "nightly_01_b"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(!glob_matches("a*b*c", "aXbYbZ"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("exact", "exact"));
     }
 
     #[test]
@@ -0,0 +1,194 @@
+//! Export a snapshot's column list as a JSON Schema or a SQL `CREATE TABLE` statement
+//!
+//! Column types are DuckDB type names as recorded in snapshot metadata.
+
+use crate::error::Result;
+use crate::hash::ColumnInfo;
+use crate::sql::quote_identifier;
+use serde_json::{json, Map, Value};
+
+/// Rendering of a snapshot schema for `show --schema-format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    JsonSchema,
+    Ddl,
+}
+
+impl SchemaFormat {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "jsonschema" | "json-schema" => Ok(Self::JsonSchema),
+            "ddl" | "sql" => Ok(Self::Ddl),
+            _ => Err(format!("Invalid schema format: {}. Use 'jsonschema' or 'ddl'", s)),
+        }
+    }
+
+    /// Render `columns` as a schema for the table `name`
+    pub fn render(&self, name: &str, columns: &[ColumnInfo]) -> Result<String> {
+        match self {
+            Self::JsonSchema => Ok(serde_json::to_string_pretty(&json_schema(name, columns))?),
+            Self::Ddl => Ok(create_table_ddl(name, columns)),
+        }
+    }
+}
+
+/// JSON Schema describing one row; nullable columns also accept `null`
+pub fn json_schema(title: &str, columns: &[ColumnInfo]) -> Value {
+    let mut properties = Map::new();
+    for column in columns {
+        let mut property = json_schema_type(&column.data_type);
+        if column.nullable {
+            if let Some(Value::String(base)) = property.get("type").cloned() {
+                property["type"] = json!([base, "null"]);
+            }
+        }
+        properties.insert(column.name.clone(), property);
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": title,
+        "type": "object",
+        "properties": properties,
+        "required": columns.iter().map(|column| column.name.as_str()).collect::<Vec<_>>(),
+        "additionalProperties": false,
+    })
+}
+
+/// `CREATE TABLE` statement with standard SQL types; non-nullable columns are `NOT NULL`
+pub fn create_table_ddl(table: &str, columns: &[ColumnInfo]) -> String {
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let not_null = if column.nullable { "" } else { " NOT NULL" };
+            format!("    {} {}{}", quote_identifier(&column.name), sql_type(&column.data_type), not_null)
+        })
+        .collect();
+    format!("CREATE TABLE {} (\n{}\n);", quote_identifier(table), definitions.join(",\n"))
+}
+
+/// Upper-cased DuckDB type without its parameters, e.g. `DECIMAL(10,2)` -> `DECIMAL`
+fn base_type(data_type: &str) -> String {
+    let upper = data_type.trim().to_uppercase();
+    match upper.find('(') {
+        Some(idx) => upper[..idx].trim().to_string(),
+        None => upper,
+    }
+}
+
+/// JSON Schema type keywords for a DuckDB type
+fn json_schema_type(data_type: &str) -> Value {
+    if let Some(element) = data_type.trim().strip_suffix("[]") {
+        return json!({ "type": "array", "items": json_schema_type(element) });
+    }
+
+    match base_type(data_type).as_str() {
+        "BOOLEAN" | "BOOL" => json!({ "type": "boolean" }),
+        "TINYINT" | "SMALLINT" | "INTEGER" | "INT" | "BIGINT" | "HUGEINT" | "UTINYINT" | "USMALLINT"
+        | "UINTEGER" | "UBIGINT" | "UHUGEINT" | "INT1" | "INT2" | "INT4" | "INT8" => json!({ "type": "integer" }),
+        "FLOAT" | "REAL" | "DOUBLE" | "DECIMAL" | "NUMERIC" => json!({ "type": "number" }),
+        "DATE" => json!({ "type": "string", "format": "date" }),
+        "TIME" => json!({ "type": "string", "format": "time" }),
+        "TIMESTAMP" | "TIMESTAMP WITH TIME ZONE" | "TIMESTAMPTZ" | "TIMESTAMP_S" | "TIMESTAMP_MS" | "TIMESTAMP_NS" | "DATETIME" => {
+            json!({ "type": "string", "format": "date-time" })
+        }
+        "UUID" => json!({ "type": "string", "format": "uuid" }),
+        "STRUCT" | "MAP" => json!({ "type": "object" }),
+        "LIST" => json!({ "type": "array" }),
+        _ => json!({ "type": "string" }),
+    }
+}
+
+/// Portable SQL type for a DuckDB type; nested types fall back to text
+fn sql_type(data_type: &str) -> String {
+    if data_type.trim().ends_with("[]") {
+        return "VARCHAR".to_string();
+    }
+
+    match base_type(data_type).as_str() {
+        "BOOLEAN" | "BOOL" => "BOOLEAN".to_string(),
+        "TINYINT" | "INT1" | "UTINYINT" | "SMALLINT" | "INT2" => "SMALLINT".to_string(),
+        "USMALLINT" | "INTEGER" | "INT" | "INT4" => "INTEGER".to_string(),
+        "UINTEGER" | "BIGINT" | "INT8" => "BIGINT".to_string(),
+        "UBIGINT" => "NUMERIC(20,0)".to_string(),
+        "HUGEINT" | "UHUGEINT" => "NUMERIC(39,0)".to_string(),
+        "FLOAT" | "REAL" => "REAL".to_string(),
+        "DOUBLE" => "DOUBLE PRECISION".to_string(),
+        // Keep precision and scale as recorded
+        "DECIMAL" | "NUMERIC" => data_type.trim().to_uppercase(),
+        "DATE" => "DATE".to_string(),
+        "TIME" => "TIME".to_string(),
+        "TIMESTAMP WITH TIME ZONE" | "TIMESTAMPTZ" => "TIMESTAMP WITH TIME ZONE".to_string(),
+        "TIMESTAMP" | "TIMESTAMP_S" | "TIMESTAMP_MS" | "TIMESTAMP_NS" | "DATETIME" => "TIMESTAMP".to_string(),
+        "UUID" => "CHAR(36)".to_string(),
+        "BLOB" | "BYTEA" | "VARBINARY" => "BLOB".to_string(),
+        _ => "VARCHAR".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mixed_columns() -> Vec<ColumnInfo> {
+        let column = |name: &str, data_type: &str, nullable: bool| ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+        };
+        vec![
+            column("id", "BIGINT", false),
+            column("name", "VARCHAR", true),
+            column("score", "DOUBLE", true),
+            column("price", "DECIMAL(10,2)", true),
+            column("active", "BOOLEAN", true),
+            column("joined", "DATE", true),
+            column("updated", "TIMESTAMP", true),
+            column("tags", "VARCHAR[]", true),
+            column("say \"hi\"", "VARCHAR", true),
+        ]
+    }
+
+    #[test]
+    fn test_json_schema_maps_duckdb_types() {
+        let schema = json_schema("users", &mixed_columns());
+
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["title"], "users");
+        assert_eq!(schema["required"].as_array().unwrap().len(), 9);
+        let properties = &schema["properties"];
+        assert_eq!(properties["id"]["type"], "integer");
+        assert_eq!(properties["name"]["type"], json!(["string", "null"]));
+        assert_eq!(properties["score"]["type"], json!(["number", "null"]));
+        assert_eq!(properties["price"]["type"], json!(["number", "null"]));
+        assert_eq!(properties["active"]["type"], json!(["boolean", "null"]));
+        assert_eq!(properties["joined"]["format"], "date");
+        assert_eq!(properties["updated"]["format"], "date-time");
+        assert_eq!(properties["tags"]["items"]["type"], "string");
+    }
+
+    #[test]
+    fn test_create_table_ddl_is_accepted_by_duckdb() {
+        let ddl = create_table_ddl("users", &mixed_columns());
+
+        assert!(ddl.starts_with("CREATE TABLE \"users\" ("));
+        assert!(ddl.contains("\"id\" BIGINT NOT NULL"));
+        assert!(ddl.contains("\"price\" DECIMAL(10,2)"));
+        assert!(ddl.contains("\"score\" DOUBLE PRECISION"));
+        assert!(ddl.contains("\"say \"\"hi\"\"\" VARCHAR"));
+
+        let connection = duckdb::Connection::open_in_memory().unwrap();
+        connection.execute_batch(&ddl).unwrap();
+        let column_count: i64 = connection
+            .query_row("SELECT COUNT(*) FROM information_schema.columns WHERE table_name = 'users'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(column_count, 9);
+    }
+
+    #[test]
+    fn test_schema_format_parse() {
+        assert_eq!(SchemaFormat::parse("jsonschema").unwrap(), SchemaFormat::JsonSchema);
+        assert_eq!(SchemaFormat::parse("DDL").unwrap(), SchemaFormat::Ddl);
+        assert!(SchemaFormat::parse("xml").is_err());
+    }
+}
@@ -1,14 +1,33 @@
 //! Snapshot creation and management
 
-use crate::archive::ArchiveManager;
+use crate::archive::{ArchiveManager, CompressionLevel, PayloadReference, SpooledEntry};
 use crate::data::{DataInfo, DataProcessor};
 use crate::error::{Result, TabdiffError};
 use crate::hash::{ColumnHash, ColumnInfo, HashComputer, RowHash, SchemaHash};
 use crate::progress::ProgressReporter;
+use crate::store::SnapshotStore;
 use crate::change_detection::ChangeDetectionResult;
+use crate::checkpoint::HashCheckpoint;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Canonical form of `path` recorded as a snapshot's source_path, falling back to the path
+/// as given when it cannot be resolved
+pub fn canonical_source_path(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Path `path` is written to before it replaces an existing file, next to it so the final
+/// rename stays on one filesystem
+pub fn staged_path(path: &Path) -> PathBuf {
+    let mut staged = path.as_os_str().to_owned();
+    staged.push(".partial");
+    PathBuf::from(staged)
+}
 
 /// Snapshot metadata stored in JSON format
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +57,86 @@ pub struct SnapshotMetadata {
     pub source_path: Option<String>,
     #[serde(default)]
     pub source_fingerprint: Option<String>,
+    // Column names were generated by DuckDB because the source had no header row
+    #[serde(default)]
+    pub synthetic_column_names: bool,
+    // Columns rows were sorted by before hashing (empty means natural file order)
+    #[serde(default)]
+    pub order_by: Vec<String>,
+    // Decimal places fractional numbers were rounded to before hashing (None means shortest form)
+    #[serde(default)]
+    pub float_precision: Option<usize>,
+    // Strings read as NULL (empty cells) before hashing, from `--na-values`
+    #[serde(default)]
+    pub na_values: Vec<String>,
+    // Text encoding the source was decoded from (None for snapshots that predate recording it)
+    #[serde(default)]
+    pub encoding: Option<String>,
+    // Order-independent digest of all row hashes (None for snapshots that predate recording it)
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    // Order-dependent digest of the row hashes, which `diff --append-only` checks another
+    // source's leading rows against (None for snapshots that predate recording it)
+    #[serde(default)]
+    pub row_order_hash: Option<String>,
+    // Free-form note on why the snapshot was taken, from `snapshot --message`
+    #[serde(default)]
+    pub description: Option<String>,
+    // JSON Pointer to the array that holds the rows of a nested JSON source (None means the whole file)
+    #[serde(default)]
+    pub json_path: Option<String>,
+    // Only the schema and row count were recorded: there are no row hashes and no archive
+    #[serde(default)]
+    pub metadata_only: bool,
+    // Compression the archive was written with (snapshots that predate recording it used the default)
+    #[serde(default)]
+    pub compression_level: CompressionLevel,
+    // blake3 hash of data.parquet, so identical row data is stored in one archive (None without full data)
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    // Keyed hash over the archive's files from `snapshot --sign-key` (None for unsigned snapshots)
+    #[serde(default)]
+    pub signature: Option<String>,
+    // Fingerprint of the key the signature was made with
+    #[serde(default)]
+    pub signed_by: Option<String>,
+    // Named chain from `snapshot --chain`, which groups snapshots of a dataset wherever its file
+    // lives (None chains by source_path)
+    #[serde(default)]
+    pub chain: Option<String>,
+    // Share of the source's rows kept by `snapshot --sample` (None for complete data)
+    #[serde(default)]
+    pub sample: Option<crate::data::SampleSpec>,
+}
+
+impl SnapshotMetadata {
+    /// Key the snapshot is grouped into a chain by: its named chain, else its canonical source path
+    pub fn chain_key(&self) -> String {
+        if let Some(chain) = &self.chain {
+            return SnapshotChain::named_key(chain);
+        }
+        // Legacy snapshot without source_path, use the original source field
+        self.source_path.clone().unwrap_or_else(|| canonical_source_path(Path::new(&self.source)))
+    }
+
+    /// Whether two snapshots provably hold the same rows, judged from their metadata alone
+    ///
+    /// Both must record a content hash, and were hashed with the same schema and settings.
+    pub fn has_same_content(&self, other: &SnapshotMetadata) -> bool {
+        self.content_hash.is_some()
+            && self.content_hash == other.content_hash
+            && self.schema_hash == other.schema_hash
+            && self.row_count == other.row_count
+            && self.synthetic_column_names == other.synthetic_column_names
+            && self.float_precision == other.float_precision
+            && self.na_values == other.na_values
+            && self.has_hash_format_of(other)
+    }
+
+    /// Whether both snapshots hashed their rows the same way, i.e. share a major format version
+    pub fn has_hash_format_of(&self, other: &SnapshotMetadata) -> bool {
+        crate::hash_format(&self.format_version) == crate::hash_format(&other.format_version)
+    }
 }
 
 /// Information about delta changes from parent snapshot
@@ -49,17 +148,58 @@ pub struct DeltaInfo {
 }
 
 
+/// Files of a snapshot archive before it is written
+struct ArchiveFiles {
+    /// Small entries held in memory
+    files: Vec<(String, Vec<u8>)>,
+    /// The full row data (data.parquet), spooled to disk when `--full-data` is set
+    data: Option<SpooledEntry>,
+}
+
 /// Snapshot creator
 pub struct SnapshotCreator {
     hash_computer: HashComputer,
     progress: ProgressReporter,
+    no_header: bool,
+    order_by: Vec<String>,
+    float_precision: Option<usize>,
+    na_values: Vec<String>,
+    encoding: crate::encoding::SourceEncoding,
+    /// Logical source (source, source_path) when the input file is only a stand-in
+    source_override: Option<(String, String)>,
+    description: Option<String>,
+    json_path: Option<String>,
+    /// Continue hashing from the checkpoint an interrupted run left behind
+    resume: bool,
+    /// Inline SELECT query snapshotted instead of the input path
+    query: Option<String>,
+    /// Table of a `.sql` input snapshotted instead of the file's SELECT query
+    table: Option<crate::sql::SourceTable>,
+    compression_level: CompressionLevel,
+    cancellation: crate::cancel::CancellationToken,
+    /// Parent used instead of the latest snapshot of the source (`Some(None)` for no parent)
+    parent: Option<Option<String>>,
+    /// Key the archive is signed with once written
+    signing_key: Option<crate::signing::SigningKey>,
+    /// Named chain to continue regardless of the input's path
+    chain: Option<String>,
+    /// Share of the rows kept instead of the complete data
+    sample: Option<crate::data::SampleSpec>,
+    /// Data-quality rules the rows must pass before anything is written
+    assertions: Vec<crate::assertions::DataAssertion>,
+    timings: crate::timings::Timings,
+    /// DuckDB resource settings of every data processor the creator opens
+    duckdb_settings: Option<crate::duckdb_config::DuckDbSettings>,
+    progress_format: crate::progress::ProgressFormat,
+    show_progress: bool,
 }
 
 impl SnapshotCreator {
     pub fn new(batch_size: usize, show_progress: bool) -> Self {
         let hash_computer = HashComputer::new(batch_size);
+        let progress_format = crate::progress::ProgressFormat::from_env();
         let progress = if show_progress {
-            ProgressReporter::new_for_snapshot(0) // Will update with actual count
+            ProgressReporter::new_for_snapshot(0, progress_format) // Will update with actual count
         } else {
             ProgressReporter::new_minimal()
         };
@@ -67,6 +207,293 @@ impl SnapshotCreator {
         Self {
             hash_computer,
             progress,
+            no_header: false,
+            order_by: Vec::new(),
+            float_precision: None,
+            na_values: Vec::new(),
+            encoding: crate::encoding::SourceEncoding::default(),
+            source_override: None,
+            description: None,
+            json_path: None,
+            resume: false,
+            query: None,
+            table: None,
+            compression_level: CompressionLevel::default(),
+            cancellation: crate::cancel::CancellationToken::global(),
+            parent: None,
+            signing_key: None,
+            chain: None,
+            sample: None,
+            assertions: Vec::new(),
+            timings: crate::timings::Timings::default(),
+            duckdb_settings: None,
+            progress_format,
+            show_progress,
+        }
+    }
+
+    /// Open data processors with these DuckDB resource settings (default: from the
+    /// TABDIFF_DUCKDB_* environment variables)
+    pub fn with_duckdb_settings(mut self, settings: crate::duckdb_config::DuckDbSettings) -> Self {
+        self.duckdb_settings = Some(settings);
+        self
+    }
+
+    /// Report progress in this format (default: from TABDIFF_PROGRESS_FORMAT)
+    pub fn with_progress_format(mut self, format: crate::progress::ProgressFormat) -> Self {
+        if self.show_progress {
+            self.progress = ProgressReporter::new_for_snapshot(0, format);
+        }
+        self.progress_format = format;
+        self
+    }
+
+    /// Data processor for reading the input, with the creator's resource and progress settings
+    fn new_data_processor(&self) -> Result<DataProcessor> {
+        let settings = match &self.duckdb_settings {
+            Some(settings) => settings.clone(),
+            None => crate::duckdb_config::DuckDbSettings::from_env()?,
+        };
+        Ok(DataProcessor::new_with_settings(crate::DEFAULT_BATCH_SIZE, &settings)?
+            .with_progress_format(self.progress_format)
+            .with_cancellation(self.cancellation.clone()))
+    }
+
+    /// Stop with `TabdiffError::Cancelled` once this token is cancelled, leaving no partial
+    /// archive behind (default: the process-wide token set by Ctrl-C)
+    pub fn with_cancellation(mut self, cancellation: crate::cancel::CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Read CSV/TSV input as header-less, recording the generated column names as synthetic
+    pub fn with_no_header(mut self, no_header: bool) -> Self {
+        self.no_header = no_header;
+        self
+    }
+
+    /// Sort rows by the given columns before hashing so row indices are reproducible
+    pub fn with_order_by(mut self, order_by: Vec<String>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// Round fractional numbers to this many decimal places before hashing
+    pub fn with_float_precision(mut self, float_precision: Option<usize>) -> Self {
+        self.float_precision = float_precision;
+        self
+    }
+
+    /// Read these strings as NULL (empty cells) before hashing
+    pub fn with_na_values(mut self, na_values: Vec<String>) -> Self {
+        self.na_values = na_values;
+        self
+    }
+
+    /// Record the encoding the input was transcoded from (the input itself must already be UTF-8)
+    pub fn with_encoding(mut self, encoding: crate::encoding::SourceEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Read the rows of a JSON input from the array at this JSON Pointer
+    pub fn with_json_path(mut self, json_path: Option<String>) -> Self {
+        self.json_path = json_path;
+        self
+    }
+
+    /// Annotate the snapshot with a note on why it was taken
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Compress the archive at this level (0 stores it uncompressed)
+    pub fn with_compression_level(mut self, compression_level: CompressionLevel) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Sign the archive with this key, recording the signature in the metadata
+    pub fn with_signing_key(mut self, signing_key: Option<crate::signing::SigningKey>) -> Self {
+        self.signing_key = signing_key;
+        self
+    }
+
+    /// Continue the named chain instead of the chain of the input's path, so a dataset keeps
+    /// its history when its file moves
+    pub fn with_chain(mut self, chain: Option<String>) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Keep only a sample of the source's rows (`snapshot --sample`)
+    pub fn with_sample(mut self, sample: Option<crate::data::SampleSpec>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Fail instead of writing the snapshot when the rows violate any of these rules (`snapshot --assert`)
+    pub fn with_assertions(mut self, assertions: Vec<crate::assertions::DataAssertion>) -> Self {
+        self.assertions = assertions;
+        self
+    }
+
+    /// Record how long each phase of snapshot creation takes (`snapshot --timings`)
+    pub fn with_timings(mut self, timings: crate::timings::Timings) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Skip rows already hashed by an interrupted run of the same snapshot
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Chain to `parent` rather than the latest snapshot of the source, so a snapshot that
+    /// replaces an existing one keeps its place in the chain
+    pub fn with_parent(mut self, parent: Option<String>) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Record a logical source instead of the input path, e.g. a file extracted from git
+    ///
+    /// `source_path` is used for chain grouping in place of the canonical input path.
+    pub fn with_source(mut self, source: String, source_path: String) -> Self {
+        self.source_override = Some((source, source_path));
+        self
+    }
+
+    /// Snapshot the result of an inline SELECT query; the input path is then ignored
+    ///
+    /// The query text is recorded as the source, and snapshots of the same query form a chain.
+    pub fn with_query(mut self, query: String) -> Self {
+        let source_path = format!("query:{}", self.hash_computer.hash_value(query.trim()));
+        self.source_override = Some((query.clone(), source_path));
+        self.query = Some(query);
+        self
+    }
+
+    /// Snapshot one table of the `.sql` input instead of its SELECT query
+    ///
+    /// Pair with `with_source` so each table of the source chains on its own.
+    pub fn with_table(mut self, table: crate::sql::SourceTable) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    /// Load the query or input file into `data_processor`
+    fn load_input(&self, data_processor: &mut DataProcessor, input_path: &Path) -> Result<DataInfo> {
+        if let Some(query) = &self.query {
+            return data_processor.load_query(query);
+        }
+        if let Some(table) = &self.table {
+            return data_processor.load_sql_table(input_path, table);
+        }
+        // Only check format for files, not directories (which can contain supported files)
+        if input_path.is_file() && !DataProcessor::is_supported_format(input_path) {
+            return Err(TabdiffError::unsupported_format(input_path));
+        }
+        data_processor.load_file(input_path)
+    }
+
+    /// Fail with every violated `--assert` rule, listed, before rows are hashed or anything is written
+    fn check_assertions(&self, data_processor: &mut DataProcessor) -> Result<()> {
+        if self.assertions.is_empty() {
+            return Ok(());
+        }
+        let violations = data_processor.assertion_violations(&self.assertions)?;
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(TabdiffError::malformed_data(format!(
+            "{} of {} data-quality assertions failed:\n{}",
+            violations.len(),
+            self.assertions.len(),
+            violations.iter().map(|violation| format!("  • {}", violation)).collect::<Vec<_>>().join("\n")
+        )))
+    }
+
+    /// Hash of the raw source: the query text, or the input file's content
+    fn source_hash(&self, input_path: &Path) -> String {
+        match &self.query {
+            Some(query) => self.hash_computer.hash_value(query.trim()),
+            None => self.file_hash(input_path).unwrap_or_else(|| self.hash_computer.hash_value("")),
+        }
+    }
+
+    /// Hash of the input file's bytes, or None for queries and inputs that are not readable files
+    fn file_hash(&self, input_path: &Path) -> Option<String> {
+        if self.query.is_some() {
+            return None;
+        }
+        let content = std::fs::read(input_path).ok()?;
+        Some(blake3::hash(&content).to_hex().to_string())
+    }
+
+    /// Whether `previous` was taken from this source with the same settings and the file still
+    /// has the same content
+    ///
+    /// Every setting `previous` records must match what this creator would record, so an update
+    /// that changes one is written rather than skipped. Queries, directories and remote inputs
+    /// cannot be compared without loading them, so they never count as unchanged.
+    pub fn is_unchanged(&self, previous: &SnapshotMetadata, input_path: &Path, full_data: bool, metadata_only: bool) -> bool {
+        let same_source = previous.source_path.as_deref()
+            .is_none_or(|source_path| source_path == self.chain_source_path(input_path));
+        // Metadata-only snapshots hash no rows, so they record none of the row settings
+        let (order_by, float_precision, na_values) = if metadata_only {
+            (&[][..], None, &[][..])
+        } else {
+            (self.order_by.as_slice(), self.float_precision, self.na_values.as_slice())
+        };
+        let same_settings = previous.synthetic_column_names == self.no_header
+            && previous.order_by == order_by
+            && previous.float_precision == float_precision
+            && previous.na_values == na_values
+            && previous.encoding.as_deref() == Some(self.encoding.as_str())
+            && previous.json_path == self.json_path
+            && previous.metadata_only == metadata_only
+            && previous.has_full_data == (full_data && !metadata_only)
+            && crate::hash_format(&previous.format_version) == crate::hash_format(crate::FORMAT_VERSION)
+            && previous.sample == self.sample;
+        same_source && same_settings && self.file_hash(input_path).is_some_and(|hash| hash == previous.source_hash)
+    }
+
+    /// Checkpoint that row hashing of snapshot `name` records its progress in
+    ///
+    /// The fingerprint covers the source file and every setting that affects row hashes, so a
+    /// checkpoint left by a run over different data or settings is never resumed.
+    fn hash_checkpoint(
+        &self,
+        input_path: &Path,
+        name: &str,
+        workspace: &crate::workspace::TabdiffWorkspace,
+    ) -> HashCheckpoint {
+        let source_path = self.chain_source_path(input_path);
+        let file_metadata = std::fs::metadata(&source_path).or_else(|_| std::fs::metadata(input_path)).ok();
+        let fingerprint = self.hash_computer.hash_value(&format!(
+            "{}|{:?}|{:?}|{}|{:?}|{:?}|{:?}|{}|{:?}|{:?}",
+            source_path,
+            file_metadata.as_ref().map(|metadata| metadata.len()),
+            file_metadata.as_ref().and_then(|metadata| metadata.modified().ok()),
+            self.no_header,
+            self.order_by,
+            self.float_precision,
+            self.na_values,
+            self.encoding.as_str(),
+            self.json_path,
+            self.sample,
+        ));
+        HashCheckpoint::new(workspace.checkpoint_path(name), fingerprint).with_resume(self.resume)
+    }
+
+    /// Canonical path used to group snapshots of the same source into a chain
+    pub fn chain_source_path(&self, input_path: &Path) -> String {
+        match &self.source_override {
+            Some((_, source_path)) => source_path.clone(),
+            None => canonical_source_path(input_path),
         }
     }
 
@@ -92,47 +519,286 @@ impl SnapshotCreator {
         full_data: bool,
         workspace: Option<&crate::workspace::TabdiffWorkspace>,
     ) -> Result<SnapshotMetadata> {
-        // Load data
-        let mut data_processor = DataProcessor::new()?;
-        
-        // Only check format for files, not directories (which can contain supported files)
-        if input_path.is_file() && !DataProcessor::is_supported_format(input_path) {
-            return Err(TabdiffError::invalid_input(format!(
-                "Unsupported file format: {}",
-                input_path.display()
-            )));
+        let (archive_files, mut metadata) = self.build_snapshot(input_path, name, full_data, workspace, true)?;
+        self.write_archive(workspace, name, archive_path, archive_files, &mut metadata, self.compression_level)?;
+
+        // Save JSON metadata
+        let json_content = serde_json::to_string_pretty(&metadata)?;
+        std::fs::write(json_path, json_content)?;
+
+        // Update parent's can_reconstruct_parent flag if we have a delta
+        if let (Some(ws), Some(parent_name)) = (workspace, &metadata.parent_snapshot) {
+            if metadata.delta_from_parent.is_some() {
+                self.update_parent_reconstruct_flag(ws, parent_name)?;
+            }
+        }
+
+        // The snapshot is complete, so there is nothing left to resume
+        if let Some(ws) = workspace {
+            self.hash_checkpoint(input_path, name, ws).remove()?;
+        }
+
+        self.progress.finish_archive("🎉 Snapshot created successfully");
+
+        Ok(metadata)
+    }
+
+    /// Write the archive of snapshot `name` and record its size and signature in `metadata`
+    ///
+    /// Row data already stored by another snapshot's archive is referenced instead of copied;
+    /// otherwise it is streamed from disk. A partial archive is removed if writing fails.
+    fn write_archive(
+        &self,
+        workspace: Option<&crate::workspace::TabdiffWorkspace>,
+        name: &str,
+        archive_path: &Path,
+        mut archive_files: ArchiveFiles,
+        metadata: &mut SnapshotMetadata,
+        compression_level: CompressionLevel,
+    ) -> Result<()> {
+        if let (Some(ws), Some(data_hash)) = (workspace, &metadata.data_hash) {
+            if let Some(holder) = ws.find_payload(data_hash, name)? {
+                if let Some(data) = archive_files.data.take() {
+                    let reference = PayloadReference { snapshot: holder, hash: data_hash.clone() };
+                    archive_files.files.push(ArchiveManager::reference_entry(data.name(), &reference)?);
+                }
+            }
+        }
+
+        // Create compressed archive with integrated progress, streaming the row data from disk
+        let phase = std::time::Instant::now();
+        {
+            let progress_ref = &self.progress;
+            let start_time = std::time::Instant::now();
+            let streams = archive_files.data.iter()
+                .map(|data| data.stream().map(|stream| crate::archive::StreamEntry {
+                    name: stream.name,
+                    size: stream.size,
+                    reader: crate::cancel::CancellableReader::new(stream.reader, self.cancellation.clone()),
+                }))
+                .collect::<Result<Vec<_>>>()?;
+            let written = self.cancellation.check().and_then(|_| ArchiveManager::create_archive_streaming(
+                archive_path, 
+                &archive_files.files,
+                streams,
+                compression_level,
+                Some(&|processed: u64, total: u64, message: &str| {
+                    progress_ref.report_archive("archive", processed, total, message, start_time);
+                })
+            ));
+            // A partial archive would look like a snapshot without metadata, so it is removed
+            if let Err(e) = written {
+                if archive_path.exists() {
+                    std::fs::remove_file(archive_path)?;
+                }
+                return Err(if self.cancellation.is_cancelled() { TabdiffError::Cancelled } else { e });
+            }
+        }
+        self.timings.record("archive write", phase);
+        metadata.archive_size = Some(std::fs::metadata(archive_path)?.len());
+        if let Some(key) = &self.signing_key {
+            metadata.signature = Some(key.sign_archive(archive_path)?);
+            metadata.signed_by = Some(key.fingerprint());
+        }
+        Ok(())
+    }
+
+    /// Record only the schema and row count of the input, writing metadata but no archive
+    ///
+    /// Rows are never hashed, so the snapshot supports schema comparison only. It joins the
+    /// source's chain without a cached delta.
+    pub fn create_metadata_only_snapshot(
+        &mut self,
+        input_path: &Path,
+        name: &str,
+        json_path: &Path,
+        workspace: Option<&crate::workspace::TabdiffWorkspace>,
+    ) -> Result<SnapshotMetadata> {
+        let mut data_processor = self.new_data_processor()?
+            .with_no_header(self.no_header)
+            .with_json_path(self.json_path.clone())
+            .with_sample(self.sample);
+
+        self.progress.finish_schema("📊 Loading schema...");
+        let phase = std::time::Instant::now();
+        let data_info = self.load_input(&mut data_processor, input_path)?;
+        self.timings.record("load", phase);
+        self.check_assertions(&mut data_processor)?;
+        let phase = std::time::Instant::now();
+        let schema_hash = self.hash_computer.hash_schema(&data_info.columns)?;
+        self.timings.record("schema hash", phase);
+
+        let canonical_source_path = self.chain_source_path(input_path);
+        let chain = self.chain_name(workspace, &canonical_source_path)?;
+        let (parent_snapshot, sequence_number) = match workspace {
+            Some(ws) => match self.chain_parent(ws, &canonical_source_path)? {
+                Some(head_name) => {
+                    let (_, parent_json_path) = ws.snapshot_paths(&head_name);
+                    let parent_metadata = SnapshotLoader::load_metadata(&parent_json_path)?;
+                    (Some(head_name), parent_metadata.sequence_number + 1)
+                }
+                None => (None, 0),
+            },
+            None => (None, 0),
+        };
+        let source_fingerprint = format!("{}:{}",
+            canonical_source_path,
+            self.hash_computer.hash_value(&format!("{}:{}", canonical_source_path, data_info.row_count))
+        );
+
+        let metadata = SnapshotMetadata {
+            format_version: crate::FORMAT_VERSION.to_string(),
+            name: name.to_string(),
+            created: Utc::now(),
+            source: self.source_override.as_ref()
+                .map(|(source, _)| source.clone())
+                .unwrap_or_else(|| input_path.to_string_lossy().to_string()),
+            source_hash: self.source_hash(input_path),
+            row_count: data_info.row_count,
+            column_count: data_info.column_count(),
+            schema_hash: schema_hash.hash,
+            columns: data_info.columns,
+            archive_size: None,
+            has_full_data: false,
+            parent_snapshot,
+            sequence_number,
+            delta_from_parent: None,
+            can_reconstruct_parent: false,
+            source_path: Some(canonical_source_path),
+            source_fingerprint: Some(source_fingerprint),
+            synthetic_column_names: self.no_header,
+            order_by: Vec::new(),
+            float_precision: None,
+            na_values: Vec::new(),
+            encoding: Some(self.encoding.as_str().to_string()),
+            content_hash: None,
+            row_order_hash: None,
+            description: self.description.clone(),
+            json_path: self.json_path.clone(),
+            metadata_only: true,
+            compression_level: self.compression_level,
+            data_hash: None,
+            signature: None,
+            signed_by: None,
+            chain,
+            sample: self.sample,
+        };
+
+        std::fs::write(json_path, serde_json::to_string_pretty(&metadata)?)?;
+        self.progress.finish_archive("🎉 Snapshot created successfully");
+
+        Ok(metadata)
+    }
+
+    /// Create a snapshot whose archive and metadata are kept by `store`
+    ///
+    /// Snapshots created this way are standalone: they record no parent or delta.
+    pub fn create_snapshot_in_store(
+        &mut self,
+        input_path: &Path,
+        name: &str,
+        store: &dyn SnapshotStore,
+        full_data: bool,
+    ) -> Result<SnapshotMetadata> {
+        let (archive_files, mut metadata) = self.build_snapshot(input_path, name, full_data, None, false)?;
+
+        // Stores take whole archives, so the row data is buffered here
+        let phase = std::time::Instant::now();
+        let mut files = archive_files.files;
+        if let Some(data) = &archive_files.data {
+            files.push((data.name().to_string(), data.read_to_vec()?));
         }
+        let archive = ArchiveManager::create_archive_bytes(&files, self.compression_level)?;
+        self.timings.record("archive write", phase);
+        metadata.archive_size = Some(archive.len() as u64);
+        if let Some(key) = &self.signing_key {
+            metadata.signature = Some(key.sign_files(&files));
+            metadata.signed_by = Some(key.fingerprint());
+        }
+        store.write_archive(name, &archive)?;
+        store.write_metadata(&metadata)?;
+
+        self.progress.finish_archive("🎉 Snapshot created successfully");
+
+        Ok(metadata)
+    }
+
+    /// Metadata `create_snapshot_with_workspace` would write, including the parent and the
+    /// delta from it, without writing the archive, the metadata or a hashing checkpoint
+    pub fn preview_snapshot(
+        &mut self,
+        input_path: &Path,
+        name: &str,
+        full_data: bool,
+        workspace: Option<&crate::workspace::TabdiffWorkspace>,
+    ) -> Result<SnapshotMetadata> {
+        let (_, metadata) = self.build_snapshot(input_path, name, full_data, workspace, false)?;
+        Ok(metadata)
+    }
+
+    /// Hash the input and assemble the archive files and metadata of a snapshot
+    ///
+    /// `archive_size` is left unset until the archive has been written.
+    fn build_snapshot(
+        &mut self,
+        input_path: &Path,
+        name: &str,
+        full_data: bool,
+        workspace: Option<&crate::workspace::TabdiffWorkspace>,
+        checkpoint: bool,
+    ) -> Result<(ArchiveFiles, SnapshotMetadata)> {
+        // Load data; row hashing is checkpointed when the snapshot belongs to a workspace
+        let mut data_processor = self.new_data_processor()?
+            .with_no_header(self.no_header)
+            .with_order_by(self.order_by.clone())
+            .with_float_precision(self.float_precision)
+            .with_na_values(self.na_values.clone())
+            .with_json_path(self.json_path.clone())
+            .with_sample(self.sample)
+            .with_hash_checkpoint(workspace.filter(|_| checkpoint).map(|ws| self.hash_checkpoint(input_path, name, ws)));
 
         // Phase 1: Load and analyze data
         self.progress.finish_schema("📊 Loading and analyzing data...");
-        let data_info = data_processor.load_file(input_path)?;
+        let phase = std::time::Instant::now();
+        let data_info = self.load_input(&mut data_processor, input_path)?;
+        self.timings.record("load", phase);
+        self.check_assertions(&mut data_processor)?;
         
         // Update progress with actual row count
         self.progress.update_estimated_rows(data_info.row_count);
 
         // Phase 2: Compute schema hash
+        let phase = std::time::Instant::now();
         let schema_hash = self.hash_computer.hash_schema(&data_info.columns)?;
+        self.timings.record("schema hash", phase);
 
         // Phase 3: Compute row hashes with progress reporting
+        let phase = std::time::Instant::now();
         let row_hashes = self.hash_computer.hash_rows_with_processor_and_progress(
             &mut data_processor,
             None // No callback needed - data.rs handles progress display directly
         )?;
+        self.timings.record("row hash", phase);
         self.progress.finish_rows(&format!("✅ Hashed {} rows", row_hashes.len()));
 
         // Find parent snapshot and compute delta if workspace is provided (using computed hashes)
+        let phase = std::time::Instant::now();
         let (parent_snapshot, sequence_number, delta_from_parent) = if let Some(ws) = workspace {
             self.find_parent_and_compute_delta(ws, &data_info, &row_hashes)?
         } else {
             (None, 0, None)
         };
+        self.timings.record("parent delta", phase);
 
         // Phase 4: Compute column hashes
+        let phase = std::time::Instant::now();
         let column_hashes = self.hash_computer.hash_columns_with_processor(&mut data_processor)?;
+        self.timings.record("column hash", phase);
         self.progress.finish_columns(&format!("✅ Hashed {} columns", column_hashes.len()));
 
         // Phase 5: Create archive files (reuse existing data_processor to avoid reloading)
         self.progress.update_archive("📦 Creating archive...");
+        let phase = std::time::Instant::now();
         let archive_files = self.create_archive_files_optimized(
             &data_info,
             &schema_hash,
@@ -143,31 +809,11 @@ impl SnapshotCreator {
             &delta_from_parent,
             &mut data_processor, // Pass existing processor to avoid reloading
         )?;
-
-        // Create compressed archive with integrated progress
-        {
-            let progress_ref = &self.progress;
-            ArchiveManager::create_archive_with_progress(
-                archive_path, 
-                &archive_files,
-                Some(&|processed: u64, total: u64, message: &str| {
-                    if let Some(pb) = &progress_ref.archive_pb {
-                        pb.set_length(total);
-                        pb.set_position(processed);
-                        pb.set_message(message.to_string());
-                    }
-                })
-            )?;
-        }
-        
-        // Get archive size
-        let archive_size = std::fs::metadata(archive_path)?.len();
+        self.timings.record("archive write", phase);
 
         // Create canonical source path and fingerprint for source tracking
-        let canonical_source_path = input_path.canonicalize()
-            .unwrap_or_else(|_| input_path.to_path_buf())
-            .to_string_lossy()
-            .to_string();
+        let canonical_source_path = self.chain_source_path(input_path);
+        let chain = self.chain_name(workspace, &canonical_source_path)?;
         
         let source_fingerprint = format!("{}:{}", 
             canonical_source_path,
@@ -182,13 +828,15 @@ impl SnapshotCreator {
             format_version: crate::FORMAT_VERSION.to_string(),
             name: name.to_string(),
             created: Utc::now(),
-            source: input_path.to_string_lossy().to_string(),
-            source_hash: self.hash_computer.hash_value(&std::fs::read_to_string(input_path).unwrap_or_default()),
+            source: self.source_override.as_ref()
+                .map(|(source, _)| source.clone())
+                .unwrap_or_else(|| input_path.to_string_lossy().to_string()),
+            source_hash: self.source_hash(input_path),
             row_count: data_info.row_count,
             column_count: data_info.column_count(),
             schema_hash: schema_hash.hash.clone(),
             columns: data_info.columns.clone(),
-            archive_size: Some(archive_size),
+            archive_size: None,
             has_full_data: full_data, // Use the actual parameter instead of hardcoded true
             parent_snapshot,
             sequence_number,
@@ -196,87 +844,141 @@ impl SnapshotCreator {
             can_reconstruct_parent: false,
             source_path: Some(canonical_source_path),
             source_fingerprint: Some(source_fingerprint),
+            synthetic_column_names: self.no_header,
+            order_by: self.order_by.clone(),
+            float_precision: self.float_precision,
+            na_values: self.na_values.clone(),
+            encoding: Some(self.encoding.as_str().to_string()),
+            content_hash: Some(self.hash_computer.content_hash(row_hashes.iter().map(|rh| rh.hash.as_str()))),
+            row_order_hash: Some(self.hash_computer.row_order_hash(row_hashes.iter().map(|rh| rh.hash.as_str()))),
+            description: self.description.clone(),
+            json_path: self.json_path.clone(),
+            metadata_only: false,
+            compression_level: self.compression_level,
+            data_hash: archive_files.data.as_ref().map(SpooledEntry::content_hash).transpose()?,
+            signature: None,
+            signed_by: None,
+            chain,
+            sample: self.sample,
         };
 
         // Set can_reconstruct_parent flag if this snapshot has a delta
         self.update_current_reconstruct_flag(&mut metadata);
 
-        // Save JSON metadata
-        let json_content = serde_json::to_string_pretty(&metadata)?;
-        std::fs::write(json_path, json_content)?;
+        Ok((archive_files, metadata))
+    }
 
-        // Update parent's can_reconstruct_parent flag if we have a delta
-        if let (Some(ws), Some(parent_name)) = (workspace, &metadata.parent_snapshot) {
-            if metadata.delta_from_parent.is_some() {
-                self.update_parent_reconstruct_flag(ws, parent_name)?;
-            }
-        }
+    /// Write a standalone full-data snapshot holding already reconstructed rows to `workspace`
+    ///
+    /// Settings and schema are taken from `base`, the snapshot whose state `rows` reproduce.
+    /// The new snapshot stores no delta, so it does not depend on `parent` for its data.
+    pub fn create_squashed_snapshot(
+        &self,
+        workspace: &crate::workspace::TabdiffWorkspace,
+        base: &SnapshotMetadata,
+        name: &str,
+        parent: Option<String>,
+        sequence_number: u64,
+        rows: &[Vec<String>],
+    ) -> Result<SnapshotMetadata> {
+        let (archive_path, json_path) = workspace.snapshot_paths(name);
+        let schema_hash = self.hash_computer.hash_schema(&base.columns)?;
+        let column_hashes = DataProcessor::compute_column_metadata_hashes(&base.columns)?;
 
-        self.progress.finish_archive("🎉 Snapshot created successfully");
+        let archive_metadata = serde_json::json!({
+            "name": name,
+            "created": Utc::now(),
+            "source": base.source,
+            "row_count": rows.len(),
+            "column_count": base.columns.len(),
+            "schema_hash": schema_hash.hash,
+            "rows_hashed": rows.len(),
+            "total_rows": rows.len()
+        });
+        let schema_data = serde_json::json!({
+            "hash": schema_hash.hash,
+            "columns": schema_hash.columns,
+            "column_hashes": column_hashes
+        });
+        // Written like any full-data snapshot's rows, so identical data is stored only once
+        let data = SpooledEntry::write_with("data.parquet", |writer| {
+            self.write_data_parquet_streaming(writer, &base.columns, |write_row| {
+                rows.iter().try_for_each(|row| write_row(row))
+            })
+        })?;
+        let archive_files = ArchiveFiles {
+            files: vec![
+                ("metadata.json".to_string(), serde_json::to_string_pretty(&archive_metadata)?.into_bytes()),
+                ("schema.json".to_string(), serde_json::to_string_pretty(&schema_data)?.into_bytes()),
+            ],
+            data: Some(data),
+        };
 
-        Ok(metadata)
-    }
+        let mut metadata = SnapshotMetadata {
+            name: name.to_string(),
+            created: Utc::now(),
+            row_count: rows.len() as u64,
+            schema_hash: schema_hash.hash,
+            archive_size: None,
+            has_full_data: true,
+            parent_snapshot: parent,
+            sequence_number,
+            delta_from_parent: None,
+            can_reconstruct_parent: false,
+            data_hash: archive_files.data.as_ref().map(SpooledEntry::content_hash).transpose()?,
+            // The base's signature covers its own archive, not this one
+            signature: None,
+            signed_by: None,
+            ..base.clone()
+        };
+        self.write_archive(Some(workspace), name, &archive_path, archive_files, &mut metadata, base.compression_level)?;
 
+        let json_content = serde_json::to_string_pretty(&metadata)?;
+        std::fs::write(&json_path, json_content)?;
 
-    /// Create data.parquet file with full dataset
-    fn create_data_parquet(
-        &self,
-        row_data: &[Vec<String>],
-        columns: &[crate::hash::ColumnInfo],
-    ) -> Result<Vec<u8>> {
-        // For now, serialize as JSON until we implement proper Parquet support
-        // TODO: Replace with actual Parquet serialization
-        let data_structure = serde_json::json!({
-            "format": "parquet_placeholder",
-            "columns": columns,
-            "rows": row_data
-        });
-        
-        Ok(serde_json::to_vec_pretty(&data_structure)?)
+        Ok(metadata)
     }
 
-    /// Create data.parquet file with streaming to avoid memory accumulation
-    fn create_data_parquet_streaming(
+    /// Write data.parquet content row by row to avoid memory accumulation
+    ///
+    /// `stream_rows` hands every row, in order, to the callback it is given.
+    fn write_data_parquet_streaming<W, S>(
         &self,
-        data_processor: &mut crate::data::DataProcessor,
+        mut writer: W,
         columns: &[crate::hash::ColumnInfo],
-        progress_callback: Option<&dyn Fn(u64, u64)>,
-    ) -> Result<Vec<u8>> {
-        use std::io::Write;
-        
-        // Create a streaming JSON structure without loading all data into memory
-        let mut buffer = Vec::new();
-        
+        stream_rows: S,
+    ) -> Result<()>
+    where
+        W: std::io::Write,
+        S: FnOnce(&mut dyn FnMut(&[String]) -> Result<()>) -> Result<()>,
+    {
         // Write JSON header
-        write!(buffer, "{{\n  \"format\": \"parquet_placeholder\",\n  \"columns\": ")?;
-        serde_json::to_writer(&mut buffer, columns)?;
-        write!(buffer, ",\n  \"rows\": [\n")?;
+        write!(writer, "{{\n  \"format\": \"parquet_placeholder\",\n  \"columns\": ")?;
+        serde_json::to_writer(&mut writer, columns)?;
+        write!(writer, ",\n  \"rows\": [\n")?;
         
         // Stream and write data row by row using the new streaming method
         let mut first_row = true;
         
-        data_processor.stream_data_with_progress(
-            |row| {
-                // Add comma separator (except for first row)
-                if !first_row {
-                    write!(buffer, ",\n")?;
-                } else {
-                    first_row = false;
-                }
-                
-                // Write row as JSON
-                write!(buffer, "    ")?;
-                serde_json::to_writer(&mut buffer, &row)?;
-                
-                Ok(())
-            },
-            progress_callback,
-        )?;
+        stream_rows(&mut |row| {
+            // Add comma separator (except for first row)
+            if !first_row {
+                writeln!(writer, ",")?;
+            } else {
+                first_row = false;
+            }
+            
+            // Write row as JSON
+            write!(writer, "    ")?;
+            serde_json::to_writer(&mut writer, row)?;
+            
+            Ok(())
+        })?;
         
         // Write JSON footer
-        write!(buffer, "\n  ]\n}}")?;
+        write!(writer, "\n  ]\n}}")?;
         
-        Ok(buffer)
+        Ok(())
     }
 
     /// Create delta.parquet file with change operations
@@ -305,7 +1007,7 @@ impl SnapshotCreator {
         full_data: bool,
         delta_from_parent: &Option<DeltaInfo>,
         data_processor: &mut DataProcessor, // Reuse existing processor to avoid reloading
-    ) -> Result<Vec<(String, Vec<u8>)>> {
+    ) -> Result<ArchiveFiles> {
         let mut files = Vec::new();
 
         // Create metadata.json
@@ -340,29 +1042,26 @@ impl SnapshotCreator {
         // This maintains consistency between snapshot creation and loading
 
         // Only create data.parquet if full_data is true (implements --full-data functionality)
-        if full_data {
-            // Create data.parquet with streaming to avoid memory accumulation
-            let data_parquet = {
-                let progress_ref = &self.progress;
-                self.create_data_parquet_streaming(
-                    data_processor,
-                    &data_info.columns,
-                    Some(&|processed: u64, total: u64| {
-                        if let Some(pb) = &progress_ref.archive_pb {
-                            pb.set_length(total);
-                            pb.set_position(processed);
-                            if processed % 50000 == 0 || processed == total {
-                                pb.set_message(format!("Streaming data ({}/{})", processed, total));
+        // data.parquet is spooled to disk rather than buffered, as it holds every row
+        let data = if full_data {
+            let progress_ref = &self.progress;
+            let start_time = std::time::Instant::now();
+            Some(SpooledEntry::write_with("data.parquet", |writer| {
+                self.write_data_parquet_streaming(writer, &data_info.columns, |write_row| {
+                    data_processor.stream_data_with_progress(
+                        |row| write_row(&row),
+                        Some(&|processed: u64, total: u64| {
+                            if processed.is_multiple_of(50000) || processed == total {
+                                let message = format!("Streaming data ({}/{})", processed, total);
+                                progress_ref.report_archive("streaming", processed, total, &message, start_time);
                             }
-                        }
-                    })
-                )?
-            };
-            files.push((
-                "data.parquet".to_string(),
-                data_parquet,
-            ));
-        }
+                        }),
+                    ).map(drop)
+                })
+            })?)
+        } else {
+            None
+        };
 
         // Create delta.parquet if we have delta information
         if let Some(delta_info) = delta_from_parent {
@@ -373,7 +1072,91 @@ impl SnapshotCreator {
             ));
         }
 
-        Ok(files)
+        Ok(ArchiveFiles { files, data })
+    }
+
+    /// Parent of a new snapshot: the one set by `with_parent`, else the latest snapshot of its chain
+    fn chain_parent(&self, workspace: &crate::workspace::TabdiffWorkspace, source_path: &str) -> Result<Option<String>> {
+        match &self.parent {
+            Some(parent) => Ok(parent.clone()),
+            None => Ok(SnapshotChain::build_chain_for_source(workspace, &self.chain_key(Some(workspace), source_path)?)?.head),
+        }
+    }
+
+    /// Named chain a new snapshot of `source_path` joins: the one set by `with_chain`, else the
+    /// one earlier snapshots of the source joined
+    fn chain_name(&self, workspace: Option<&crate::workspace::TabdiffWorkspace>, source_path: &str) -> Result<Option<String>> {
+        match (&self.chain, workspace) {
+            (Some(chain), _) => Ok(Some(chain.clone())),
+            (None, Some(ws)) => SnapshotChain::chain_of_source(ws, source_path),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Key a new snapshot of `source_path` is chained by (see `SnapshotMetadata::chain_key`)
+    fn chain_key(&self, workspace: Option<&crate::workspace::TabdiffWorkspace>, source_path: &str) -> Result<String> {
+        Ok(match self.chain_name(workspace, source_path)? {
+            Some(chain) => SnapshotChain::named_key(&chain),
+            None => source_path.to_string(),
+        })
+    }
+
+    /// Recompute `child`'s delta against `parent` after `parent` was replaced by a new snapshot
+    ///
+    /// Both copies of the delta are rewritten: the metadata's and the archive's delta.parquet.
+    /// A signed child is re-signed with this creator's key, or loses its signature without one,
+    /// since its archive changed. Returns false, leaving the child without a delta, when either
+    /// snapshot lacks full data.
+    pub fn relink_child(&self, workspace: &crate::workspace::TabdiffWorkspace, parent: &str, child: &str) -> Result<bool> {
+        let (parent_archive_path, parent_json_path) = workspace.snapshot_paths(parent);
+        let (child_archive_path, child_json_path) = workspace.snapshot_paths(child);
+        let parent_metadata = SnapshotLoader::load_metadata(&parent_json_path)?;
+        let mut child_metadata = SnapshotLoader::load_metadata(&child_json_path)?;
+
+        child_metadata.delta_from_parent = if parent_metadata.has_full_data && child_metadata.has_full_data
+            && parent_archive_path.exists() && child_archive_path.exists()
+        {
+            let (parent_schema, parent_row_data) = self.load_cached_schema_and_data(&parent_archive_path)?;
+            let (child_schema, child_row_data) = self.load_cached_schema_and_data(&child_archive_path)?;
+            let changes = crate::change_detection::ChangeDetector::detect_changes_with_options(
+                &parent_schema,
+                &parent_row_data,
+                &child_schema,
+                &child_row_data,
+                &crate::change_detection::DetectionOptions::default().with_cancellation(self.cancellation.clone()),
+            )?;
+            let compressed_size = serde_json::to_string(&changes)?.len() as u64;
+            Some(DeltaInfo {
+                parent_name: parent.to_string(),
+                changes,
+                compressed_size,
+            })
+        } else {
+            None
+        };
+        child_metadata.can_reconstruct_parent = child_metadata.delta_from_parent.is_some();
+
+        if child_archive_path.exists() {
+            let mut entries = ArchiveManager::extract_archive_entries(&child_archive_path)?;
+            entries.retain(|(entry_name, _)| entry_name != "delta.parquet");
+            if let Some(delta_info) = &child_metadata.delta_from_parent {
+                entries.push(("delta.parquet".to_string(), self.create_delta_parquet(delta_info)?));
+            }
+            // The archive is replaced only once its successor is complete
+            let staged_path = staged_path(&child_archive_path);
+            ArchiveManager::create_archive_with_progress(&staged_path, &entries, child_metadata.compression_level, None)?;
+            std::fs::rename(&staged_path, &child_archive_path)?;
+            child_metadata.archive_size = Some(std::fs::metadata(&child_archive_path)?.len());
+            if child_metadata.signature.is_some() {
+                child_metadata.signature = self.signing_key.as_ref()
+                    .map(|key| key.sign_archive(&child_archive_path))
+                    .transpose()?;
+                child_metadata.signed_by = self.signing_key.as_ref().map(|key| key.fingerprint());
+            }
+        }
+        std::fs::write(&child_json_path, serde_json::to_string_pretty(&child_metadata)?)?;
+
+        Ok(child_metadata.can_reconstruct_parent)
     }
 
     /// Find parent snapshot and compute delta using cached hashes (FIXED ARCHITECTURE)
@@ -384,39 +1167,19 @@ impl SnapshotCreator {
         _current_row_hashes: &[crate::hash::RowHash],
     ) -> Result<(Option<String>, u64, Option<DeltaInfo>)> {
         // Create canonical source path for current file
-        let current_canonical_path = current_data_info.source.canonicalize()
-            .unwrap_or_else(|_| current_data_info.source.clone())
-            .to_string_lossy()
-            .to_string();
+        let current_canonical_path = self.chain_source_path(&current_data_info.source);
 
-        // Build source-aware snapshot chain for the current file only
-        let chain = SnapshotChain::build_chain_for_source(workspace, &current_canonical_path)?;
-        
-        if let Some(head_name) = &chain.head {
+        if let Some(head_name) = &self.chain_parent(workspace, &current_canonical_path)? {
             // Load parent snapshot metadata
             let (parent_archive_path, parent_json_path) = workspace.snapshot_paths(head_name);
             
             if parent_json_path.exists() {
                 let parent_metadata = SnapshotLoader::load_metadata(&parent_json_path)?;
                 
-                // Double-check that parent is from the same source
-                if let Some(parent_source_path) = &parent_metadata.source_path {
-                    if parent_source_path != &current_canonical_path {
-                        // Parent is from different source, treat as first snapshot
-                        return Ok((None, 0, None));
-                    }
-                } else {
-                    // Legacy snapshot without source_path, check original source field
-                    let parent_canonical_path = std::path::Path::new(&parent_metadata.source)
-                        .canonicalize()
-                        .unwrap_or_else(|_| std::path::PathBuf::from(&parent_metadata.source))
-                        .to_string_lossy()
-                        .to_string();
-                    
-                    if parent_canonical_path != current_canonical_path {
-                        // Parent is from different source, treat as first snapshot
-                        return Ok((None, 0, None));
-                    }
+                // Double-check that parent is from the same chain
+                if parent_metadata.chain_key() != self.chain_key(Some(workspace), &current_canonical_path)? {
+                    // Parent is from different source, treat as first snapshot
+                    return Ok((None, 0, None));
                 }
                 
                 // CRITICAL FIX: Always check for schema changes, not just row changes
@@ -426,11 +1189,12 @@ impl SnapshotCreator {
                     let current_row_data = self.extract_current_row_data(current_data_info)?;
                     
                     // Step 2: Always run comprehensive change detection (schema + rows)
-                    let changes = crate::change_detection::ChangeDetector::detect_changes(
+                    let changes = crate::change_detection::ChangeDetector::detect_changes_with_options(
                         &parent_schema,
                         &parent_row_data,
                         &current_data_info.columns,
                         &current_row_data,
+                        &crate::change_detection::DetectionOptions::default().with_cancellation(self.cancellation.clone()),
                     )?;
                     
                     // Step 3: Create delta info regardless of whether changes exist
@@ -589,43 +1353,20 @@ impl SnapshotCreator {
 
     /// Load cached schema from archive
     fn load_cached_schema(&self, archive_path: &Path) -> Result<Vec<crate::hash::ColumnInfo>> {
-        let files = ArchiveManager::extract_archive(archive_path)?;
-        
-        for (filename, content) in files {
-            if filename == "schema.json" {
-                let content_str = String::from_utf8(content)?;
-                let schema_data: serde_json::Value = serde_json::from_str(&content_str)?;
-                
-                if let Some(columns_data) = schema_data.get("columns") {
-                    if let Some(columns_array) = columns_data.as_array() {
-                        let mut columns = Vec::new();
-                        for col_value in columns_array {
-                            if let (Some(name), Some(data_type), Some(nullable)) = (
-                                col_value.get("name").and_then(|v| v.as_str()),
-                                col_value.get("data_type").and_then(|v| v.as_str()),
-                                col_value.get("nullable").and_then(|v| v.as_bool())
-                            ) {
-                                columns.push(crate::hash::ColumnInfo {
-                                    name: name.to_string(),
-                                    data_type: data_type.to_string(),
-                                    nullable,
-                                });
-                            }
-                        }
-                        return Ok(columns);
-                    }
-                }
-            }
-        }
-        
-        Ok(Vec::new())
+        SnapshotLoader::load_schema_only(archive_path)
     }
 
     /// Extract current row data from data info (we need to reload from source)
     fn extract_current_row_data(&self, current_data_info: &DataInfo) -> Result<Vec<Vec<String>>> {
         // Create a new data processor to extract the current data
-        let mut data_processor = DataProcessor::new()?;
-        data_processor.load_file(&current_data_info.source)?;
+        let mut data_processor = self.new_data_processor()?
+            .with_no_header(self.no_header)
+            .with_order_by(self.order_by.clone())
+            .with_float_precision(self.float_precision)
+            .with_na_values(self.na_values.clone())
+            .with_json_path(self.json_path.clone())
+            .with_sample(self.sample);
+        self.load_input(&mut data_processor, &current_data_info.source)?;
         
         // Extract the full row data
         let row_data = data_processor.extract_data_chunked_with_progress(None)?;
@@ -646,12 +1387,59 @@ impl SnapshotLoader {
         Ok(metadata)
     }
 
+    /// Load the column schema recorded in an archive's schema.json, without extracting the row data
+    pub fn load_schema_only<P: AsRef<Path>>(archive_path: P) -> Result<Vec<crate::hash::ColumnInfo>> {
+        let content = ArchiveManager::extract_file(archive_path, "schema.json")?
+            .ok_or_else(|| TabdiffError::archive("Missing schema.json"))?;
+        let schema_data: serde_json::Value = serde_json::from_slice(&content)?;
+        Ok(Self::schema_columns(&schema_data))
+    }
+
+    /// Load the metadata.json stored in an archive, without extracting the row data
+    pub fn load_metadata_from_archive<P: AsRef<Path>>(archive_path: P) -> Result<serde_json::Value> {
+        let content = ArchiveManager::extract_file(archive_path, "metadata.json")?
+            .ok_or_else(|| TabdiffError::archive("Missing metadata.json"))?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Columns listed in the content of a schema.json; entries missing a field are skipped
+    pub fn schema_columns(schema_data: &serde_json::Value) -> Vec<crate::hash::ColumnInfo> {
+        schema_data.get("columns")
+            .and_then(|columns| columns.as_array())
+            .map(|columns| {
+                columns.iter()
+                    .filter_map(|col_value| {
+                        Some(crate::hash::ColumnInfo {
+                            name: col_value.get("name")?.as_str()?.to_string(),
+                            data_type: col_value.get("data_type")?.as_str()?.to_string(),
+                            nullable: col_value.get("nullable")?.as_bool()?,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Load full snapshot data from archive
     pub fn load_full_snapshot<P: AsRef<Path>>(
         archive_path: P,
     ) -> Result<FullSnapshotData> {
-        let files = ArchiveManager::extract_archive(archive_path)?;
-        
+        Self::parse_archive_files(ArchiveManager::extract_archive(archive_path)?)
+    }
+
+    /// Load full snapshot data of a snapshot kept by `store`
+    pub fn load_full_snapshot_from_store(store: &dyn SnapshotStore, name: &str) -> Result<FullSnapshotData> {
+        let archive = store.read_archive(name)?
+            .ok_or_else(|| TabdiffError::archive(format!("Snapshot '{}' has no archive data", name)))?;
+        let files = ArchiveManager::resolve_references(ArchiveManager::extract_archive_bytes(&archive)?, |snapshot| {
+            let archive = store.read_archive(snapshot)?
+                .ok_or_else(|| TabdiffError::archive(format!("Snapshot '{}' has no archive data", snapshot)))?;
+            ArchiveManager::extract_archive_bytes(&archive)
+        })?;
+        Self::parse_archive_files(files)
+    }
+
+    fn parse_archive_files(files: Vec<(String, Vec<u8>)>) -> Result<FullSnapshotData> {
         let mut metadata = None;
         let mut schema_data = None;
         let mut row_data = None;
@@ -705,6 +1493,21 @@ pub struct FullSnapshotData {
     pub delta_data: Option<serde_json::Value>,
 }
 
+impl FullSnapshotData {
+    /// Full row data as strings (empty when the snapshot stores hashes only)
+    pub fn rows(&self) -> Vec<Vec<String>> {
+        self.row_data.get("rows")
+            .and_then(|rows| rows.as_array())
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| row.as_array())
+                    .map(|row| row.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
 /// Snapshot chain management for tracking relationships between snapshots
 #[derive(Debug, Clone)]
 pub struct SnapshotChain {
@@ -739,35 +1542,19 @@ impl SnapshotChain {
     }
 
     /// Build snapshot chain for a specific source file
+    ///
+    /// `source_path` is a canonical source path or the key of a named chain (`named_key`). A
+    /// path whose snapshots joined a named chain builds that whole chain, so a moved file still
+    /// finds its history.
     pub fn build_chain_for_source(workspace: &crate::workspace::TabdiffWorkspace, source_path: &str) -> Result<Self> {
-        let snapshot_names = workspace.list_snapshots()?;
-        let mut snapshots = Vec::new();
-        
-        for name in snapshot_names {
-            let (_, json_path) = workspace.snapshot_paths(&name);
-            if json_path.exists() {
-                let metadata = SnapshotLoader::load_metadata(&json_path)?;
-                
-                // Check if this snapshot is from the same source
-                let is_same_source = if let Some(snapshot_source_path) = &metadata.source_path {
-                    // Use the stored canonical source path
-                    snapshot_source_path == source_path
-                } else {
-                    // Legacy snapshot without source_path, check original source field
-                    let snapshot_canonical_path = std::path::Path::new(&metadata.source)
-                        .canonicalize()
-                        .unwrap_or_else(|_| std::path::PathBuf::from(&metadata.source))
-                        .to_string_lossy()
-                        .to_string();
-                    
-                    snapshot_canonical_path == source_path
-                };
-                
-                if is_same_source {
-                    snapshots.push(metadata);
-                }
-            }
-        }
+        let all = Self::load_all_metadata(workspace)?;
+        let key = match Self::chain_in(&all, source_path) {
+            Some(chain) => Self::named_key(&chain),
+            None => source_path.to_string(),
+        };
+        let mut snapshots: Vec<SnapshotMetadata> = all.into_iter()
+            .filter(|metadata| metadata.chain_key() == key)
+            .collect();
         
         // Sort by sequence number and creation time
         snapshots.sort_by(|a, b| {
@@ -780,6 +1567,34 @@ impl SnapshotChain {
         
         Ok(Self { snapshots, head })
     }
+
+    /// Chain key of the chain named `chain`, kept apart from source paths by its prefix
+    pub fn named_key(chain: &str) -> String {
+        format!("chain:{}", chain)
+    }
+
+    /// Named chain the latest snapshot taken at `source_path` joined, if any
+    pub fn chain_of_source(workspace: &crate::workspace::TabdiffWorkspace, source_path: &str) -> Result<Option<String>> {
+        Ok(Self::chain_in(&Self::load_all_metadata(workspace)?, source_path))
+    }
+
+    fn chain_in(all: &[SnapshotMetadata], source_path: &str) -> Option<String> {
+        all.iter()
+            .filter(|metadata| metadata.source_path.as_deref() == Some(source_path))
+            .max_by(|a, b| a.created.cmp(&b.created))
+            .and_then(|metadata| metadata.chain.clone())
+    }
+
+    fn load_all_metadata(workspace: &crate::workspace::TabdiffWorkspace) -> Result<Vec<SnapshotMetadata>> {
+        let mut all = Vec::new();
+        for name in workspace.list_snapshots()? {
+            let (_, json_path) = workspace.snapshot_paths(&name);
+            if json_path.exists() {
+                all.push(SnapshotLoader::load_metadata(&json_path)?);
+            }
+        }
+        Ok(all)
+    }
     
     /// Find path from one snapshot to another
     pub fn find_path_to_snapshot(&self, target: &str) -> Option<Vec<String>> {
@@ -799,6 +1614,51 @@ impl SnapshotChain {
         Some(path)
     }
     
+    /// Rebuild the full rows of `target` from the nearest ancestor with full data, applying deltas forward
+    pub fn reconstruct_rows(
+        &self,
+        workspace: &crate::workspace::TabdiffWorkspace,
+        target: &str,
+    ) -> Result<Vec<Vec<String>>> {
+        let path = self.find_path_to_snapshot(target).ok_or_else(|| TabdiffError::SnapshotNotFound {
+            name: target.to_string(),
+        })?;
+        let metadata_for = |name: &str| self.snapshots.iter().find(|s| s.name == name);
+
+        // Walk back from the target to the nearest snapshot whose archive still holds every row
+        let mut base = None;
+        for (position, name) in path.iter().enumerate().rev() {
+            let (archive_path, _) = workspace.snapshot_paths(name);
+            let metadata = match metadata_for(name) {
+                Some(metadata) if metadata.has_full_data && archive_path.exists() => metadata,
+                _ => continue,
+            };
+            let rows = SnapshotLoader::load_full_snapshot(&archive_path)?.rows();
+            if rows.len() as u64 == metadata.row_count {
+                base = Some((position, rows));
+                break;
+            }
+        }
+        let (base_position, mut rows) = base.ok_or_else(|| {
+            TabdiffError::data_processing(format!("No snapshot with full data found in the history of '{}'", target))
+        })?;
+
+        for pair in path[base_position..].windows(2) {
+            let (parent, child) = match (metadata_for(&pair[0]), metadata_for(&pair[1])) {
+                (Some(parent), Some(child)) => (parent, child),
+                _ => return Err(TabdiffError::data_processing(format!("Snapshot chain to '{}' is incomplete", target))),
+            };
+            let delta = child.delta_from_parent.as_ref().ok_or_else(|| {
+                TabdiffError::data_processing(format!(
+                    "Snapshot '{}' has no delta from '{}' to reconstruct from", child.name, parent.name
+                ))
+            })?;
+            rows = delta.changes.apply_to(&parent.columns, &rows, &child.columns, child.row_count as usize)?;
+        }
+
+        Ok(rows)
+    }
+
     /// Check if a snapshot can be safely deleted (has child that can reconstruct it)
     pub fn can_safely_delete(&self, snapshot: &str) -> bool {
         // Find children of this snapshot
@@ -922,6 +1782,9 @@ impl SnapshotChain {
     }
 
     /// Find snapshots that can have their full data cleaned up (selective cleanup)
+    ///
+    /// `keep_full` applies to each source chain, so every tracked source keeps full data for
+    /// its own most recent snapshots however many snapshots other sources have.
     pub fn find_data_cleanup_candidates(
         &self,
         keep_full: usize,
@@ -929,31 +1792,37 @@ impl SnapshotChain {
     ) -> Result<Vec<&SnapshotMetadata>> {
         let mut candidates = Vec::new();
         
-        // Count total archives
-        let mut archives_with_files = Vec::new();
+        // Data cleanup strategy:
+        // 1. Always keep full data for the most recent N snapshots of each source (head + keep_full-1)
+        // 2. Remove full data from ALL other snapshots but preserve deltas
+        // 3. Can reconstruct any snapshot through delta chains from its source's head
+        let mut chain_keys: Vec<String> = Vec::new();
         for snapshot in &self.snapshots {
-            let (archive_path, _) = workspace.snapshot_paths(&snapshot.name);
-            if archive_path.exists() {
-                archives_with_files.push(snapshot);
+            let key = snapshot.chain_key();
+            if !chain_keys.contains(&key) {
+                chain_keys.push(key);
             }
         }
         
-        // Data cleanup strategy:
-        // 1. Always keep full data for the most recent N snapshots (head + keep_full-1)
-        // 2. Remove full data from ALL other snapshots but preserve deltas
-        // 3. Can reconstruct any snapshot through delta chains from head
-        
-        // Sort archives by sequence number (newest first, so head is first)
-        let mut sorted_archives = archives_with_files.clone();
-        sorted_archives.sort_by_key(|s| std::cmp::Reverse(s.sequence_number));
-        
-        // Keep full data for the most recent keep_full snapshots
-        for (index, snapshot) in sorted_archives.iter().enumerate() {
-            // Clean up all snapshots except the most recent keep_full
-            if index >= keep_full {
-                // Check if this snapshot can be reconstructed from the chain
-                if self.can_be_reconstructed(&snapshot.name) {
-                    candidates.push(*snapshot);
+        for key in chain_keys {
+            let source_chain = Self::build_chain_for_source(workspace, &key)?;
+            
+            // Chains are ordered oldest first, so the newest archives come first in reverse
+            let archives = source_chain.snapshots.iter().rev().filter(|snapshot| {
+                let (archive_path, _) = workspace.snapshot_paths(&snapshot.name);
+                archive_path.exists()
+            });
+            for snapshot in archives.skip(keep_full) {
+                // Check if this snapshot can be reconstructed from its chain
+                if !source_chain.can_be_reconstructed(&snapshot.name) {
+                    continue;
+                }
+                // A path whose snapshots later joined a named chain resolves to that chain too
+                if candidates.iter().any(|c: &&SnapshotMetadata| c.name == snapshot.name) {
+                    continue;
+                }
+                if let Some(snapshot) = self.snapshots.iter().find(|s| s.name == snapshot.name) {
+                    candidates.push(snapshot);
                 }
             }
         }
@@ -1043,6 +1912,22 @@ mod tests {
             can_reconstruct_parent: false,
             source_path: Some("/path/to/test.csv".to_string()),
             source_fingerprint: Some("test_fingerprint".to_string()),
+            synthetic_column_names: false,
+            order_by: Vec::new(),
+            float_precision: None,
+            na_values: Vec::new(),
+            encoding: None,
+            content_hash: None,
+            row_order_hash: None,
+            description: None,
+            json_path: None,
+            metadata_only: false,
+            compression_level: CompressionLevel::default(),
+            data_hash: None,
+            signature: None,
+            signed_by: None,
+            chain: None,
+            sample: None,
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -1076,6 +1961,22 @@ mod tests {
             can_reconstruct_parent: false,
             source_path: Some("/path/to/test.csv".to_string()),
             source_fingerprint: Some("test_fingerprint".to_string()),
+            synthetic_column_names: false,
+            order_by: Vec::new(),
+            float_precision: None,
+            na_values: Vec::new(),
+            encoding: None,
+            content_hash: None,
+            row_order_hash: None,
+            description: None,
+            json_path: None,
+            metadata_only: false,
+            compression_level: CompressionLevel::default(),
+            data_hash: None,
+            signature: None,
+            signed_by: None,
+            chain: None,
+            sample: None,
         };
 
         let json_content = serde_json::to_string_pretty(&metadata).unwrap();
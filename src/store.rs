@@ -0,0 +1,116 @@
+//! Storage backends for snapshot archives and metadata
+
+use crate::error::{Result, TabdiffError};
+use crate::snapshot::{SnapshotLoader, SnapshotMetadata};
+use crate::workspace::TabdiffWorkspace;
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+/// Where snapshot archives and their metadata are kept
+///
+/// `TabdiffWorkspace` keeps them as files under `.tabdiff/`; `MemorySnapshotStore` keeps them
+/// in RAM for one-off comparisons and embedders that want nothing written to disk.
+pub trait SnapshotStore {
+    /// Store the compressed archive of a snapshot
+    fn write_archive(&self, name: &str, archive: &[u8]) -> Result<()>;
+
+    /// Compressed archive of a snapshot, or None when it has none
+    fn read_archive(&self, name: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Store snapshot metadata under its name
+    fn write_metadata(&self, metadata: &SnapshotMetadata) -> Result<()>;
+
+    /// Metadata of a stored snapshot
+    fn read_metadata(&self, name: &str) -> Result<SnapshotMetadata>;
+
+    /// Names of all stored snapshots, sorted
+    fn list(&self) -> Result<Vec<String>>;
+}
+
+impl SnapshotStore for TabdiffWorkspace {
+    fn write_archive(&self, name: &str, archive: &[u8]) -> Result<()> {
+        let (archive_path, _) = self.snapshot_paths(name);
+        std::fs::write(archive_path, archive)?;
+        Ok(())
+    }
+
+    fn read_archive(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let (archive_path, _) = self.snapshot_paths(name);
+        if !archive_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(std::fs::read(archive_path)?))
+    }
+
+    fn write_metadata(&self, metadata: &SnapshotMetadata) -> Result<()> {
+        let (_, json_path) = self.snapshot_paths(&metadata.name);
+        std::fs::write(json_path, serde_json::to_string_pretty(metadata)?)?;
+        Ok(())
+    }
+
+    fn read_metadata(&self, name: &str) -> Result<SnapshotMetadata> {
+        let (_, json_path) = self.snapshot_paths(name);
+        if !json_path.exists() {
+            return Err(TabdiffError::SnapshotNotFound { name: name.to_string() });
+        }
+        SnapshotLoader::load_metadata(json_path)
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        self.list_snapshots()
+    }
+}
+
+/// Snapshot store held entirely in memory and discarded with it
+#[derive(Debug, Default)]
+pub struct MemorySnapshotStore {
+    archives: RwLock<HashMap<String, Vec<u8>>>,
+    metadata: RwLock<HashMap<String, SnapshotMetadata>>,
+}
+
+impl MemorySnapshotStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SnapshotStore for MemorySnapshotStore {
+    fn write_archive(&self, name: &str, archive: &[u8]) -> Result<()> {
+        self.archives.write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.to_string(), archive.to_vec());
+        Ok(())
+    }
+
+    fn read_archive(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.archives.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned())
+    }
+
+    fn write_metadata(&self, metadata: &SnapshotMetadata) -> Result<()> {
+        self.metadata.write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(metadata.name.clone(), metadata.clone());
+        Ok(())
+    }
+
+    fn read_metadata(&self, name: &str) -> Result<SnapshotMetadata> {
+        self.metadata.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned()
+            .ok_or_else(|| TabdiffError::SnapshotNotFound { name: name.to_string() })
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        let mut names: Vec<String> = self.metadata.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+}
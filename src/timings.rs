@@ -0,0 +1,96 @@
+//! Wall-clock timings of command phases (`--timings`)
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Each phase's name and the time spent in it
+type Phases = Vec<(String, Duration)>;
+
+/// Records how long each phase of a command took
+///
+/// Clones share one record, so a handle can be passed to the code that runs a phase. A
+/// disabled handle (the default) records nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    phases: Option<Arc<Mutex<Phases>>>,
+}
+
+impl Timings {
+    pub fn new(enabled: bool) -> Self {
+        Self { phases: enabled.then(|| Arc::new(Mutex::new(Vec::new()))) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.phases.is_some()
+    }
+
+    /// Record that `phase` ran from `started` until now; a phase that runs again adds to its time
+    pub fn record(&self, phase: &str, started: Instant) {
+        let Some(phases) = &self.phases else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        let mut phases = phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match phases.iter_mut().find(|(name, _)| name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase.to_string(), elapsed)),
+        }
+    }
+
+    /// Recorded phases in the order they first ran
+    pub fn phases(&self) -> Phases {
+        self.phases.as_ref()
+            .map(|phases| phases.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone())
+            .unwrap_or_default()
+    }
+
+    /// Table of the phases with their seconds and share of the total (None when nothing was recorded)
+    pub fn render(&self) -> Option<String> {
+        let phases = self.phases();
+        if phases.is_empty() {
+            return None;
+        }
+        let total: Duration = phases.iter().map(|(_, duration)| *duration).sum();
+        let width = phases.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("total".len());
+        let mut table = String::from("⏱️  Timings:\n");
+        for (name, duration) in &phases {
+            let share = if total.is_zero() { 0.0 } else { duration.as_secs_f64() / total.as_secs_f64() * 100.0 };
+            table.push_str(&format!("   {:<width$}  {:>9.3}s  {:>5.1}%\n", name, duration.as_secs_f64(), share));
+        }
+        table.push_str(&format!("   {:<width$}  {:>9.3}s\n", "total", total.as_secs_f64()));
+        Some(table)
+    }
+
+    /// Print the table to stderr, keeping stdout free for reports and JSON
+    pub fn print(&self) {
+        if let Some(table) = self.render() {
+            eprint!("\n{}", table);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases_accumulate_and_render_in_order() {
+        let timings = Timings::new(true);
+        let shared = timings.clone();
+        let started = Instant::now();
+        shared.record("load", started);
+        timings.record("row hash", started);
+        timings.record("load", started);
+
+        let names: Vec<String> = timings.phases().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["load", "row hash"]);
+        let table = timings.render().unwrap();
+        assert!(table.contains("   load      "), "Unexpected table: {}", table);
+        assert!(table.lines().last().unwrap().trim_start().starts_with("total"));
+
+        let disabled = Timings::default();
+        disabled.record("load", started);
+        assert!(!disabled.is_enabled());
+        assert!(disabled.render().is_none());
+    }
+}
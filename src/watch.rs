@@ -0,0 +1,92 @@
+//! Helpers for long-running watch loops: interval options, interruptible sleeps and write debouncing
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// How long a file must stay unchanged before it is read, so half-written files are skipped
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Granularity at which sleeps check for an interrupt
+const POLL_STEP: Duration = Duration::from_millis(50);
+
+/// Interval and optional iteration bound for a watch loop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatchOptions {
+    pub interval: Duration,
+    pub max_iterations: Option<usize>,
+}
+
+impl WatchOptions {
+    /// Parse a positive interval in seconds (fractions allowed, e.g. "0.5")
+    pub fn parse_interval(s: &str) -> Result<Duration, String> {
+        let seconds: f64 = s.parse()
+            .map_err(|_| format!("Invalid watch interval: '{}'. Must be a number of seconds.", s))?;
+
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Err("Watch interval must be greater than 0".to_string());
+        }
+
+        Ok(Duration::from_secs_f64(seconds))
+    }
+}
+
+/// Whether Ctrl-C has cancelled the process-wide token since it was last reset
+pub fn interrupted() -> bool {
+    crate::cancel::CancellationToken::global().is_cancelled()
+}
+
+/// Sleep for `duration`, returning false early if interrupted
+pub fn sleep_interruptible(duration: Duration) -> bool {
+    let deadline = std::time::Instant::now() + duration;
+    while !interrupted() {
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        std::thread::sleep(POLL_STEP.min(deadline - now));
+    }
+    false
+}
+
+/// Size and modification time of a file, used to detect in-progress writes
+#[derive(Debug, Clone, PartialEq)]
+struct FileState {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileState {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Wait until `path` has stopped changing for `debounce` (or an interrupt arrives)
+pub fn wait_for_stable(path: &Path, debounce: Duration) {
+    let mut previous = FileState::read(path);
+    while sleep_interruptible(debounce) {
+        let current = FileState::read(path);
+        if current == previous {
+            return;
+        }
+        previous = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(WatchOptions::parse_interval("2").unwrap(), Duration::from_secs(2));
+        assert_eq!(WatchOptions::parse_interval("0.25").unwrap(), Duration::from_millis(250));
+        assert!(WatchOptions::parse_interval("0").is_err());
+        assert!(WatchOptions::parse_interval("-1").is_err());
+        assert!(WatchOptions::parse_interval("soon").is_err());
+    }
+}
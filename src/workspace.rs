@@ -1,10 +1,129 @@
 //! Workspace management for tabdiff operations
 
 use crate::error::Result;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Default name of the workspace directory under the project root
+pub const DEFAULT_WORKSPACE_DIR: &str = ".tabdiff";
+
+/// File in the project root that records a non-default workspace layout
+pub const SETTINGS_FILE: &str = ".tabdiffrc";
+
+/// Environment variable naming the directory to look for the workspace from when `--workspace` is not given
+pub const WORKSPACE_ENV: &str = "TABDIFF_WORKSPACE";
+
+/// Workspace layout chosen at `init`, read back so later commands find the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSettings {
+    /// Name of the workspace directory under the project root
+    #[serde(default = "default_workspace_dir")]
+    pub dir: String,
+    /// Whether archives are added to the project's .gitignore
+    #[serde(default = "default_gitignore")]
+    pub gitignore: bool,
+}
+
+fn default_workspace_dir() -> String {
+    DEFAULT_WORKSPACE_DIR.to_string()
+}
+
+fn default_gitignore() -> bool {
+    true
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            dir: default_workspace_dir(),
+            gitignore: default_gitignore(),
+        }
+    }
+}
+
+impl WorkspaceSettings {
+    /// Settings recorded under `root`, or the defaults when none were recorded
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Record the settings under `root`; the default layout needs no settings file
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(SETTINGS_FILE);
+        if *self == Self::default() && !path.exists() {
+            return Ok(());
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Option defaults set with `tabdiff config`, kept under "defaults" in the workspace config
+///
+/// Commands resolve each option as: flag on the command line, then this default, then the
+/// built-in default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WorkspaceDefaults {
+    /// `snapshot --compression-level`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<crate::archive::CompressionLevel>,
+    /// `snapshot --float-precision`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_precision: Option<usize>,
+    /// `diff` and `status --float-tolerance`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_tolerance: Option<f64>,
+    /// `diff` and `status --threads`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+}
+
+impl WorkspaceDefaults {
+    /// Every key `tabdiff config` accepts
+    pub const KEYS: [&'static str; 4] = ["compression-level", "float-precision", "float-tolerance", "threads"];
+
+    /// Value of `key` as it would be given on the command line, or None when unset
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "compression-level" => self.compression_level.map(|level| level.level().to_string()),
+            "float-precision" => self.float_precision.map(|precision| precision.to_string()),
+            "float-tolerance" => self.float_tolerance.map(|tolerance| tolerance.to_string()),
+            "threads" => self.threads.map(|threads| threads.to_string()),
+            _ => return Err(Self::unknown_key(key)),
+        })
+    }
+
+    /// Set `key` from a command-line style value, validated like the flag; None unsets it
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        fn parse<T>(value: Option<&str>, parser: fn(&str) -> std::result::Result<T, String>) -> Result<Option<T>> {
+            value.map(parser).transpose().map_err(crate::error::TabdiffError::invalid_input)
+        }
+        match key {
+            "compression-level" => self.compression_level = parse(value, crate::archive::CompressionLevel::parse)?,
+            "float-precision" => self.float_precision = parse(value, crate::cli::validate_float_precision)?,
+            "float-tolerance" => self.float_tolerance = parse(value, crate::cli::validate_float_tolerance)?,
+            "threads" => self.threads = parse(value, |s| s.parse::<usize>().map_err(|_| format!("Invalid thread count: '{}'", s)))?,
+            _ => return Err(Self::unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    fn unknown_key(key: &str) -> crate::error::TabdiffError {
+        crate::error::TabdiffError::invalid_input(format!(
+            "Unknown config key '{}'. Valid keys: {}",
+            key,
+            Self::KEYS.join(", ")
+        ))
+    }
+}
+
 /// Manages the .tabdiff workspace directory
 #[derive(Debug, Clone)]
 pub struct TabdiffWorkspace {
@@ -18,18 +137,30 @@ pub struct TabdiffWorkspace {
 
 impl TabdiffWorkspace {
     /// Find existing workspace or create a new one
+    ///
+    /// The search walks up from `start_dir` (the `--workspace` flag), else from `TABDIFF_WORKSPACE`,
+    /// else from the current directory, so commands run in a subdirectory use the project's workspace.
     pub fn find_or_create(start_dir: Option<&Path>) -> Result<Self> {
-        let current_dir = std::env::current_dir()?;
-        let start = start_dir.unwrap_or(&current_dir);
+        let start = Self::start_dir(start_dir)?;
         
         // First try to find existing .tabdiff directory
-        if let Some(workspace) = Self::find_existing(start)? {
+        if let Some(workspace) = Self::find_existing(&start)? {
             return Ok(workspace);
         }
         
         // If not found, create in current directory or specified directory
-        let root = start.to_path_buf();
-        Self::create_new(root)
+        Self::create_new(start)
+    }
+    
+    /// Directory the workspace is looked up from: the flag, then `TABDIFF_WORKSPACE`, then the current directory
+    pub fn start_dir(workspace_flag: Option<&Path>) -> Result<PathBuf> {
+        if let Some(dir) = workspace_flag {
+            return Ok(dir.to_path_buf());
+        }
+        match std::env::var_os(WORKSPACE_ENV) {
+            Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+            _ => Ok(std::env::current_dir()?),
+        }
     }
     
     /// Find existing .tabdiff workspace by walking up directory tree
@@ -37,8 +168,8 @@ impl TabdiffWorkspace {
         let mut current = start_dir;
         
         loop {
-            let tabdiff_dir = current.join(".tabdiff");
-            if tabdiff_dir.exists() && tabdiff_dir.is_dir() {
+            let tabdiff_dir = current.join(DEFAULT_WORKSPACE_DIR);
+            if current.join(SETTINGS_FILE).is_file() || tabdiff_dir.is_dir() {
                 return Ok(Some(Self::from_root(current.to_path_buf())?));
             }
             
@@ -60,6 +191,7 @@ impl TabdiffWorkspace {
     
     /// Create a new workspace in the specified root directory
     pub fn create_new(root: PathBuf) -> Result<Self> {
+        let settings = WorkspaceSettings::load(&root)?;
         let workspace = Self::from_root(root)?;
         
         // Create directories
@@ -70,16 +202,19 @@ impl TabdiffWorkspace {
         workspace.create_config()?;
         
         // Update .gitignore
-        workspace.ensure_gitignore()?;
+        if settings.gitignore {
+            workspace.ensure_gitignore()?;
+        }
         
         log::info!("Created tabdiff workspace at: {}", workspace.root.display());
         
         Ok(workspace)
     }
     
-    /// Create workspace from root directory path
+    /// Create workspace from root directory path, honouring a recorded workspace directory name
     pub fn from_root(root: PathBuf) -> Result<Self> {
-        let tabdiff_dir = root.join(".tabdiff");
+        let settings = WorkspaceSettings::load(&root)?;
+        let tabdiff_dir = root.join(&settings.dir);
         let diffs_dir = tabdiff_dir.join("diffs");
         
         Ok(Self {
@@ -96,9 +231,22 @@ impl TabdiffWorkspace {
         (archive_path, json_path)
     }
     
+    /// Get path of the row hash checkpoint kept while snapshot `name` is being created
+    pub fn checkpoint_path(&self, name: &str) -> PathBuf {
+        self.tabdiff_dir.join(format!("{}.checkpoint", name))
+    }
+    
     /// Get path for a diff result
     pub fn diff_path(&self, name1: &str, name2: &str) -> PathBuf {
-        self.diffs_dir.join(format!("{}-{}.json", name1, name2))
+        Self::diff_path_in(&self.diffs_dir, name1, name2, None)
+    }
+    
+    /// Get path for a diff result in `dir`, with an optional suffix after the snapshot names
+    pub fn diff_path_in(dir: &Path, name1: &str, name2: &str, suffix: Option<&str>) -> PathBuf {
+        match suffix {
+            Some(suffix) => dir.join(format!("{}-{}-{}.json", name1, name2, suffix)),
+            None => dir.join(format!("{}-{}.json", name1, name2)),
+        }
     }
     
     /// List all available snapshots
@@ -149,11 +297,7 @@ impl TabdiffWorkspace {
                     snapshot_source_path == source_path
                 } else if let Some(snapshot_source) = metadata.get("source").and_then(|v| v.as_str()) {
                     // Legacy snapshot without source_path, check original source field
-                    let snapshot_canonical_path = std::path::Path::new(snapshot_source)
-                        .canonicalize()
-                        .unwrap_or_else(|_| std::path::PathBuf::from(snapshot_source))
-                        .to_string_lossy()
-                        .to_string();
+                    let snapshot_canonical_path = crate::snapshot::canonical_source_path(std::path::Path::new(snapshot_source));
                     
                     snapshot_canonical_path == source_path
                 } else {
@@ -252,25 +396,104 @@ impl TabdiffWorkspace {
             return Ok(()); // Don't overwrite existing config unless forced
         }
         
+        let settings = WorkspaceSettings::load(&self.root)?;
         let config = serde_json::json!({
             "version": crate::FORMAT_VERSION,
             "created": chrono::Utc::now(),
             "default_batch_size": crate::DEFAULT_BATCH_SIZE,
-            "default_sample_size": crate::DEFAULT_SAMPLE_SIZE
+            "default_sample_size": crate::DEFAULT_SAMPLE_SIZE,
+            "dir": settings.dir,
+            "gitignore": settings.gitignore
         });
         
         fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
         Ok(())
     }
     
+    /// Default baseline snapshot recorded for a source with `set-baseline`
+    pub fn baseline_for_source(&self, source_path: &str) -> Result<Option<String>> {
+        let config_path = self.tabdiff_dir.join("config.json");
+        if !config_path.exists() {
+            return Ok(None);
+        }
+        
+        let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+        Ok(config
+            .get("baselines")
+            .and_then(|baselines| baselines.get(source_path))
+            .and_then(|name| name.as_str())
+            .map(|name| name.to_string()))
+    }
+    
+    /// Record the default baseline snapshot for a source, or clear it with `None`
+    pub fn set_baseline(&self, source_path: &str, snapshot: Option<&str>) -> Result<()> {
+        self.create_config()?;
+        let config_path = self.tabdiff_dir.join("config.json");
+        let mut config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path)?)?;
+        
+        let baselines = config
+            .as_object_mut()
+            .map(|object| object.entry("baselines").or_insert_with(|| serde_json::json!({})))
+            .and_then(|baselines| baselines.as_object_mut())
+            .ok_or_else(|| crate::error::TabdiffError::config(format!(
+                "Invalid workspace config: {}",
+                config_path.display()
+            )))?;
+        match snapshot {
+            Some(name) => {
+                baselines.insert(source_path.to_string(), serde_json::Value::String(name.to_string()));
+            }
+            None => {
+                baselines.remove(source_path);
+            }
+        }
+        
+        fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
+        Ok(())
+    }
+    
+    /// Option defaults set with `tabdiff config`
+    pub fn defaults(&self) -> Result<WorkspaceDefaults> {
+        let config_path = self.tabdiff_dir.join("config.json");
+        if !config_path.exists() {
+            return Ok(WorkspaceDefaults::default());
+        }
+        
+        let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+        match config.get("defaults") {
+            Some(defaults) => Ok(serde_json::from_value(defaults.clone())?),
+            None => Ok(WorkspaceDefaults::default()),
+        }
+    }
+    
+    /// Replace the option defaults in the workspace config
+    pub fn save_defaults(&self, defaults: &WorkspaceDefaults) -> Result<()> {
+        self.create_config()?;
+        let config_path = self.tabdiff_dir.join("config.json");
+        let mut config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path)?)?;
+        
+        let object = config.as_object_mut().ok_or_else(|| crate::error::TabdiffError::config(format!(
+            "Invalid workspace config: {}",
+            config_path.display()
+        )))?;
+        object.insert("defaults".to_string(), serde_json::to_value(defaults)?);
+        
+        fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
+        Ok(())
+    }
+    
     /// Ensure .gitignore contains tabdiff entries
     pub fn ensure_gitignore(&self) -> Result<()> {
         let gitignore_path = self.root.join(".gitignore");
-        let tabdiff_ignore = "# Ignore compressed snapshot archives\n.tabdiff/*.tabdiff\n";
+        let dir_name = self.tabdiff_dir.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(default_workspace_dir);
+        let archive_pattern = format!("{}/*.tabdiff", dir_name);
+        let tabdiff_ignore = format!("# Ignore compressed snapshot archives\n{}\n", archive_pattern);
         
         if gitignore_path.exists() {
             let content = fs::read_to_string(&gitignore_path)?;
-            if !content.contains(".tabdiff/*.tabdiff") {
+            if !content.contains(&archive_pattern) {
                 let new_content = if content.ends_with('\n') {
                     format!("{}\n{}", content, tabdiff_ignore)
                 } else {
@@ -365,6 +588,7 @@ impl TabdiffWorkspace {
                     stats.archives_removed += 1;
                     stats.bytes_freed += metadata.len();
                 }
+                self.release_archive(name)?;
                 fs::remove_file(archive_path)?;
             }
             
@@ -374,6 +598,267 @@ impl TabdiffWorkspace {
         
         Ok(stats)
     }
+
+    /// Snapshot other than `exclude` whose archive stores row data with blake3 hash `data_hash`
+    ///
+    /// Only archives holding the data themselves qualify, so references never chain.
+    pub fn find_payload(&self, data_hash: &str, exclude: &str) -> Result<Option<String>> {
+        for name in self.snapshots_with_data_hash(Some(data_hash))? {
+            let (archive_path, _) = self.snapshot_paths(&name);
+            if name != exclude && archive_path.exists() && crate::archive::ArchiveManager::list_archive_contents(&archive_path)?
+                .iter()
+                .any(|entry| entry.path == "data.parquet")
+            {
+                return Ok(Some(name));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Move content other archives reference from `name`'s archive, before that archive is removed
+    ///
+    /// The first referencing archive takes a copy of the content and the others are pointed at it.
+    pub fn release_archive(&self, name: &str) -> Result<()> {
+        use crate::archive::{ArchiveManager, PayloadReference};
+
+        let (archive_path, json_path) = self.snapshot_paths(name);
+        if !archive_path.exists() {
+            return Ok(());
+        }
+        // Archives without metadata (gc orphans) may be referenced by any snapshot with full data
+        let data_hash = crate::snapshot::SnapshotLoader::load_metadata(&json_path)
+            .ok()
+            .and_then(|metadata| metadata.data_hash);
+        let mut payloads: Option<Vec<(String, Vec<u8>)>> = None;
+        let mut new_holders: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+
+        for referrer in self.snapshots_with_data_hash(data_hash.as_deref())? {
+            let (referrer_archive_path, referrer_json_path) = self.snapshot_paths(&referrer);
+            if referrer == name || !referrer_archive_path.exists() {
+                continue;
+            }
+            let mut entries = ArchiveManager::extract_archive_entries(&referrer_archive_path)?;
+            let mut changed = false;
+            for entry in entries.iter_mut() {
+                let Some((entry_name, reference)) = ArchiveManager::parse_reference(entry)? else {
+                    continue;
+                };
+                if reference.snapshot != name {
+                    continue;
+                }
+                *entry = match new_holders.get(&entry_name) {
+                    Some(holder) => ArchiveManager::reference_entry(&entry_name, &PayloadReference {
+                        snapshot: holder.clone(),
+                        hash: reference.hash,
+                    })?,
+                    None => {
+                        if payloads.is_none() {
+                            payloads = Some(ArchiveManager::extract_archive(&archive_path)?);
+                        }
+                        let content = payloads.iter().flatten()
+                            .find(|(path, _)| *path == entry_name)
+                            .map(|(_, content)| content.clone())
+                            .ok_or_else(|| crate::error::TabdiffError::archive(format!(
+                                "Snapshot '{}' references {} in '{}', which does not hold it",
+                                referrer, entry_name, name
+                            )))?;
+                        new_holders.insert(entry_name.clone(), referrer.clone());
+                        (entry_name, content)
+                    }
+                };
+                changed = true;
+            }
+            if !changed {
+                continue;
+            }
+
+            let mut metadata = crate::snapshot::SnapshotLoader::load_metadata(&referrer_json_path)?;
+            ArchiveManager::create_archive_with_progress(&referrer_archive_path, &entries, metadata.compression_level, None)?;
+            metadata.archive_size = Some(fs::metadata(&referrer_archive_path)?.len());
+            fs::write(&referrer_json_path, serde_json::to_string_pretty(&metadata)?)?;
+            log::info!("Moved data shared with '{}' into the archive of '{}'", name, referrer);
+        }
+        Ok(())
+    }
+
+    /// Snapshots recording row data with hash `data_hash`, or with any hash when it is None
+    fn snapshots_with_data_hash(&self, data_hash: Option<&str>) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for name in self.list_snapshots()? {
+            let (_, json_path) = self.snapshot_paths(&name);
+            let Ok(metadata) = crate::snapshot::SnapshotLoader::load_metadata(&json_path) else {
+                continue;
+            };
+            if metadata.data_hash.is_some() && (data_hash.is_none() || metadata.data_hash.as_deref() == data_hash) {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Find diff files and archives that no snapshot accounts for any more
+    ///
+    /// A diff is orphaned when a snapshot it compares no longer exists, and an archive when its
+    /// metadata JSON is gone. Metadata without an archive is left alone: cleanup and hash-only
+    /// snapshots produce it deliberately. Files of snapshots in the chain are never returned.
+    pub fn find_orphaned_files(&self) -> Result<Vec<OrphanedFile>> {
+        let chain = crate::snapshot::SnapshotChain::build_chain(self)?;
+        let snapshots: std::collections::HashSet<&str> = chain.snapshots.iter().map(|s| s.name.as_str()).collect();
+        let mut orphans = Vec::new();
+        
+        if self.tabdiff_dir.exists() {
+            for entry in fs::read_dir(&self.tabdiff_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("tabdiff") || !path.is_file() {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                if !snapshots.contains(name) {
+                    orphans.push(OrphanedFile::new(path.clone(), "archive without snapshot metadata".to_string())?);
+                }
+            }
+        }
+        
+        if self.diffs_dir.exists() {
+            for entry in fs::read_dir(&self.diffs_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") || !path.is_file() {
+                    continue;
+                }
+                if let Some(missing) = Self::missing_diff_snapshot(&path, &snapshots) {
+                    orphans.push(OrphanedFile::new(path.clone(), format!("compares missing snapshot '{}'", missing))?);
+                }
+            }
+        }
+        
+        orphans.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(orphans)
+    }
+    
+    /// Name of a snapshot a diff file compares that no longer exists, if any
+    ///
+    /// Uses the `base`/`compare` fields when the diff is readable, otherwise the
+    /// `<base>-<compare>.json` file name, which is ambiguous when names contain '-'.
+    fn missing_diff_snapshot(path: &Path, snapshots: &std::collections::HashSet<&str>) -> Option<String> {
+        let recorded = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|diff| Some((diff.get("base")?.as_str()?.to_string(), diff.get("compare")?.as_str()?.to_string())));
+        if let Some((base, compare)) = recorded {
+            return [base, compare].into_iter().find(|name| !snapshots.contains(name.as_str()));
+        }
+        
+        let stem = path.file_stem()?.to_str()?;
+        let split_matches = stem.match_indices('-').any(|(idx, _)| {
+            snapshots.contains(&stem[..idx]) && snapshots.contains(&stem[idx + 1..])
+        });
+        (!split_matches).then(|| stem.to_string())
+    }
+    
+    /// Delete the given orphaned files
+    pub fn remove_orphaned_files(&self, orphans: &[OrphanedFile]) -> Result<GcStats> {
+        let mut stats = GcStats::default();
+        for orphan in orphans {
+            // Snapshots may still reference row data stored in an orphaned archive
+            if orphan.path.extension().and_then(|ext| ext.to_str()) == Some("tabdiff") {
+                if let Some(name) = orphan.path.file_stem().and_then(|stem| stem.to_str()) {
+                    self.release_archive(name)?;
+                }
+            }
+            fs::remove_file(&orphan.path)?;
+            stats.files_removed += 1;
+            stats.bytes_freed += orphan.size;
+            log::info!("Removed orphaned file: {}", orphan.path.display());
+        }
+        Ok(stats)
+    }
+}
+
+/// Extra component in default diff filenames, so diffs between equally named snapshots don't overwrite each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffNameSuffix {
+    /// UTC time the diff was written
+    Timestamp,
+    /// Short hash of the two sides' sources; stable across runs
+    SourceHash,
+}
+
+impl DiffNameSuffix {
+    pub fn parse(s: &str) -> std::result::Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "timestamp" => Ok(Self::Timestamp),
+            "source-hash" => Ok(Self::SourceHash),
+            _ => Err(format!("Invalid diff name suffix: {}. Use 'timestamp' or 'source-hash'", s)),
+        }
+    }
+    
+    /// The filename component for a diff between `baseline_source` and `current_source`
+    pub fn render(&self, baseline_source: &str, current_source: &str) -> String {
+        match self {
+            Self::Timestamp => chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ").to_string(),
+            Self::SourceHash => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(baseline_source.as_bytes());
+                hasher.update(&[0]);
+                hasher.update(current_source.as_bytes());
+                hasher.finalize().to_hex()[..12].to_string()
+            }
+        }
+    }
+}
+
+/// A workspace file that no existing snapshot accounts for
+#[derive(Debug, Clone)]
+pub struct OrphanedFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Why the file is considered orphaned
+    pub reason: String,
+}
+
+impl OrphanedFile {
+    fn new(path: PathBuf, reason: String) -> Result<Self> {
+        let size = fs::metadata(&path)?.len();
+        Ok(Self { path, size, reason })
+    }
+}
+
+/// A workspace in a private temp directory, removed with all its snapshots when dropped
+///
+/// For scripted one-off comparisons that should leave no `.tabdiff` directory behind.
+#[derive(Debug)]
+pub struct TemporaryWorkspace {
+    workspace: TabdiffWorkspace,
+}
+
+impl TemporaryWorkspace {
+    pub fn new() -> Result<Self> {
+        let root = std::env::temp_dir().join(format!(
+            "tabdiff-workspace-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&root)?;
+        let workspace = TabdiffWorkspace::create_new(root)?;
+        Ok(Self { workspace })
+    }
+}
+
+impl std::ops::Deref for TemporaryWorkspace {
+    type Target = TabdiffWorkspace;
+
+    fn deref(&self) -> &TabdiffWorkspace {
+        &self.workspace
+    }
+}
+
+impl Drop for TemporaryWorkspace {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.workspace.root);
+    }
 }
 
 /// Statistics about the workspace
@@ -393,6 +878,13 @@ pub struct CleanupStats {
     pub bytes_freed: u64,
 }
 
+/// Statistics about garbage collection
+#[derive(Debug, Default)]
+pub struct GcStats {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,546 @@
+//! Tabular writers for exporting change detection results and delimited data
+
+use crate::change_detection::{ChangeDetectionResult, RowUnchanged};
+use crate::error::{Result, TabdiffError};
+use crate::hash::ColumnInfo;
+use parquet::basic::{ConvertedType, Repetition, Type as PhysicalType};
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::Type;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A single change in flat, tabular form: one row per changed cell or schema element
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub change_type: String,
+    pub row_index: Option<u64>,
+    /// `--key` values of the changed row
+    pub key: Option<String>,
+    pub column: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl ChangeEvent {
+    fn schema(change_type: &str, column: Option<&str>, before: Option<String>, after: Option<String>) -> Self {
+        Self {
+            change_type: change_type.to_string(),
+            row_index: None,
+            key: None,
+            column: column.map(|c| c.to_string()),
+            before,
+            after,
+        }
+    }
+
+    fn cell(change_type: &str, row_index: u64, key: Option<String>, column: &str, before: Option<String>, after: Option<String>) -> Self {
+        Self {
+            change_type: change_type.to_string(),
+            row_index: Some(row_index),
+            key,
+            column: Some(column.to_string()),
+            before,
+            after,
+        }
+    }
+
+    /// Values of the event's `EVENT_FIELDS`, as delimited exports write them
+    pub fn fields(&self) -> Vec<String> {
+        vec![
+            self.change_type.clone(),
+            self.row_index.map_or_else(|| ALL.to_string(), |i| i.to_string()),
+            self.key.clone().unwrap_or_default(),
+            self.column.clone().unwrap_or_else(|| ALL.to_string()),
+            self.before.clone().unwrap_or_default(),
+            self.after.clone().unwrap_or_default(),
+        ]
+    }
+
+    /// Flatten a change detection result into events (schema changes first, then rows)
+    pub fn flatten(changes: &ChangeDetectionResult, keys: &RowKeys) -> Vec<ChangeEvent> {
+        let mut events = Vec::new();
+        let schema = &changes.schema_changes;
+
+        if let Some(order) = &schema.column_order {
+            events.push(Self::schema("column_order", None, Some(order.before.join(",")), Some(order.after.join(","))));
+        }
+        for addition in &schema.columns_added {
+            events.push(Self::schema("column_added", Some(&addition.name), None, Some(addition.data_type.clone())));
+        }
+        for removal in &schema.columns_removed {
+            events.push(Self::schema("column_removed", Some(&removal.name), Some(removal.data_type.clone()), None));
+        }
+        for rename in &schema.columns_renamed {
+            events.push(Self::schema("column_renamed", Some(&rename.to), Some(rename.from.clone()), Some(rename.to.clone())));
+        }
+        for type_change in &schema.type_changes {
+            events.push(Self::schema("type_changed", Some(&type_change.column), Some(type_change.from.clone()), Some(type_change.to.clone())));
+        }
+
+        for modification in &changes.row_changes.modified {
+            let key = keys.of_modified(modification.row_index);
+            for (column, change) in sorted_entries(&modification.changes) {
+                events.push(Self::cell("modified", modification.row_index, key.clone(), column, Some(change.before.clone()), Some(change.after.clone())));
+            }
+        }
+        for addition in &changes.row_changes.added {
+            let key = keys.of_data(&addition.data);
+            for (column, value) in sorted_entries(&addition.data) {
+                events.push(Self::cell("added", addition.row_index, key.clone(), column, None, Some(value.clone())));
+            }
+        }
+        for removal in &changes.row_changes.removed {
+            let key = keys.of_data(&removal.data);
+            for (column, value) in sorted_entries(&removal.data) {
+                events.push(Self::cell("removed", removal.row_index, key.clone(), column, Some(value.clone()), None));
+            }
+        }
+
+        events
+    }
+
+    /// Flatten unchanged rows into events whose before and after are the same value
+    pub fn flatten_unchanged(rows: &[RowUnchanged], keys: &RowKeys) -> Vec<ChangeEvent> {
+        let mut events = Vec::new();
+        for row in rows {
+            let key = keys.of_data(&row.data);
+            for (column, value) in sorted_entries(&row.data) {
+                events.push(Self::cell("unchanged", row.row_index, key.clone(), column, Some(value.clone()), Some(value.clone())));
+            }
+        }
+        events
+    }
+}
+
+/// `--key` values of changed rows, for the `key` field of change events
+///
+/// Multi-column keys are joined with `|`. Added, removed and unchanged rows carry all their
+/// values; a modification only records its changed cells, so its key is looked up up front.
+#[derive(Debug, Clone, Default)]
+pub struct RowKeys {
+    columns: Vec<String>,
+    modified: HashMap<u64, String>,
+}
+
+impl RowKeys {
+    pub fn new(columns: Vec<String>) -> Self {
+        Self { columns, modified: HashMap::new() }
+    }
+
+    /// Record the key of the modified row reported as `row_index` from its current values
+    pub fn with_modified_row(mut self, row_index: u64, schema: &[ColumnInfo], row: &[String]) -> Self {
+        let value_of = |column: &str| schema.iter()
+            .position(|col| col.name == column)
+            .and_then(|i| row.get(i))
+            .map(|value| value.as_str());
+        if let Some(key) = self.join(value_of) {
+            self.modified.insert(row_index, key);
+        }
+        self
+    }
+
+    fn of_modified(&self, row_index: u64) -> Option<String> {
+        self.modified.get(&row_index).cloned()
+    }
+
+    fn of_data(&self, data: &HashMap<String, String>) -> Option<String> {
+        self.join(|column| data.get(column).map(|value| value.as_str()))
+    }
+
+    fn join<'a>(&self, value_of: impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        let values: Vec<&str> = self.columns.iter().map(|column| value_of(column).unwrap_or("")).collect();
+        Some(values.join("|"))
+    }
+}
+
+/// HashMap entries in column-name order so exports are deterministic
+fn sorted_entries<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Field names of a change event, in the order every tabular export writes them
+const EVENT_FIELDS: [&str; 6] = ["change_type", "row_index", "key", "column", "before", "after"];
+
+/// Row index and column of a delimited event that applies to every row or column
+const ALL: &str = "*";
+
+/// Columns of a change event export, as a schema for `DataWriter::write_rows`
+fn event_schema() -> Vec<ColumnInfo> {
+    EVENT_FIELDS.iter()
+        .map(|name| ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+        .collect()
+}
+
+/// Writer for tabular output: snapshot rows or flattened change events
+///
+/// Implement `write_rows` to add an output format and register it with [`register_writer`];
+/// change events are written as rows of `EVENT_FIELDS` unless a format overrides `write_events`.
+pub trait DataWriter {
+    /// Write `rows` under `schema`'s columns, with a header row if the format has one
+    fn write_rows(&self, path: &Path, schema: &[ColumnInfo], rows: &[Vec<String>], include_header: bool) -> Result<()>;
+
+    fn write_events(&self, path: &Path, events: &[ChangeEvent]) -> Result<()> {
+        let rows: Vec<Vec<String>> = events.iter().map(ChangeEvent::fields).collect();
+        self.write_rows(path, &event_schema(), &rows, true)
+    }
+}
+
+/// Creates a writer, given the `--delimiter` override
+pub type WriterFactory = fn(Option<char>) -> Box<dyn DataWriter>;
+
+/// A registered output format
+struct WriterFormat {
+    name: String,
+    extensions: Vec<String>,
+    factory: WriterFactory,
+}
+
+impl WriterFormat {
+    fn new(name: &str, extensions: &[&str], factory: WriterFactory) -> Self {
+        Self {
+            name: name.to_lowercase(),
+            extensions: extensions.iter().map(|ext| ext.trim_start_matches('.').to_lowercase()).collect(),
+            factory,
+        }
+    }
+}
+
+/// Output formats shared by `export`, `rollback` and `diff -o`, latest registration first
+fn formats() -> &'static RwLock<Vec<WriterFormat>> {
+    static FORMATS: OnceLock<RwLock<Vec<WriterFormat>>> = OnceLock::new();
+    FORMATS.get_or_init(|| RwLock::new(vec![
+        WriterFormat::new("parquet", &["parquet"], |_| Box::new(ParquetWriter)),
+        WriterFormat::new("tsv", &["tsv"], |delimiter| Box::new(CsvWriter::new(delimiter.unwrap_or('\t')))),
+        WriterFormat::new("csv", &["csv"], |delimiter| Box::new(CsvWriter::new(delimiter.unwrap_or(',')))),
+    ]))
+}
+
+/// Register an output format under `name` and the given file extensions
+///
+/// A format registered under an existing name replaces it, and extensions of later
+/// registrations take precedence over earlier ones (including the built-in formats).
+pub fn register_writer(name: &str, extensions: &[&str], factory: WriterFactory) {
+    let format = WriterFormat::new(name, extensions, factory);
+    let mut formats = formats().write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    formats.retain(|existing| existing.name != format.name);
+    formats.insert(0, format);
+}
+
+/// Names of the registered output formats, sorted
+pub fn writer_formats() -> Vec<String> {
+    let formats = formats().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut names: Vec<String> = formats.iter().map(|format| format.name.clone()).collect();
+    names.sort();
+    names
+}
+
+/// Writer for a format name such as `--format parquet`
+///
+/// `delimiter` overrides the field separator of delimited formats.
+pub fn writer_for_format(name: &str, delimiter: Option<char>) -> Result<Box<dyn DataWriter>> {
+    let formats = formats().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let name = name.to_lowercase();
+    match formats.iter().find(|format| format.name == name) {
+        Some(format) => Ok((format.factory)(delimiter)),
+        None => {
+            drop(formats);
+            Err(TabdiffError::invalid_input(format!(
+                "Unknown output format: '{}'. Available formats: {}", name, writer_formats().join(", ")
+            )))
+        }
+    }
+}
+
+/// Pick a tabular writer from the output file extension (None means JSON)
+///
+/// `delimiter` overrides the field separator of delimited formats.
+pub fn writer_for_path(path: &Path, delimiter: Option<char>) -> Option<Box<dyn DataWriter>> {
+    let extension = path.extension().and_then(|ext| ext.to_str())?.to_lowercase();
+    let formats = formats().read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    formats.iter()
+        .find(|format| format.extensions.contains(&extension))
+        .map(|format| (format.factory)(delimiter))
+}
+
+/// Field separator implied by a file extension: tab for `.tsv`, comma otherwise
+pub fn delimiter_for_path(path: &Path) -> char {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("tsv") => '\t',
+        _ => ',',
+    }
+}
+
+/// Quote a field when it contains the delimiter, a quote or a line break
+fn escape_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Delimited text of `rows` under `schema`'s columns, with an optional header row
+///
+/// Rows shorter than the schema are padded with empty fields. Without columns there is no
+/// header row to write, so the content is empty rather than a lone newline.
+pub fn delimited_content(
+    schema: &[crate::hash::ColumnInfo],
+    rows: &[Vec<String>],
+    include_header: bool,
+    delimiter: char,
+) -> String {
+    let separator = delimiter.to_string();
+    let mut content = String::new();
+    if include_header && !schema.is_empty() {
+        let headers: Vec<String> = schema.iter().map(|col| escape_field(&col.name, delimiter)).collect();
+        content.push_str(&headers.join(&separator));
+        content.push('\n');
+    }
+    for row in rows {
+        let fields: Vec<String> = (0..schema.len())
+            .map(|i| escape_field(row.get(i).map_or("", |value| value.as_str()), delimiter))
+            .collect();
+        content.push_str(&fields.join(&separator));
+        content.push('\n');
+    }
+    content
+}
+
+/// Delimited text; change events get a `change_type,row_index,key,column,before,after` header
+///
+/// Schema changes apply to every row, so their `row_index` is `*`, as is the `column` of a
+/// column reorder.
+pub struct CsvWriter {
+    delimiter: char,
+}
+
+impl CsvWriter {
+    pub fn new(delimiter: char) -> Self {
+        Self { delimiter }
+    }
+
+    fn escape(&self, value: &str) -> String {
+        escape_field(value, self.delimiter)
+    }
+}
+
+impl CsvWriter {
+    /// Write the header and one line per event to `out`
+    pub fn write_to(&self, out: &mut impl Write, events: &[ChangeEvent]) -> Result<()> {
+        let separator = self.delimiter.to_string();
+        writeln!(out, "{}", EVENT_FIELDS.join(&separator))?;
+        for event in events {
+            let fields: Vec<String> = event.fields().iter().map(|field| self.escape(field)).collect();
+            writeln!(out, "{}", fields.join(&separator))?;
+        }
+        Ok(())
+    }
+}
+
+impl DataWriter for CsvWriter {
+    fn write_rows(&self, path: &Path, schema: &[ColumnInfo], rows: &[Vec<String>], include_header: bool) -> Result<()> {
+        std::fs::write(path, delimited_content(schema, rows, include_header, self.delimiter))?;
+        Ok(())
+    }
+
+    fn write_events(&self, path: &Path, events: &[ChangeEvent]) -> Result<()> {
+        self.write_to(&mut File::create(path)?, events)
+    }
+}
+
+/// Parquet file of string columns, or of typed columns with one row per change event
+pub struct ParquetWriter;
+
+impl ParquetWriter {
+    const SCHEMA: &'static str = "
+        message change_event {
+            REQUIRED BYTE_ARRAY change_type (UTF8);
+            OPTIONAL INT64 row_index;
+            OPTIONAL BYTE_ARRAY key (UTF8);
+            OPTIONAL BYTE_ARRAY column (UTF8);
+            OPTIONAL BYTE_ARRAY before (UTF8);
+            OPTIONAL BYTE_ARRAY after (UTF8);
+        }
+    ";
+
+    /// Split optional strings into non-null values and definition levels
+    fn optional_strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> (Vec<ByteArray>, Vec<i16>) {
+        let mut present = Vec::new();
+        let mut levels = Vec::new();
+        for value in values {
+            match value {
+                Some(v) => {
+                    present.push(ByteArray::from(v));
+                    levels.push(1);
+                }
+                None => levels.push(0),
+            }
+        }
+        (present, levels)
+    }
+}
+
+impl DataWriter for ParquetWriter {
+    /// Every column is a required UTF8 string; Parquet always stores column names
+    fn write_rows(&self, path: &Path, schema: &[ColumnInfo], rows: &[Vec<String>], _include_header: bool) -> Result<()> {
+        let fields = schema.iter()
+            .map(|col| {
+                Type::primitive_type_builder(&col.name, PhysicalType::BYTE_ARRAY)
+                    .with_repetition(Repetition::REQUIRED)
+                    .with_converted_type(ConvertedType::UTF8)
+                    .build()
+                    .map(Arc::new)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let message = Arc::new(Type::group_type_builder("row").with_fields(fields).build()?);
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(File::create(path)?, message, props)?;
+        let mut row_group = writer.next_row_group()?;
+
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column()? {
+            let values: Vec<ByteArray> = rows.iter()
+                .map(|row| ByteArray::from(row.get(index).map_or("", |value| value.as_str())))
+                .collect();
+            column.typed::<ByteArrayType>().write_batch(&values, None, None)?;
+            column.close()?;
+            index += 1;
+        }
+
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn write_events(&self, path: &Path, events: &[ChangeEvent]) -> Result<()> {
+        let schema = Arc::new(parse_message_type(Self::SCHEMA)?);
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(File::create(path)?, schema, props)?;
+        let mut row_group = writer.next_row_group()?;
+
+        // change_type (required)
+        if let Some(mut column) = row_group.next_column()? {
+            let values: Vec<ByteArray> = events.iter().map(|e| ByteArray::from(e.change_type.as_str())).collect();
+            column.typed::<ByteArrayType>().write_batch(&values, None, None)?;
+            column.close()?;
+        }
+
+        // row_index (optional)
+        if let Some(mut column) = row_group.next_column()? {
+            let values: Vec<i64> = events.iter().filter_map(|e| e.row_index.map(|i| i as i64)).collect();
+            let levels: Vec<i16> = events.iter().map(|e| if e.row_index.is_some() { 1 } else { 0 }).collect();
+            column.typed::<Int64Type>().write_batch(&values, Some(&levels), None)?;
+            column.close()?;
+        }
+
+        // key, column, before, after (optional strings)
+        let string_columns: [fn(&ChangeEvent) -> Option<&str>; 4] = [
+            |e| e.key.as_deref(),
+            |e| e.column.as_deref(),
+            |e| e.before.as_deref(),
+            |e| e.after.as_deref(),
+        ];
+        for accessor in string_columns {
+            if let Some(mut column) = row_group.next_column()? {
+                let (values, levels) = Self::optional_strings(events.iter().map(accessor));
+                column.typed::<ByteArrayType>().write_batch(&values, Some(&levels), None)?;
+                column.close()?;
+            }
+        }
+
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_for_path() {
+        assert!(writer_for_path(Path::new("changes.parquet"), None).is_some());
+        assert!(writer_for_path(Path::new("changes.CSV"), None).is_some());
+        assert!(writer_for_path(Path::new("changes.tsv"), None).is_some());
+        assert!(writer_for_path(Path::new("changes.json"), None).is_none());
+        assert!(writer_for_path(Path::new("changes"), None).is_none());
+        assert_eq!(delimiter_for_path(Path::new("changes.TSV")), '\t');
+        assert_eq!(delimiter_for_path(Path::new("changes.csv")), ',');
+    }
+
+    #[test]
+    fn test_registered_writer_is_found_by_name_and_extension() {
+        struct NullWriter;
+        impl DataWriter for NullWriter {
+            fn write_rows(&self, _: &Path, _: &[ColumnInfo], _: &[Vec<String>], _: bool) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        register_writer("Null", &[".null"], |_| Box::new(NullWriter));
+        assert!(writer_formats().contains(&"null".to_string()));
+        assert!(writer_for_format("NULL", None).is_ok());
+        assert!(writer_for_path(Path::new("rows.null"), None).is_some());
+
+        let err = writer_for_format("avro", None).err().unwrap().to_string();
+        assert!(err.contains("Unknown output format: 'avro'"), "{}", err);
+        assert!(err.contains("csv, "), "{}", err);
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let csv = CsvWriter::new(',');
+        assert_eq!(csv.escape("plain"), "plain");
+        assert_eq!(csv.escape("a,b"), "\"a,b\"");
+        assert_eq!(csv.escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+
+        let tsv = CsvWriter::new('\t');
+        assert_eq!(tsv.escape("a,b"), "a,b");
+        assert_eq!(tsv.escape("a\tb"), "\"a\tb\"");
+    }
+
+    #[test]
+    fn test_csv_events_have_stable_columns() {
+        let mut changes = ChangeDetectionResult::default();
+        changes.schema_changes.column_order = Some(crate::change_detection::ColumnOrderChange {
+            before: vec!["id".to_string(), "name".to_string()],
+            after: vec!["name".to_string(), "id".to_string()],
+        });
+        changes.row_changes.added.push(crate::change_detection::RowAddition {
+            row_index: 2,
+            data: HashMap::from([("id".to_string(), "7".to_string()), ("name".to_string(), "Smith, Jo".to_string())]),
+        });
+        let events = ChangeEvent::flatten(&changes, &RowKeys::new(vec!["id".to_string()]));
+
+        let mut out = Vec::new();
+        CsvWriter::new(',').write_to(&mut out, &events).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), concat!(
+            "change_type,row_index,key,column,before,after\n",
+            "column_order,*,,*,\"id,name\",\"name,id\"\n",
+            "added,2,7,id,,7\n",
+            "added,2,7,name,,\"Smith, Jo\"\n",
+        ));
+    }
+
+    #[test]
+    fn test_delimited_content_without_rows() {
+        let schema: Vec<crate::hash::ColumnInfo> = ["id", "name"].iter()
+            .map(|name| crate::hash::ColumnInfo { name: name.to_string(), data_type: "VARCHAR".to_string(), nullable: true })
+            .collect();
+        assert_eq!(delimited_content(&schema, &[], true, ','), "id,name\n");
+        assert_eq!(delimited_content(&schema, &[], false, ','), "");
+        assert_eq!(delimited_content(&[], &[], true, ','), "");
+    }
+}
@@ -151,6 +151,13 @@ impl TestFixture {
         self.create_csv(name, &data)
     }
 
+    /// The JSON metadata file of snapshot `name`, parsed
+    pub fn snapshot_metadata(&self, name: &str) -> serde_json::Value {
+        let (_, json_path) = self.workspace.snapshot_paths(name);
+        serde_json::from_str(&fs::read_to_string(json_path).expect("Snapshot metadata should exist"))
+            .expect("Snapshot metadata should be JSON")
+    }
+
     /// Assert that a snapshot exists
     pub fn assert_snapshot_exists(&self, name: &str) {
         assert!(self.workspace.snapshot_exists(name), "Snapshot '{}' should exist", name);
@@ -181,7 +188,7 @@ impl CliTestRunner {
     /// Run a tabdiff command and return the result
     pub fn run_command(&self, args: &[&str]) -> Result<()> {
         use tabdiff::cli::Cli;
-        use tabdiff::commands::execute_command;
+        use tabdiff::commands::{execute_command, EngineSettings};
         use clap::Parser;
 
         // Build command line arguments
@@ -195,7 +202,7 @@ impl CliTestRunner {
         // Execute command with the workspace path from CLI (if any)
         // If no --workspace flag was provided, use the fixture root as default
         let workspace_path = cli.workspace.as_deref().or(Some(self.fixture.root()));
-        execute_command(cli.command, workspace_path)
+        execute_command(cli.command, workspace_path, &EngineSettings::default())
     }
 
     /// Run a command and expect it to succeed
@@ -211,10 +218,96 @@ impl CliTestRunner {
         String::new()
     }
 
+    /// Write each version's content to `file` in turn and snapshot it under the version's name
+    pub fn snapshot_versions(&self, file: &str, versions: &[(&str, &str)]) -> PathBuf {
+        let path = self.fixture.root().join(file);
+        for (name, content) in versions {
+            fs::write(&path, content).expect("Failed to write test file");
+            self.expect_success(&["snapshot", path.to_str().unwrap(), "--name", name]);
+        }
+        path
+    }
+
+    /// Parse the JSON diff file at `path`, relative to the fixture root unless absolute
+    pub fn read_diff_json(&self, path: impl AsRef<Path>) -> serde_json::Value {
+        let content = fs::read_to_string(self.fixture.root().join(path)).expect("Diff file should exist");
+        serde_json::from_str(&content).expect("Diff file should be JSON")
+    }
+
     /// Run a command and expect it to fail
     pub fn expect_failure(&self, args: &[&str]) -> tabdiff::TabdiffError {
         self.run_command(args).expect_err("Command should fail")
     }
+
+    /// The tabdiff binary pointed at the fixture workspace
+    fn binary_command(&self, args: &[&str]) -> std::process::Command {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_tabdiff"));
+        command.args(["--workspace", self.fixture.root().to_str().unwrap()]).args(args);
+        command
+    }
+
+    /// Run a command to completion and expect success
+    fn expect_binary_success(mut command: std::process::Command) -> std::process::Output {
+        let output = command.output().expect("Failed to run tabdiff binary");
+        assert!(output.status.success(), "tabdiff failed: {}", String::from_utf8_lossy(&output.stderr));
+        output
+    }
+
+    /// Run the tabdiff binary against the fixture workspace, expect success and return stdout
+    pub fn run_binary_stdout(&self, args: &[&str]) -> String {
+        let output = Self::expect_binary_success(self.binary_command(args));
+        String::from_utf8(output.stdout).expect("stdout should be UTF-8")
+    }
+
+    /// Like `run_binary_stdout`, parsing stdout as JSON, e.g. the output of `status --json`
+    pub fn run_binary_json(&self, args: &[&str]) -> serde_json::Value {
+        serde_json::from_str(&self.run_binary_stdout(args)).expect("stdout should be JSON")
+    }
+
+    /// Like `run_binary_stdout`, returning stderr, where progress, warnings and timings go
+    pub fn run_binary_stderr(&self, args: &[&str]) -> String {
+        let output = Self::expect_binary_success(self.binary_command(args));
+        String::from_utf8(output.stderr).expect("stderr should be UTF-8")
+    }
+
+    /// Start the tabdiff binary against the fixture workspace with stdout piped, for commands
+    /// such as `status --watch` whose output is read while they run
+    pub fn spawn_binary(&self, args: &[&str]) -> std::process::Child {
+        self.binary_command(args)
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to run tabdiff binary")
+    }
+
+    /// Run the tabdiff binary in `dir` without --workspace, with TABDIFF_WORKSPACE set to
+    /// `env_workspace` (or unset), expect success and return stdout
+    pub fn run_binary_in(dir: &Path, env_workspace: Option<&Path>, args: &[&str]) -> String {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_tabdiff"));
+        command.args(args).current_dir(dir);
+        match env_workspace {
+            Some(workspace) => command.env("TABDIFF_WORKSPACE", workspace),
+            None => command.env_remove("TABDIFF_WORKSPACE"),
+        };
+        let output = Self::expect_binary_success(command);
+        String::from_utf8(output.stdout).expect("stdout should be UTF-8")
+    }
+
+    /// Like `run_binary_stdout`, feeding `stdin` to the binary, e.g. answers to its prompts
+    pub fn run_binary_with_stdin(&self, args: &[&str], stdin: &str) -> String {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let mut child = self.binary_command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to run tabdiff binary");
+        child.stdin.take().unwrap().write_all(stdin.as_bytes()).expect("Failed to write stdin");
+        let output = child.wait_with_output().expect("Failed to wait for tabdiff binary");
+        assert!(output.status.success(), "tabdiff failed: {}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).expect("stdout should be UTF-8")
+    }
 }
 
 /// Sample data generators for testing
@@ -397,3 +397,94 @@ fn test_extremely_large_single_row() {
     ]);
     runner.fixture().assert_snapshot_exists("large_row");
 }
+
+fn diff_json(runner: &CliTestRunner, snapshot1: &str, snapshot2: &str) -> serde_json::Value {
+    let diff_path = runner.fixture().workspace.diff_path(snapshot1, snapshot2);
+    serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_header_only_csv_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv("empty.csv", &sample_data::empty_table_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "empty"]);
+
+    // The header row names the columns rather than being read as a data row
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("empty");
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.row_count, 0);
+    assert_eq!(metadata.column_count, 3);
+
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--compare-to", "empty", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    assert!(json["schema_changes"]["columns_added"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_between_empty_and_populated_snapshots() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv("data.csv", &sample_data::empty_table_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "empty"]);
+    runner.fixture().create_csv("data.csv", &sample_data::simple_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "populated"]);
+
+    runner.expect_success(&["diff", "empty", "populated"]);
+    let diff = diff_json(&runner, "empty", "populated");
+    assert_eq!(diff["row_changes"], serde_json::json!({"added": 3, "removed": 0, "modified": 0}));
+
+    runner.expect_success(&["diff", "populated", "empty"]);
+    let diff = diff_json(&runner, "populated", "empty");
+    assert_eq!(diff["row_changes"], serde_json::json!({"added": 0, "removed": 3, "modified": 0}));
+
+    // The populated file against the empty snapshot: every row is new
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--compare-to", "empty", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 3);
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_rollback_to_empty_snapshot_keeps_header() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv("data.csv", &sample_data::empty_table_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "empty"]);
+    runner.fixture().create_csv("data.csv", &sample_data::simple_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "populated"]);
+
+    runner.expect_success(&["rollback", csv_path.to_str().unwrap(), "--to", "empty", "--force"]);
+    assert_eq!(fs::read_to_string(&csv_path).unwrap(), "id,name,price\n");
+
+    // The truncated file reads back as the empty snapshot, and rolls forward again
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--compare-to", "empty", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+    runner.expect_success(&["rollback", csv_path.to_str().unwrap(), "--to", "populated", "--force"]);
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "restored"]);
+    runner.expect_success(&["diff", "populated", "restored"]);
+    let diff = diff_json(&runner, "populated", "restored");
+    assert_eq!(diff["rows_changed"], 0);
+}
+
+#[test]
+fn test_csv_with_quote_and_space_in_headers() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().root().join("quoted_headers.csv");
+    fs::write(&csv_path, "id,\"sa\"\"les\",unit price\n2,20,1.50\n1,10,2.25\n").unwrap();
+    
+    // Column names are interpolated into the hashing and ordering queries
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "quoted", "--order-by", "sa\"les"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("quoted");
+    let metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    let names: Vec<&str> = metadata["columns"].as_array().unwrap()
+        .iter()
+        .map(|col| col["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["id", "sa\"les", "unit price"]);
+    assert_eq!(metadata["row_count"], 2);
+    
+    fs::write(&csv_path, "id,\"sa\"\"les\",unit price\n2,20,1.75\n1,10,2.25\n").unwrap();
+    runner.expect_success(&["status", csv_path.to_str().unwrap(), "--compare-to", "quoted"]);
+}
@@ -0,0 +1,79 @@
+//! Tests for `diff --append-only`, which only looks at the rows appended to a growing source
+
+use crate::common::CliTestRunner;
+
+/// Event log with ids `ids`
+fn events_csv(ids: std::ops::Range<usize>) -> String {
+    let mut content = String::from("id,event\n");
+    for id in ids {
+        content.push_str(&format!("{},event-{}\n", id, id));
+    }
+    content
+}
+
+#[test]
+fn test_append_only_reports_exactly_the_appended_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", &events_csv(0..5)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("events.csv", &events_csv(0..8)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--append-only", "-o", "diff.json"]);
+    let diff = runner.read_diff_json("diff.json");
+    assert_eq!(diff["row_changes"], serde_json::json!({ "modified": 0, "added": 3, "removed": 0 }), "Unexpected diff: {}", diff);
+    let added_ids: Vec<&str> = diff["sample_changes"].as_array().unwrap().iter()
+        .map(|change| change["data"]["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(added_ids.len(), 3);
+    assert!(added_ids.iter().all(|id| ["5", "6", "7"].contains(id)), "Unexpected additions: {:?}", added_ids);
+
+    // Only the prefix check ran: no schema detection, row classification or cell analysis
+    let stderr = runner.run_binary_stderr(&["diff", "v1", "v2", "--append-only", "--timings"]);
+    assert!(stderr.contains("prefix check"), "Unexpected stderr: {}", stderr);
+    for phase in ["detect schema", "classify rows", "cell analysis"] {
+        assert!(!stderr.contains(phase), "Unexpected phase {} in: {}", phase, stderr);
+    }
+}
+
+#[test]
+fn test_append_only_against_a_data_file() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", &events_csv(0..5)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("events.csv", &events_csv(0..7)).unwrap();
+
+    let output = runner.run_binary_stdout(&["diff", "v1", path.to_str().unwrap(), "--current-format", "csv", "--append-only", "-o", "diff.json"]);
+    assert!(output.contains("compared only the 2 appended rows"), "Unexpected output: {}", output);
+    let diff = runner.read_diff_json("diff.json");
+    assert_eq!(diff["row_changes"], serde_json::json!({ "modified": 0, "added": 2, "removed": 0 }), "Unexpected diff: {}", diff);
+    let added_rows: Vec<u64> = diff["sample_changes"].as_array().unwrap().iter()
+        .map(|change| change["row_index"].as_u64().unwrap())
+        .collect();
+    assert_eq!(added_rows.len(), 2);
+    assert!(added_rows.iter().all(|row| [5, 6].contains(row)), "Unexpected row indices: {:?}", added_rows);
+}
+
+#[test]
+fn test_rewritten_prefix_falls_back_to_full_diff() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", &events_csv(0..5)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    let rewritten = events_csv(0..6).replace("1,event-1\n", "1,event-one\n");
+    runner.fixture().create_csv_raw("events.csv", &rewritten).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--append-only", "-o", "diff.json"]);
+    assert!(output.contains("falling back to a full diff"), "Unexpected output: {}", output);
+    let diff = runner.read_diff_json("diff.json");
+    assert_eq!(diff["row_changes"], serde_json::json!({ "modified": 1, "added": 1, "removed": 0 }), "Unexpected diff: {}", diff);
+}
+
+#[test]
+fn test_append_only_conflicts_with_key() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", &events_csv(0..2)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    runner.expect_failure(&["diff", "v1", "v2", "--append-only", "--key", "id"]);
+}
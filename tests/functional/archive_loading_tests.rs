@@ -0,0 +1,30 @@
+//! Tests for reading single files from snapshot archives without extracting the row data
+
+use crate::common::CliTestRunner;
+use tabdiff::snapshot::SnapshotLoader;
+
+#[test]
+fn test_schema_only_load_does_not_read_row_data() {
+    let runner = CliTestRunner::new().unwrap();
+    let mut csv = String::from("id,name,amount\n");
+    for i in 0..20000 {
+        csv.push_str(&format!("{},customer_{},{}.{:02}\n", i, i, i * 3, i % 100));
+    }
+    let csv_path = runner.fixture().create_csv_raw("large.csv", &csv).unwrap();
+    // Stored uncompressed, so cutting the file leaves the entries before the cut readable
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "large", "--compression-level", "0"]);
+    let (archive_path, _) = runner.fixture().workspace.snapshot_paths("large");
+
+    // data.parquet is the last and by far the largest entry; cutting the archive in half
+    // leaves it incomplete, so any load that reads it fails
+    let archive = std::fs::read(&archive_path).unwrap();
+    std::fs::write(&archive_path, &archive[..archive.len() / 2]).unwrap();
+    assert!(SnapshotLoader::load_full_snapshot(&archive_path).is_err());
+
+    let schema = SnapshotLoader::load_schema_only(&archive_path).unwrap();
+    let names: Vec<&str> = schema.iter().map(|column| column.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "name", "amount"]);
+
+    let metadata = SnapshotLoader::load_metadata_from_archive(&archive_path).unwrap();
+    assert_eq!(metadata["row_count"], 20000);
+}
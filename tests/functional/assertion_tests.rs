@@ -0,0 +1,57 @@
+//! Tests for `snapshot --assert`, which refuses to snapshot data that breaks a quality rule
+
+use crate::common::CliTestRunner;
+
+/// Snapshot `content` as "v1" with `assertions` and return the error, asserting nothing was written
+fn failed_snapshot(content: &str, assertions: &[&str]) -> String {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("orders.csv", content).unwrap();
+    let mut args = vec!["snapshot", path.to_str().unwrap(), "--name", "v1"];
+    for assertion in assertions {
+        args.extend(["--assert", *assertion]);
+    }
+
+    let error = runner.expect_failure(&args).to_string();
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("v1");
+    assert!(!archive_path.exists() && !json_path.exists(), "A snapshot was written despite: {}", error);
+    error
+}
+
+#[test]
+fn test_null_id_fails_no_nulls() {
+    let error = failed_snapshot("id,name\n1,alice\n,bob\n3,carol\n", &["no-nulls=id"]);
+    assert!(error.contains("no-nulls=id: 1 row with NULL id"), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_duplicate_id_fails_unique() {
+    let error = failed_snapshot("id,name\n1,alice\n2,bob\n1,carol\n", &["no-nulls=id", "unique=id"]);
+    assert!(error.contains("1 of 2 data-quality assertions failed"), "Unexpected error: {}", error);
+    assert!(error.contains("unique=id: 1 row"), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_every_violation_is_listed() {
+    let error = failed_snapshot("id,name\n1,alice\n1,\n", &["unique=id", "no-nulls=name", "row-count-min=1000", "no-nulls=email"]);
+    for rule in ["unique=id", "no-nulls=name", "row-count-min=1000: only 2 rows", "no-nulls=email: there is no column 'email'"] {
+        assert!(error.contains(rule), "Missing {} in: {}", rule, error);
+    }
+}
+
+#[test]
+fn test_passing_assertions_write_the_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("orders.csv", "id,name\n1,alice\n2,bob\n").unwrap();
+    runner.expect_success(&[
+        "snapshot", path.to_str().unwrap(), "--name", "v1",
+        "--assert", "no-nulls=id", "--assert", "unique=id,name", "--assert", "row-count-min=2", "--assert", "row-count-max=2",
+    ]);
+    assert!(runner.fixture().workspace.snapshot_paths("v1").1.exists());
+}
+
+#[test]
+fn test_unknown_rule_is_rejected() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("orders.csv", "id\n1\n").unwrap();
+    runner.expect_failure(&["snapshot", path.to_str().unwrap(), "--assert", "positive=id"]);
+}
@@ -0,0 +1,45 @@
+//! Tests for reading `.avro` input through DuckDB's avro extension
+
+use crate::common::CliTestRunner;
+use std::path::{Path, PathBuf};
+
+/// Committed fixture holding the same rows as `users.csv`
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+}
+
+fn stat_json(runner: &CliTestRunner, path: &Path) -> serde_json::Value {
+    let output = runner.run_binary_stdout(&["stat", path.to_str().unwrap(), "--json"]);
+    serde_json::from_str(&output).unwrap()
+}
+
+#[test]
+fn test_avro_matches_equivalent_csv() {
+    let runner = CliTestRunner::new().unwrap();
+    let avro = stat_json(&runner, &fixture_path("users.avro"));
+    let csv = stat_json(&runner, &fixture_path("users.csv"));
+    
+    assert_eq!(avro["row_count"], 3);
+    assert_eq!(avro["row_count"], csv["row_count"]);
+    let names = |json: &serde_json::Value| -> Vec<String> {
+        json["columns"].as_array().unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(names(&avro), vec!["id", "name", "score", "active"]);
+    assert_eq!(names(&avro), names(&csv));
+}
+
+#[test]
+fn test_snapshot_and_status_of_avro_file() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().root().join("users.avro");
+    std::fs::copy(fixture_path("users.avro"), &path).unwrap();
+    
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+}
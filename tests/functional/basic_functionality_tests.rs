@@ -227,4 +227,450 @@ fn test_end_to_end_workflow_basic() {
     // Verify diff was generated
     let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
     assertions::assert_file_exists_and_not_empty(&diff_path);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_diff_detect_renames_with_moved_column() {
+    let runner = CliTestRunner::new().unwrap();
+
+    let baseline_csv = runner
+        .fixture()
+        .create_csv_raw(
+            "baseline.csv",
+            "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,2.25\n",
+        )
+        .unwrap();
+    let renamed_csv = runner
+        .fixture()
+        .create_csv_raw(
+            "renamed.csv",
+            "id,cost,name\n1,1.50,Apple\n2,0.75,Banana\n3,2.25,Cherry\n",
+        )
+        .unwrap();
+
+    runner.expect_success(&[
+        "snapshot",
+        baseline_csv.to_str().unwrap(),
+        "--name",
+        "baseline",
+    ]);
+    runner.expect_success(&[
+        "snapshot",
+        renamed_csv.to_str().unwrap(),
+        "--name",
+        "renamed",
+    ]);
+
+    runner.expect_success(&["diff", "baseline", "renamed", "--detect-renames"]);
+
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "renamed");
+    let diff_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    let columns_changed: Vec<String> = diff_json["columns_changed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().trim().to_string())
+        .collect();
+
+    assert_eq!(columns_changed, vec!["price → cost (renamed)"]);
+}
+
+#[test]
+fn test_diff_renders_column_moves() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv("baseline.csv", &sample_data::simple_csv_data()).unwrap();
+    let reordered_csv = runner.fixture().create_csv("reordered.csv", &sample_data::columns_reordered_csv_data()).unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", reordered_csv.to_str().unwrap(), "--name", "reordered"]);
+    
+    // id,name,price → price,id,name: every column moves
+    let output = runner.run_binary_stdout(&["diff", "baseline", "reordered"]);
+    assert!(output.contains("Columns moved: 3"), "Unexpected output: {}", output);
+    for line in ["price: position 2 → 0", "id: position 0 → 1", "name: position 1 → 2"] {
+        assert!(output.contains(line), "Missing '{}' in output: {}", line, output);
+    }
+    
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "reordered");
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff_json["column_moves"][0], serde_json::json!({"column": "price", "from": 2, "to": 0}));
+}
+
+#[test]
+fn test_diff_numeric_delta_reports_magnitude_of_price_change() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n").unwrap();
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name,price\n1,Apple,1.75\n2,Plantain,0.75\n").unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "baseline", "current", "--numeric-delta"]);
+    assert!(output.contains("Row 0 price: 1.5 → 1.75 (+0.25, +16.7%)"), "Unexpected output: {}", output);
+    // The name change is in the changed-cells table, but not among the numeric changes
+    let numeric = output.split("Numeric changes:").nth(1).unwrap_or_default();
+    assert!(!numeric.contains("Plantain"), "Non-numeric change listed as numeric: {}", output);
+    
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "current");
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    let samples = diff_json["sample_changes"].as_array().unwrap();
+    let price = samples.iter().find_map(|sample| sample["changes"].get("price")).unwrap();
+    assert_eq!(price["delta"], serde_json::json!(0.25));
+    assert!((price["pct_change"].as_f64().unwrap() - 16.667).abs() < 0.001, "Unexpected change: {}", price);
+    let name = samples.iter().find_map(|sample| sample["changes"].get("name")).unwrap();
+    assert!(name.get("delta").is_none() && name.get("pct_change").is_none(), "Unexpected change: {}", name);
+    
+    // Without the flag the JSON keeps only before and after
+    runner.expect_success(&["diff", "baseline", "current"]);
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    let price = diff_json["sample_changes"].as_array().unwrap().iter().find_map(|sample| sample["changes"].get("price")).unwrap();
+    assert!(price.get("delta").is_none(), "Unexpected change: {}", price);
+}
+
+#[test]
+fn test_diff_output_as_parquet_and_csv() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,2.25\n").unwrap();
+    // Row 0 price modified, Banana removed, Date added
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name,price\n1,Apple,1.99\n3,Cherry,2.25\n4,Date,3.00\n").unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    runner.expect_success(&["diff", "baseline", "current", "-o", "changes.parquet"]);
+    runner.expect_success(&["diff", "baseline", "current", "-o", "changes.csv"]);
+    
+    // One row per changed cell: 1 modified + 3 removed + 3 added
+    let parquet_path = runner.fixture().root().join("changes.parquet");
+    let reader = SerializedFileReader::new(fs::File::open(&parquet_path).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 7);
+    
+    let csv_path = runner.fixture().root().join("changes.csv");
+    let mut processor = tabdiff::data::DataProcessor::new().unwrap();
+    let info = processor.load_file(&csv_path).unwrap();
+    assert_eq!(info.row_count, 7);
+    assert_eq!(info.column_names(), vec!["change_type", "row_index", "key", "column", "before", "after"]);
+    
+    let csv_content = fs::read_to_string(&csv_path).unwrap();
+    assert!(csv_content.contains("modified,0,,price,1.5,1.99"), "Unexpected CSV: {}", csv_content);
+}
+
+#[test]
+fn test_diff_format_csv_flattens_changes() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    // Bob renamed to a value containing a comma, Carol added
+    runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alice\n2,\"Smith, Bob\"\n3,Carol\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--key", "id", "--format", "csv"]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "change_type,row_index,key,column,before,after");
+    // One modified cell plus both cells of the added row
+    assert_eq!(lines.len() - 1, 3, "Unexpected CSV: {}", output);
+    assert!(lines.contains(&"modified,1,2,name,Bob,\"Smith, Bob\""), "Unexpected CSV: {}", output);
+    assert!(lines.contains(&"added,2,3,name,,Carol"), "Unexpected CSV: {}", output);
+    
+    // --output gets the same table whatever its extension
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "--format", "csv", "-o", "changes.out"]);
+    assert_eq!(fs::read_to_string(runner.fixture().root().join("changes.out")).unwrap(), output);
+    
+    runner.expect_failure(&["status", csv_path.to_str().unwrap(), "--compare-to", "v1", "--format", "csv"]);
+}
+
+#[test]
+fn test_diff_include_unchanged_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,2.25\n5,Elder,4.00\n").unwrap();
+    // Apple modified, Banana removed, Date added, Cherry and Elder unchanged
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name,price\n1,Apple,1.99\n3,Cherry,2.25\n5,Elder,4.00\n4,Date,3.00\n").unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    runner.expect_success(&["diff", "baseline", "current", "--include-unchanged"]);
+    
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "current");
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    let unchanged = diff_json["unchanged"].as_array().unwrap();
+    let modified = diff_json["row_changes"]["modified"].as_u64().unwrap();
+    let added = diff_json["row_changes"]["added"].as_u64().unwrap();
+    
+    assert_eq!(unchanged.len() as u64 + modified + added, 4);
+    assert_eq!(diff_json["row_changes"]["unchanged"], 2);
+    assert_eq!(diff_json["unchanged_truncated"], false);
+    let names: Vec<&str> = unchanged.iter().map(|row| row["data"]["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["Cherry", "Elder"]);
+    
+    // --max-unchanged caps the materialized rows but not the count
+    runner.expect_success(&["diff", "baseline", "current", "--include-unchanged", "--max-unchanged", "1"]);
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff_json["unchanged"].as_array().unwrap().len(), 1);
+    assert_eq!(diff_json["row_changes"]["unchanged"], 2);
+    assert_eq!(diff_json["unchanged_truncated"], true);
+    
+    // Without the flag the diff stays change-only
+    runner.expect_success(&["diff", "baseline", "current"]);
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert!(diff_json.get("unchanged").is_none());
+}
+
+#[test]
+fn test_diff_context_rows_around_change() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name\n1,A\n2,B\n3,C\n4,D\n5,E\n6,F\n7,G\n").unwrap();
+    // Only the middle row changes
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name\n1,A\n2,B\n3,C\n4,X\n5,E\n6,F\n7,G\n").unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    runner.expect_success(&["diff", "baseline", "current", "--context", "2"]);
+    
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "current");
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff_json["rows_changed"], 1);
+    let context = diff_json["context"].as_array().unwrap();
+    let indices: Vec<u64> = context.iter().map(|row| row["row_index"].as_u64().unwrap()).collect();
+    assert_eq!(indices, vec![1, 2, 4, 5]);
+    let names: Vec<&str> = context.iter().map(|row| row["data"]["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["B", "C", "E", "F"]);
+}
+
+#[test]
+fn test_diff_stat_summary_line() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name\n1,A\n2,B\n3,C\n4,D\n").unwrap();
+    // id 2 modified, id 3 removed, id 5 added
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name\n1,A\n2,X\n4,D\n5,E\n").unwrap();
+    
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "baseline", "current", "--stat", "--key", "id"]);
+    assert_eq!(output.trim(), "1 file changed, +1 rows, -1 rows, ~1 modified, 0 schema changes");
+    
+    // The full diff is still written
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "current");
+    let diff_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff_json["row_changes"]["added"], 1);
+    assert_eq!(diff_json["row_changes"]["removed"], 1);
+    assert_eq!(diff_json["row_changes"]["modified"], 1);
+}
+
+#[test]
+fn test_diff_single_thread_matches_default() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", "id,name\n1,A\n2,B\n3,C\n4,D\n").unwrap();
+    let current_csv = runner.fixture().create_csv_raw("current.csv", "id,name\n1,A\n2,X\n4,D\n5,E\n").unwrap();
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    runner.expect_success(&["diff", "baseline", "current", "--output", "parallel.json"]);
+    runner.expect_success(&["diff", "baseline", "current", "--output", "single.json", "--threads", "1"]);
+    
+    let read = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(runner.fixture().root().join(name)).unwrap()).unwrap()
+    };
+    assert_eq!(read("single.json"), read("parallel.json"));
+}
+
+#[test]
+fn test_diff_output_dir_and_name_suffix() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let data_csv = runner.fixture().create_csv_raw("data.csv", "id,name\n1,A\n2,B\n").unwrap();
+    runner.expect_success(&["snapshot", data_csv.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,A\n2,X\n").unwrap();
+    runner.expect_success(&["snapshot", data_csv.to_str().unwrap(), "--name", "v2"]);
+    
+    let first_dir = runner.fixture().root().join("reports/first");
+    let second_dir = runner.fixture().root().join("reports/second");
+    runner.expect_success(&["diff", "v1", "v2", "--output-dir", first_dir.to_str().unwrap()]);
+    runner.expect_success(&["diff", "v1", "v2", "--output-dir", second_dir.to_str().unwrap()]);
+    assert!(first_dir.join("v1-v2.json").exists());
+    assert!(second_dir.join("v1-v2.json").exists());
+    assert!(!runner.fixture().workspace.diff_path("v1", "v2").exists());
+    
+    // The source hash suffix is the same on every run, so reruns replace rather than accumulate
+    runner.expect_success(&["diff", "v1", "v2", "--output-dir", "reports/hashed", "--name-suffix", "source-hash"]);
+    runner.expect_success(&["diff", "v1", "v2", "--output-dir", "reports/hashed", "--name-suffix", "source-hash"]);
+    let hashed: Vec<_> = fs::read_dir(runner.fixture().root().join("reports/hashed")).unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(hashed.len(), 1);
+    assert!(hashed[0].starts_with("v1-v2-") && hashed[0].ends_with(".json"));
+    
+    runner.expect_failure(&["diff", "v1", "v2", "--name-suffix", "random"]);
+}
+
+#[test]
+fn test_compression_level_trades_size_for_speed() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    // Separate sources, so neither snapshot stores a delta against the other
+    let fast_csv = runner.fixture().create_large_csv("fast.csv", 500, 4).unwrap();
+    let small_csv = runner.fixture().root().join("small.csv");
+    fs::copy(&fast_csv, &small_csv).unwrap();
+    
+    runner.expect_success(&["snapshot", fast_csv.to_str().unwrap(), "--name", "fast", "--compression-level", "0"]);
+    runner.expect_success(&["snapshot", small_csv.to_str().unwrap(), "--name", "small", "--compression-level", "9"]);
+    runner.expect_failure(&["snapshot", small_csv.to_str().unwrap(), "--name", "bad", "--compression-level", "12"]);
+    
+    let (fast_archive, fast_json) = runner.fixture().workspace.snapshot_paths("fast");
+    let (small_archive, small_json) = runner.fixture().workspace.snapshot_paths("small");
+    assert!(fs::metadata(&small_archive).unwrap().len() <= fs::metadata(&fast_archive).unwrap().len());
+    
+    let fast_metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(&fast_json).unwrap()).unwrap();
+    let small_metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(&small_json).unwrap()).unwrap();
+    assert_eq!(fast_metadata["compression_level"], 0);
+    assert_eq!(small_metadata["compression_level"], 9);
+    
+    // Both archives extract to the same rows
+    runner.expect_success(&["diff", "fast", "small", "--include-unchanged", "--output", "levels.json"]);
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(runner.fixture().root().join("levels.json")).unwrap()).unwrap();
+    assert_eq!(diff["rows_changed"], 0);
+    assert_eq!(diff["row_changes"]["unchanged"], 500);
+}
+
+#[test]
+fn test_snapshot_without_name_generates_sequential_names() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("sales.csv", "id,amount\n1,10\n2,20\n").unwrap();
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap()]);
+    runner.fixture().create_csv_raw("sales.csv", "id,amount\n1,10\n2,25\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap()]);
+    
+    runner.fixture().assert_snapshot_exists("sales_0000");
+    runner.fixture().assert_snapshot_exists("sales_0001");
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("sales_0001");
+    let metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(metadata["parent_snapshot"], "sales_0000");
+    assert_eq!(metadata["sequence_number"], 1);
+    
+    // The next name is already taken by another source's snapshot, so it is skipped
+    let other_path = runner.fixture().create_csv_raw("other.csv", "id\n1\n").unwrap();
+    runner.expect_success(&["snapshot", other_path.to_str().unwrap(), "--name", "sales_0002"]);
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap()]);
+    runner.fixture().assert_snapshot_exists("sales_0003");
+}
+
+#[test]
+fn test_diff_against_parent_uses_cached_delta() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "parent"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.99\n2,Banana,0.75\n3,Cherry,2.00\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "child"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "child", "--against-parent"]);
+    assert!(output.contains("parent → child"), "Unexpected output: {}", output);
+    assert!(output.contains("cached delta"), "Unexpected output: {}", output);
+    
+    let diff_path = runner.fixture().workspace.diff_path("parent", "child");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff["row_changes"]["modified"], 1);
+    assert_eq!(diff["row_changes"]["added"], 1);
+    assert_eq!(diff["row_changes"]["removed"], 0);
+    
+    // An option the cached delta was not detected with recomputes the same changes
+    let output = runner.run_binary_stdout(&["diff", "child", "--against-parent", "--key", "id"]);
+    assert!(!output.contains("cached delta"), "Unexpected output: {}", output);
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff["row_changes"]["modified"], 1);
+    assert_eq!(diff["row_changes"]["added"], 1);
+    
+    let output = runner.run_binary_stdout(&["diff", "parent", "--against-parent"]);
+    assert!(output.contains("first snapshot"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_diff_compat_classifies_schema_changes() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let v1 = runner.fixture().create_csv_raw("v1.csv", "id,name,phone\n1,Apple,555\n2,Banana,556\n").unwrap();
+    let added = runner.fixture().create_csv_raw("added.csv", "id,name,phone,email\n1,Apple,555,a@x.io\n2,Banana,556,b@x.io\n").unwrap();
+    let dropped = runner.fixture().create_csv_raw("dropped.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", v1.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_success(&["snapshot", added.to_str().unwrap(), "--name", "added"]);
+    runner.expect_success(&["snapshot", dropped.to_str().unwrap(), "--name", "dropped"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "v1", "added", "--compat"]);
+    assert!(output.contains("Schema compatibility: backward-compatible"), "Unexpected output: {}", output);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "added");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff["compatibility"]["level"], "backward");
+    assert_eq!(diff["compatibility"]["changes"][0]["kind"], "column_added");
+    
+    let output = runner.run_binary_stdout(&["diff", "v1", "dropped", "--compat"]);
+    assert!(output.contains("Schema compatibility: breaking"), "Unexpected output: {}", output);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "dropped");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff["compatibility"]["level"], "breaking");
+}
+
+#[test]
+fn test_snapshot_dry_run_writes_nothing() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n3,Cherry\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2", "--dry-run"]);
+    assert!(output.contains("Rows: 3"), "Unexpected output: {}", output);
+    assert!(output.contains("Parent: v1"), "Unexpected output: {}", output);
+    assert!(output.contains("+1 rows"), "Unexpected output: {}", output);
+    
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("v2");
+    assert!(!archive_path.exists());
+    assert!(!json_path.exists());
+    assert!(!runner.fixture().workspace.checkpoint_path("v2").exists());
+    assert_eq!(runner.fixture().workspace.list_snapshots().unwrap(), vec!["v1".to_string()]);
+}
+
+#[test]
+fn test_diff_since_matches_direct_endpoint_diff() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,9.99\n2,Banana,0.80\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    // Apple's price goes back, so only Banana's change and the new row remain overall
+    runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.80\n3,Cherry,2.00\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v3"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "v3", "--since", "v1", "--steps", "--output", "since.json"]);
+    assert!(output.contains("v1 → v2: 1 file changed, +0 rows, -0 rows, ~2 modified"), "Unexpected output: {}", output);
+    assert!(output.contains("v2 → v3: 1 file changed, +1 rows, -0 rows, ~1 modified"), "Unexpected output: {}", output);
+    runner.expect_success(&["diff", "v1", "v3", "--output", "direct.json"]);
+    
+    let read = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(runner.fixture().root().join(name)).unwrap()).unwrap()
+    };
+    let (since, direct) = (read("since.json"), read("direct.json"));
+    assert_eq!(since["base"], "v1");
+    assert_eq!(since["compare"], "v3");
+    assert_eq!(since["row_changes"], direct["row_changes"]);
+    assert_eq!(since["sample_changes"], direct["sample_changes"]);
+    assert_eq!(since["row_changes"]["modified"], 1);
+    assert_eq!(since["row_changes"]["added"], 1);
+    
+    // Only ancestors of the head can start the range
+    runner.expect_failure(&["diff", "v2", "--since", "v3"]);
+}
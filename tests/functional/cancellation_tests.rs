@@ -0,0 +1,60 @@
+//! Tests for cancelling hashing and snapshot creation through a `CancellationToken`
+
+use crate::common::CliTestRunner;
+use std::cell::Cell;
+use tabdiff::cancel::CancellationToken;
+use tabdiff::data::DataProcessor;
+use tabdiff::snapshot::SnapshotCreator;
+use tabdiff::TabdiffError;
+
+#[test]
+fn test_cancelling_mid_hash_stops_with_cancelled() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_large_csv("large.csv", 5000, 3).unwrap();
+
+    let token = CancellationToken::new();
+    let mut processor = DataProcessor::new().unwrap().with_cancellation(token.clone());
+    processor.load_file(&path).unwrap();
+
+    // Cancel at the first progress report, a thousand rows in
+    let reports = Cell::new(0);
+    let cancel_on_progress = |_: u64, _: u64| {
+        reports.set(reports.get() + 1);
+        token.cancel();
+    };
+    let result = processor.compute_row_hashes_with_progress(Some(&cancel_on_progress));
+    assert!(
+        matches!(result, Err(TabdiffError::Cancelled)),
+        "Expected Cancelled, got {:?}",
+        result.map(|hashes| hashes.len())
+    );
+    assert_eq!(reports.get(), 1);
+
+    // An uncancelled processor over the same file hashes every row
+    let mut processor = DataProcessor::new().unwrap().with_cancellation(CancellationToken::new());
+    processor.load_file(&path).unwrap();
+    assert_eq!(processor.compute_row_hashes_sql().unwrap().len(), 5000);
+}
+
+#[test]
+fn test_cancelled_snapshot_leaves_no_files() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_large_csv("large.csv", 2000, 3).unwrap();
+    let workspace = &runner.fixture().workspace;
+    let (archive_path, json_path) = workspace.snapshot_paths("v1");
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut creator = SnapshotCreator::new(1000, false).with_cancellation(token);
+    let result = creator.create_snapshot_with_workspace(&path, "v1", &archive_path, &json_path, true, Some(workspace));
+
+    assert!(matches!(result, Err(TabdiffError::Cancelled)));
+    assert!(!archive_path.exists());
+    assert!(!json_path.exists());
+    assert!(workspace.list_snapshots().unwrap().is_empty());
+
+    // The same snapshot succeeds with a fresh token
+    let mut creator = SnapshotCreator::new(1000, false).with_cancellation(CancellationToken::new());
+    creator.create_snapshot_with_workspace(&path, "v1", &archive_path, &json_path, true, Some(workspace)).unwrap();
+    assert!(archive_path.exists());
+}
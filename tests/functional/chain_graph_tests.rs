@@ -0,0 +1,52 @@
+//! Tests for `chain --graph`, which prints the snapshot chain as a DOT or Mermaid graph
+
+use crate::common::CliTestRunner;
+
+/// Workspace with v1 -> v2 -> v3 of one file and a separate root of another
+fn chained_workspace() -> CliTestRunner {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,alice\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,alice\n2,bob\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,alice\n2,bob\n3,carol\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v3"]);
+    let other = runner.fixture().create_csv_raw("other.csv", "id\n1\n").unwrap();
+    runner.expect_success(&["snapshot", other.to_str().unwrap(), "--name", "other"]);
+    runner
+}
+
+#[test]
+fn test_dot_graph_has_a_node_per_snapshot_and_an_edge_per_parent() {
+    let runner = chained_workspace();
+    let output = runner.run_binary_stdout(&["chain", "--graph", "dot"]);
+
+    assert!(output.trim_start().starts_with("digraph snapshots {"), "Unexpected output: {}", output);
+    for name in ["v1", "v2", "v3", "other"] {
+        let nodes = output.lines().filter(|line| line.trim_start().starts_with(&format!("\"{}\" [label=", name))).count();
+        assert_eq!(nodes, 1, "Expected one node for {}: {}", name, output);
+    }
+    assert!(output.contains("\"v3\" [label=\"v3\\nseq 2, 3 rows\""), "Unexpected output: {}", output);
+
+    let edges: Vec<&str> = output.lines().filter(|line| line.contains(" -> ")).collect();
+    assert_eq!(edges.len(), 2, "Unexpected edges: {:?}", edges);
+    assert!(edges.iter().any(|edge| edge.trim_start().starts_with("\"v1\" -> \"v2\"")));
+    assert!(edges.iter().any(|edge| edge.trim_start().starts_with("\"v2\" -> \"v3\"")));
+}
+
+#[test]
+fn test_mermaid_graph_links_parents_to_children() {
+    let runner = chained_workspace();
+    let output = runner.run_binary_stdout(&["chain", "--graph", "mermaid"]);
+
+    assert!(output.trim_start().starts_with("flowchart LR"), "Unexpected output: {}", output);
+    assert_eq!(output.matches("<br/>seq ").count(), 4, "Unexpected output: {}", output);
+    let edges = output.lines().filter(|line| line.contains("==>") || line.contains("-.->")).count();
+    assert_eq!(edges, 2, "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_unknown_graph_format_is_rejected() {
+    let runner = chained_workspace();
+    runner.expect_failure(&["chain", "--graph", "svg"]);
+}
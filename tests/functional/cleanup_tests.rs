@@ -0,0 +1,51 @@
+//! Tests for `cleanup --keep-full`, which keeps full data per source chain
+
+use crate::common::CliTestRunner;
+
+/// Snapshot names listed by a dry-run cleanup
+fn cleanup_candidates(stdout: &str) -> Vec<String> {
+    stdout.lines()
+        .skip_while(|line| !line.contains("would have data cleaned up"))
+        .skip(1)
+        .filter_map(|line| line.trim_start().strip_prefix("• "))
+        .filter_map(|line| line.split(" (seq:").next().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_keep_full_applies_to_each_source() {
+    let runner = CliTestRunner::new().unwrap();
+    let a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,a\n").unwrap();
+    let b = runner.fixture().create_csv_raw("b.csv", "id,name\n1,b\n").unwrap();
+    runner.expect_success(&["snapshot", a.to_str().unwrap(), "--name", "a1"]);
+    runner.expect_success(&["snapshot", b.to_str().unwrap(), "--name", "b1"]);
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,a\n2,aa\n").unwrap();
+    runner.expect_success(&["snapshot", a.to_str().unwrap(), "--name", "a2"]);
+    runner.fixture().create_csv_raw("b.csv", "id,name\n1,b\n2,bb\n").unwrap();
+    runner.expect_success(&["snapshot", b.to_str().unwrap(), "--name", "b2"]);
+
+    let stdout = runner.run_binary_stdout(&["cleanup", "--keep-full", "1", "--dry-run"]);
+    let mut candidates = cleanup_candidates(&stdout);
+    candidates.sort();
+    assert_eq!(candidates, vec!["a1", "b1"], "Unexpected output: {}", stdout);
+}
+
+#[test]
+fn test_keep_full_spares_source_with_single_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,a\n").unwrap();
+    let b = runner.fixture().create_csv_raw("b.csv", "id,name\n1,b\n").unwrap();
+    runner.expect_success(&["snapshot", b.to_str().unwrap(), "--name", "b1"]);
+    runner.expect_success(&["snapshot", a.to_str().unwrap(), "--name", "a1"]);
+    for (i, content) in ["id,name\n1,a\n2,x\n", "id,name\n1,a\n2,y\n"].iter().enumerate() {
+        runner.fixture().create_csv_raw("a.csv", content).unwrap();
+        let name = format!("a{}", i + 2);
+        runner.expect_success(&["snapshot", a.to_str().unwrap(), "--name", &name]);
+    }
+
+    let stdout = runner.run_binary_stdout(&["cleanup", "--keep-full", "1", "--dry-run"]);
+    let candidates = cleanup_candidates(&stdout);
+    assert!(!candidates.contains(&"b1".to_string()), "b1 is the newest snapshot of its source: {}", stdout);
+    assert!(!candidates.contains(&"a3".to_string()), "a3 is the newest snapshot of its source: {}", stdout);
+    assert!(candidates.contains(&"a1".to_string()), "Unexpected output: {}", stdout);
+}
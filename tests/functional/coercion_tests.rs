@@ -0,0 +1,49 @@
+//! Tests for `--coerce` per-column type coercion in `diff` and `status`
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+#[test]
+fn test_status_coerced_bool_column_reports_no_changes() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("users.csv", "id,active\n1,true\n2,false\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("users.csv", "id,active\n1,1\n2,0\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["modified"].as_array().unwrap().len(), 2);
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--coerce", "active:bool"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+    assert!(json.get("coercion_failures").is_none());
+}
+
+#[test]
+fn test_diff_flags_unparseable_coerced_values() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("users.csv", "id,active\n1,true\n2,unknown\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("users.csv", "id,active\n1,1\n2,unknown\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2", "--coerce", "active:bool"]);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["rows_changed"], 0);
+    assert_eq!(diff["coercion_failures"][0]["column"], "active");
+    assert_eq!(diff["coercion_failures"][0]["value"], "unknown");
+}
+
+#[test]
+fn test_coerce_rejects_unknown_column_and_type() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("users.csv", "id,active\n1,true\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    
+    runner.expect_failure(&["status", path.to_str().unwrap(), "--coerce", "missing:bool"]);
+    runner.expect_failure(&["status", path.to_str().unwrap(), "--coerce", "active:float"]);
+}
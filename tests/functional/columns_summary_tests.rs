@@ -0,0 +1,39 @@
+//! Tests for `columns_summary`, the modified-row count of each column in `diff` and `status`
+
+use crate::common::CliTestRunner;
+
+const BASELINE: &str = "id,name,price\n1,apple,1.00\n2,pear,2.00\n3,plum,3.00\n4,fig,4.00\n5,kiwi,5.00\n";
+const CURRENT: &str = "id,name,price\n1,apple,1.10\n2,pear,2.20\n3,plum,3.30\n4,figs,4.40\n5,kiwi,5.00\n";
+
+#[test]
+fn test_status_counts_modifications_per_column() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("fruit.csv", BASELINE).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("fruit.csv", CURRENT).unwrap();
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--key", "id"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["columns_summary"], serde_json::json!({ "price": 4, "name": 1 }), "Unexpected output: {}", output);
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--key", "id"]);
+    assert!(output.contains("Changes by column: price: 4 changes, name: 1 change"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_diff_counts_modifications_per_column() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("fruit.csv", BASELINE).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("fruit.csv", CURRENT).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "-o", "diff.json"]);
+    let diff: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(runner.fixture().root().join("diff.json")).unwrap()
+    ).unwrap();
+    assert_eq!(diff["columns_summary"], serde_json::json!({ "price": 4, "name": 1 }), "Unexpected diff: {}", diff);
+
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--key", "id"]);
+    assert!(output.contains("Changes by column: price: 4 changes, name: 1 change"), "Unexpected output: {}", output);
+}
@@ -0,0 +1,51 @@
+//! Tests for workspace option defaults set with `tabdiff config`
+
+use crate::common::CliTestRunner;
+
+fn modified_rows(runner: &CliTestRunner, args: &[&str]) -> usize {
+    let output = runner.run_binary_stdout(args);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    json["row_changes"]["modified"].as_array().unwrap().len()
+}
+
+#[test]
+fn test_config_float_tolerance_applies_to_status() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("prices.csv", "id,price\n1,1.500\n2,2.000\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("prices.csv", "id,price\n1,1.5004\n2,2.500\n").unwrap();
+    let status = ["status", path.to_str().unwrap(), "--json"];
+    assert_eq!(modified_rows(&runner, &status), 2);
+    
+    runner.expect_success(&["config", "--set", "float-tolerance=0.001"]);
+    assert_eq!(runner.run_binary_stdout(&["config", "--get", "float-tolerance"]).trim(), "0.001");
+    
+    // Only the price that moved by more than the tolerance is still a change
+    assert_eq!(modified_rows(&runner, &status), 1);
+    
+    // A flag on the command line wins over the config
+    assert_eq!(modified_rows(&runner, &["status", path.to_str().unwrap(), "--json", "--float-tolerance", "0"]), 2);
+    
+    runner.expect_success(&["config", "--unset", "float-tolerance"]);
+    assert_eq!(modified_rows(&runner, &status), 2);
+}
+
+#[test]
+fn test_config_rejects_unknown_keys_and_invalid_values() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let error = runner.expect_failure(&["config", "--set", "hash-algorithm=md5"]);
+    assert!(error.to_string().contains("float-tolerance"), "Unexpected error: {}", error);
+    runner.expect_failure(&["config", "--set", "float-tolerance=-1"]);
+    runner.expect_failure(&["config", "--set", "compression-level=12"]);
+    
+    // A rejected setting leaves the others in the same invocation unsaved
+    runner.expect_failure(&["config", "--set", "threads=2", "--set", "float-precision=x"]);
+    let output = runner.run_binary_stdout(&["config", "--list"]);
+    assert!(output.contains("threads (not set)"), "Unexpected output: {}", output);
+    
+    runner.expect_success(&["config", "--set", "threads=2", "--set", "compression-level=0"]);
+    let output = runner.run_binary_stdout(&["config"]);
+    assert!(output.contains("threads = 2"), "Unexpected output: {}", output);
+    assert!(output.contains("compression-level = 0"), "Unexpected output: {}", output);
+}
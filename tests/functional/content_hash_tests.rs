@@ -0,0 +1,51 @@
+//! Tests for the content-hash fast path that skips row comparison for identical data
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_diff_of_identical_snapshots_takes_fast_path() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2"]);
+    let diff = runner.read_diff_json(runner.fixture().workspace.diff_path("v1", "v2"));
+    assert_eq!(diff["content_identical"], true);
+    assert_eq!(diff["rows_changed"], 0);
+    assert_eq!(diff["schema_changed"], false);
+}
+
+#[test]
+fn test_diff_of_changed_snapshots_compares_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Cherry\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2"]);
+    let diff = runner.read_diff_json(runner.fixture().workspace.diff_path("v1", "v2"));
+    assert!(diff.get("content_identical").is_none());
+    assert_eq!(diff["rows_changed"], 1);
+}
+
+#[test]
+fn test_status_of_unchanged_file_takes_fast_path() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+
+    // Only a full row comparison classifies rows, which the timings report as a phase
+    let timings = runner.run_binary_stderr(&["status", path.to_str().unwrap(), "--json", "--timings"]);
+    assert!(!timings.contains("classify rows"), "Rows were compared: {}", timings);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Blueberry\n").unwrap();
+    let timings = runner.run_binary_stderr(&["status", path.to_str().unwrap(), "--json", "--timings"]);
+    assert!(timings.contains("classify rows"), "Rows were not compared: {}", timings);
+}
@@ -0,0 +1,42 @@
+//! Tests for storing identical row data once across snapshot archives
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_identical_snapshots_share_row_data() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_large_csv("large.csv", 5000, 5).unwrap();
+    let copy_path = runner.fixture().root().join("copy.csv");
+    std::fs::copy(&csv_path, &copy_path).unwrap();
+
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--full-data", "true"]);
+    runner.expect_success(&["snapshot", copy_path.to_str().unwrap(), "--name", "v2", "--full-data", "true"]);
+
+    let workspace = &runner.fixture().workspace;
+    let archive_size = |name: &str| std::fs::metadata(workspace.snapshot_paths(name).0).unwrap().len();
+    let (single, total) = (archive_size("v1"), archive_size("v1") + archive_size("v2"));
+    assert!(total < single + single / 4, "Archives of identical data take {} bytes, one alone {}", total, single);
+
+    // The shared row data loads transparently from either snapshot
+    let data = runner.run_binary_stdout(&["show", "v1", "--raw", "--file", "data.parquet"]);
+    assert!(data.contains("\"rows\""), "Unexpected output: {}", data);
+    assert_eq!(runner.run_binary_stdout(&["show", "v2", "--raw", "--file", "data.parquet"]), data);
+}
+
+#[test]
+fn test_removing_shared_archive_keeps_referencing_snapshots_loadable() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_large_csv("large.csv", 2000, 4).unwrap();
+    let copy_path = runner.fixture().root().join("copy.csv");
+    std::fs::copy(&csv_path, &copy_path).unwrap();
+
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--full-data", "true"]);
+    runner.expect_success(&["snapshot", copy_path.to_str().unwrap(), "--name", "copy", "--full-data", "true"]);
+    let data = runner.run_binary_stdout(&["show", "copy", "--raw", "--file", "data.parquet"]);
+
+    // gc of v1's archive moves the data it held into the archive that referenced it
+    std::fs::remove_file(runner.fixture().workspace.snapshot_paths("v1").1).unwrap();
+    runner.expect_success(&["gc", "--force"]);
+    assert!(!runner.fixture().workspace.snapshot_paths("v1").0.exists());
+    assert_eq!(runner.run_binary_stdout(&["show", "copy", "--raw", "--file", "data.parquet"]), data);
+}
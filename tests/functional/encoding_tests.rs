@@ -0,0 +1,39 @@
+//! Tests for reading non-UTF-8 input with `--encoding`
+
+use crate::common::CliTestRunner;
+use std::fs;
+use tabdiff::snapshot::SnapshotLoader;
+use tabdiff::TabdiffError;
+
+/// "José" and "Müller" encoded as ISO-8859-1
+const LATIN1_CONTENT: &[u8] = b"id,name\n1,Jos\xe9\n2,M\xfcller\n";
+
+#[test]
+fn test_snapshot_latin1_file() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().root().join("names.csv");
+    fs::write(&csv_path, LATIN1_CONTENT).unwrap();
+    let csv_arg = csv_path.to_str().unwrap();
+
+    let error = runner.expect_failure(&["snapshot", csv_arg, "--name", "utf8"]);
+    assert!(matches!(error, TabdiffError::MalformedData { .. }), "unexpected error: {}", error);
+
+    runner.expect_success(&["snapshot", csv_arg, "--name", "latin1", "--encoding", "latin-1"]);
+
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("latin1");
+    let metadata = SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.encoding.as_deref(), Some("iso-8859-1"));
+    // The chain tracks the original file, not its UTF-8 copy
+    let canonical = csv_path.canonicalize().unwrap().to_string_lossy().to_string();
+    assert_eq!(metadata.source_path.as_deref(), Some(canonical.as_str()));
+
+    let rows = SnapshotLoader::load_full_snapshot(&archive_path).unwrap().rows();
+    assert_eq!(rows, vec![
+        vec!["1".to_string(), "José".to_string()],
+        vec!["2".to_string(), "Müller".to_string()],
+    ]);
+
+    // Status decodes the current file with the encoding recorded in the baseline
+    let output = runner.run_binary_stdout(&["status", csv_arg]);
+    assert!(output.contains("Rows: unchanged"), "unexpected status output: {}", output);
+}
@@ -0,0 +1,51 @@
+//! Tests for exporting snapshot rows through the output format registry
+
+use crate::common::CliTestRunner;
+use std::path::Path;
+use tabdiff::error::Result;
+use tabdiff::hash::ColumnInfo;
+use tabdiff::writer::{register_writer, DataWriter};
+
+/// Writes a one-line summary of what it was given, so tests can see it was chosen
+struct SummaryWriter;
+
+impl DataWriter for SummaryWriter {
+    fn write_rows(&self, path: &Path, schema: &[ColumnInfo], rows: &[Vec<String>], include_header: bool) -> Result<()> {
+        let columns: Vec<&str> = schema.iter().map(|col| col.name.as_str()).collect();
+        std::fs::write(path, format!("custom {} rows of {} header={}", rows.len(), columns.join("|"), include_header))?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_export_routes_to_registered_custom_writer() {
+    register_writer("custom", &["summary"], |_| Box::new(SummaryWriter));
+
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--full-data", "true"]);
+
+    runner.expect_success(&["export", "v1", "out.txt", "--format", "custom"]);
+    let exported = std::fs::read_to_string(runner.fixture().root().join("out.txt")).unwrap();
+    assert_eq!(exported, "custom 2 rows of id|name header=true");
+
+    // The registered extension selects the writer without --format
+    runner.expect_success(&["export", "v1", "out.summary"]);
+    assert!(runner.fixture().root().join("out.summary").exists());
+}
+
+#[test]
+fn test_export_writes_delimited_rows_and_rejects_unknown_formats() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,\"Smith, Jo\"\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--full-data", "true"]);
+
+    runner.expect_success(&["export", "v1", "exports/data.tsv"]);
+    let exported = std::fs::read_to_string(runner.fixture().root().join("exports/data.tsv")).unwrap();
+    assert_eq!(exported, "id\tname\n1\tSmith, Jo\n2\tBob\n");
+
+    let error = runner.expect_failure(&["export", "v1", "data.out"]);
+    assert!(error.to_string().contains("use --format"), "Unexpected error: {}", error);
+    let error = runner.expect_failure(&["export", "v1", "data.out", "--format", "avro"]);
+    assert!(error.to_string().contains("Unknown output format: 'avro'"), "Unexpected error: {}", error);
+}
@@ -0,0 +1,83 @@
+//! Tests for `--only` filtering of diff and status output
+
+use crate::common::CliTestRunner;
+
+const BASELINE: &str = "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,2.25\n";
+// Row 1 modified, row 2 removed, row 4 added
+const CURRENT: &str = "id,name,price\n1,Apple,1.99\n3,Cherry,2.25\n4,Date,3.00\n";
+
+#[test]
+fn test_status_only_removed() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", BASELINE).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    runner.fixture().create_csv_raw("data.csv", CURRENT).unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--only", "removed"]);
+    assert!(output.contains("Removed rows: 1"), "Unexpected output: {}", output);
+    assert!(!output.contains("Added rows"), "Unexpected output: {}", output);
+    assert!(!output.contains("Modified rows"), "Unexpected output: {}", output);
+    // The summary header still reports every category
+    assert!(output.contains("Summary: +1 rows, -1 rows, 1 modified"), "Unexpected output: {}", output);
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--only", "removed", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["removed"].as_array().unwrap().len(), 1);
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    assert!(json.get("schema_changes").is_none());
+    assert_eq!(json["summary"]["added"], 1);
+    assert_eq!(json["summary"]["modified"], 1);
+    
+    // Multiple --only values union
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--only", "removed", "--only", "added", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["removed"].as_array().unwrap().len(), 1);
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_only_removed() {
+    let runner = CliTestRunner::new().unwrap();
+    let baseline_csv = runner.fixture().create_csv_raw("baseline.csv", BASELINE).unwrap();
+    let current_csv = runner.fixture().create_csv_raw("current.csv", CURRENT).unwrap();
+    runner.expect_success(&["snapshot", baseline_csv.to_str().unwrap(), "--name", "baseline"]);
+    runner.expect_success(&["snapshot", current_csv.to_str().unwrap(), "--name", "current"]);
+    
+    runner.expect_success(&["diff", "baseline", "current", "--only", "removed"]);
+    
+    let diff_path = runner.fixture().workspace.diff_path("baseline", "current");
+    let diff_json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&diff_path).unwrap()).unwrap();
+    let samples = diff_json["sample_changes"].as_array().unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0]["type"], "removed");
+    assert_eq!(diff_json["row_changes"]["added"], 1);
+    assert_eq!(diff_json["row_changes"]["modified"], 1);
+    assert_eq!(diff_json["only"], serde_json::json!(["removed"]));
+}
+
+#[test]
+fn test_where_keeps_only_matching_modifications() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,12.00\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    // Apple got more expensive, Banana and Cherry got cheaper ("9.50" < "12.00" only numerically)
+    runner.fixture().create_csv_raw("prices.csv", "id,name,price\n1,Apple,1.99\n2,Banana,0.70\n3,Cherry,9.50\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--where", "price decreased", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    let mut rows: Vec<u64> = modified.iter().map(|m| m["row_index"].as_u64().unwrap()).collect();
+    rows.sort();
+    assert_eq!(rows, vec![1, 2]);
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--where", "price > 5", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    assert_eq!(modified.len(), 1);
+    assert_eq!(modified[0]["row_index"], 2);
+    
+    runner.expect_failure(&["status", csv_path.to_str().unwrap(), "--where", "cost decreased"]);
+    runner.expect_failure(&["status", csv_path.to_str().unwrap(), "--where", "price"]);
+}
@@ -0,0 +1,83 @@
+//! Tests for `--float-precision` and cross-format hashing consistency
+
+use crate::common::CliTestRunner;
+use parquet::data_type::{DoubleType, FloatType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tabdiff::data::DataProcessor;
+
+const CSV_CONTENT: &str = "id,ratio,price\n1,0.1,1.5\n2,0.3,2.25\n";
+
+/// Write the same numbers as CSV_CONTENT, typed as FLOAT/DOUBLE and computed rather than parsed
+fn write_prices_parquet(path: &Path) {
+    let schema = Arc::new(parse_message_type("
+        message prices {
+            REQUIRED INT64 id;
+            REQUIRED FLOAT ratio;
+            REQUIRED DOUBLE price;
+        }
+    ").unwrap());
+    let mut writer = SerializedFileWriter::new(fs::File::create(path).unwrap(), schema, Arc::new(WriterProperties::builder().build())).unwrap();
+    let mut row_group = writer.next_row_group().unwrap();
+    
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<Int64Type>().write_batch(&[1, 2], None, None).unwrap();
+    column.close().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<FloatType>().write_batch(&[0.1, 0.1 + 0.2], None, None).unwrap();
+    column.close().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<DoubleType>().write_batch(&[1.5, 0.75 * 3.0], None, None).unwrap();
+    column.close().unwrap();
+    
+    row_group.close().unwrap();
+    writer.close().unwrap();
+}
+
+fn row_hashes(path: &Path, float_precision: Option<usize>) -> Vec<String> {
+    let mut processor = DataProcessor::new().unwrap().with_float_precision(float_precision);
+    processor.load_file(path).unwrap();
+    processor.compute_row_hashes_sql().unwrap()
+        .into_iter()
+        .map(|row_hash| row_hash.hash)
+        .collect()
+}
+
+#[test]
+fn test_csv_and_parquet_hash_identically() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", CSV_CONTENT).unwrap();
+    let parquet_path = runner.fixture().root().join("prices.parquet");
+    write_prices_parquet(&parquet_path);
+    
+    assert_eq!(row_hashes(&csv_path, Some(10)), row_hashes(&parquet_path, Some(10)));
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "from_csv", "--float-precision", "10"]);
+    runner.expect_success(&["snapshot", parquet_path.to_str().unwrap(), "--name", "from_parquet", "--float-precision", "10"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("from_csv");
+    let metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(json_path).unwrap()).unwrap();
+    assert_eq!(metadata["float_precision"], 10);
+    
+    // Only the physical column types differ, never the rows
+    runner.expect_success(&["diff", "from_csv", "from_parquet"]);
+    let diff_path = runner.fixture().workspace.diff_path("from_csv", "from_parquet");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["rows_changed"], 0, "Unexpected diff: {}", diff);
+}
+
+#[test]
+fn test_diff_requires_matching_float_precision() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", CSV_CONTENT).unwrap();
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "rounded", "--float-precision", "4"]);
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "exact"]);
+    
+    let error = runner.expect_failure(&["diff", "rounded", "exact"]);
+    assert!(error.to_string().contains("different float precision"), "Unexpected error: {}", error);
+}
@@ -0,0 +1,83 @@
+//! Tests for `--baseline-format`/`--current-format` reader overrides
+
+use crate::common::CliTestRunner;
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+const CSV_CONTENT: &str = "id,name,price\n1,Apple,1.5\n2,Banana,2.25\n";
+
+/// Write the same rows as CSV_CONTENT as Parquet
+fn write_prices_parquet(path: &Path) {
+    let schema = Arc::new(parse_message_type("
+        message prices {
+            REQUIRED INT64 id;
+            REQUIRED BYTE_ARRAY name (UTF8);
+            REQUIRED DOUBLE price;
+        }
+    ").unwrap());
+    let mut writer = SerializedFileWriter::new(fs::File::create(path).unwrap(), schema, Arc::new(WriterProperties::builder().build())).unwrap();
+    let mut row_group = writer.next_row_group().unwrap();
+    
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<Int64Type>().write_batch(&[1, 2], None, None).unwrap();
+    column.close().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<ByteArrayType>().write_batch(&[ByteArray::from("Apple"), ByteArray::from("Banana")], None, None).unwrap();
+    column.close().unwrap();
+    let mut column = row_group.next_column().unwrap().unwrap();
+    column.typed::<DoubleType>().write_batch(&[1.5, 2.25], None, None).unwrap();
+    column.close().unwrap();
+    
+    row_group.close().unwrap();
+    writer.close().unwrap();
+}
+
+fn rows_changed(runner: &CliTestRunner, base: &str, compare: &str) -> serde_json::Value {
+    let diff_path = runner.fixture().workspace.diff_path(base, compare);
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    diff["rows_changed"].clone()
+}
+
+#[test]
+fn test_diff_csv_and_parquet_files() {
+    let runner = CliTestRunner::new().unwrap();
+    // Extensions that do not reveal the format, so only the overrides can read them
+    let csv_path = runner.fixture().create_csv_raw("baseline.txt", CSV_CONTENT).unwrap();
+    let parquet_path = runner.fixture().root().join("current.dat");
+    write_prices_parquet(&parquet_path);
+    
+    runner.expect_success(&[
+        "diff", csv_path.to_str().unwrap(), parquet_path.to_str().unwrap(),
+        "--baseline-format", "csv", "--current-format", "parquet",
+    ]);
+    assert_eq!(rows_changed(&runner, "baseline", "current"), 0);
+}
+
+#[test]
+fn test_diff_snapshot_against_parquet_file() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", CSV_CONTENT).unwrap();
+    let parquet_path = runner.fixture().root().join("current.dat");
+    write_prices_parquet(&parquet_path);
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "from_csv"]);
+    runner.expect_success(&["diff", "from_csv", parquet_path.to_str().unwrap(), "--current-format", "parquet"]);
+    assert_eq!(rows_changed(&runner, "from_csv", "current"), 0);
+    
+    let output = runner.run_binary_stdout(&[
+        "status", parquet_path.to_str().unwrap(), "--compare-to", "from_csv", "--current-format", "parquet",
+    ]);
+    assert!(output.contains("Rows: unchanged"), "unexpected status output: {}", output);
+}
+
+#[test]
+fn test_invalid_format_is_rejected() {
+    let runner = CliTestRunner::new().unwrap();
+    let error = runner.expect_failure(&["diff", "a", "b", "--current-format", "xlsx"]);
+    assert!(error.to_string().contains("Invalid format"), "Unexpected error: {}", error);
+}
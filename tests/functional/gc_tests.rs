@@ -0,0 +1,42 @@
+//! Tests for `gc` removal of orphaned diffs and archives
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+#[test]
+fn test_gc_removes_only_orphaned_files() {
+    let runner = CliTestRunner::new().unwrap();
+    let workspace = &runner.fixture().workspace;
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n3,Cherry\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v3"]);
+    runner.expect_success(&["diff", "v1", "v2"]);
+    
+    // A diff against a deleted snapshot, and a snapshot whose metadata was removed by hand
+    let orphaned_diff = workspace.diff_path("gone", "v2");
+    fs::write(&orphaned_diff, r#"{"base": "gone", "compare": "v2"}"#).unwrap();
+    let (v3_archive, v3_json) = workspace.snapshot_paths("v3");
+    fs::remove_file(&v3_json).unwrap();
+    
+    let output = runner.run_binary_stdout(&["gc", "--dry-run"]);
+    assert!(output.contains("Found 2 orphaned file(s)"), "Unexpected output: {}", output);
+    assert!(orphaned_diff.exists() && v3_archive.exists());
+    
+    let output = runner.run_binary_stdout(&["gc", "--force"]);
+    assert!(output.contains("Removed 2 file(s)"), "Unexpected output: {}", output);
+    assert!(!orphaned_diff.exists());
+    assert!(!v3_archive.exists());
+    
+    // Everything belonging to existing snapshots is kept
+    for name in ["v1", "v2"] {
+        let (archive, json) = workspace.snapshot_paths(name);
+        assert!(archive.exists() && json.exists(), "{} was removed", name);
+    }
+    assert!(workspace.diff_path("v1", "v2").exists());
+    
+    let output = runner.run_binary_stdout(&["gc", "--force"]);
+    assert!(output.contains("No orphaned files found"), "Unexpected output: {}", output);
+}
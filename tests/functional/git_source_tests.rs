@@ -0,0 +1,67 @@
+//! Tests for snapshotting files at git revisions (`git:<rev>:<path>` inputs)
+
+use crate::common::CliTestRunner;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=tabdiff", "-c", "user.email=tabdiff@example.com"])
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .unwrap();
+    assert!(status.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&status.stderr));
+}
+
+#[test]
+fn test_diff_between_git_revisions() {
+    let runner = CliTestRunner::new().unwrap();
+    let repo = runner.fixture().root();
+    
+    git(repo, &["init", "-q"]);
+    runner.fixture().create_csv_raw("f.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n").unwrap();
+    git(repo, &["add", "f.csv"]);
+    git(repo, &["commit", "-q", "-m", "first version"]);
+    runner.fixture().create_csv_raw("f.csv", "id,name,price\n1,Apple,1.99\n2,Banana,0.75\n3,Cherry,2.25\n").unwrap();
+    git(repo, &["commit", "-q", "-a", "-m", "second version"]);
+    // The working tree differs from both revisions
+    runner.fixture().create_csv_raw("f.csv", "id,name,price\n9,Zucchini,9.99\n").unwrap();
+    
+    runner.expect_success(&["snapshot", "git:HEAD~1:f.csv", "--name", "before"]);
+    runner.expect_success(&["snapshot", "git:HEAD:f.csv", "--name", "after"]);
+    
+    let (_, before_json) = runner.fixture().workspace.snapshot_paths("before");
+    let (_, after_json) = runner.fixture().workspace.snapshot_paths("after");
+    let before: serde_json::Value = serde_json::from_str(&fs::read_to_string(before_json).unwrap()).unwrap();
+    let after: serde_json::Value = serde_json::from_str(&fs::read_to_string(after_json).unwrap()).unwrap();
+    assert_eq!(before["source"], "git:HEAD~1:f.csv");
+    assert_eq!(before["source_path"], "f.csv");
+    assert_eq!(before["row_count"], 2);
+    assert_eq!(after["row_count"], 3);
+    // Revisions of the same file share a chain
+    assert_eq!(after["parent_snapshot"], "before");
+    
+    runner.expect_success(&["diff", "before", "after"]);
+    let diff_path = runner.fixture().workspace.diff_path("before", "after");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["row_changes"]["modified"], 1);
+    assert_eq!(diff["row_changes"]["added"], 1);
+    assert_eq!(diff["row_changes"]["removed"], 0);
+}
+
+#[test]
+fn test_git_source_with_unknown_revision_fails() {
+    let runner = CliTestRunner::new().unwrap();
+    let repo = runner.fixture().root();
+    
+    git(repo, &["init", "-q"]);
+    runner.fixture().create_csv_raw("f.csv", "id,name\n1,Apple\n").unwrap();
+    git(repo, &["add", "f.csv"]);
+    git(repo, &["commit", "-q", "-m", "only version"]);
+    
+    let error = runner.expect_failure(&["snapshot", "git:HEAD~5:f.csv", "--name", "missing"]);
+    assert!(error.to_string().contains("HEAD~5"), "Unexpected error: {}", error);
+    runner.fixture().assert_snapshot_not_exists("missing");
+}
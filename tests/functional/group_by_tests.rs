@@ -0,0 +1,64 @@
+//! Tests for `--group-by` change counts per column value in `diff` and `status`
+
+use crate::common::CliTestRunner;
+
+const BASELINE: &str = "id,category,price\n1,books,10\n2,books,12\n3,toys,5\n4,toys,7\n5,garden,20\n6,garden,22\n";
+// books: 1 repriced, 8 added; toys: 3 and 4 repriced, 7 added; garden: 5 removed
+const CURRENT: &str = "id,category,price\n1,books,11\n2,books,12\n3,toys,6\n4,toys,8\n6,garden,22\n7,toys,9\n8,books,3\n";
+
+/// (value, modified, added, removed) of each group in a `groups` JSON value
+fn group_counts(groups: &serde_json::Value) -> Vec<(String, u64, u64, u64)> {
+    groups["groups"].as_array().unwrap().iter()
+        .map(|group| (
+            group["value"].as_str().unwrap().to_string(),
+            group["modified"].as_u64().unwrap(),
+            group["added"].as_u64().unwrap(),
+            group["removed"].as_u64().unwrap(),
+        ))
+        .collect()
+}
+
+fn expected() -> Vec<(String, u64, u64, u64)> {
+    vec![
+        ("toys".to_string(), 2, 1, 0),
+        ("books".to_string(), 1, 1, 0),
+        ("garden".to_string(), 0, 0, 1),
+    ]
+}
+
+#[test]
+fn test_diff_group_by_counts_changes_per_category() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("products.csv", BASELINE).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("products.csv", CURRENT).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "--group-by", "category", "-o", "diff.json"]);
+    let diff: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(runner.fixture().root().join("diff.json")).unwrap()
+    ).unwrap();
+    assert_eq!(diff["groups"]["column"], "category");
+    assert_eq!(group_counts(&diff["groups"]), expected());
+
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--key", "id", "--group-by", "category"]);
+    assert!(output.contains("Changes by category"), "Unexpected output: {}", output);
+
+    let error = runner.expect_failure(&["diff", "v1", "v2", "--group-by", "region"]);
+    assert!(error.to_string().contains("Cannot group by 'region'"), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_status_group_by_counts_changes_per_category() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("products.csv", BASELINE).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("products.csv", CURRENT).unwrap();
+
+    // Line numbers renumber the reported rows, but groups still come from the right rows
+    let output = runner.run_binary_stdout(&[
+        "status", path.to_str().unwrap(), "--json", "--key", "id", "--group-by", "category", "--line-numbers",
+    ]);
+    let status: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(group_counts(&status["groups"]), expected());
+}
@@ -0,0 +1,57 @@
+//! Tests for `diff --invert`, which reports the change set of the opposite direction
+
+use crate::common::CliTestRunner;
+
+const OLD: &str = "id,name,price\n1,apple,1.50\n2,pear,2.00\n3,plum,3.00\n4,fig,4.00\n";
+const NEW: &str = "id,name,price\n1,apple,1.50\n2,pear,2.00\n4,fig,4.40\n5,kiwi,5.00\n";
+
+/// Sample changes as sorted strings, since the order within a change kind is not part of the result
+fn samples(diff: &serde_json::Value) -> Vec<String> {
+    let mut samples: Vec<String> = diff["sample_changes"].as_array().unwrap().iter()
+        .map(|change| change.to_string())
+        .collect();
+    samples.sort();
+    samples
+}
+
+fn assert_same_diff(inverted: &serde_json::Value, reverse: &serde_json::Value) {
+    assert_eq!(inverted["base"], reverse["base"]);
+    assert_eq!(inverted["compare"], reverse["compare"]);
+    assert_eq!(inverted["row_changes"], reverse["row_changes"]);
+    assert_eq!(inverted["columns_changed"], reverse["columns_changed"]);
+    assert_eq!(inverted["row_count"], reverse["row_count"]);
+    assert_eq!(samples(inverted), samples(reverse));
+}
+
+#[test]
+fn test_invert_matches_reverse_diff() {
+    let runner = CliTestRunner::new().unwrap();
+    runner.snapshot_versions("fruit.csv", &[("old", OLD), ("new", NEW)]);
+
+    runner.expect_success(&["diff", "old", "new", "--invert", "-o", "inverted.json"]);
+    runner.expect_success(&["diff", "new", "old", "-o", "reverse.json"]);
+    let inverted = runner.read_diff_json("inverted.json");
+    let reverse = runner.read_diff_json("reverse.json");
+
+    assert_eq!(inverted["base"], "new");
+    assert_eq!(inverted["compare"], "old");
+    assert_same_diff(&inverted, &reverse);
+}
+
+#[test]
+fn test_invert_with_key_swaps_added_and_removed_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    runner.snapshot_versions("fruit.csv", &[("old", OLD), ("new", NEW)]);
+
+    runner.expect_success(&["diff", "old", "new", "--key", "id", "--invert", "-o", "inverted.json"]);
+    runner.expect_success(&["diff", "new", "old", "--key", "id", "-o", "reverse.json"]);
+    let inverted = runner.read_diff_json("inverted.json");
+    let reverse = runner.read_diff_json("reverse.json");
+
+    // kiwi was added going forward, so it is removed going back; plum the other way round
+    assert_eq!(inverted["row_changes"]["added"], 1);
+    assert_eq!(inverted["row_changes"]["removed"], 1);
+    assert_eq!(inverted["row_changes"]["modified"], 1);
+    assert!(inverted["sample_changes"].to_string().contains("plum"));
+    assert_same_diff(&inverted, &reverse);
+}
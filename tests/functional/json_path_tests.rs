@@ -0,0 +1,38 @@
+//! Tests for `--json-path` row selection in nested JSON inputs
+
+use crate::common::CliTestRunner;
+
+const NESTED: &str = r#"{"meta": {"version": 1}, "data": {"tags": [{"tag": "x"}], "items": [{"id": 1, "name": "Apple"}, {"id": 2, "name": "Banana"}, {"id": 3, "name": "Cherry"}]}}"#;
+
+#[test]
+fn test_snapshot_uses_array_at_json_path_as_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("nested.json", NESTED).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1", "--json-path", "/data/items"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("v1");
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.row_count, 3);
+    let columns: Vec<&str> = metadata.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(columns, vec!["id", "name"]);
+    assert_eq!(metadata.json_path.as_deref(), Some("/data/items"));
+    
+    // status reads the current file through the recorded path
+    runner.fixture().create_csv_raw("nested.json", &NESTED.replace("Banana", "Blueberry")).unwrap();
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    assert_eq!(modified.len(), 1);
+    assert_eq!(modified[0]["changes"]["name"]["after"], "Blueberry");
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_json_path_rejects_invalid_pointer_and_non_json_input() {
+    let runner = CliTestRunner::new().unwrap();
+    let json = runner.fixture().create_csv_raw("nested.json", NESTED).unwrap();
+    let csv = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n").unwrap();
+    
+    runner.expect_failure(&["snapshot", json.to_str().unwrap(), "--name", "v1", "--json-path", "data/items"]);
+    runner.expect_failure(&["snapshot", csv.to_str().unwrap(), "--name", "v2", "--json-path", "/data/items"]);
+}
@@ -0,0 +1,73 @@
+//! Tests for `--format junit` reports
+
+use crate::common::CliTestRunner;
+
+/// Check that every element is closed in order, returning the element names as opened
+fn assert_well_formed(xml: &str) -> Vec<String> {
+    let body = xml.trim().strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+        .unwrap_or_else(|| panic!("Missing XML declaration: {}", xml));
+    let mut open = Vec::new();
+    let mut elements = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        assert!(rest[..start].trim().is_empty(), "Unexpected text in: {}", xml);
+        let end = rest[start..].find('>').expect("unterminated tag") + start;
+        let tag = &rest[start + 1..end];
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop().as_deref(), Some(name), "Mismatched closing tag in: {}", xml);
+        } else {
+            let name = tag.split_whitespace().next().unwrap().trim_end_matches('/').to_string();
+            assert_eq!(tag.matches('"').count() % 2, 0, "Unbalanced quotes in <{}>", tag);
+            elements.push(name.clone());
+            if !tag.ends_with('/') {
+                open.push(name);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    assert!(rest.trim().is_empty() && open.is_empty(), "Unclosed elements {:?} in: {}", open, xml);
+    elements
+}
+
+#[test]
+fn test_diff_junit_reports_each_change_as_failure() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alice\n2,Bob\n3,Carol\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alice\n2,Robert & <Bob>\n3,Carol\n4,Dave\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--format", "junit"]);
+    let elements = assert_well_formed(&output);
+    let failures = elements.iter().filter(|name| *name == "failure").count();
+    // One modified row and one added row
+    assert_eq!(failures, 2, "Unexpected report: {}", output);
+    assert_eq!(elements.iter().filter(|name| *name == "testcase").count(), 2);
+    assert!(output.contains("<testsuite name=\"v1 → v2\" tests=\"2\" failures=\"2\">"), "Unexpected report: {}", output);
+    assert!(output.contains("name: &apos;Bob&apos; → &apos;Robert &amp; &lt;Bob&gt;&apos;"), "Unexpected report: {}", output);
+    assert!(!output.contains("Comparing snapshots"), "Unexpected report: {}", output);
+    
+    let output = runner.run_binary_stdout(&["diff", "v1", "v1", "--format", "junit"]);
+    let elements = assert_well_formed(&output);
+    assert!(!elements.iter().any(|name| name == "failure"), "Unexpected report: {}", output);
+    assert!(output.contains("failures=\"0\""), "Unexpected report: {}", output);
+}
+
+#[test]
+fn test_status_junit_matches_change_count() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--format", "junit"]);
+    assert_well_formed(&output);
+    assert!(output.contains("tests=\"1\" failures=\"0\""), "Unexpected report: {}", output);
+    
+    runner.fixture().create_csv_raw("people.csv", "id,name\n1,Alicia\n").unwrap();
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--format", "junit"]);
+    let elements = assert_well_formed(&output);
+    assert_eq!(elements.iter().filter(|name| *name == "failure").count(), 2, "Unexpected report: {}", output);
+    
+    runner.expect_failure(&["status", csv_path.to_str().unwrap(), "--format", "junit", "--json"]);
+    runner.expect_failure(&["diff", "v1", "v1", "--format", "xml"]);
+}
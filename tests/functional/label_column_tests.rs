@@ -0,0 +1,45 @@
+//! Tests for `diff --label-column`, which names changed rows by a column value
+
+use crate::common::CliTestRunner;
+
+const BASELINE: &str = "id,name,price\n40,apple,1.00\n41,pear,2.00\n42,plum,1.50\n";
+const CURRENT: &str = "id,name,price\n40,apple,1.00\n41,pear,2.00\n42,plum,1.75\n43,fig,4.00\n";
+
+#[test]
+fn test_changes_are_labeled_by_id() {
+    let runner = CliTestRunner::new().unwrap();
+    runner.snapshot_versions("fruit.csv", &[("v1", BASELINE), ("v2", CURRENT)]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "--label-column", "id", "-o", "diff.json"]);
+    let diff = runner.read_diff_json("diff.json");
+    let samples = diff["sample_changes"].as_array().unwrap();
+    let label_of = |kind: &str| samples.iter()
+        .find(|sample| sample["type"] == kind)
+        .map(|sample| sample["label"].clone())
+        .unwrap();
+    assert_eq!(label_of("modified"), "id=42");
+    assert_eq!(label_of("added"), "id=43");
+
+    let output = runner.run_binary_stdout(&[
+        "diff", "v1", "v2", "--key", "id", "--label-column", "id", "--numeric-delta",
+    ]);
+    assert!(output.contains("Row id=42 price: 1.5 → 1.75"), "Unexpected output: {}", output);
+    assert!(output.contains("id=42  price"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_missing_label_column_falls_back_to_index() {
+    let runner = CliTestRunner::new().unwrap();
+    runner.snapshot_versions("fruit.csv", &[("v1", BASELINE), ("v2", CURRENT)]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "--label-column", "sku", "-o", "diff.json"]);
+    let diff = runner.read_diff_json("diff.json");
+    let modified = diff["sample_changes"].as_array().unwrap().iter()
+        .find(|sample| sample["type"] == "modified")
+        .unwrap();
+    assert!(modified.get("label").is_none());
+    assert_eq!(modified["row_index"], 2);
+
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2", "--key", "id", "--label-column", "sku"]);
+    assert!(output.contains("neither side has a column 'sku'"), "Unexpected output: {}", output);
+}
@@ -0,0 +1,71 @@
+//! Tests for the `list` command
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_list_source_filters_and_reports_details() {
+    let runner = CliTestRunner::new().unwrap();
+    let a_path = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    let b_path = runner.fixture().create_csv_raw("b.csv", "id,city\n1,Paris\n").unwrap();
+    
+    runner.expect_success(&["snapshot", a_path.to_str().unwrap(), "--name", "a1"]);
+    runner.expect_success(&["snapshot", b_path.to_str().unwrap(), "--name", "b1"]);
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,Alice\n2,Bob\n3,Carol\n").unwrap();
+    runner.expect_success(&["snapshot", a_path.to_str().unwrap(), "--name", "a2"]);
+    
+    let output = runner.run_binary_stdout(&["list", "--source", "a.csv", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let entries = json.as_array().unwrap();
+    let names: Vec<&str> = entries.iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["a1", "a2"]);
+    
+    assert!(entries[0]["parent"].is_null());
+    assert_eq!(entries[1]["parent"], "a1");
+    assert_eq!(entries[1]["row_count"], 3);
+    assert_eq!(entries[1]["has_full_data"], true);
+    assert!(entries[1]["created"].is_string());
+    assert!(entries[1]["archive_size"].as_u64().unwrap() > 0);
+    
+    let output = runner.run_binary_stdout(&["list", "--source", "a.csv"]);
+    assert!(output.contains("a2"), "Unexpected output: {}", output);
+    assert!(output.contains("3 rows"), "Unexpected output: {}", output);
+    assert!(output.contains("parent a1"), "Unexpected output: {}", output);
+    assert!(!output.contains("b1"), "Unexpected output: {}", output);
+    
+    let output = runner.run_binary_stdout(&["list", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_glob_resolves_exactly_the_matching_snapshots() {
+    use tabdiff::resolver::{SnapshotRef, SnapshotResolver};
+    
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Alice\n").unwrap();
+    for name in ["nightly_1", "weekly_1", "nightly_2", "weekly_2", "nightly_3"] {
+        runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", name]);
+    }
+    
+    let resolver = SnapshotResolver::new(runner.fixture().workspace.clone());
+    let resolved = resolver.resolve_many(&[SnapshotRef::from_string("nightly_*".to_string())]).unwrap();
+    let names: Vec<&str> = resolved.iter().map(|snapshot| snapshot.name.as_str()).collect();
+    assert_eq!(names, vec!["nightly_1", "nightly_2", "nightly_3"]);
+    
+    // Names and patterns mix; a snapshot matched twice is returned once
+    let refs = ["weekly_2", "*_2"].map(|reference| SnapshotRef::from_string(reference.to_string()));
+    let resolved = resolver.resolve_many(&refs).unwrap();
+    let names: Vec<&str> = resolved.iter().map(|snapshot| snapshot.name.as_str()).collect();
+    assert_eq!(names, vec!["weekly_2", "nightly_2"]);
+    
+    // A single reference must match one snapshot, and a pattern must match at least one
+    assert!(resolver.resolve(&SnapshotRef::from_string("nightly_*".to_string())).is_err());
+    assert_eq!(resolver.resolve(&SnapshotRef::from_string("weekly_?".to_string())).map(|s| s.name).ok(), None);
+    assert_eq!(resolver.resolve(&SnapshotRef::from_string("weekly_1*".to_string())).unwrap().name, "weekly_1");
+    assert!(resolver.resolve_many(&[SnapshotRef::from_string("monthly_*".to_string())]).is_err());
+    
+    let output = runner.run_binary_stdout(&["list", "nightly_*", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let names: Vec<&str> = json.as_array().unwrap().iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["nightly_1", "nightly_2", "nightly_3"]);
+}
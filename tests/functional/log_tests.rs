@@ -0,0 +1,50 @@
+//! Tests for the per-source `log` command
+
+use crate::common::{CliTestRunner, sample_data};
+use tabdiff::snapshot::SnapshotChain;
+
+#[test]
+fn test_log_lists_snapshots_for_source_in_order() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv("data.csv", &sample_data::simple_csv_data()).unwrap();
+    let other_path = runner.fixture().create_csv("other.csv", &sample_data::simple_csv_data()).unwrap();
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_success(&["snapshot", other_path.to_str().unwrap(), "--name", "other"]);
+    
+    runner.fixture().update_csv("data.csv", &sample_data::updated_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.fixture().update_csv("data.csv", &sample_data::rows_added_csv_data()).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v3"]);
+    
+    // One tree line per snapshot of this file, oldest first
+    let text = runner.run_binary_stdout(&["log", csv_path.to_str().unwrap()]);
+    let names: Vec<&str> = text.lines()
+        .filter_map(|line| line.strip_prefix("├─ ").or_else(|| line.strip_prefix("└─ ")))
+        .map(|line| line.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(names, vec!["v1", "v2", "v3"], "Unexpected log: {}", text);
+    
+    // The JSON log lists exactly this file's snapshots, oldest first
+    let log: serde_json::Value = serde_json::from_str(&runner.run_binary_stdout(&["log", csv_path.to_str().unwrap(), "--json"])).unwrap();
+    let entries = log["snapshots"].as_array().unwrap();
+    let names: Vec<&str> = entries.iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(entries.len(), 3, "Unexpected log: {}", log);
+    assert_eq!(names, vec!["v1", "v2", "v3"]);
+    assert_eq!(entries[0]["parent"], serde_json::Value::Null);
+    assert_eq!(entries[2]["parent"], "v2");
+    
+    // It matches the source chain the log is built from
+    let canonical = csv_path.canonicalize().unwrap().to_string_lossy().to_string();
+    assert_eq!(log["source"], canonical.as_str());
+    let chain = SnapshotChain::build_chain_for_source(&runner.fixture().workspace, &canonical).unwrap();
+    let names: Vec<&str> = chain.snapshots.iter().map(|s| s.name.as_str()).collect();
+    
+    assert_eq!(names, vec!["v1", "v2", "v3"]);
+    assert!(chain.snapshots[0].delta_from_parent.is_none());
+    
+    let v3_summary = chain.snapshots[2].delta_from_parent.as_ref().unwrap().changes.summary();
+    assert!(v3_summary.contains("schema unchanged"), "Unexpected summary: {}", v3_summary);
+}
@@ -0,0 +1,46 @@
+//! Tests for `--match-threshold`, which decides when an added and a removed row pair up
+
+use crate::common::CliTestRunner;
+
+/// Counts of (modified, added, removed) rows reported by `status --json`
+fn row_change_counts(runner: &CliTestRunner, args: &[&str]) -> (usize, usize, usize) {
+    let output = runner.run_binary_stdout(args);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let count = |kind: &str| json["row_changes"][kind].as_array().unwrap().len();
+    (count("modified"), count("added"), count("removed"))
+}
+
+#[test]
+fn test_threshold_shifts_between_modification_and_add_remove() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name,city,age\n1,alice,paris,30\n2,bob,rome,40\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    // Alice moved to another position and city: 3 of 4 columns still match
+    runner.fixture().create_csv_raw("people.csv", "id,name,city,age\n2,bob,rome,40\n1,alice,berlin,30\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_eq!(row_change_counts(&runner, &["status", path, "--json"]), (1, 0, 0));
+    assert_eq!(row_change_counts(&runner, &["status", path, "--json", "--match-threshold", "0.2"]), (1, 0, 0));
+    assert_eq!(row_change_counts(&runner, &["status", path, "--json", "--match-threshold", "0.9"]), (0, 1, 1));
+}
+
+#[test]
+fn test_low_threshold_pairs_wide_row_edits() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name,city,age\n1,alice,paris,30\n2,bob,rome,40\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    // Half of Alice's columns changed, which the default threshold treats as a different row
+    runner.fixture().create_csv_raw("people.csv", "id,name,city,age\n2,bob,rome,40\n1,alice,berlin,31\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_eq!(row_change_counts(&runner, &["status", path, "--json"]), (0, 1, 1));
+    assert_eq!(row_change_counts(&runner, &["status", path, "--json", "--match-threshold", "0.4"]), (1, 0, 0));
+}
+
+#[test]
+fn test_threshold_outside_unit_range_is_rejected() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,alice\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_failure(&["status", path.to_str().unwrap(), "--match-threshold", "1.5"]);
+}
@@ -0,0 +1,94 @@
+//! Tests for the `merge` command that squashes part of a snapshot chain
+
+use crate::common::CliTestRunner;
+use tabdiff::snapshot::{SnapshotChain, SnapshotLoader};
+
+#[test]
+fn test_merge_squashes_middle_of_chain() {
+    let runner = CliTestRunner::new().unwrap();
+    let workspace = &runner.fixture().workspace;
+    let file = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n3,Cherry\n").unwrap();
+    let file_arg = file.to_str().unwrap();
+    runner.expect_success(&["snapshot", file_arg, "--name", "s1"]);
+
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Blueberry\n3,Cherry\n4,Date\n").unwrap();
+    runner.expect_success(&["snapshot", file_arg, "--name", "s2"]);
+
+    // s3 stores hashes only, so its state has to be rebuilt from s2 plus the s2 → s3 delta
+    let s3_content = "id,name\n2,Blueberry\n3,Cranberry\n4,Date\n";
+    runner.fixture().create_csv_raw("data.csv", s3_content).unwrap();
+    runner.expect_success(&["snapshot", file_arg, "--name", "s3", "--hash-only"]);
+
+    runner.fixture().create_csv_raw("data.csv", "id,name\n2,Blueberry\n3,Cranberry\n4,Date\n5,Elderberry\n").unwrap();
+    runner.expect_success(&["snapshot", file_arg, "--name", "s4"]);
+
+    runner.expect_success(&["merge", "s2", "s3", "--name", "squashed"]);
+
+    let (archive_path, json_path) = workspace.snapshot_paths("squashed");
+    let squashed = SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(squashed.parent_snapshot.as_deref(), Some("s1"));
+    assert!(squashed.has_full_data);
+    assert!(squashed.delta_from_parent.is_none());
+    assert_eq!(squashed.row_count, 3);
+
+    let rows = SnapshotLoader::load_full_snapshot(&archive_path).unwrap().rows();
+    assert_eq!(rows, vec![
+        vec!["2".to_string(), "Blueberry".to_string()],
+        vec!["3".to_string(), "Cranberry".to_string()],
+        vec!["4".to_string(), "Date".to_string()],
+    ]);
+
+    // s4 now builds on the squashed snapshot; s2 and s3 drop out of its history
+    let canonical = file.canonicalize().unwrap().to_string_lossy().to_string();
+    let chain = SnapshotChain::build_chain_for_source(workspace, &canonical).unwrap();
+    assert_eq!(chain.find_path_to_snapshot("s4").unwrap(), vec!["s1", "squashed", "s4"]);
+
+    // The squashed snapshot takes a sequence number of its own and s4 still follows it
+    let order: Vec<&str> = chain.snapshots.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(order, vec!["s1", "s2", "s3", "squashed", "s4"]);
+    assert_eq!(chain.head.as_deref(), Some("s4"));
+    let mut sequence_numbers: Vec<u64> = chain.snapshots.iter().map(|s| s.sequence_number).collect();
+    sequence_numbers.dedup();
+    assert_eq!(sequence_numbers.len(), chain.snapshots.len(), "Duplicate sequence numbers: {:?}", sequence_numbers);
+    assert!(chain.validate().unwrap().is_empty());
+
+    // Rolling back to the squashed snapshot restores s3's content
+    runner.expect_success(&["rollback", file_arg, "--to", "squashed", "--force"]);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), s3_content);
+}
+
+#[test]
+fn test_merge_requires_ancestor_range() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "s1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "s2"]);
+
+    let error = runner.expect_failure(&["merge", "s2", "s1", "--name", "squashed"]);
+    assert!(error.to_string().contains("not an ancestor"), "Unexpected error: {}", error);
+    assert!(!runner.fixture().workspace.snapshot_exists("squashed"));
+}
+
+#[test]
+fn test_merge_shares_row_data_with_end_of_range() {
+    let runner = CliTestRunner::new().unwrap();
+    let workspace = &runner.fixture().workspace;
+    let file = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "s1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Blueberry\n3,Cherry\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "s2"]);
+
+    runner.expect_success(&["merge", "s1", "s2", "--name", "squashed"]);
+
+    // The squash reproduces s2's rows, so its archive refers to s2's row data instead of a copy
+    let metadata = |name: &str| SnapshotLoader::load_metadata(&workspace.snapshot_paths(name).1).unwrap();
+    assert_eq!(metadata("squashed").data_hash, metadata("s2").data_hash);
+    let (archive_path, _) = workspace.snapshot_paths("squashed");
+    let entries: Vec<String> = tabdiff::archive::ArchiveManager::list_archive_contents(&archive_path).unwrap()
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect();
+    assert!(entries.contains(&"data.parquet.ref".to_string()), "Unexpected entries: {:?}", entries);
+    assert_eq!(SnapshotLoader::load_full_snapshot(&archive_path).unwrap().rows().len(), 3);
+}
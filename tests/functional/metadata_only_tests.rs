@@ -0,0 +1,47 @@
+//! Tests for `snapshot --metadata-only` and schema-only comparison against such snapshots
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+#[test]
+fn test_metadata_only_snapshot_writes_no_archive() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("wide.csv", "id,name\n1,Alice\n2,Bob\n3,Carol\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1", "--metadata-only"]);
+    
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("v1");
+    assert!(!archive_path.exists());
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert!(metadata.metadata_only);
+    assert!(!metadata.has_full_data);
+    assert!(metadata.content_hash.is_none());
+    assert_eq!(metadata.row_count, 3);
+    assert_eq!(metadata.column_count, 2);
+    
+    runner.expect_failure(&["snapshot", path.to_str().unwrap(), "--name", "v2", "--metadata-only", "--hash-only"]);
+}
+
+#[test]
+fn test_schema_diff_against_metadata_only_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("wide.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1", "--metadata-only"]);
+    runner.fixture().create_csv_raw("wide.csv", "id,name,email\n1,Alice,a@example.com\n2,Robert,b@example.com\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2", "--only", "schema"]);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["schema_changed"], true);
+    assert_eq!(diff["schema_only"], true);
+    assert_eq!(diff["rows_changed"], 0);
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--compare-to", "v1", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["schema_changes"]["columns_added"][0]["name"], "email");
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    
+    // Row-level detail can't be produced without rows
+    runner.expect_failure(&["diff", "v1", "v2", "--only", "modified"]);
+    runner.expect_failure(&["status", path.to_str().unwrap(), "--compare-to", "v1", "--key", "id"]);
+}
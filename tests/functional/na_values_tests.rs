@@ -0,0 +1,79 @@
+//! Tests for `--na-values`, which reads sentinel strings such as `NA` as empty cells
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+fn assert_unchanged(status: &serde_json::Value) {
+    for category in ["modified", "added", "removed"] {
+        assert!(status["row_changes"][category].as_array().unwrap().is_empty(), "Unexpected {} rows: {}", category, status);
+    }
+    assert!(status["schema_changes"]["type_changes"].as_array().unwrap().is_empty(), "Unexpected type changes: {}", status);
+}
+
+#[test]
+fn test_status_reads_na_as_empty_cell() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,score,note\n1,,ok\n2,5,\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v1", "--full-data", "true"]);
+
+    fs::write(&csv_path, "id,score,note\n1,NA,ok\n2,5,NA\n").unwrap();
+    let status = runner.run_binary_json(&["status", path, "--json"]);
+    assert_eq!(status["row_changes"]["modified"].as_array().unwrap().len(), 2, "NA is text without --na-values: {}", status);
+
+    assert_unchanged(&runner.run_binary_json(&["status", path, "--json", "--na-values", "NA"]));
+}
+
+#[test]
+fn test_snapshot_records_na_values_for_status() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,score\n1,N/A\n2,5\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v1", "--full-data", "true", "--na-values", "N/A,-"]);
+
+    assert_eq!(runner.fixture().snapshot_metadata("v1")["na_values"], serde_json::json!(["N/A", "-"]));
+
+    // The recorded sentinels apply without repeating the option
+    fs::write(&csv_path, "id,score\n1,-\n2,5\n").unwrap();
+    assert_unchanged(&runner.run_binary_json(&["status", path, "--json"]));
+}
+
+#[test]
+fn test_diff_compares_na_as_empty_cell() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("notes.csv", "id,note\n1,\n2,ok\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v1", "--full-data", "true"]);
+    fs::write(&csv_path, "id,note\n1,NULL\n2,ok\n").unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v2", "--full-data", "true"]);
+
+    runner.expect_success(&["diff", "v1", "v2", "-o", "plain.json"]);
+    runner.expect_success(&["diff", "v1", "v2", "--na-values", "NULL", "-o", "na.json"]);
+    let rows_changed = |name: &str| runner.read_diff_json(name)["rows_changed"].as_u64().unwrap();
+    assert_eq!(rows_changed("plain.json"), 1);
+    assert_eq!(rows_changed("na.json"), 0);
+}
+
+#[test]
+fn test_diff_against_file_uses_recorded_na_values() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,score\n1,NA\n2,5\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v1", "--full-data", "true", "--na-values", "NA"]);
+
+    runner.expect_success(&["diff", "v1", path, "--current-format", "csv", "-o", "file.json"]);
+    let diff = runner.read_diff_json("file.json");
+    assert_eq!(diff["rows_changed"].as_u64().unwrap(), 0, "NA should read as the empty cell the snapshot stored: {}", diff);
+}
+
+#[test]
+fn test_diff_rejects_snapshots_with_different_na_values() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,score\n1,NA\n2,5\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "v1", "--full-data", "true", "--na-values", "NA"]);
+    runner.expect_success(&["snapshot", path, "--name", "v2", "--full-data", "true"]);
+
+    let error = runner.expect_failure(&["diff", "v1", "v2"]);
+    assert!(error.to_string().contains("--na-values"), "Unexpected error: {}", error);
+}
@@ -0,0 +1,60 @@
+//! Tests for `snapshot --chain`, which continues a chain wherever the file lives
+
+use crate::common::CliTestRunner;
+use std::fs;
+use std::path::PathBuf;
+
+const CONTENT: &str = "id,name,price\n1,apple,1.50\n2,pear,2.00\n";
+
+/// Write `content` to `<dir>/sales.csv` under the fixture root
+fn write_copy(runner: &CliTestRunner, dir: &str, content: &str) -> PathBuf {
+    let path = runner.fixture().root().join(dir).join("sales.csv");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_named_chain_spans_two_paths() {
+    let runner = CliTestRunner::new().unwrap();
+    let first = write_copy(&runner, "machine-a", CONTENT);
+    let second = write_copy(&runner, "machine-b", "id,name,price\n1,apple,1.75\n2,pear,2.00\n");
+
+    runner.expect_success(&["snapshot", first.to_str().unwrap(), "--name", "v1", "--chain", "sales"]);
+    runner.expect_success(&["snapshot", second.to_str().unwrap(), "--name", "v2", "--chain", "sales"]);
+
+    let v1 = runner.fixture().snapshot_metadata("v1");
+    let v2 = runner.fixture().snapshot_metadata("v2");
+    assert_eq!(v1["chain"], "sales");
+    assert_eq!(v2["chain"], "sales");
+    assert_ne!(v1["source_path"], v2["source_path"]);
+    assert_eq!(v2["parent_snapshot"], "v1");
+    assert_eq!(v2["sequence_number"], 1);
+
+    // Either path lists the whole chain
+    let output = runner.run_binary_stdout(&["list", "--source", second.to_str().unwrap(), "--json"]);
+    let listed: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let names: Vec<&str> = listed.as_array().unwrap().iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["v1", "v2"]);
+
+    // Later snapshots of a chained path stay in the chain without repeating --chain
+    write_copy(&runner, "machine-b", "id,name,price\n1,apple,1.75\n2,pear,2.25\n");
+    runner.expect_success(&["snapshot", second.to_str().unwrap(), "--name", "v3"]);
+    let v3 = runner.fixture().snapshot_metadata("v3");
+    assert_eq!(v3["chain"], "sales");
+    assert_eq!(v3["parent_snapshot"], "v2");
+}
+
+#[test]
+fn test_paths_without_chain_stay_separate() {
+    let runner = CliTestRunner::new().unwrap();
+    let first = write_copy(&runner, "machine-a", CONTENT);
+    let second = write_copy(&runner, "machine-b", CONTENT);
+
+    runner.expect_success(&["snapshot", first.to_str().unwrap(), "--name", "a1", "--chain", "sales"]);
+    runner.expect_success(&["snapshot", second.to_str().unwrap(), "--name", "b1"]);
+
+    let b1 = runner.fixture().snapshot_metadata("b1");
+    assert!(b1["chain"].is_null());
+    assert!(b1["parent_snapshot"].is_null());
+}
@@ -0,0 +1,91 @@
+//! Tests for rolling back only selected rows or columns
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+const SNAPSHOT_CONTENT: &str = "id,name,price\n1,Apple,100\n2,Banana,200\n3,Cherry,300\n";
+const EDITED_CONTENT: &str = "id,name,price\n1,Green Apple,999\n2,Banana,999\n3,Cherry,999\n4,Date,400\n";
+
+#[test]
+fn test_rollback_single_column_keeps_other_edits() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", SNAPSHOT_CONTENT).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+
+    // A bad bulk update to price alongside a legitimate rename and a new row
+    fs::write(&csv_path, EDITED_CONTENT).unwrap();
+
+    runner.expect_success(&["rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--columns", "price", "--force"]);
+
+    let content = fs::read_to_string(&csv_path).unwrap();
+    assert_eq!(content, "id,name,price\n1,Green Apple,100\n2,Banana,200\n3,Cherry,300\n4,Date,400\n");
+}
+
+#[test]
+fn test_rollback_selected_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", SNAPSHOT_CONTENT).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    fs::write(&csv_path, EDITED_CONTENT).unwrap();
+
+    runner.expect_success(&["rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--rows", "1-3", "--force"]);
+
+    let content = fs::read_to_string(&csv_path).unwrap();
+    assert_eq!(content, "id,name,price\n1,Green Apple,999\n2,Banana,200\n3,Cherry,300\n");
+}
+
+#[test]
+fn test_partial_rollback_dry_run_shows_filtered_operations() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", SNAPSHOT_CONTENT).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    fs::write(&csv_path, EDITED_CONTENT).unwrap();
+
+    let output = runner.run_binary_stdout(&[
+        "rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--columns", "price", "--dry-run",
+    ]);
+    assert!(output.contains("Rollback operations: 3"), "Unexpected output: {}", output);
+    assert!(output.contains("Row 0: price '999' → '100'"), "Unexpected output: {}", output);
+    assert!(!output.contains("Green Apple"), "Name edits should not be listed: {}", output);
+    assert!(!output.contains("Remove row"), "Added rows should not be listed: {}", output);
+    assert_eq!(fs::read_to_string(&csv_path).unwrap(), EDITED_CONTENT);
+
+    let error = runner.expect_failure(&["rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--columns", "cost", "--dry-run"]);
+    assert!(error.to_string().contains("Unknown column"), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_interactive_rollback_applies_only_accepted_changes() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", SNAPSHOT_CONTENT).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    fs::write(&csv_path, EDITED_CONTENT).unwrap();
+
+    // Roll back row 1, keep row 2, then keep everything after it (rows 3 and the added row)
+    let stdout = runner.run_binary_with_stdin(
+        &["rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--interactive"],
+        "y\nn\nq\n",
+    );
+
+    assert!(stdout.contains("Change 1/4"), "stdout: {}", stdout);
+    assert!(stdout.contains("Change 3/4"), "stdout: {}", stdout);
+    assert!(!stdout.contains("Change 4/4"), "stdout: {}", stdout);
+    let content = fs::read_to_string(&csv_path).unwrap();
+    assert_eq!(content, "id,name,price\n1,Apple,100\n2,Banana,999\n3,Cherry,999\n4,Date,400\n");
+}
+
+#[test]
+fn test_interactive_rollback_rejecting_everything_leaves_file_untouched() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("prices.csv", SNAPSHOT_CONTENT).unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline"]);
+    fs::write(&csv_path, EDITED_CONTENT).unwrap();
+
+    let stdout = runner.run_binary_with_stdin(
+        &["rollback", csv_path.to_str().unwrap(), "--to", "baseline", "--interactive"],
+        "n\nn\nn\nn\n",
+    );
+
+    assert!(stdout.contains("No changes accepted"), "stdout: {}", stdout);
+    assert_eq!(fs::read_to_string(&csv_path).unwrap(), EDITED_CONTENT);
+}
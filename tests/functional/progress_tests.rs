@@ -0,0 +1,36 @@
+//! Tests for machine-readable progress output
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_json_progress_events_on_stderr() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_large_csv("large.csv", 5000, 4).unwrap();
+
+    // Run the real binary so stderr can be captured
+    let stderr = runner.run_binary_stderr(&[
+        "--progress-format", "json",
+        "snapshot", csv_path.to_str().unwrap(), "--name", "large",
+    ]);
+    assert!(!stderr.contains("Processed:"), "Text progress should not be emitted in JSON mode");
+
+    // Log lines share stderr; progress events are the JSON objects
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("Not a JSON event: {:?} ({})", line, e)))
+        .collect();
+
+    let hashing: Vec<_> = events.iter().filter(|e| e["phase"] == "hashing").collect();
+    assert!(!hashing.is_empty(), "Expected hashing progress events");
+    for event in &events {
+        assert!(event["processed"].is_u64());
+        assert!(event["total"].is_u64());
+        assert!(event["rate"].is_number());
+    }
+
+    let last = hashing.last().unwrap();
+    assert_eq!(last["processed"], 5000);
+    assert_eq!(last["total"], 5000);
+    assert!(events.iter().any(|e| e["phase"] == "archive"), "Expected archive progress events");
+}
@@ -0,0 +1,138 @@
+//! Tests for reading `http(s)://` inputs through DuckDB's httpfs extension
+
+use crate::common::CliTestRunner;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+/// Minimal HTTP server answering HEAD and (ranged) GET requests for every path with one body
+struct FileServer {
+    port: u16,
+    body: Arc<Mutex<Vec<u8>>>,
+}
+
+impl FileServer {
+    fn start(body: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let body = Arc::new(Mutex::new(body.as_bytes().to_vec()));
+        let served = Arc::clone(&body);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let body = served.lock().unwrap().clone();
+                let _ = Self::respond(stream, &body);
+            }
+        });
+        Self { port, body }
+    }
+
+    fn url(&self, name: &str) -> String {
+        format!("http://127.0.0.1:{}/{}", self.port, name)
+    }
+
+    fn set_body(&self, body: &str) {
+        *self.body.lock().unwrap() = body.as_bytes().to_vec();
+    }
+
+    fn respond(mut stream: TcpStream, body: &[u8]) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut range = None;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("range") {
+                    range = value.trim().strip_prefix("bytes=").and_then(|spec| {
+                        let (start, end) = spec.split_once('-')?;
+                        let start: usize = start.parse().ok()?;
+                        let end = end.parse().map_or(body.len() - 1, |end: usize| end.min(body.len() - 1));
+                        Some((start, end))
+                    });
+                }
+            }
+        }
+
+        let (status, content, content_range) = match range {
+            Some((start, end)) => ("206 Partial Content", &body[start..=end], format!("Content-Range: bytes {}-{}/{}\r\n", start, end, body.len())),
+            None => ("200 OK", body, String::new()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\n{}Content-Type: text/csv\r\nConnection: close\r\n\r\n",
+            status, content.len(), content_range
+        )?;
+        if !request_line.starts_with("HEAD") {
+            stream.write_all(content)?;
+        }
+        stream.flush()
+    }
+}
+
+/// Whether httpfs is already in the extension directory, so URL inputs load without a download
+fn httpfs_available() -> bool {
+    use tabdiff::duckdb_config::{extension_state, DuckDbSettings, ExtensionState};
+
+    let connection = duckdb::Connection::open_in_memory().unwrap();
+    if let Some(directory) = DuckDbSettings::from_env().unwrap().extension_directory {
+        let sql = format!("SET extension_directory='{}'", directory.display().to_string().replace('\'', "''"));
+        connection.execute(&sql, []).unwrap();
+    }
+    extension_state(&connection, "httpfs").unwrap() != ExtensionState::NotInstalled
+}
+
+#[test]
+fn test_remote_csv_loads_and_diffs() {
+    if !httpfs_available() {
+        eprintln!("Skipping: the httpfs extension is not installed and would have to be downloaded");
+        return;
+    }
+    let server = FileServer::start("id,name,price\n1,Widget,9.99\n2,Gadget,19.99\n");
+    let url = server.url("products.csv");
+    let runner = CliTestRunner::new().unwrap();
+
+    runner.expect_success(&["snapshot", &url, "--name", "remote_v1", "--full-data", "true"]);
+    assert_eq!(runner.fixture().snapshot_metadata("remote_v1")["row_count"], 2);
+
+    server.set_body("id,name,price\n1,Widget,9.99\n2,Gadget,24.99\n3,Doohickey,4.99\n");
+    runner.expect_success(&["diff", "remote_v1", &url, "--current-format", "csv", "-o", "diff.json"]);
+    let diff = runner.read_diff_json("diff.json");
+    assert_eq!(diff["row_changes"], serde_json::json!({ "modified": 1, "added": 1, "removed": 0 }), "Unexpected diff: {}", diff);
+}
+
+#[test]
+#[ignore = "downloads the httpfs extension; run with --ignored when online"]
+fn test_snapshot_and_status_of_remote_csv() {
+    let server = FileServer::start("id,name,price\n1,Widget,9.99\n2,Gadget,19.99\n");
+    let url = server.url("products.csv");
+    let runner = CliTestRunner::new().unwrap();
+
+    runner.expect_success(&["snapshot", &url, "--name", "remote_v1", "--full-data", "true"]);
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("remote_v1");
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap();
+    assert_eq!(metadata["row_count"], 2);
+    assert_eq!(metadata["source_path"], url.as_str(), "The URL is the source's identity");
+
+    // Without --compare-to the snapshot is found through the URL
+    server.set_body("id,name,price\n1,Widget,9.99\n2,Gadget,24.99\n3,Doohickey,4.99\n");
+    let output = runner.run_binary_stdout(&["status", &url, "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["modified"].as_array().unwrap().len(), 1);
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+}
+
+#[test]
+#[ignore = "downloads the httpfs extension; run with --ignored when online"]
+fn test_rollback_rejects_remote_input() {
+    let server = FileServer::start("id,name\n1,Widget\n");
+    let url = server.url("products.csv");
+    let runner = CliTestRunner::new().unwrap();
+
+    runner.expect_success(&["snapshot", &url, "--name", "remote_v1", "--full-data", "true"]);
+    let error = runner.expect_failure(&["rollback", &url, "--to", "remote_v1", "--force"]);
+    assert!(error.to_string().contains("only local files can be rewritten"), "Unexpected error: {}", error);
+}
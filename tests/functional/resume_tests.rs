@@ -0,0 +1,24 @@
+//! Tests for `snapshot --resume` and row hash checkpoints
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+#[test]
+fn test_snapshot_resume_ignores_foreign_checkpoint_and_cleans_up() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("rows.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v0"]);
+    
+    // A checkpoint left by a run over some other input must not be resumed
+    let checkpoint_path = runner.fixture().workspace.checkpoint_path("v1");
+    fs::write(&checkpoint_path, "tabdiff-hash-checkpoint v1 other-input\nabc123\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1", "--resume"]);
+    assert!(!checkpoint_path.exists());
+    
+    let load = |name: &str| {
+        let (_, json_path) = runner.fixture().workspace.snapshot_paths(name);
+        tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap()
+    };
+    assert_eq!(load("v1").content_hash, load("v0").content_hash);
+    assert!(!runner.fixture().workspace.checkpoint_path("v0").exists());
+}
@@ -0,0 +1,107 @@
+//! Tests for `--key` row matching in `diff` and `status`
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+#[test]
+fn test_status_key_matches_rows_after_insert() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("users.csv", "id,name\n1,Alice\n2,Bob\n3,Carol\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("users.csv", "id,name\n4,Dave\n1,Alice\n2,Robert\n3,Carol\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--key", "id"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    assert_eq!(modified.len(), 1);
+    assert_eq!(modified[0]["changes"]["name"]["before"], "Bob");
+    assert_eq!(modified[0]["changes"]["name"]["after"], "Robert");
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_composite_key_and_unknown_key_column() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("sales.csv", "region,id,amount\neu,1,10\nus,1,20\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("sales.csv", "region,id,amount\nus,1,25\neu,1,10\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2", "--key", "region,id"]);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["rows_changed"], 1);
+    
+    runner.expect_failure(&["diff", "v1", "v2", "--key", "missing"]);
+}
+
+#[test]
+fn test_diff_rejects_duplicate_keys_unless_allowed() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("orders.csv", "id,item\n1,pen\n2,ink\n1,pad\n3,cap\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("orders.csv", "id,item\n1,pad\n2,ink\n1,pen\n3,cap\n3,box\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    // Both sides repeat a key; the error names each duplicated value and its rows
+    let error = runner.expect_failure(&["diff", "v1", "v2", "--key", "id"]).to_string();
+    assert!(error.contains("does not identify rows uniquely"), "Unexpected error: {}", error);
+    assert!(error.contains("id=1 (baseline rows 0, 2)"), "Unexpected error: {}", error);
+    assert!(error.contains("id=3 (current rows 3, 4)"), "Unexpected error: {}", error);
+    assert!(error.contains("--allow-duplicate-keys"), "Unexpected error: {}", error);
+    
+    // Allowed, rows sharing a key pair up by content: the swapped id=1 rows are unchanged
+    runner.expect_success(&["diff", "v1", "v2", "--key", "id", "--allow-duplicate-keys"]);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(diff_path).unwrap()).unwrap();
+    assert_eq!(diff["row_changes"]["modified"], 0);
+    assert_eq!(diff["row_changes"]["added"], 1);
+    assert_eq!(diff["row_changes"]["removed"], 0);
+}
+
+#[test]
+fn test_duplicate_rows_warn_about_hash_quality() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", "id,name\n1,a\n1,a\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("events.csv", "id,name\n1,a\n1,a\n2,b\n3,c\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["hash_quality"]["unique_base_hashes"], 2);
+    assert!(json["hash_quality"]["base_collision_rate"].as_f64().unwrap() > 0.0);
+    
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap()]);
+    assert!(output.contains("many rows share a hash"));
+    assert!(output.contains("--key"));
+    
+    // Matching by key bypasses content hashing, so there is nothing to warn about
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--key", "id,name", "--allow-duplicate-keys"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json.get("hash_quality").is_none());
+}
+
+#[test]
+fn test_ignore_row_order_compares_rows_as_a_set() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("export.csv", "id,name\n1,a\n2,b\n3,c\n4,d\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("export.csv", "id,name\n3,c\n1,a\n4,d\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+    
+    runner.expect_success(&["diff", "v1", "v2", "--ignore-row-order"]);
+    let diff_path = runner.fixture().workspace.diff_path("v1", "v2");
+    let diff: serde_json::Value = serde_json::from_str(&fs::read_to_string(&diff_path).unwrap()).unwrap();
+    assert_eq!(diff["rows_changed"], 0);
+    
+    // An edited row is a removal plus an addition, never a modification
+    runner.fixture().create_csv_raw("export.csv", "id,name\n3,c\n1,a\n4,d\n2,z\n").unwrap();
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--ignore-row-order"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty());
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert_eq!(json["row_changes"]["removed"].as_array().unwrap().len(), 1);
+    
+    runner.expect_failure(&["diff", "v1", "v2", "--ignore-row-order", "--key", "id"]);
+}
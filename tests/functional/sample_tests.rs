@@ -0,0 +1,87 @@
+//! Tests for `snapshot --sample` and the `--auto-sample` guard of `diff` and `status`
+
+use crate::common::CliTestRunner;
+
+/// CSV with one row per id in `ids`
+fn rows_csv(ids: std::ops::Range<usize>) -> String {
+    let mut content = String::from("id,value\n");
+    for id in ids {
+        content.push_str(&format!("{},item-{}\n", id, id));
+    }
+    content
+}
+
+fn snapshot_metadata(runner: &CliTestRunner, name: &str) -> serde_json::Value {
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths(name);
+    serde_json::from_str(&std::fs::read_to_string(json_path).unwrap()).unwrap()
+}
+
+#[test]
+fn test_sample_snapshot_records_a_share_of_the_rows() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", &rows_csv(0..200)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "base", "--sample", "0.1", "--sample-seed", "7"]);
+
+    let metadata = snapshot_metadata(&runner, "base");
+    let row_count = metadata["row_count"].as_u64().unwrap();
+    assert!(row_count > 0 && row_count < 60, "Unexpected sample size: {}", row_count);
+    assert_eq!(metadata["sample"], serde_json::json!({ "fraction": 0.1, "seed": 7 }));
+
+    // The same fraction and seed keep the same rows
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "again", "--sample", "0.1", "--sample-seed", "7"]);
+    assert_eq!(snapshot_metadata(&runner, "again")["content_hash"], metadata["content_hash"]);
+}
+
+#[test]
+fn test_sample_against_full_file_needs_auto_sample() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", &rows_csv(0..200)).unwrap();
+    let path = path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "base", "--sample", "0.1", "--sample-seed", "7"]);
+
+    runner.expect_failure(&["status", path]);
+    runner.expect_failure(&["diff", "base", path, "--current-format", "csv"]);
+
+    let status: serde_json::Value = serde_json::from_str(&runner.run_binary_stdout(&["status", path, "--json", "--auto-sample"])).unwrap();
+    assert!(status["row_changes"]["added"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+    assert!(status["row_changes"]["removed"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+
+    runner.expect_success(&["diff", "base", path, "--current-format", "csv", "--auto-sample", "-o", "diff.json"]);
+    let diff: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(runner.fixture().root().join("diff.json")).unwrap()
+    ).unwrap();
+    assert_eq!(diff["rows_changed"], 0, "Unexpected diff: {}", diff);
+}
+
+#[test]
+fn test_auto_sample_reports_only_sampled_additions() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", &rows_csv(0..200)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "base", "--sample", "0.1", "--sample-seed", "7"]);
+    // Appended rows enter the sample at its rate; the existing rows stay where they were
+    runner.fixture().create_csv_raw("data.csv", &rows_csv(0..400)).unwrap();
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--auto-sample", "--key", "id"]);
+    let status: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let count = |kind: &str| status["row_changes"][kind].as_array().unwrap().len();
+    assert_eq!(count("removed"), 0, "Unexpected status: {}", status);
+    assert_eq!(count("modified"), 0, "Unexpected status: {}", status);
+    assert!(count("added") > 0 && count("added") < 60, "Unexpected status: {}", status);
+}
+
+#[test]
+fn test_snapshots_with_different_samples_are_not_compared() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", &rows_csv(0..200)).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "full"]);
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "sampled", "--sample", "0.1"]);
+
+    runner.expect_failure(&["diff", "full", "sampled"]);
+}
+
+#[test]
+fn test_sample_fraction_must_be_positive() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", &rows_csv(0..10)).unwrap();
+    runner.expect_failure(&["snapshot", path.to_str().unwrap(), "--name", "base", "--sample", "0"]);
+}
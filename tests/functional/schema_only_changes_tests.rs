@@ -0,0 +1,40 @@
+//! Tests for `--report-schema-only-changes`, which compares type-changed columns as their type
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_leading_zero_ids_report_type_change_without_modifications() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n001,a\n002,b\n003,c\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["schema_changes"]["type_changes"].as_array().unwrap().len(), 1, "Unexpected output: {}", output);
+    assert_eq!(json["row_changes"]["modified"].as_array().unwrap().len(), 3);
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--report-schema-only-changes"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let type_changes = json["schema_changes"]["type_changes"].as_array().unwrap();
+    assert_eq!(type_changes.len(), 1, "Unexpected output: {}", output);
+    assert_eq!(type_changes[0]["column"], "id");
+    assert!(json["row_changes"]["modified"].as_array().unwrap().is_empty(), "Unexpected output: {}", output);
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["removed"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_still_reports_changed_values_of_type_changed_column() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n001,a\n002,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n5,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    runner.expect_success(&["diff", "v1", "v2", "--report-schema-only-changes", "-o", "diff.json"]);
+    let diff: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(runner.fixture().root().join("diff.json")).unwrap()
+    ).unwrap();
+    assert_eq!(diff["row_changes"]["modified"], 1, "Unexpected diff: {}", diff);
+}
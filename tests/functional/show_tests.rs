@@ -0,0 +1,106 @@
+//! Tests for the `show` command
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_show_diff_parent_uses_cached_delta() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "parent"]);
+    
+    runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.99\n2,Banana,0.75\n3,Cherry,2.00\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "child"]);
+    
+    // Remove the parent archive so the output can only come from the cached delta
+    let (parent_archive, _) = runner.fixture().workspace.snapshot_paths("parent");
+    std::fs::remove_file(&parent_archive).unwrap();
+    
+    let output = runner.run_binary_stdout(&["show", "child", "--diff-parent"]);
+    assert!(output.contains("'parent' → 'child'"), "Unexpected output: {}", output);
+    assert!(output.contains("Modified rows: 1"), "Unexpected output: {}", output);
+    assert!(output.contains("price: '1.5' → '1.99'"), "Unexpected output: {}", output);
+    assert!(output.contains("Added rows: 1"), "Unexpected output: {}", output);
+    
+    let output = runner.run_binary_stdout(&["show", "child", "--diff-parent", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["parent"], "parent");
+    assert_eq!(json["changes"]["row_changes"]["added"].as_array().unwrap().len(), 1);
+    
+    let output = runner.run_binary_stdout(&["show", "parent", "--diff-parent"]);
+    assert!(output.contains("first snapshot / no delta"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_snapshot_message_is_stored_and_shown() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "baseline", "-m", "pre-migration baseline"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "after"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("baseline");
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.description.as_deref(), Some("pre-migration baseline"));
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("after");
+    assert!(tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap().description.is_none());
+    
+    let output = runner.run_binary_stdout(&["show", "baseline"]);
+    assert!(output.contains("Description: pre-migration baseline"), "Unexpected output: {}", output);
+    let output = runner.run_binary_stdout(&["list"]);
+    assert!(output.contains("baseline - pre-migration baseline"), "Unexpected output: {}", output);
+    let output = runner.run_binary_stdout(&["chain"]);
+    assert!(output.contains("Description: pre-migration baseline"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_show_schema_format_exports_columns() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("typed.csv", "id,name,score,joined\n1,Alice,9.5,2024-01-02\n2,Bob,7.25,2024-03-04\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "typed"]);
+    
+    let output = runner.run_binary_stdout(&["show", "typed", "--schema-format", "jsonschema"]);
+    let schema: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["id"]["type"][0], "integer");
+    assert_eq!(schema["properties"]["name"]["type"][0], "string");
+    assert_eq!(schema["properties"]["score"]["type"][0], "number");
+    assert_eq!(schema["properties"]["joined"]["format"], "date");
+    
+    let output = runner.run_binary_stdout(&["show", "typed", "--schema-format", "ddl"]);
+    assert!(output.trim().starts_with("CREATE TABLE \"typed\" ("), "Unexpected output: {}", output);
+    assert!(output.contains("\"id\" BIGINT"), "Unexpected output: {}", output);
+    assert!(output.contains("\"joined\" DATE"), "Unexpected output: {}", output);
+    assert!(output.trim().ends_with(");"));
+    
+    runner.expect_failure(&["show", "typed", "--schema-format", "xml"]);
+}
+
+#[test]
+fn test_show_raw_lists_and_dumps_archive_files() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--full-data", "true"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,Apple\n2,Blueberry\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2", "--full-data", "true"]);
+    
+    let output = runner.run_binary_stdout(&["show", "v2", "--raw", "--json"]);
+    let listing: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+    let names: Vec<&str> = listing.iter().map(|file| file["name"].as_str().unwrap()).collect();
+    for expected in ["metadata.json", "schema.json", "data.parquet", "delta.parquet"] {
+        assert!(names.contains(&expected), "Missing {} in {:?}", expected, names);
+    }
+    assert!(listing.iter().all(|file| file["size"].as_u64().unwrap() > 0), "Empty file in {:?}", listing);
+    
+    let output = runner.run_binary_stdout(&["show", "v2", "--raw"]);
+    assert!(output.contains("├─ metadata.json ("), "Unexpected output: {}", output);
+    
+    let schema = runner.run_binary_stdout(&["show", "v2", "--raw", "--file", "schema.json"]);
+    let schema: serde_json::Value = serde_json::from_str(&schema).unwrap();
+    assert_eq!(schema["columns"].as_array().unwrap().len(), 2);
+    
+    runner.expect_failure(&["show", "v2", "--raw", "--file", "missing.json"]);
+}
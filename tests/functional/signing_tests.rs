@@ -0,0 +1,52 @@
+//! Tests for signing snapshots and verifying their signatures
+
+use crate::common::CliTestRunner;
+use tabdiff::archive::{ArchiveManager, CompressionLevel};
+use tabdiff::snapshot::SnapshotLoader;
+
+#[test]
+fn test_signed_snapshot_verifies_until_its_archive_is_changed() {
+    let runner = CliTestRunner::new().unwrap();
+    let root = runner.fixture().root().to_path_buf();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    let key_path = root.join("snapshot.key");
+    std::fs::write(&key_path, "correct horse battery staple\n").unwrap();
+    let key = key_path.to_str().unwrap();
+
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--sign-key", key]);
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("v1");
+    let metadata = SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert!(metadata.signature.is_some());
+    assert_eq!(metadata.signed_by.as_deref().map(str::len), Some(16));
+
+    runner.expect_success(&["verify", "v1", "--check-signature", "--sign-key", key]);
+
+    // A different key is reported as such rather than as tampering
+    let other_key_path = root.join("other.key");
+    std::fs::write(&other_key_path, "another secret").unwrap();
+    let error = runner.expect_failure(&["verify", "v1", "--check-signature", "--sign-key", other_key_path.to_str().unwrap()]);
+    assert!(error.to_string().contains("was signed with key"), "Unexpected error: {}", error);
+
+    // Rewrite the archive with one file changed; it still reads fine, but no longer matches
+    let mut files = ArchiveManager::extract_archive_entries(&archive_path).unwrap();
+    let (_, content) = files.iter_mut().find(|(name, _)| name == "metadata.json").unwrap();
+    content.extend_from_slice(b"\n");
+    ArchiveManager::create_archive_with_progress(&archive_path, &files, CompressionLevel::default(), None).unwrap();
+
+    runner.expect_success(&["verify", "v1"]);
+    let error = runner.expect_failure(&["verify", "v1", "--check-signature", "--sign-key", key]);
+    assert!(error.to_string().contains("changed after it was signed"), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_check_signature_fails_for_unsigned_snapshots() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,Alice\n").unwrap();
+    let key_path = runner.fixture().root().join("snapshot.key");
+    std::fs::write(&key_path, "secret").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+
+    runner.expect_success(&["verify", "v1"]);
+    let error = runner.expect_failure(&["verify", "v1", "--check-signature", "--sign-key", key_path.to_str().unwrap()]);
+    assert!(error.to_string().contains("is not signed"), "Unexpected error: {}", error);
+}
@@ -504,4 +504,66 @@ ORDER BY p.product_id;
     assert_eq!(metadata["name"], "cte_snapshot");
     assert_eq!(metadata["row_count"], 3);
     assert_eq!(metadata["column_count"], 4);
-}
\ No newline at end of file
+}
+#[test]
+fn test_snapshot_from_inline_query() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,x\n1,5\n2,-3\n3,0\n4,8\n").unwrap();
+    let query = format!("SELECT * FROM read_csv('{}') WHERE x > 0", csv_path.display());
+    
+    runner.expect_success(&["snapshot", "--from-query", query.as_str(), "--name", "q"]);
+    
+    let (archive_path, json_path) = runner.fixture().workspace.snapshot_paths("q");
+    assert!(archive_path.exists());
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.row_count, 2);
+    assert_eq!(metadata.source, query);
+    assert!(metadata.source_path.as_deref().unwrap().starts_with("query:"));
+    
+    // Snapshots of the same query form a chain
+    runner.expect_success(&["snapshot", "--from-query", query.as_str(), "--name", "q2"]);
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("q2");
+    let metadata = tabdiff::snapshot::SnapshotLoader::load_metadata(&json_path).unwrap();
+    assert_eq!(metadata.parent_snapshot.as_deref(), Some("q"));
+    
+    runner.expect_failure(&["snapshot", csv_path.to_str().unwrap(), "--from-query", query.as_str(), "--name", "q3"]);
+    runner.expect_failure(&["snapshot", "--name", "q4"]);
+}
+
+#[test]
+fn test_sql_all_tables_creates_a_chain_per_table() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let write_shop = |customers: &str, orders: &str| {
+        let sql_path = runner.fixture().temp_dir.path().join("shop.sql");
+        fs::write(&sql_path, format!(
+            "CREATE TABLE customers (id INTEGER, name VARCHAR);\n\
+             INSERT INTO customers VALUES {};\n\
+             CREATE TABLE orders (id INTEGER, customer_id INTEGER, total DOUBLE);\n\
+             INSERT INTO orders VALUES {};\n",
+            customers, orders
+        )).unwrap();
+        sql_path
+    };
+    
+    let sql_path = write_shop("(1, 'Ann'), (2, 'Bob')", "(10, 1, 9.5), (11, 2, 12.0), (12, 1, 3.25)");
+    runner.expect_success(&["snapshot", sql_path.to_str().unwrap(), "--name", "v1", "--all-tables"]);
+    
+    let metadata = |name: &str| -> serde_json::Value {
+        let (_, json_path) = runner.fixture().workspace.snapshot_paths(name);
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap()
+    };
+    assert_eq!(metadata("v1::customers")["row_count"], 2);
+    assert_eq!(metadata("v1::orders")["row_count"], 3);
+    assert!(metadata("v1::orders")["parent_snapshot"].is_null());
+    
+    // Each table continues its own chain
+    write_shop("(1, 'Ann'), (2, 'Bob'), (3, 'Cy')", "(10, 1, 9.5), (11, 2, 12.0), (12, 1, 3.25)");
+    runner.expect_success(&["snapshot", sql_path.to_str().unwrap(), "--name", "v2", "--all-tables"]);
+    
+    assert_eq!(metadata("v2::customers")["row_count"], 3);
+    assert_eq!(metadata("v2::customers")["parent_snapshot"], "v1::customers");
+    assert_eq!(metadata("v2::orders")["parent_snapshot"], "v1::orders");
+    assert_eq!(metadata("v2::customers")["delta_from_parent"]["changes"]["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert!(metadata("v2::orders")["delta_from_parent"]["changes"]["row_changes"]["added"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,27 @@
+//! Tests for the `stat` command
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_stat_reports_counts_and_schema() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name,price\n1,Apple,1.50\n2,Banana,0.75\n3,Cherry,2.25\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["stat", csv_path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    
+    assert_eq!(json["row_count"], 3);
+    assert_eq!(json["column_count"], 3);
+    let columns: Vec<(&str, &str)> = json["columns"].as_array().unwrap()
+        .iter()
+        .map(|c| (c["name"].as_str().unwrap(), c["data_type"].as_str().unwrap()))
+        .collect();
+    assert_eq!(columns, vec![("id", "BIGINT"), ("name", "VARCHAR"), ("price", "DOUBLE")]);
+    
+    let output = runner.run_binary_stdout(&["stat", csv_path.to_str().unwrap()]);
+    assert!(output.contains("Rows: 3"), "Unexpected output: {}", output);
+    assert!(output.contains("price (DOUBLE)"), "Unexpected output: {}", output);
+    
+    // A dry read never creates snapshots
+    assert!(runner.fixture().workspace.list_snapshots().unwrap().is_empty());
+}
@@ -0,0 +1,47 @@
+//! Tests for `status --schema-only`, which compares schemas without loading any rows
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_schema_only_reports_column_changes_without_row_data() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,alice\n2,bob\n").unwrap();
+    // A hash-only snapshot stores no rows, so any row comparison against it fails
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1", "--hash-only"]);
+    runner.fixture().create_csv_raw("people.csv", "id,name,email\n1,alice,a@x.org\n2,robert,b@x.org\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    runner.expect_failure(&["status", path]);
+
+    let status = runner.run_binary_json(&["status", path, "--schema-only", "--json"]);
+    let added = status["schema_changes"]["columns_added"].as_array().unwrap();
+    assert_eq!(added.len(), 1, "Unexpected status: {}", status);
+    assert_eq!(added[0]["name"], "email");
+    // Bob's rename is a row change, which is not looked at
+    assert!(status["row_changes"]["modified"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+}
+
+#[test]
+fn test_schema_only_with_matching_schema_reports_no_changes() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,alice\n2,bob\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("people.csv", "id,name\n1,alice\n2,robert\n3,carol\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    let status = runner.run_binary_json(&["status", path, "--json"]);
+    assert_eq!(status["row_changes"]["modified"].as_array().unwrap().len(), 1, "Unexpected status: {}", status);
+
+    let status = runner.run_binary_json(&["status", path, "--schema-only", "--json"]);
+    assert!(status["schema_changes"]["columns_added"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+    assert!(status["row_changes"]["modified"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+    assert!(status["row_changes"]["added"].as_array().unwrap().is_empty(), "Unexpected status: {}", status);
+}
+
+#[test]
+fn test_schema_only_conflicts_with_row_options() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("people.csv", "id,name\n1,alice\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_failure(&["status", path.to_str().unwrap(), "--schema-only", "--key", "id"]);
+}
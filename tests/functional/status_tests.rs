@@ -0,0 +1,262 @@
+//! Tests for the `status` command: default baseline selection, `set-baseline`, watch mode, self-checks
+//! and the column-overlap guard
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_status_defaults_to_latest_snapshot_of_same_source() {
+    let runner = CliTestRunner::new().unwrap();
+    let file_a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    let file_b = runner.fixture().create_csv_raw("b.csv", "code,value\nX,10\nY,20\n").unwrap();
+    
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a1"]);
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a2"]);
+    // b1 is the most recent snapshot in the workspace, but of a different file
+    runner.expect_success(&["snapshot", file_b.to_str().unwrap(), "--name", "b1"]);
+    
+    let output = runner.run_binary_stdout(&["status", file_a.to_str().unwrap()]);
+    assert!(output.contains("against snapshot 'a2'"), "Unexpected output: {}", output);
+    
+    let output = runner.run_binary_stdout(&["status", file_a.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(json["schema_changes"]["columns_added"].as_array().unwrap().is_empty());
+    assert!(json["row_changes"]["added"].as_array().unwrap().is_empty());
+    
+    // --compare-to still overrides the default
+    let output = runner.run_binary_stdout(&["status", file_a.to_str().unwrap(), "--compare-to", "a1"]);
+    assert!(output.contains("against snapshot 'a1'"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_status_without_snapshot_of_source_fails() {
+    let runner = CliTestRunner::new().unwrap();
+    let file_a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    let file_c = runner.fixture().create_csv_raw("c.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a1"]);
+    
+    let error = runner.expect_failure(&["status", file_c.to_str().unwrap()]);
+    let message = error.to_string();
+    assert!(message.contains("No snapshots found for"), "Unexpected error: {}", message);
+    assert!(message.contains("c.csv"), "Unexpected error: {}", message);
+}
+
+#[test]
+fn test_status_warns_about_unrelated_schemas() {
+    let runner = CliTestRunner::new().unwrap();
+    let sales = runner.fixture().create_csv_raw("sales.csv", "order_id,total\n1,9.99\n2,24.50\n").unwrap();
+    let users = runner.fixture().create_csv_raw("users.csv", "user_id,email\n1,a@example.com\n").unwrap();
+    runner.expect_success(&["snapshot", users.to_str().unwrap(), "--name", "users"]);
+    
+    let output = runner.run_binary_stdout(&["status", sales.to_str().unwrap(), "--compare-to", "users"]);
+    assert!(output.contains("share only 0% of their column names"), "Unexpected output: {}", output);
+    
+    let error = runner.expect_failure(&["status", sales.to_str().unwrap(), "--compare-to", "users", "--strict"]);
+    assert!(error.to_string().contains("may be unrelated datasets"), "Unexpected error: {}", error);
+    
+    // A threshold of 0 accepts any pair of schemas
+    runner.expect_success(&["status", sales.to_str().unwrap(), "--compare-to", "users", "--strict", "--min-column-overlap", "0"]);
+    runner.expect_failure(&["status", sales.to_str().unwrap(), "--compare-to", "users", "--min-column-overlap", "1.5"]);
+}
+
+#[test]
+fn test_status_uses_recorded_baseline_over_latest_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "base"]);
+    runner.expect_success(&["set-baseline", file.to_str().unwrap(), "base"]);
+    
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "newer"]);
+    
+    // The baseline wins even though 'newer' is the head of the chain
+    let output = runner.run_binary_stdout(&["status", file.to_str().unwrap()]);
+    assert!(output.contains("against snapshot 'base'"), "Unexpected output: {}", output);
+    let output = runner.run_binary_stdout(&["status", file.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["added"].as_array().unwrap().len(), 1);
+    
+    // --compare-to still overrides the baseline
+    let output = runner.run_binary_stdout(&["status", file.to_str().unwrap(), "--compare-to", "newer"]);
+    assert!(output.contains("against snapshot 'newer'"), "Unexpected output: {}", output);
+    
+    // Clearing falls back to the chain head
+    runner.expect_success(&["set-baseline", file.to_str().unwrap(), "--clear"]);
+    let output = runner.run_binary_stdout(&["status", file.to_str().unwrap()]);
+    assert!(output.contains("against snapshot 'newer'"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_set_baseline_requires_existing_snapshot() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    
+    let error = runner.expect_failure(&["set-baseline", file.to_str().unwrap(), "missing"]);
+    assert!(matches!(error, tabdiff::TabdiffError::SnapshotNotFound { .. }), "Unexpected error: {}", error);
+}
+
+#[test]
+fn test_status_watch_reports_change_mid_loop() {
+    use std::io::{BufRead, BufReader};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let runner = CliTestRunner::new().unwrap();
+    let file_a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a1"]);
+
+    let mut child = runner.spawn_binary(&["status", file_a.to_str().unwrap(), "--watch", "0.2", "--max-iterations", "15"]);
+
+    let stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let reader = std::thread::spawn(move || {
+        let mut lines = Vec::new();
+        for line in BufReader::new(stdout).lines() {
+            let line = line.unwrap();
+            let _ = sender.send(line.clone());
+            lines.push(line);
+        }
+        lines
+    });
+
+    // Wait for the initial (unchanged) report before modifying the file
+    loop {
+        let line = receiver.recv_timeout(Duration::from_secs(30)).expect("No initial status printed");
+        if line.contains("Rows: unchanged") {
+            break;
+        }
+    }
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+
+    assert!(child.wait().unwrap().success());
+    let output = reader.join().unwrap().join("\n");
+
+    assert_eq!(output.matches("Rows: unchanged").count(), 1, "Unchanged status repeated: {}", output);
+    assert_eq!(output.matches("Status changed at").count(), 1, "Unexpected output: {}", output);
+    assert!(output.contains("Rows changed: 1"), "Unexpected output: {}", output);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_status_watch_stops_cleanly_on_interrupt() {
+    use std::io::{BufRead, BufReader};
+    use std::time::Duration;
+
+    let runner = CliTestRunner::new().unwrap();
+    let file_a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n").unwrap();
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a1"]);
+
+    let mut child = runner.spawn_binary(&["status", file_a.to_str().unwrap(), "--watch", "0.2", "--max-iterations", "1000"]);
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    while !lines.next().expect("No initial status printed").unwrap().contains("Rows: unchanged") {}
+
+    // SAFETY: kill only sends a signal to the child started above
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    let deadline = std::time::Instant::now() + Duration::from_secs(30);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        assert!(std::time::Instant::now() < deadline, "Watch loop ignored Ctrl-C");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    assert!(status.success(), "Unexpected exit status: {:?}", status);
+}
+
+#[test]
+fn test_status_line_numbers_report_source_lines() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("users.csv", "id,name\n1,Alice\n2,Bob\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("users.csv", "id,name\n1,Alicia\n2,Bob\n").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["modified"][0]["row_index"], 0);
+    
+    // The first data row is on line 2, after the header
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--json", "--line-numbers"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["row_changes"]["modified"][0]["row_index"], 2);
+    assert_eq!(json["row_changes"]["modified"][0]["changes"]["name"]["after"], "Alicia");
+}
+
+#[test]
+fn test_status_against_self_flags_inconsistencies() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_mixed_types_csv("mixed.csv").unwrap();
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--against-self", "--json"]);
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let mixed: Vec<&str> = report["mixed_type_columns"].as_array().unwrap()
+        .iter()
+        .map(|column| column["column"].as_str().unwrap())
+        .collect();
+    assert!(mixed.contains(&"price"), "Unexpected report: {}", output);
+    assert!(mixed.contains(&"created_at"), "Unexpected report: {}", output);
+    assert!(!mixed.contains(&"notes"), "Unexpected report: {}", output);
+    
+    let created_at = report["mixed_type_columns"].as_array().unwrap()
+        .iter()
+        .find(|column| column["column"] == "created_at")
+        .unwrap();
+    assert_eq!(created_at["inferred_type"], "DATE");
+    assert!(created_at["examples"].as_array().unwrap().contains(&serde_json::json!("invalid-date")));
+    
+    // "Product,D" is written unquoted, so its row has an extra field
+    let anomalies = report["field_count_anomalies"].as_array().unwrap();
+    assert_eq!(anomalies.len(), 1, "Unexpected report: {}", output);
+    assert_eq!(anomalies[0]["line"], 5);
+    assert_eq!(anomalies[0]["found"], 7);
+    
+    let output = runner.run_binary_stdout(&["status", csv_path.to_str().unwrap(), "--against-self"]);
+    assert!(output.contains("Mixed-type columns"), "Unexpected output: {}", output);
+    
+    runner.expect_failure(&["status", csv_path.to_str().unwrap(), "--against-self", "--compare-to", "v1"]);
+}
+
+#[test]
+fn test_status_against_self_reads_na_values_as_empty() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("scores.csv", "id,score\n1,10\n2,NA\n3,12\n4,NA\n5,7\n").unwrap();
+    let path = csv_path.to_str().unwrap();
+    let mixed_columns = |args: &[&str]| {
+        let report: serde_json::Value = serde_json::from_str(&runner.run_binary_stdout(args)).unwrap();
+        report["mixed_type_columns"].as_array().unwrap().len()
+    };
+
+    assert_eq!(mixed_columns(&["status", path, "--against-self", "--json"]), 1);
+    assert_eq!(mixed_columns(&["status", path, "--against-self", "--json", "--na-values", "NA"]), 0);
+
+    // Options that only apply to a comparison are rejected rather than ignored
+    runner.expect_failure(&["status", path, "--against-self", "--threads", "2"]);
+    runner.expect_failure(&["status", path, "--against-self", "--strict"]);
+}
+
+#[test]
+fn test_status_output_saves_comprehensive_result() {
+    let runner = CliTestRunner::new().unwrap();
+    let file_a = runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apple\n2,Banana\n").unwrap();
+    runner.expect_success(&["snapshot", file_a.to_str().unwrap(), "--name", "a1"]);
+    runner.fixture().create_csv_raw("a.csv", "id,name\n1,Apricot\n2,Banana\n3,Cherry\n").unwrap();
+    
+    // The pretty output still goes to the terminal; the file gets the full JSON result
+    let output = runner.run_binary_stdout(&["status", file_a.to_str().unwrap(), "--output", "s.json"]);
+    assert!(output.contains("📊 tabdiff status"), "Unexpected output: {}", output);
+    
+    let saved_path = runner.fixture().root().join("s.json");
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&saved_path).unwrap()).unwrap();
+    for section in ["modified", "added", "removed"] {
+        assert!(saved["row_changes"][section].is_array(), "Missing row_changes.{} in {}", section, saved);
+    }
+    assert_eq!(saved["row_changes"]["modified"].as_array().unwrap().len(), 1);
+    assert_eq!(saved["row_changes"]["added"].as_array().unwrap().len(), 1);
+    assert!(saved["schema_changes"].is_object());
+    
+    // --only narrows what is printed, not what is saved
+    runner.expect_success(&["status", file_a.to_str().unwrap(), "--only", "added", "--json", "-o", "nested/s.json"]);
+    let saved: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(runner.fixture().root().join("nested/s.json")).unwrap()
+    ).unwrap();
+    assert_eq!(saved["row_changes"]["modified"].as_array().unwrap().len(), 1);
+}
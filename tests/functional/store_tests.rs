@@ -0,0 +1,53 @@
+//! Tests for snapshot stores that leave no `.tabdiff` directory behind
+
+use std::fs;
+use tabdiff::change_detection::ChangeDetector;
+use tabdiff::snapshot::{SnapshotCreator, SnapshotLoader};
+use tabdiff::store::{MemorySnapshotStore, SnapshotStore};
+use tabdiff::workspace::TemporaryWorkspace;
+use tempfile::TempDir;
+
+#[test]
+fn test_diff_in_memory_snapshots() {
+    let dir = TempDir::new().unwrap();
+    let before_path = dir.path().join("before.csv");
+    let after_path = dir.path().join("after.csv");
+    fs::write(&before_path, "id,name\n1,Alice\n2,Bob\n").unwrap();
+    fs::write(&after_path, "id,name\n1,Alice\n2,Bobby\n").unwrap();
+
+    let store = MemorySnapshotStore::new();
+    SnapshotCreator::new(1000, false).create_snapshot_in_store(&before_path, "v1", &store, true).unwrap();
+    SnapshotCreator::new(1000, false).create_snapshot_in_store(&after_path, "v2", &store, true).unwrap();
+    assert_eq!(store.list().unwrap(), vec!["v1".to_string(), "v2".to_string()]);
+
+    let metadata1 = store.read_metadata("v1").unwrap();
+    let metadata2 = store.read_metadata("v2").unwrap();
+    assert!(metadata1.parent_snapshot.is_none());
+    let rows1 = SnapshotLoader::load_full_snapshot_from_store(&store, "v1").unwrap().rows();
+    let rows2 = SnapshotLoader::load_full_snapshot_from_store(&store, "v2").unwrap().rows();
+
+    let changes = ChangeDetector::detect_changes(&metadata1.columns, &rows1, &metadata2.columns, &rows2).unwrap();
+    assert_eq!(changes.row_changes.modified.len(), 1);
+    assert_eq!(changes.row_changes.modified[0].changes["name"].after, "Bobby");
+    assert!(changes.row_changes.added.is_empty());
+    assert!(changes.row_changes.removed.is_empty());
+
+    assert!(!dir.path().join(".tabdiff").exists());
+    assert!(store.read_metadata("v3").is_err());
+}
+
+#[test]
+fn test_temporary_workspace_is_removed_on_drop() {
+    let dir = TempDir::new().unwrap();
+    let csv_path = dir.path().join("data.csv");
+    fs::write(&csv_path, "id,name\n1,Alice\n").unwrap();
+
+    let workspace = TemporaryWorkspace::new().unwrap();
+    let root = workspace.root.clone();
+    SnapshotCreator::new(1000, false).create_snapshot_in_store(&csv_path, "v1", &*workspace, true).unwrap();
+    assert!(workspace.snapshot_exists("v1"));
+    assert_eq!(SnapshotLoader::load_full_snapshot_from_store(&*workspace, "v1").unwrap().rows().len(), 1);
+
+    drop(workspace);
+    assert!(!root.exists());
+}
@@ -0,0 +1,44 @@
+//! Tests for rendering DATE, TIME and TIMESTAMP values as ISO-8601
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_changed_dates_and_times_are_iso_8601() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw(
+        "events.csv",
+        "id,day,at,logged\n1,2024-01-15,10:30:00,2024-01-15 10:30:00\n2,2024-03-01,08:00:00,2024-03-01 08:00:00\n",
+    ).unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw(
+        "events.csv",
+        "id,day,at,logged\n1,2024-02-01,11:45:30,2024-02-01 11:45:30.250\n2,2024-03-01,08:00:00,2024-03-01 08:00:00\n",
+    ).unwrap();
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json", "--key", "id"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    assert_eq!(modified.len(), 1, "Unexpected output: {}", output);
+
+    let changes = &modified[0]["changes"];
+    assert_eq!(changes["day"]["before"], "2024-01-15");
+    assert_eq!(changes["day"]["after"], "2024-02-01");
+    assert_eq!(changes["at"]["before"], "10:30:00");
+    assert_eq!(changes["at"]["after"], "11:45:30");
+    assert_eq!(changes["logged"]["before"], "2024-01-15T10:30:00");
+    assert_eq!(changes["logged"]["after"], "2024-02-01T11:45:30.250");
+}
+
+#[test]
+fn test_unchanged_dates_hash_identically() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("events.csv", "id,day\n1,2024-01-15\n2,2024-01-16\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("events.csv", "id,day\n1,2024-01-15\n2,2024-01-17\n").unwrap();
+
+    let output = runner.run_binary_stdout(&["status", path.to_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let modified = json["row_changes"]["modified"].as_array().unwrap();
+    assert_eq!(modified.len(), 1, "Unexpected output: {}", output);
+    assert_eq!(modified[0]["changes"]["day"]["after"], "2024-01-17");
+}
@@ -0,0 +1,48 @@
+//! Tests for `--timings`, which prints the wall-clock time of each phase to stderr
+
+use crate::common::CliTestRunner;
+
+/// Phase labels of the timings table, in order
+fn phases(stderr: &str) -> Vec<String> {
+    stderr.lines()
+        .skip_while(|line| !line.contains("Timings:"))
+        .skip(1)
+        .take_while(|line| line.starts_with("   "))
+        .filter_map(|line| line.trim_start().split("  ").next().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_snapshot_timings_list_every_phase() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,c\n").unwrap();
+
+    let stderr = runner.run_binary_stderr(&["snapshot", path.to_str().unwrap(), "--name", "v2", "--timings"]);
+    assert_eq!(
+        phases(&stderr),
+        vec!["load", "schema hash", "row hash", "parent delta", "column hash", "archive write", "total"],
+        "Unexpected stderr: {}", stderr
+    );
+}
+
+#[test]
+fn test_diff_and_status_timings_list_comparison_phases() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,c\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "v2"]);
+
+    let expected = vec!["load baseline", "load current", "detect schema", "classify rows", "cell analysis", "total"];
+    let stderr = runner.run_binary_stderr(&["diff", "v1", "v2", "--timings"]);
+    assert_eq!(phases(&stderr), expected, "Unexpected stderr: {}", stderr);
+
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,d\n").unwrap();
+    let stderr = runner.run_binary_stderr(&["status", path.to_str().unwrap(), "--compare-to", "v1", "--timings"]);
+    assert_eq!(phases(&stderr), expected, "Unexpected stderr: {}", stderr);
+
+    let output = runner.run_binary_stdout(&["diff", "v1", "v2"]);
+    assert!(!output.contains("Timings:"));
+}
@@ -0,0 +1,57 @@
+//! Tests for tab-delimited rollback and diff output
+
+use crate::common::CliTestRunner;
+use std::fs;
+
+const SNAPSHOT_CONTENT: &str = "id\tname\tnote\n1\tAlice\tsmith, jr.\n2\tBob\tsemi; colon\n3\tCarol\tplain\n";
+
+/// Rows of a tab-delimited file as DuckDB reads them, all values as text
+fn read_tsv_with_duckdb(path: &std::path::Path) -> Vec<Vec<String>> {
+    let connection = duckdb::Connection::open_in_memory().unwrap();
+    let sql = format!(
+        "SELECT id, name, note FROM read_csv('{}', delim='\\t', header=true, all_varchar=true) ORDER BY id",
+        path.display()
+    );
+    let mut stmt = connection.prepare(&sql).unwrap();
+    let rows = stmt.query_map([], |row| {
+        Ok((0..3).map(|i| row.get::<_, Option<String>>(i).unwrap().unwrap_or_default()).collect::<Vec<String>>())
+    }).unwrap();
+    rows.map(|row| row.unwrap()).collect()
+}
+
+#[test]
+fn test_rollback_tsv_round_trips_through_duckdb() {
+    let runner = CliTestRunner::new().unwrap();
+    let tsv_path = runner.fixture().root().join("people.tsv");
+    fs::write(&tsv_path, SNAPSHOT_CONTENT).unwrap();
+    let original = read_tsv_with_duckdb(&tsv_path);
+    runner.expect_success(&["snapshot", tsv_path.to_str().unwrap(), "--name", "baseline"]);
+    
+    fs::write(&tsv_path, "id\tname\tnote\n1\tAlice\tchanged\n3\tCarol\tplain\n4\tDave\tnew\n").unwrap();
+    runner.expect_success(&["rollback", tsv_path.to_str().unwrap(), "--to", "baseline", "--force"]);
+    
+    let content = fs::read_to_string(&tsv_path).unwrap();
+    assert!(content.starts_with("id\tname\tnote\n"), "Unexpected content: {}", content);
+    assert_eq!(read_tsv_with_duckdb(&tsv_path), original);
+    assert_eq!(original[0][2], "smith, jr.");
+    assert_eq!(original[1][2], "semi; colon");
+}
+
+#[test]
+fn test_diff_output_delimiter() {
+    let runner = CliTestRunner::new().unwrap();
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,c\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    let tsv_output = runner.fixture().root().join("changes.tsv");
+    runner.expect_success(&["diff", "v1", "v2", "--output", tsv_output.to_str().unwrap()]);
+    let content = fs::read_to_string(&tsv_output).unwrap();
+    assert!(content.starts_with("change_type\trow_index\tkey\tcolumn\tbefore\tafter\n"), "Unexpected content: {}", content);
+    
+    let csv_output = runner.fixture().root().join("changes.csv");
+    runner.expect_success(&["diff", "v1", "v2", "--output", csv_output.to_str().unwrap(), "--delimiter", ";"]);
+    let content = fs::read_to_string(&csv_output).unwrap();
+    assert!(content.starts_with("change_type;row_index;key;column;before;after\n"), "Unexpected content: {}", content);
+}
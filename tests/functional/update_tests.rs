@@ -0,0 +1,62 @@
+//! Tests for `snapshot --update`, which replaces a snapshot only when the source content changed
+
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_update_is_a_noop_on_unchanged_data() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    runner.expect_success(&["snapshot", path, "--name", "latest", "--update"]);
+    let first = runner.fixture().snapshot_metadata("latest");
+    assert_eq!(first["row_count"], 2);
+
+    let output = runner.run_binary_stdout(&["snapshot", path, "--name", "latest", "--update"]);
+    assert!(output.contains("unchanged"), "Unexpected output: {}", output);
+    assert_eq!(runner.fixture().snapshot_metadata("latest"), first);
+}
+
+#[test]
+fn test_update_overwrites_after_an_edit() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    let path = path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "latest", "--update"]);
+
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+    let output = runner.run_binary_stdout(&["snapshot", path, "--name", "latest", "--update"]);
+    assert!(!output.contains("unchanged"), "Unexpected output: {}", output);
+
+    let updated = runner.fixture().snapshot_metadata("latest");
+    assert_eq!(updated["row_count"], 3);
+    assert!(updated["parent_snapshot"].is_null());
+    let list: serde_json::Value = serde_json::from_str(&runner.run_binary_stdout(&["list", "--json"])).unwrap();
+    assert_eq!(list.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_update_overwrites_when_settings_change() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,price\n1,2.5\n5,3.25\n").unwrap();
+    let path = path.to_str().unwrap();
+    runner.expect_success(&["snapshot", path, "--name", "latest", "--update"]);
+
+    let output = runner.run_binary_stdout(&["snapshot", path, "--name", "latest", "--update", "--float-precision", "2", "--na-values", "5"]);
+    assert!(!output.contains("unchanged"), "Unexpected output: {}", output);
+    let updated = runner.fixture().snapshot_metadata("latest");
+    assert_eq!(updated["float_precision"], 2);
+    assert_eq!(updated["na_values"], serde_json::json!(["5"]));
+
+    let output = runner.run_binary_stdout(&["snapshot", path, "--name", "latest", "--update", "--float-precision", "2", "--na-values", "5"]);
+    assert!(output.contains("unchanged"), "Unexpected output: {}", output);
+}
+
+#[test]
+fn test_existing_snapshot_without_update_is_rejected() {
+    let runner = CliTestRunner::new().unwrap();
+    let path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n").unwrap();
+    runner.expect_success(&["snapshot", path.to_str().unwrap(), "--name", "latest"]);
+    runner.expect_failure(&["snapshot", path.to_str().unwrap(), "--name", "latest"]);
+    runner.expect_failure(&["snapshot", path.to_str().unwrap(), "--name", "latest", "--update", "--overwrite"]);
+}
@@ -0,0 +1,53 @@
+//! Tests for locating the workspace from subdirectories, `TABDIFF_WORKSPACE` and `--workspace`
+
+use crate::common::CliTestRunner;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn has_snapshot(root: &Path, name: &str) -> bool {
+    root.join(".tabdiff").join(format!("{}.json", name)).is_file()
+}
+
+#[test]
+fn test_command_in_nested_subdirectory_uses_root_workspace() {
+    let temp = TempDir::new().unwrap();
+    let root = temp.path();
+    CliTestRunner::run_binary_in(root, None, &["init"]);
+    let nested = root.join("data").join("daily");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(nested.join("data.csv"), "id,name\n1,a\n").unwrap();
+
+    CliTestRunner::run_binary_in(&nested, None, &["snapshot", "data.csv", "--name", "v1"]);
+    CliTestRunner::run_binary_in(&nested, None, &["status", "data.csv"]);
+
+    assert!(has_snapshot(root, "v1"));
+    assert!(!nested.join(".tabdiff").exists());
+    assert!(!root.join("data").join(".tabdiff").exists());
+}
+
+#[test]
+fn test_workspace_env_and_flag_precedence() {
+    let temp = TempDir::new().unwrap();
+    let env_root = temp.path().join("env");
+    let flag_root = temp.path().join("flag");
+    let elsewhere = temp.path().join("elsewhere");
+    for dir in [&env_root, &flag_root, &elsewhere] {
+        fs::create_dir_all(dir).unwrap();
+        CliTestRunner::run_binary_in(dir, None, &["init"]);
+    }
+    let data = elsewhere.join("data.csv");
+    fs::write(&data, "id,name\n1,a\n").unwrap();
+    let data = data.to_str().unwrap();
+
+    // The environment variable overrides the current directory
+    CliTestRunner::run_binary_in(&elsewhere, Some(&env_root), &["snapshot", data, "--name", "from_env"]);
+    assert!(has_snapshot(&env_root, "from_env"));
+    assert!(!has_snapshot(&elsewhere, "from_env"));
+
+    // The flag overrides the environment variable
+    let flag = flag_root.to_str().unwrap();
+    CliTestRunner::run_binary_in(&elsewhere, Some(&env_root), &["--workspace", flag, "snapshot", data, "--name", "from_flag"]);
+    assert!(has_snapshot(&flag_root, "from_flag"));
+    assert!(!has_snapshot(&env_root, "from_flag"));
+}
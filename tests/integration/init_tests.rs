@@ -127,7 +127,7 @@ fn test_init_config_file_format() {
     let config: serde_json::Value = serde_json::from_str(&content).unwrap();
     
     // Verify config structure
-    assert_eq!(config["version"], "1.0.0");
+    assert_eq!(config["version"], "3.0.0");
     assert!(config["created"].is_string());
     assert_eq!(config["default_batch_size"], 10000);
     assert_eq!(config["default_sample_size"], 1000);
@@ -201,3 +201,58 @@ fn test_init_permissions() {
     let content = std::fs::read_to_string(&test_file).unwrap();
     assert_eq!(content, "test content");
 }
+
+#[test]
+fn test_init_with_custom_dir_name() {
+    let runner = CliTestRunner::new().unwrap();
+    let project = runner.fixture().root().join("project");
+    std::fs::create_dir(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    
+    runner.expect_success(&["--workspace", project_arg, "init", "--dir", "tabdiff-state"]);
+    
+    let state_dir = project.join("tabdiff-state");
+    assertions::assert_dir_exists(&state_dir);
+    assert!(!project.join(".tabdiff").exists());
+    let config: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(state_dir.join("config.json")).unwrap()).unwrap();
+    assert_eq!(config["dir"], "tabdiff-state");
+    let gitignore = std::fs::read_to_string(project.join(".gitignore")).unwrap();
+    assert!(gitignore.contains("tabdiff-state/*.tabdiff"));
+    
+    // Later commands find the recorded directory
+    let csv_path = project.join("data.csv");
+    std::fs::write(&csv_path, "id,name\n1,Alice\n").unwrap();
+    runner.expect_success(&["--workspace", project_arg, "snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    assert!(state_dir.join("v1.json").exists());
+    assert!(state_dir.join("v1.tabdiff").exists());
+    assert!(!project.join(".tabdiff").exists());
+}
+
+#[test]
+fn test_init_no_gitignore() {
+    let runner = CliTestRunner::new().unwrap();
+    let project = runner.fixture().root().join("project");
+    std::fs::create_dir(&project).unwrap();
+    let gitignore_path = project.join(".gitignore");
+    std::fs::write(&gitignore_path, "*.log\n").unwrap();
+    let project_arg = project.to_str().unwrap();
+    
+    runner.expect_success(&["--workspace", project_arg, "init", "--no-gitignore"]);
+    runner.expect_success(&["--workspace", project_arg, "init", "--force"]);
+    
+    assertions::assert_dir_exists(&project.join(".tabdiff"));
+    assert_eq!(std::fs::read_to_string(&gitignore_path).unwrap(), "*.log\n");
+    
+    let csv_path = project.join("data.csv");
+    std::fs::write(&csv_path, "id,name\n1,Alice\n").unwrap();
+    runner.expect_success(&["--workspace", project_arg, "snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    assert_eq!(std::fs::read_to_string(&gitignore_path).unwrap(), "*.log\n");
+}
+
+#[test]
+fn test_init_rejects_nested_dir_name() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let error = runner.expect_failure(&["init", "--dir", "a/b"]);
+    assert!(error.to_string().contains("Invalid workspace directory name"));
+}
@@ -103,7 +103,7 @@ fn test_snapshot_command_metadata_content() {
     
     // Verify metadata content
     assert_eq!(metadata["name"], "metadata_test");
-    assert_eq!(metadata["format_version"], "1.0.0");
+    assert_eq!(metadata["format_version"], "3.0.0");
     assert_eq!(metadata["row_count"], 3); // 3 data rows in simple_csv_data
     assert_eq!(metadata["column_count"], 3); // id, name, price
     assert!(metadata["schema_hash"].is_string());
@@ -135,3 +135,126 @@ fn test_snapshot_command_archive_creation() {
 
 
 
+
+#[test]
+fn test_snapshot_command_no_header() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    // All-text rows so DuckDB would otherwise sniff the first row as a header
+    let csv_path = runner.fixture().create_csv_raw("people.csv", "alice,smith\nbob,jones\ncarol,white\n").unwrap();
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "with_header"]);
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "no_header", "--no-header"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("with_header");
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(metadata["row_count"], 2);
+    assert_eq!(metadata["synthetic_column_names"], false);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("no_header");
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(metadata["row_count"], 3, "Header row should be counted as data");
+    assert_eq!(metadata["synthetic_column_names"], true);
+    assert_eq!(metadata["columns"][0]["name"], "column0");
+    assert_eq!(metadata["columns"][1]["name"], "column1");
+    
+    // Comparing the two warns but still succeeds
+    runner.expect_success(&["diff", "with_header", "no_header"]);
+}
+
+#[test]
+fn test_snapshot_command_order_by() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let sorted_csv = runner.fixture().create_csv_raw("sorted.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+    let shuffled_csv = runner.fixture().create_csv_raw("shuffled.csv", "id,name\n3,c\n1,a\n2,b\n").unwrap();
+    
+    runner.expect_success(&["snapshot", sorted_csv.to_str().unwrap(), "--name", "sorted", "--order-by", "id"]);
+    runner.expect_success(&["snapshot", shuffled_csv.to_str().unwrap(), "--name", "shuffled", "--order-by", "id"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("shuffled");
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(metadata["order_by"], serde_json::json!(["id"]));
+    
+    // Stored rows follow the requested ordering regardless of physical file order
+    let (sorted_archive, _) = runner.fixture().workspace.snapshot_paths("sorted");
+    let (shuffled_archive, _) = runner.fixture().workspace.snapshot_paths("shuffled");
+    let sorted_data = tabdiff::snapshot::SnapshotLoader::load_full_snapshot(&sorted_archive).unwrap();
+    let shuffled_data = tabdiff::snapshot::SnapshotLoader::load_full_snapshot(&shuffled_archive).unwrap();
+    assert_eq!(sorted_data.row_data["rows"], shuffled_data.row_data["rows"]);
+    
+    // Unknown sort columns are rejected
+    runner.expect_failure(&["snapshot", sorted_csv.to_str().unwrap(), "--name", "bad", "--order-by", "nope"]);
+}
+
+#[test]
+fn test_snapshot_overwrite_replaces_leaf() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    // Without --overwrite an existing name is still rejected
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n3,c\n4,d\n").unwrap();
+    let error = runner.expect_failure(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    assert!(error.to_string().contains("--overwrite"), "Unexpected error: {}", error);
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2", "--overwrite"]);
+    
+    let (_, json_path) = runner.fixture().workspace.snapshot_paths("v2");
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(metadata["row_count"], 4);
+    assert_eq!(metadata["parent_snapshot"], "v1");
+    assert_eq!(metadata["sequence_number"], 1);
+    assert_eq!(metadata["delta_from_parent"]["changes"]["row_changes"]["added"].as_array().unwrap().len(), 2);
+    assert_eq!(runner.fixture().workspace.list_snapshots().unwrap().len(), 2);
+}
+
+#[test]
+fn test_snapshot_overwrite_parent_needs_force() {
+    let runner = CliTestRunner::new().unwrap();
+    
+    let csv_path = runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1"]);
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n2,b\n3,c\n").unwrap();
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v2"]);
+    
+    // v2's delta was computed against v1, so replacing v1 needs --force
+    runner.fixture().create_csv_raw("data.csv", "id,name\n1,a\n").unwrap();
+    let error = runner.expect_failure(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--overwrite"]);
+    let message = error.to_string();
+    assert!(message.contains("parent of 'v2'") && message.contains("--force"), "Unexpected error: {}", message);
+    let (_, v1_json) = runner.fixture().workspace.snapshot_paths("v1");
+    let v1: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&v1_json).unwrap()).unwrap();
+    assert_eq!(v1["row_count"], 2, "A refused overwrite must leave the snapshot untouched");
+    
+    runner.expect_success(&["snapshot", csv_path.to_str().unwrap(), "--name", "v1", "--overwrite", "--force"]);
+    
+    let v1: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&v1_json).unwrap()).unwrap();
+    assert_eq!(v1["row_count"], 1);
+    assert!(v1["parent_snapshot"].is_null());
+    assert_eq!(v1["sequence_number"], 0);
+    
+    // v2 stays chained to v1, with its delta recomputed against the new data
+    let (_, v2_json) = runner.fixture().workspace.snapshot_paths("v2");
+    let v2: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&v2_json).unwrap()).unwrap();
+    assert_eq!(v2["parent_snapshot"], "v1");
+    assert_eq!(v2["can_reconstruct_parent"], true);
+    assert_eq!(v2["delta_from_parent"]["changes"]["row_changes"]["added"].as_array().unwrap().len(), 2);
+
+    // The archive's copy of the delta was rewritten too
+    let (v2_archive, _) = runner.fixture().workspace.snapshot_paths("v2");
+    let entries = tabdiff::archive::ArchiveManager::extract_archive_entries(&v2_archive).unwrap();
+    let (_, delta) = entries.iter().find(|(name, _)| name == "delta.parquet").unwrap();
+    let delta: serde_json::Value = serde_json::from_slice(delta).unwrap();
+    assert_eq!(delta["changes"], v2["delta_from_parent"]["changes"]);
+
+    // Nothing staged for the replacement is left behind
+    let leftovers: Vec<_> = std::fs::read_dir(v2_archive.parent().unwrap()).unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".partial"))
+        .collect();
+    assert!(leftovers.is_empty(), "Unexpected staged files: {:?}", leftovers);
+}
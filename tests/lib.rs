@@ -8,6 +8,7 @@ pub mod common;
 pub mod unit {
     pub mod cli_tests;
     pub mod workspace_tests;
+    pub mod error_tests;
 }
 
 // Integration tests
@@ -28,6 +29,55 @@ pub mod functional {
     pub mod core_validation_tests;
     pub mod sql_tests;
     pub mod date_rollback_tests;
+    pub mod log_tests;
+    pub mod progress_tests;
+    pub mod show_tests;
+    pub mod filter_tests;
+    pub mod stat_tests;
+    pub mod status_tests;
+    pub mod git_source_tests;
+    pub mod float_precision_tests;
+    pub mod merge_tests;
+    pub mod partial_rollback_tests;
+    pub mod encoding_tests;
+    pub mod format_override_tests;
+    pub mod store_tests;
+    pub mod coercion_tests;
+    pub mod content_hash_tests;
+    pub mod row_identity_tests;
+    pub mod avro_tests;
+    pub mod gc_tests;
+    pub mod json_path_tests;
+    pub mod metadata_only_tests;
+    pub mod resume_tests;
+    pub mod tsv_output_tests;
+    pub mod list_tests;
+    pub mod junit_tests;
+    pub mod cancellation_tests;
+    pub mod config_tests;
+    pub mod dedup_tests;
+    pub mod export_tests;
+    pub mod remote_tests;
+    pub mod na_values_tests;
+    pub mod signing_tests;
+    pub mod group_by_tests;
+    pub mod archive_loading_tests;
+    pub mod invert_tests;
+    pub mod label_column_tests;
+    pub mod named_chain_tests;
+    pub mod timings_tests;
+    pub mod cleanup_tests;
+    pub mod schema_only_changes_tests;
+    pub mod workspace_discovery_tests;
+    pub mod match_threshold_tests;
+    pub mod columns_summary_tests;
+    pub mod update_tests;
+    pub mod temporal_format_tests;
+    pub mod sample_tests;
+    pub mod chain_graph_tests;
+    pub mod status_schema_only_tests;
+    pub mod assertion_tests;
+    pub mod append_only_tests;
 }
 
 // Re-export common utilities for easy access
@@ -1,6 +1,7 @@
 //! Unit tests for CLI argument parsing - focused on core functionality
 
 use tabdiff::cli::{Cli, Commands};
+use tabdiff::change_detection::RowRange;
 use clap::Parser;
 
 #[test]
@@ -10,12 +11,34 @@ fn test_cli_snapshot_command_with_full_data() {
     ]).unwrap();
     
     match cli.command {
-        Commands::Snapshot { input, name, batch_size, full_data, hash_only } => {
-            assert_eq!(input, "data.csv");
-            assert_eq!(name, "test");
+        Commands::Snapshot { input, from_query, name, batch_size, full_data, hash_only, metadata_only, no_header, order_by, float_precision, na_values, encoding, message, json_path, resume, compression_level, sign_key, chain, sample, sample_seed, assertions, all_tables, dry_run, overwrite, update, force, timings } => {
+            assert_eq!(input.as_deref(), Some("data.csv"));
+            assert!(from_query.is_none());
+            assert_eq!(name.as_deref(), Some("test"));
             assert_eq!(batch_size, 10000);
             assert!(full_data);
             assert!(!hash_only);
+            assert!(!metadata_only);
+            assert!(!no_header);
+            assert!(order_by.is_empty());
+            assert!(float_precision.is_none());
+            assert!(na_values.is_empty());
+            assert!(encoding.is_none());
+            assert!(message.is_none());
+            assert!(json_path.is_none());
+            assert!(!resume);
+            assert!(compression_level.is_none());
+            assert!(sign_key.is_none());
+            assert!(chain.is_none());
+            assert!(sample.is_none());
+            assert_eq!(sample_seed, 0);
+            assert!(assertions.is_empty());
+            assert!(!all_tables);
+            assert!(!dry_run);
+            assert!(!overwrite);
+            assert!(!update);
+            assert!(!force);
+            assert!(!timings);
         }
         _ => panic!("Expected Snapshot command"),
     }
@@ -28,18 +51,39 @@ fn test_cli_rollback_command() {
     ]).unwrap();
     
     match cli.command {
-        Commands::Rollback { input, to, to_date, dry_run, force, backup } => {
+        Commands::Rollback { input, to, to_date, dry_run, force, interactive, backup, rows, columns, delimiter } => {
             assert_eq!(input, "data.csv");
             assert_eq!(to, Some("baseline".to_string()));
             assert_eq!(to_date, None);
             assert!(!dry_run);
             assert!(!force);
+            assert!(!interactive);
             assert!(backup);
+            assert!(rows.is_empty());
+            assert!(columns.is_empty());
+            assert!(delimiter.is_none());
         }
         _ => panic!("Expected Rollback command"),
     }
 }
 
+#[test]
+fn test_cli_partial_rollback_command() {
+    let cli = Cli::try_parse_from([
+        "tabdiff", "rollback", "data.csv", "--to", "baseline", "--rows", "3,10-12", "--columns", "price,qty"
+    ]).unwrap();
+    
+    match cli.command {
+        Commands::Rollback { rows, columns, .. } => {
+            assert_eq!(rows, vec![RowRange { start: 3, end: 3 }, RowRange { start: 10, end: 12 }]);
+            assert_eq!(columns, vec!["price".to_string(), "qty".to_string()]);
+        }
+        _ => panic!("Expected Rollback command"),
+    }
+    
+    assert!(Cli::try_parse_from(["tabdiff", "rollback", "data.csv", "--to", "baseline", "--rows", "12-10"]).is_err());
+}
+
 #[test]
 fn test_cli_rollback_command_with_date() {
     let cli = Cli::try_parse_from(&[
@@ -47,7 +91,7 @@ fn test_cli_rollback_command_with_date() {
     ]).unwrap();
     
     match cli.command {
-        Commands::Rollback { input, to, to_date, dry_run, force, backup } => {
+        Commands::Rollback { input, to, to_date, dry_run, force, backup, .. } => {
             assert_eq!(input, "data.csv");
             assert_eq!(to, None);
             assert_eq!(to_date, Some("2025-01-01 15:00:00".to_string()));
@@ -77,8 +121,9 @@ fn test_cli_status_command_with_json() {
 
 #[test]
 fn test_cli_missing_required_args() {
-    // Missing snapshot name
-    assert!(Cli::try_parse_from(&["tabdiff", "snapshot", "data.csv"]).is_err());
+    // Missing snapshot input (the name is generated when omitted)
+    assert!(Cli::try_parse_from(&["tabdiff", "snapshot", "--name", "v1"]).is_err());
+    assert!(Cli::try_parse_from(&["tabdiff", "snapshot", "data.csv"]).is_ok());
     
     // Missing rollback target (neither --to nor --to-date)
     assert!(Cli::try_parse_from(&["tabdiff", "rollback", "data.csv"]).is_ok()); // CLI parsing succeeds but validation fails in command
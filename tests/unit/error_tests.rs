@@ -0,0 +1,66 @@
+//! Unit tests for structured error variants and their stable codes
+
+use tabdiff::data::DataProcessor;
+use tabdiff::TabdiffError;
+use crate::common::CliTestRunner;
+
+#[test]
+fn test_error_codes_are_stable() {
+    assert_eq!(TabdiffError::invalid_input("x").code(), 10);
+    assert_eq!(TabdiffError::file_not_found("missing.csv").code(), 11);
+    assert_eq!(TabdiffError::unsupported_format("data.txt").code(), 12);
+    assert_eq!(TabdiffError::malformed_data("x").code(), 13);
+    assert_eq!(TabdiffError::workspace("x").code(), 20);
+    assert_eq!(TabdiffError::SnapshotNotFound { name: "x".to_string() }.code(), 21);
+    assert_eq!(TabdiffError::schema_mismatch("x").code(), 30);
+    assert_eq!(TabdiffError::hash_algorithm_mismatch("x").code(), 31);
+    assert_eq!(TabdiffError::data_processing("x").code(), 40);
+    assert_eq!(TabdiffError::git("x").code(), 60);
+}
+
+#[test]
+fn test_missing_file_is_file_not_found() {
+    let runner = CliTestRunner::new().unwrap();
+    let missing = runner.fixture().root().join("missing.csv");
+
+    let error = DataProcessor::new().unwrap().load_file(&missing).unwrap_err();
+    assert!(matches!(error, TabdiffError::FileNotFound { ref path } if path == &missing), "Unexpected error: {:?}", error);
+    assert_eq!(error.code(), 11);
+
+    let error = runner.expect_failure(&["snapshot", missing.to_str().unwrap(), "--name", "v1"]);
+    assert!(matches!(error, TabdiffError::FileNotFound { .. }), "Unexpected error: {:?}", error);
+}
+
+#[test]
+fn test_unsupported_extension_is_unsupported_format() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().create_csv_raw("notes.txt", "a\n1\n").unwrap();
+
+    let error = runner.expect_failure(&["snapshot", file.to_str().unwrap(), "--name", "v1"]);
+    assert!(matches!(error, TabdiffError::UnsupportedFormat { .. }), "Unexpected error: {:?}", error);
+    assert_eq!(error.code(), 12);
+}
+
+#[test]
+fn test_invalid_csv_is_malformed_data() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().root().join("invalid.csv");
+    std::fs::write(&file, b"a,b\n\xff\xfe,1\n").unwrap();
+
+    let error = DataProcessor::new().unwrap().load_file(&file).unwrap_err();
+    assert!(matches!(error, TabdiffError::MalformedData { .. }), "Unexpected error: {:?}", error);
+    assert_eq!(error.code(), 13);
+    assert!(error.to_string().contains("invalid.csv"));
+}
+
+#[test]
+fn test_float_precision_mismatch_is_hash_algorithm_mismatch() {
+    let runner = CliTestRunner::new().unwrap();
+    let file = runner.fixture().create_csv_raw("data.csv", "id,price\n1,1.5\n").unwrap();
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "v1"]);
+    runner.expect_success(&["snapshot", file.to_str().unwrap(), "--name", "v2", "--float-precision", "2"]);
+
+    let error = runner.expect_failure(&["diff", "v1", "v2"]);
+    assert!(matches!(error, TabdiffError::HashAlgorithmMismatch { .. }), "Unexpected error: {:?}", error);
+    assert_eq!(error.code(), 31);
+}